@@ -4926,6 +4926,128 @@ async fn test_document_highlights(
     });
 }
 
+#[gpui::test(iterations = 10)]
+async fn test_document_links(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    client_a
+        .fs()
+        .insert_tree(
+            "/root-1",
+            json!({
+                "main.rs": "// See https://zed.dev and lib.rs\nfn main() {}",
+                "lib.rs": "",
+            }),
+        )
+        .await;
+
+    // Set up a fake language server that sends one of its links without a target.
+    let mut language = Language::new(
+        LanguageConfig {
+            name: "Rust".into(),
+            path_suffixes: vec!["rs".to_string()],
+            ..Default::default()
+        },
+        Some(tree_sitter_rust::language()),
+    );
+    let mut fake_language_servers = language
+        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                document_link_provider: Some(lsp::DocumentLinkOptions {
+                    resolve_provider: Some(true),
+                    work_done_progress_options: Default::default(),
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        }))
+        .await;
+    client_a.language_registry().add(Arc::new(language));
+
+    let (project_a, worktree_id) = client_a.build_local_project("/root-1", cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.build_remote_project(project_id, cx_b).await;
+
+    // Open the file on client B.
+    let open_b = project_b.update(cx_b, |p, cx| p.open_buffer((worktree_id, "main.rs"), cx));
+    let buffer_b = cx_b.executor().spawn(open_b).await.unwrap();
+
+    // Request document links as the guest.
+    let fake_language_server = fake_language_servers.next().await.unwrap();
+    fake_language_server.handle_request::<lsp::request::DocumentLinkRequest, _, _>(
+        |params, _| async move {
+            assert_eq!(params.text_document.uri.as_str(), "file:///root-1/main.rs");
+            Ok(Some(vec![
+                lsp::DocumentLink {
+                    range: lsp::Range::new(lsp::Position::new(0, 27), lsp::Position::new(0, 33)),
+                    target: None,
+                    tooltip: None,
+                    data: Some(json!("lib.rs")),
+                },
+                lsp::DocumentLink {
+                    range: lsp::Range::new(lsp::Position::new(0, 7), lsp::Position::new(0, 22)),
+                    target: Some(lsp::Url::parse("https://zed.dev").unwrap()),
+                    tooltip: Some("Open zed.dev".into()),
+                    data: None,
+                },
+            ]))
+        },
+    );
+    fake_language_server.handle_request::<lsp::request::DocumentLinkResolve, _, _>(
+        |link, _| async move {
+            assert_eq!(link.data, Some(json!("lib.rs")));
+            Ok(lsp::DocumentLink {
+                target: Some(lsp::Url::from_file_path("/root-1/lib.rs").unwrap()),
+                ..link
+            })
+        },
+    );
+
+    let links = project_b
+        .update(cx_b, |p, cx| p.document_links(&buffer_b, cx))
+        .await
+        .unwrap();
+
+    buffer_b.read_with(cx_b, |buffer, _| {
+        let snapshot = buffer.snapshot();
+
+        let links = links
+            .into_iter()
+            .map(|link| {
+                (
+                    link.range.to_offset(&snapshot),
+                    link.target.to_string(),
+                    link.tooltip,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            links,
+            &[
+                (
+                    7..22,
+                    "https://zed.dev/".to_string(),
+                    Some("Open zed.dev".to_string())
+                ),
+                (27..33, "file:///root-1/lib.rs".to_string(), None),
+            ]
+        )
+    });
+}
+
 #[gpui::test(iterations = 10)]
 async fn test_lsp_hover(
     executor: BackgroundExecutor,
@@ -8,7 +8,7 @@ use collections::{CommandPaletteFilter, HashMap};
 use fuzzy::{StringMatch, StringMatchCandidate};
 use gpui::{
     actions, Action, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView,
-    ParentElement, Render, Styled, View, ViewContext, VisualContext, WeakView,
+    ParentElement, Render, Styled, Task, View, ViewContext, VisualContext, WeakView,
 };
 use picker::{Picker, PickerDelegate};
 
@@ -36,19 +36,23 @@ pub struct CommandPalette {
 
 impl CommandPalette {
     fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
-        workspace.register_action(|workspace, _: &Toggle, cx| {
-            let Some(previous_focus_handle) = cx.focused() else {
-                return;
-            };
-            let telemetry = workspace.client().telemetry().clone();
-            workspace.toggle_modal(cx, move |cx| {
-                CommandPalette::new(previous_focus_handle, telemetry, cx)
-            });
+        workspace.register_action(|workspace, _: &Toggle, cx| Self::toggle(workspace, "", cx));
+    }
+
+    /// Toggles the command palette, opening it with `query` already typed.
+    pub fn toggle(workspace: &mut Workspace, query: &str, cx: &mut ViewContext<Workspace>) {
+        let Some(previous_focus_handle) = cx.focused() else {
+            return;
+        };
+        let telemetry = workspace.client().telemetry().clone();
+        workspace.toggle_modal(cx, move |cx| {
+            CommandPalette::new(previous_focus_handle, query, telemetry, cx)
         });
     }
 
     fn new(
         previous_focus_handle: FocusHandle,
+        query: &str,
         telemetry: Arc<Telemetry>,
         cx: &mut ViewContext<Self>,
     ) -> Self {
@@ -81,7 +85,13 @@ impl CommandPalette {
             previous_focus_handle,
         );
 
-        let picker = cx.new_view(|cx| Picker::new(delegate, cx));
+        let picker = cx.new_view(|cx| {
+            let picker = Picker::new(delegate, cx);
+            if !query.is_empty() {
+                picker.set_query(query, cx);
+            }
+            picker
+        });
         Self { picker }
     }
 }
@@ -101,7 +111,7 @@ impl Render for CommandPalette {
 }
 
 pub type CommandPaletteInterceptor =
-    Box<dyn Fn(&str, &AppContext) -> Option<CommandInterceptResult>>;
+    Box<dyn Fn(&str, &AppContext) -> Task<Vec<CommandInterceptResult>>>;
 
 pub struct CommandInterceptResult {
     pub action: Box<dyn Action>,
@@ -227,40 +237,44 @@ impl PickerDelegate for CommandPaletteDelegate {
                 .await
             };
 
-            let mut intercept_result = cx
-                .try_read_global(|interceptor: &CommandPaletteInterceptor, cx| {
+            let intercept_task =
+                cx.try_read_global(|interceptor: &CommandPaletteInterceptor, cx| {
                     (interceptor)(&query, cx)
-                })
-                .flatten();
+                });
+            let mut intercept_results = match intercept_task {
+                Some(task) => task.await,
+                None => Vec::new(),
+            };
 
             if *RELEASE_CHANNEL == ReleaseChannel::Dev {
                 if parse_zed_link(&query).is_some() {
-                    intercept_result = Some(CommandInterceptResult {
+                    intercept_results = vec![CommandInterceptResult {
                         action: OpenZedURL { url: query.clone() }.boxed_clone(),
                         string: query.clone(),
                         positions: vec![],
-                    })
+                    }]
                 }
             }
 
-            if let Some(CommandInterceptResult {
-                action,
-                string,
-                positions,
-            }) = intercept_result
-            {
+            for (ix, intercept_result) in intercept_results.into_iter().enumerate() {
+                let CommandInterceptResult {
+                    action,
+                    string,
+                    positions,
+                } = intercept_result;
                 if let Some(idx) = matches
                     .iter()
+                    .skip(ix)
                     .position(|m| commands[m.candidate_id].action.type_id() == action.type_id())
                 {
-                    matches.remove(idx);
+                    matches.remove(ix + idx);
                 }
                 commands.push(Command {
                     name: string.clone(),
                     action,
                 });
                 matches.insert(
-                    0,
+                    ix,
                     StringMatch {
                         candidate_id: commands.len() - 1,
                         string,
@@ -0,0 +1,872 @@
+pub mod types;
+
+pub use types::*;
+
+use anyhow::{anyhow, Context, Result};
+use collections::HashMap;
+use futures::{channel::oneshot, io::BufWriter, AsyncRead, AsyncWrite, FutureExt};
+use gpui::{AsyncAppContext, BackgroundExecutor, Task};
+use parking_lot::Mutex;
+use postage::{barrier, prelude::Stream};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use smol::{
+    channel,
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::TcpStream,
+    process::{self, Child},
+};
+use std::{
+    fmt,
+    future::Future,
+    io::Write,
+    path::{Path, PathBuf},
+    process::Stdio,
+    sync::{
+        atomic::{AtomicU64, Ordering::SeqCst},
+        Arc,
+    },
+    time::Duration,
+};
+use util::ResultExt;
+
+const CONTENT_LEN_HEADER: &str = "Content-Length: ";
+const DAP_REQUEST_TIMEOUT: Duration = Duration::from_secs(60 * 2);
+const TCP_CONNECT_ATTEMPTS: usize = 10;
+const TCP_CONNECT_RETRY_INTERVAL: Duration = Duration::from_millis(250);
+
+type EventHandler = Box<dyn Send + FnMut(Value, AsyncAppContext)>;
+type ResponseHandler = Box<dyn Send + FnOnce(Result<Value>)>;
+
+/// How to launch a debug adapter and connect to it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct DebugAdapterBinary {
+    pub path: PathBuf,
+    #[serde(default)]
+    pub arguments: Vec<String>,
+    /// When set, the adapter is reached over TCP instead of its stdio.
+    #[serde(default)]
+    pub connection: Option<TcpConnection>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct TcpConnection {
+    #[serde(default = "default_host")]
+    pub host: String,
+    pub port: u16,
+}
+
+fn default_host() -> String {
+    "127.0.0.1".into()
+}
+
+/// Identifies a running debug adapter.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct DebugAdapterClientId(pub usize);
+
+/// A running debug adapter, spoken to with the Debug Adapter Protocol.
+pub struct DebugAdapterClient {
+    id: DebugAdapterClientId,
+    name: String,
+    next_seq: AtomicU64,
+    outbound_tx: channel::Sender<String>,
+    capabilities: Capabilities,
+    event_handlers: Arc<Mutex<HashMap<&'static str, EventHandler>>>,
+    response_handlers: Arc<Mutex<Option<HashMap<u64, ResponseHandler>>>>,
+    executor: BackgroundExecutor,
+    #[allow(clippy::type_complexity)]
+    io_tasks: Mutex<Option<(Task<Option<()>>, Task<Option<()>>)>>,
+    output_done_rx: Mutex<Option<barrier::Receiver>>,
+    _server: Option<Mutex<Child>>,
+}
+
+/// Handle to a debug adapter event subscription.
+pub struct Subscription {
+    event: &'static str,
+    event_handlers: Option<Arc<Mutex<HashMap<&'static str, EventHandler>>>>,
+}
+
+/// A message exchanged with a debug adapter.
+///
+/// [DAP Specification](https://microsoft.github.io/debug-adapter-protocol/specification#Base_Protocol_ProtocolMessage)
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum Message {
+    Request(RequestMessage),
+    Response(ResponseMessage),
+    Event(EventMessage),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RequestMessage {
+    seq: u64,
+    command: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    arguments: Option<Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ResponseMessage {
+    seq: u64,
+    request_seq: u64,
+    success: bool,
+    command: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body: Option<Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct EventMessage {
+    seq: u64,
+    event: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body: Option<Value>,
+}
+
+impl DebugAdapterClient {
+    /// Starts a debug adapter process and connects to it, either via its stdio or over TCP.
+    pub async fn new(
+        id: DebugAdapterClientId,
+        name: String,
+        binary: DebugAdapterBinary,
+        working_dir: &Path,
+        cx: AsyncAppContext,
+    ) -> Result<Self> {
+        let mut command = process::Command::new(&binary.path);
+        command
+            .current_dir(working_dir)
+            .args(&binary.arguments)
+            .kill_on_drop(true);
+
+        if let Some(connection) = binary.connection.clone() {
+            let server = command
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()?;
+            let stream = Self::connect_tcp(&connection, &cx).await?;
+            let (stdout, stdin) = futures::AsyncReadExt::split(stream);
+            Ok(Self::new_internal(
+                id,
+                name,
+                stdin,
+                stdout,
+                None::<process::ChildStderr>,
+                Some(server),
+                cx,
+            ))
+        } else {
+            let mut server = command
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .spawn()?;
+            let stdin = server.stdin.take().unwrap();
+            let stdout = server.stdout.take().unwrap();
+            let stderr = server.stderr.take().unwrap();
+            Ok(Self::new_internal(
+                id,
+                name,
+                stdin,
+                stdout,
+                Some(stderr),
+                Some(server),
+                cx,
+            ))
+        }
+    }
+
+    async fn connect_tcp(connection: &TcpConnection, cx: &AsyncAppContext) -> Result<TcpStream> {
+        let address = format!("{}:{}", connection.host, connection.port);
+        let mut last_error = None;
+        for _ in 0..TCP_CONNECT_ATTEMPTS {
+            match TcpStream::connect(address.as_str()).await {
+                Ok(stream) => return Ok(stream),
+                Err(error) => {
+                    last_error = Some(error);
+                    // The adapter may still be starting up, give it a moment to bind the port.
+                    cx.background_executor()
+                        .timer(TCP_CONNECT_RETRY_INTERVAL)
+                        .await;
+                }
+            }
+        }
+        Err(anyhow!(
+            "failed to connect to debug adapter at {address}: {:?}",
+            last_error
+        ))
+    }
+
+    fn new_internal<Stdin, Stdout, Stderr>(
+        id: DebugAdapterClientId,
+        name: String,
+        stdin: Stdin,
+        stdout: Stdout,
+        stderr: Option<Stderr>,
+        server: Option<Child>,
+        cx: AsyncAppContext,
+    ) -> Self
+    where
+        Stdin: AsyncWrite + Unpin + Send + 'static,
+        Stdout: AsyncRead + Unpin + Send + 'static,
+        Stderr: AsyncRead + Unpin + Send + 'static,
+    {
+        let (outbound_tx, outbound_rx) = channel::unbounded::<String>();
+        let (output_done_tx, output_done_rx) = barrier::channel();
+        let event_handlers = Arc::new(Mutex::new(HashMap::<_, EventHandler>::default()));
+        let response_handlers =
+            Arc::new(Mutex::new(Some(HashMap::<_, ResponseHandler>::default())));
+
+        let stdout_input_task = cx.spawn({
+            let event_handlers = event_handlers.clone();
+            let response_handlers = response_handlers.clone();
+            let outbound_tx = outbound_tx.clone();
+            move |cx| {
+                Self::handle_input(stdout, event_handlers, response_handlers, outbound_tx, cx)
+                    .log_err()
+            }
+        });
+        let stderr_input_task = stderr
+            .map(|stderr| cx.spawn(|_| Self::handle_stderr(stderr).log_err()))
+            .unwrap_or_else(|| Task::Ready(Some(None)));
+        let input_task = cx.spawn(|_| async move {
+            let (stdout, stderr) = futures::join!(stdout_input_task, stderr_input_task);
+            stdout.or(stderr)
+        });
+        let output_task = cx.background_executor().spawn({
+            Self::handle_output(
+                stdin,
+                outbound_rx,
+                output_done_tx,
+                response_handlers.clone(),
+            )
+            .log_err()
+        });
+
+        Self {
+            id,
+            name,
+            next_seq: AtomicU64::new(1),
+            outbound_tx,
+            capabilities: Default::default(),
+            event_handlers,
+            response_handlers,
+            executor: cx.background_executor().clone(),
+            io_tasks: Mutex::new(Some((input_task, output_task))),
+            output_done_rx: Mutex::new(Some(output_done_rx)),
+            _server: server.map(Mutex::new),
+        }
+    }
+
+    async fn handle_input<Stdout>(
+        stdout: Stdout,
+        event_handlers: Arc<Mutex<HashMap<&'static str, EventHandler>>>,
+        response_handlers: Arc<Mutex<Option<HashMap<u64, ResponseHandler>>>>,
+        outbound_tx: channel::Sender<String>,
+        cx: AsyncAppContext,
+    ) -> Result<()>
+    where
+        Stdout: AsyncRead + Unpin + Send + 'static,
+    {
+        let mut stdout = BufReader::new(stdout);
+        let _clear_response_handlers = util::defer({
+            let response_handlers = response_handlers.clone();
+            move || {
+                response_handlers.lock().take();
+            }
+        });
+        let mut buffer = Vec::new();
+        loop {
+            let message_len = Self::read_header(&mut stdout, &mut buffer).await?;
+            buffer.resize(message_len, 0);
+            stdout.read_exact(&mut buffer).await?;
+            log::trace!("incoming message: {}", String::from_utf8_lossy(&buffer));
+
+            match serde_json::from_slice::<Message>(&buffer) {
+                Ok(Message::Event(event)) => {
+                    if let Some(handler) = event_handlers.lock().get_mut(event.event.as_str()) {
+                        handler(event.body.unwrap_or(Value::Null), cx.clone());
+                    } else {
+                        log::info!("unhandled debug adapter event {}", event.event);
+                    }
+                }
+                Ok(Message::Response(response)) => {
+                    if let Some(handler) = response_handlers
+                        .lock()
+                        .as_mut()
+                        .and_then(|handlers| handlers.remove(&response.request_seq))
+                    {
+                        if response.success {
+                            handler(Ok(response.body.unwrap_or(Value::Null)));
+                        } else {
+                            handler(Err(anyhow!(
+                                "{} failed: {}",
+                                response.command,
+                                response.message.unwrap_or_default()
+                            )));
+                        }
+                    }
+                }
+                Ok(Message::Request(request)) => {
+                    // Reverse requests such as `runInTerminal` are not supported yet, so tell the
+                    // adapter instead of leaving it waiting for an answer.
+                    log::info!("unsupported debug adapter request {}", request.command);
+                    let response = Message::Response(ResponseMessage {
+                        seq: 0,
+                        request_seq: request.seq,
+                        success: false,
+                        command: request.command,
+                        message: Some("unsupported request".into()),
+                        body: None,
+                    });
+                    if let Some(response) = serde_json::to_string(&response).log_err() {
+                        outbound_tx.try_send(response).ok();
+                    }
+                }
+                Err(error) => {
+                    log::warn!(
+                        "failed to deserialize debug adapter message {error}:\n{}",
+                        String::from_utf8_lossy(&buffer)
+                    );
+                }
+            }
+
+            // Don't starve the main thread when receiving lots of messages at once.
+            smol::future::yield_now().await;
+        }
+    }
+
+    /// Reads header lines up to and including the blank separator line, returning the content length.
+    async fn read_header<Stdout>(
+        stdout: &mut BufReader<Stdout>,
+        buffer: &mut Vec<u8>,
+    ) -> Result<usize>
+    where
+        Stdout: AsyncRead + Unpin + Send + 'static,
+    {
+        let mut message_len = None;
+        loop {
+            buffer.clear();
+            if stdout.read_until(b'\n', buffer).await? == 0 {
+                return Err(anyhow!("debug adapter closed its output"));
+            }
+            let line = std::str::from_utf8(buffer)?.trim_end();
+            if line.is_empty() {
+                if message_len.is_some() {
+                    break;
+                } else {
+                    continue;
+                }
+            }
+            if let Some(len) = line.strip_prefix(CONTENT_LEN_HEADER) {
+                message_len = Some(len.parse()?);
+            }
+        }
+        message_len.ok_or_else(|| anyhow!("missing {CONTENT_LEN_HEADER:?} header"))
+    }
+
+    async fn handle_stderr<Stderr>(stderr: Stderr) -> Result<()>
+    where
+        Stderr: AsyncRead + Unpin + Send + 'static,
+    {
+        let mut stderr = BufReader::new(stderr);
+        let mut buffer = Vec::new();
+        loop {
+            buffer.clear();
+            if stderr.read_until(b'\n', &mut buffer).await? == 0 {
+                return Ok(());
+            }
+            log::trace!(
+                "incoming stderr message: {}",
+                String::from_utf8_lossy(&buffer)
+            );
+
+            // Don't starve the main thread when receiving lots of messages at once.
+            smol::future::yield_now().await;
+        }
+    }
+
+    async fn handle_output<Stdin>(
+        stdin: Stdin,
+        outbound_rx: channel::Receiver<String>,
+        output_done_tx: barrier::Sender,
+        response_handlers: Arc<Mutex<Option<HashMap<u64, ResponseHandler>>>>,
+    ) -> Result<()>
+    where
+        Stdin: AsyncWrite + Unpin + Send + 'static,
+    {
+        let mut stdin = BufWriter::new(stdin);
+        let _clear_response_handlers = util::defer({
+            let response_handlers = response_handlers.clone();
+            move || {
+                response_handlers.lock().take();
+            }
+        });
+        let mut content_len_buffer = Vec::new();
+        while let Ok(message) = outbound_rx.recv().await {
+            log::trace!("outgoing message: {}", message);
+            content_len_buffer.clear();
+            write!(content_len_buffer, "{}", message.len()).unwrap();
+            stdin.write_all(CONTENT_LEN_HEADER.as_bytes()).await?;
+            stdin.write_all(&content_len_buffer).await?;
+            stdin.write_all("\r\n\r\n".as_bytes()).await?;
+            stdin.write_all(message.as_bytes()).await?;
+            stdin.flush().await?;
+        }
+        drop(output_done_tx);
+        Ok(())
+    }
+
+    /// Sends the `initialize` request and records the adapter's capabilities.
+    ///
+    /// [DAP Specification](https://microsoft.github.io/debug-adapter-protocol/specification#Requests_Initialize)
+    pub async fn initialize(mut self, adapter_id: String) -> Result<Arc<Self>> {
+        let capabilities = self
+            .request::<Initialize>(InitializeRequestArguments {
+                client_id: Some("zed".into()),
+                client_name: Some("Zed".into()),
+                adapter_id,
+                lines_start_at1: Some(true),
+                columns_start_at1: Some(true),
+                path_format: Some("path".into()),
+                supports_variable_type: Some(true),
+                supports_run_in_terminal_request: Some(false),
+            })
+            .await?;
+        self.capabilities = capabilities.unwrap_or_default();
+        Ok(Arc::new(self))
+    }
+
+    /// Asks the adapter to end the debuggee and prepares the [`DebugAdapterClient`] to be dropped.
+    pub fn shutdown(&self) -> Option<impl 'static + Send + Future<Output = Option<()>>> {
+        if let Some(tasks) = self.io_tasks.lock().take() {
+            let response_handlers = self.response_handlers.clone();
+            let outbound_tx = self.outbound_tx.clone();
+            let mut output_done = self.output_done_rx.lock().take().unwrap();
+            let disconnect = Self::request_internal::<Disconnect>(
+                &self.next_seq,
+                &response_handlers,
+                &outbound_tx,
+                &self.executor,
+                DisconnectArguments {
+                    restart: Some(false),
+                    terminate_debuggee: Some(true),
+                },
+            );
+            let executor = self.executor.clone();
+            Some(
+                async move {
+                    log::debug!("debug adapter shutdown started");
+                    futures::select! {
+                        response = disconnect.fuse() => { response?; }
+                        _ = executor.timer(Duration::from_secs(5)).fuse() => {
+                            log::warn!("debug adapter did not respond to disconnect request");
+                        }
+                    }
+                    outbound_tx.close();
+                    response_handlers.lock().take();
+                    output_done.recv().await;
+                    log::debug!("debug adapter shutdown finished");
+                    drop(tasks);
+                    anyhow::Ok(())
+                }
+                .log_err(),
+            )
+        } else {
+            None
+        }
+    }
+
+    /// Registers a handler for an event sent by the debug adapter.
+    ///
+    /// [DAP Specification](https://microsoft.github.io/debug-adapter-protocol/specification#Base_Protocol_Event)
+    #[must_use]
+    pub fn on_event<E, F>(&self, mut f: F) -> Subscription
+    where
+        E: Event,
+        F: 'static + Send + FnMut(E::Body, AsyncAppContext),
+    {
+        let prev_handler = self.event_handlers.lock().insert(
+            E::EVENT,
+            Box::new(move |body, cx| {
+                if let Some(body) = serde_json::from_value(body).log_err() {
+                    f(body, cx);
+                }
+            }),
+        );
+        assert!(
+            prev_handler.is_none(),
+            "registered multiple handlers for the same DAP event"
+        );
+        Subscription {
+            event: E::EVENT,
+            event_handlers: Some(self.event_handlers.clone()),
+        }
+    }
+
+    /// Removes an event handler registered via [`Self::on_event`].
+    pub fn remove_event_handler<E: Event>(&self) {
+        self.event_handlers.lock().remove(E::EVENT);
+    }
+
+    /// Get the name of the running debug adapter.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the id of the running debug adapter.
+    pub fn id(&self) -> DebugAdapterClientId {
+        self.id
+    }
+
+    /// Get the reported capabilities of the running debug adapter.
+    pub fn capabilities(&self) -> &Capabilities {
+        &self.capabilities
+    }
+
+    /// Sends a request to the debug adapter.
+    ///
+    /// [DAP Specification](https://microsoft.github.io/debug-adapter-protocol/specification#Base_Protocol_Request)
+    pub fn request<R: Request>(
+        &self,
+        arguments: R::Arguments,
+    ) -> impl 'static + Future<Output = Result<R::Response>> {
+        Self::request_internal::<R>(
+            &self.next_seq,
+            &self.response_handlers,
+            &self.outbound_tx,
+            &self.executor,
+            arguments,
+        )
+    }
+
+    fn request_internal<R: Request>(
+        next_seq: &AtomicU64,
+        response_handlers: &Mutex<Option<HashMap<u64, ResponseHandler>>>,
+        outbound_tx: &channel::Sender<String>,
+        executor: &BackgroundExecutor,
+        arguments: R::Arguments,
+    ) -> impl 'static + Future<Output = Result<R::Response>> {
+        let seq = next_seq.fetch_add(1, SeqCst);
+        let arguments = serde_json::to_value(arguments).unwrap();
+        let message = serde_json::to_string(&Message::Request(RequestMessage {
+            seq,
+            command: R::COMMAND.into(),
+            arguments: (!arguments.is_null()).then_some(arguments),
+        }))
+        .unwrap();
+
+        let (tx, rx) = oneshot::channel();
+        let handle_response = response_handlers
+            .lock()
+            .as_mut()
+            .ok_or_else(|| anyhow!("debug adapter shut down"))
+            .map(|handlers| {
+                handlers.insert(
+                    seq,
+                    Box::new(move |result| {
+                        let response = result.and_then(|body| {
+                            serde_json::from_value(body).context("failed to deserialize response")
+                        });
+                        _ = tx.send(response);
+                    }),
+                );
+            });
+
+        let send = outbound_tx
+            .try_send(message)
+            .context("failed to write to debug adapter");
+
+        let mut timeout = executor.timer(DAP_REQUEST_TIMEOUT).fuse();
+        async move {
+            handle_response?;
+            send?;
+
+            let command = R::COMMAND;
+            futures::select! {
+                response = rx.fuse() => response?,
+                _ = timeout => {
+                    log::error!("cancelled DAP request {command:?} seq {seq} which took over {DAP_REQUEST_TIMEOUT:?}");
+                    anyhow::bail!("DAP request timeout");
+                }
+            }
+        }
+    }
+}
+
+impl Drop for DebugAdapterClient {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown() {
+            self.executor.spawn(shutdown).detach();
+        }
+    }
+}
+
+impl Subscription {
+    /// Detaching a subscription handle prevents it from unsubscribing on drop.
+    pub fn detach(&mut self) {
+        self.event_handlers = None;
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        if let Some(handlers) = &self.event_handlers {
+            handlers.lock().remove(self.event);
+        }
+    }
+}
+
+impl fmt::Display for DebugAdapterClientId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl fmt::Debug for DebugAdapterClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DebugAdapterClient")
+            .field("id", &self.id.0)
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(any(test, feature = "test-support"))]
+type FakeRequestHandler = Box<dyn Send + FnMut(Value) -> Result<Value>>;
+
+/// Mock debug adapter for use in tests.
+#[cfg(any(test, feature = "test-support"))]
+#[derive(Clone)]
+pub struct FakeDebugAdapter {
+    request_handlers: Arc<Mutex<HashMap<&'static str, FakeRequestHandler>>>,
+    requests_rx: channel::Receiver<(String, Value)>,
+    outbound_tx: channel::Sender<String>,
+    next_seq: Arc<AtomicU64>,
+    _io_task: Arc<Task<Option<()>>>,
+}
+
+#[cfg(any(test, feature = "test-support"))]
+impl FakeDebugAdapter {
+    /// Construct a fake debug adapter along with a client connected to it.
+    pub fn new(
+        name: String,
+        capabilities: Capabilities,
+        cx: AsyncAppContext,
+    ) -> (DebugAdapterClient, FakeDebugAdapter) {
+        let (stdin_writer, stdin_reader) = async_pipe::pipe();
+        let (stdout_writer, stdout_reader) = async_pipe::pipe();
+        let (requests_tx, requests_rx) = channel::unbounded();
+        let (outbound_tx, outbound_rx) = channel::unbounded::<String>();
+
+        let client = DebugAdapterClient::new_internal(
+            DebugAdapterClientId(0),
+            name,
+            stdin_writer,
+            stdout_reader,
+            None::<async_pipe::PipeReader>,
+            None,
+            cx.clone(),
+        );
+
+        let request_handlers = Arc::new(Mutex::new(
+            HashMap::<&'static str, FakeRequestHandler>::default(),
+        ));
+        let next_seq = Arc::new(AtomicU64::new(1));
+        let io_task = cx.background_executor().spawn({
+            let request_handlers = request_handlers.clone();
+            let next_seq = next_seq.clone();
+            let outbound_tx = outbound_tx.clone();
+            async move {
+                let output = async {
+                    let mut stdout = BufWriter::new(stdout_writer);
+                    while let Ok(message) = outbound_rx.recv().await {
+                        let header = format!("{CONTENT_LEN_HEADER}{}\r\n\r\n", message.len());
+                        stdout.write_all(header.as_bytes()).await?;
+                        stdout.write_all(message.as_bytes()).await?;
+                        stdout.flush().await?;
+                    }
+                    anyhow::Ok(())
+                };
+                let input = async {
+                    let mut stdin = BufReader::new(stdin_reader);
+                    let mut buffer = Vec::new();
+                    while let Ok(len) = Self::read_request_header(&mut stdin, &mut buffer).await {
+                        buffer.resize(len, 0);
+                        stdin.read_exact(&mut buffer).await?;
+                        let Message::Request(request) = serde_json::from_slice::<Message>(&buffer)?
+                        else {
+                            continue;
+                        };
+                        let arguments = request.arguments.unwrap_or(Value::Null);
+                        requests_tx
+                            .try_send((request.command.clone(), arguments.clone()))
+                            .ok();
+                        let result = match request_handlers.lock().get_mut(request.command.as_str())
+                        {
+                            Some(handler) => handler(arguments),
+                            None => Ok(Value::Null),
+                        };
+                        let response = Message::Response(ResponseMessage {
+                            seq: next_seq.fetch_add(1, SeqCst),
+                            request_seq: request.seq,
+                            success: result.is_ok(),
+                            command: request.command,
+                            message: result.as_ref().err().map(|error| error.to_string()),
+                            body: result.ok().filter(|body| !body.is_null()),
+                        });
+                        outbound_tx.try_send(serde_json::to_string(&response)?)?;
+                    }
+                    anyhow::Ok(())
+                };
+                futures::future::try_join(output, input).await.map(|_| ())
+            }
+            .log_err()
+        });
+
+        let fake = FakeDebugAdapter {
+            request_handlers,
+            requests_rx,
+            outbound_tx,
+            next_seq,
+            _io_task: Arc::new(io_task),
+        };
+        fake.handle_request::<Initialize, _>(move |_| Ok(Some(capabilities.clone())));
+        (client, fake)
+    }
+
+    async fn read_request_header(
+        stdin: &mut BufReader<async_pipe::PipeReader>,
+        buffer: &mut Vec<u8>,
+    ) -> Result<usize> {
+        DebugAdapterClient::read_header(stdin, buffer).await
+    }
+
+    /// Registers a handler for a specific kind of request, replacing any existing handler.
+    pub fn handle_request<R, F>(&self, mut handler: F)
+    where
+        R: 'static + Request,
+        F: 'static + Send + FnMut(R::Arguments) -> Result<R::Response>,
+    {
+        self.request_handlers.lock().insert(
+            R::COMMAND,
+            Box::new(move |arguments| {
+                let arguments = serde_json::from_value(arguments)?;
+                Ok(serde_json::to_value(handler(arguments)?)?)
+            }),
+        );
+    }
+
+    /// Sends an event to the client.
+    pub fn send_event<E: Event>(&self, body: E::Body) {
+        let body = serde_json::to_value(body).unwrap();
+        let message = Message::Event(EventMessage {
+            seq: self.next_seq.fetch_add(1, SeqCst),
+            event: E::EVENT.into(),
+            body: (!body.is_null()).then_some(body),
+        });
+        self.outbound_tx
+            .try_send(serde_json::to_string(&message).unwrap())
+            .ok();
+    }
+
+    /// Consumes received requests until it finds one of the specified type.
+    pub async fn receive_request<R: Request>(&mut self) -> R::Arguments {
+        use futures::StreamExt as _;
+
+        loop {
+            let (command, arguments) = self.requests_rx.next().await.unwrap();
+            if command == R::COMMAND {
+                return serde_json::from_value(arguments).unwrap();
+            } else {
+                log::info!("skipping request in fake debug adapter {:?}", command);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+
+    #[ctor::ctor]
+    fn init_logger() {
+        if std::env::var("RUST_LOG").is_ok() {
+            env_logger::init();
+        }
+    }
+
+    #[gpui::test]
+    async fn test_fake(cx: &mut TestAppContext) {
+        let (client, mut fake) = FakeDebugAdapter::new(
+            "the-adapter".to_string(),
+            Capabilities {
+                supports_configuration_done_request: Some(true),
+                ..Default::default()
+            },
+            cx.to_async(),
+        );
+
+        let (stopped_tx, stopped_rx) = channel::unbounded();
+        client
+            .on_event::<Stopped, _>(move |body, _| stopped_tx.try_send(body).unwrap())
+            .detach();
+
+        let client = client.initialize("fake".into()).await.unwrap();
+        assert_eq!(
+            client.capabilities().supports_configuration_done_request,
+            Some(true)
+        );
+
+        fake.handle_request::<SetBreakpoints, _>(|arguments| {
+            Ok(SetBreakpointsResponse {
+                breakpoints: arguments
+                    .breakpoints
+                    .into_iter()
+                    .map(|breakpoint| Breakpoint {
+                        verified: true,
+                        line: Some(breakpoint.line),
+                        ..Default::default()
+                    })
+                    .collect(),
+            })
+        });
+        let response = client
+            .request::<SetBreakpoints>(SetBreakpointsArguments {
+                source: Source {
+                    path: Some("/a/b.rs".into()),
+                    ..Default::default()
+                },
+                breakpoints: vec![SourceBreakpoint {
+                    line: 3,
+                    ..Default::default()
+                }],
+                source_modified: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(response.breakpoints.len(), 1);
+        assert_eq!(response.breakpoints[0].line, Some(3));
+        assert_eq!(
+            fake.receive_request::<SetBreakpoints>().await.source.path,
+            Some("/a/b.rs".into())
+        );
+
+        fake.handle_request::<Threads, _>(|_| Err(anyhow!("no threads")));
+        assert!(client.request::<Threads>(None).await.is_err());
+
+        fake.send_event::<Stopped>(StoppedEventBody {
+            reason: "breakpoint".into(),
+            thread_id: Some(1),
+            ..Default::default()
+        });
+        let stopped = stopped_rx.recv().await.unwrap();
+        assert_eq!(stopped.reason, "breakpoint");
+        assert_eq!(stopped.thread_id, Some(1));
+    }
+}
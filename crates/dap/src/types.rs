@@ -0,0 +1,482 @@
+//! Debug Adapter Protocol message types.
+//!
+//! Only the subset of the protocol needed to drive a basic debugging session is modeled here.
+//!
+//! [DAP Specification](https://microsoft.github.io/debug-adapter-protocol/specification)
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+/// A request sent from the client to the debug adapter.
+pub trait Request {
+    type Arguments: Serialize + DeserializeOwned + Send + 'static;
+    type Response: Serialize + DeserializeOwned + Send + 'static;
+    const COMMAND: &'static str;
+}
+
+/// An event sent from the debug adapter to the client.
+pub trait Event {
+    type Body: Serialize + DeserializeOwned + Send + 'static;
+    const EVENT: &'static str;
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Capabilities {
+    #[serde(default)]
+    pub supports_configuration_done_request: Option<bool>,
+    #[serde(default)]
+    pub supports_function_breakpoints: Option<bool>,
+    #[serde(default)]
+    pub supports_conditional_breakpoints: Option<bool>,
+    #[serde(default)]
+    pub supports_evaluate_for_hovers: Option<bool>,
+    #[serde(default)]
+    pub supports_set_variable: Option<bool>,
+    #[serde(default)]
+    pub supports_terminate_request: Option<bool>,
+    #[serde(default)]
+    pub support_terminate_debuggee: Option<bool>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Source {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_reference: Option<u64>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceBreakpoint {
+    /// The 1-based line of the breakpoint.
+    pub line: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub column: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Breakpoint {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<u64>,
+    pub verified: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<Source>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<u64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Thread {
+    pub id: u64,
+    pub name: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StackFrame {
+    pub id: u64,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<Source>,
+    /// The 1-based line within the source.
+    pub line: u64,
+    pub column: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Scope {
+    pub name: String,
+    pub variables_reference: u64,
+    #[serde(default)]
+    pub expensive: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Variable {
+    pub name: String,
+    pub value: String,
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub type_: Option<String>,
+    /// When greater than zero, the variable is structured and its children can be fetched with
+    /// a [`Variables`] request.
+    #[serde(default)]
+    pub variables_reference: u64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InitializeRequestArguments {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_name: Option<String>,
+    pub adapter_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lines_start_at1: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub columns_start_at1: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path_format: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub supports_variable_type: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub supports_run_in_terminal_request: Option<bool>,
+}
+
+pub enum Initialize {}
+
+impl Request for Initialize {
+    type Arguments = InitializeRequestArguments;
+    type Response = Option<Capabilities>;
+    const COMMAND: &'static str = "initialize";
+}
+
+/// Launch arguments are adapter specific, so they are passed through verbatim.
+pub enum Launch {}
+
+impl Request for Launch {
+    type Arguments = Value;
+    type Response = Option<Value>;
+    const COMMAND: &'static str = "launch";
+}
+
+/// Attach arguments are adapter specific, so they are passed through verbatim.
+pub enum Attach {}
+
+impl Request for Attach {
+    type Arguments = Value;
+    type Response = Option<Value>;
+    const COMMAND: &'static str = "attach";
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetBreakpointsArguments {
+    pub source: Source,
+    pub breakpoints: Vec<SourceBreakpoint>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_modified: Option<bool>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetBreakpointsResponse {
+    pub breakpoints: Vec<Breakpoint>,
+}
+
+pub enum SetBreakpoints {}
+
+impl Request for SetBreakpoints {
+    type Arguments = SetBreakpointsArguments;
+    type Response = SetBreakpointsResponse;
+    const COMMAND: &'static str = "setBreakpoints";
+}
+
+pub enum ConfigurationDone {}
+
+impl Request for ConfigurationDone {
+    type Arguments = Option<Value>;
+    type Response = Option<Value>;
+    const COMMAND: &'static str = "configurationDone";
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThreadsResponse {
+    pub threads: Vec<Thread>,
+}
+
+pub enum Threads {}
+
+impl Request for Threads {
+    type Arguments = Option<Value>;
+    type Response = ThreadsResponse;
+    const COMMAND: &'static str = "threads";
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StackTraceArguments {
+    pub thread_id: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_frame: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub levels: Option<u64>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StackTraceResponse {
+    pub stack_frames: Vec<StackFrame>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_frames: Option<u64>,
+}
+
+pub enum StackTrace {}
+
+impl Request for StackTrace {
+    type Arguments = StackTraceArguments;
+    type Response = StackTraceResponse;
+    const COMMAND: &'static str = "stackTrace";
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScopesArguments {
+    pub frame_id: u64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScopesResponse {
+    pub scopes: Vec<Scope>,
+}
+
+pub enum Scopes {}
+
+impl Request for Scopes {
+    type Arguments = ScopesArguments;
+    type Response = ScopesResponse;
+    const COMMAND: &'static str = "scopes";
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VariablesArguments {
+    pub variables_reference: u64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VariablesResponse {
+    pub variables: Vec<Variable>,
+}
+
+pub enum Variables {}
+
+impl Request for Variables {
+    type Arguments = VariablesArguments;
+    type Response = VariablesResponse;
+    const COMMAND: &'static str = "variables";
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThreadArguments {
+    pub thread_id: u64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContinueResponse {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub all_threads_continued: Option<bool>,
+}
+
+pub enum Continue {}
+
+impl Request for Continue {
+    type Arguments = ThreadArguments;
+    type Response = Option<ContinueResponse>;
+    const COMMAND: &'static str = "continue";
+}
+
+pub enum Next {}
+
+impl Request for Next {
+    type Arguments = ThreadArguments;
+    type Response = Option<Value>;
+    const COMMAND: &'static str = "next";
+}
+
+pub enum StepIn {}
+
+impl Request for StepIn {
+    type Arguments = ThreadArguments;
+    type Response = Option<Value>;
+    const COMMAND: &'static str = "stepIn";
+}
+
+pub enum StepOut {}
+
+impl Request for StepOut {
+    type Arguments = ThreadArguments;
+    type Response = Option<Value>;
+    const COMMAND: &'static str = "stepOut";
+}
+
+pub enum Pause {}
+
+impl Request for Pause {
+    type Arguments = ThreadArguments;
+    type Response = Option<Value>;
+    const COMMAND: &'static str = "pause";
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvaluateArguments {
+    pub expression: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frame_id: Option<u64>,
+    /// One of `watch`, `repl`, `hover` or `clipboard`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvaluateResponse {
+    pub result: String,
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub type_: Option<String>,
+    #[serde(default)]
+    pub variables_reference: u64,
+}
+
+pub enum Evaluate {}
+
+impl Request for Evaluate {
+    type Arguments = EvaluateArguments;
+    type Response = EvaluateResponse;
+    const COMMAND: &'static str = "evaluate";
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisconnectArguments {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub restart: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub terminate_debuggee: Option<bool>,
+}
+
+pub enum Disconnect {}
+
+impl Request for Disconnect {
+    type Arguments = DisconnectArguments;
+    type Response = Option<Value>;
+    const COMMAND: &'static str = "disconnect";
+}
+
+pub enum Initialized {}
+
+impl Event for Initialized {
+    type Body = Option<Value>;
+    const EVENT: &'static str = "initialized";
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoppedEventBody {
+    /// The reason for the event, such as `step`, `breakpoint`, `exception` or `pause`.
+    pub reason: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thread_id: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub all_threads_stopped: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+}
+
+pub enum Stopped {}
+
+impl Event for Stopped {
+    type Body = StoppedEventBody;
+    const EVENT: &'static str = "stopped";
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContinuedEventBody {
+    pub thread_id: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub all_threads_continued: Option<bool>,
+}
+
+pub enum Continued {}
+
+impl Event for Continued {
+    type Body = ContinuedEventBody;
+    const EVENT: &'static str = "continued";
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExitedEventBody {
+    pub exit_code: i64,
+}
+
+pub enum Exited {}
+
+impl Event for Exited {
+    type Body = ExitedEventBody;
+    const EVENT: &'static str = "exited";
+}
+
+pub enum Terminated {}
+
+impl Event for Terminated {
+    type Body = Option<Value>;
+    const EVENT: &'static str = "terminated";
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThreadEventBody {
+    /// Either `started` or `exited`.
+    pub reason: String,
+    pub thread_id: u64,
+}
+
+pub enum ThreadEvent {}
+
+impl Event for ThreadEvent {
+    type Body = ThreadEventBody;
+    const EVENT: &'static str = "thread";
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputEventBody {
+    /// One of `console`, `important`, `stdout`, `stderr` or `telemetry`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    pub output: String,
+}
+
+pub enum Output {}
+
+impl Event for Output {
+    type Body = OutputEventBody;
+    const EVENT: &'static str = "output";
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BreakpointEventBody {
+    /// One of `changed`, `new` or `removed`.
+    pub reason: String,
+    pub breakpoint: Breakpoint,
+}
+
+pub enum BreakpointEvent {}
+
+impl Event for BreakpointEvent {
+    type Body = BreakpointEventBody;
+    const EVENT: &'static str = "breakpoint";
+}
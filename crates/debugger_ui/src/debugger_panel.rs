@@ -0,0 +1,599 @@
+use crate::{adapter_picker::DebugAdapterPicker, debugger_panel_settings::DebuggerPanelSettings};
+use anyhow::{anyhow, Result};
+use db::kvp::KEY_VALUE_STORE;
+use editor::{scroll::autoscroll::Autoscroll, Editor};
+use gpui::{
+    actions, div, AppContext, AsyncWindowContext, EventEmitter, FocusHandle, FocusableView,
+    InteractiveElement, IntoElement, Model, ParentElement, Pixels, Render, Styled, Subscription,
+    Task, View, ViewContext, VisualContext, WeakView, WindowContext,
+};
+use language::ToPoint;
+use menu::Confirm;
+use project::{
+    debugger::{DebugSession, DebugSessionEvent, DebugSessionStatus},
+    project_settings::ProjectSettings,
+    Fs, Project,
+};
+use serde::{Deserialize, Serialize};
+use settings::Settings;
+use std::sync::Arc;
+use ui::{prelude::*, ListItem};
+use util::{ResultExt, TryFutureExt};
+use workspace::{
+    dock::{DockPosition, Panel, PanelEvent},
+    Workspace,
+};
+
+const DEBUGGER_PANEL_KEY: &'static str = "DebuggerPanel";
+
+actions!(
+    debugger,
+    [
+        Start,
+        Stop,
+        Continue,
+        Pause,
+        StepOver,
+        StepIn,
+        StepOut,
+        ToggleFocus
+    ]
+);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(
+        |workspace: &mut Workspace, _: &mut ViewContext<Workspace>| {
+            workspace
+                .register_action(|workspace, _: &ToggleFocus, cx| {
+                    workspace.toggle_panel_focus::<DebuggerPanel>(cx);
+                })
+                .register_action(DebuggerPanel::start)
+                .register_action(|workspace, _: &Stop, cx| {
+                    DebuggerPanel::update_active(workspace, cx, |panel, cx| panel.stop(cx));
+                })
+                .register_action(|workspace, _: &Continue, cx| {
+                    DebuggerPanel::update_session(workspace, cx, DebugSession::continue_thread);
+                })
+                .register_action(|workspace, _: &Pause, cx| {
+                    DebuggerPanel::update_session(workspace, cx, DebugSession::pause);
+                })
+                .register_action(|workspace, _: &StepOver, cx| {
+                    DebuggerPanel::update_session(workspace, cx, DebugSession::step_over);
+                })
+                .register_action(|workspace, _: &StepIn, cx| {
+                    DebuggerPanel::update_session(workspace, cx, DebugSession::step_in);
+                })
+                .register_action(|workspace, _: &StepOut, cx| {
+                    DebuggerPanel::update_session(workspace, cx, DebugSession::step_out);
+                });
+        },
+    )
+    .detach();
+}
+
+pub struct DebuggerPanel {
+    project: Model<Project>,
+    workspace: WeakView<Workspace>,
+    fs: Arc<dyn Fs>,
+    focus_handle: FocusHandle,
+    width: Option<Pixels>,
+    active_session: Option<Model<DebugSession>>,
+    variables: Vec<(String, Vec<dap::Variable>)>,
+    variables_frame_id: Option<u64>,
+    repl_editor: View<Editor>,
+    pending_serialization: Task<Option<()>>,
+    load_variables: Task<Option<()>>,
+    session_subscription: Option<Subscription>,
+    _subscriptions: Vec<Subscription>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedDebuggerPanel {
+    width: Option<Pixels>,
+}
+
+impl DebuggerPanel {
+    pub fn load(
+        workspace: WeakView<Workspace>,
+        cx: AsyncWindowContext,
+    ) -> Task<Result<View<Self>>> {
+        cx.spawn(|mut cx| async move {
+            let serialized_panel = if let Some(panel) = cx
+                .background_executor()
+                .spawn(async move { KEY_VALUE_STORE.read_kvp(DEBUGGER_PANEL_KEY) })
+                .await
+                .log_err()
+                .flatten()
+            {
+                Some(serde_json::from_str::<SerializedDebuggerPanel>(&panel)?)
+            } else {
+                None
+            };
+
+            workspace.update(&mut cx, |workspace, cx| {
+                let panel = cx.new_view(|cx| Self::new(workspace, cx));
+                if let Some(serialized_panel) = serialized_panel {
+                    panel.update(cx, |panel, cx| {
+                        panel.width = serialized_panel.width;
+                        cx.notify();
+                    });
+                }
+                panel
+            })
+        })
+    }
+
+    fn new(workspace: &Workspace, cx: &mut ViewContext<Self>) -> Self {
+        let project = workspace.project().clone();
+        let repl_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text("Evaluate an expression…", cx);
+            editor
+        });
+        let subscriptions = vec![cx.observe(&project, |this, _, cx| {
+            this.sync_active_session(cx);
+            cx.notify();
+        })];
+
+        let mut this = Self {
+            project,
+            workspace: workspace.weak_handle(),
+            fs: workspace.app_state().fs.clone(),
+            focus_handle: cx.focus_handle(),
+            width: None,
+            active_session: None,
+            variables: Vec::new(),
+            variables_frame_id: None,
+            repl_editor,
+            pending_serialization: Task::ready(None),
+            load_variables: Task::ready(None),
+            session_subscription: None,
+            _subscriptions: subscriptions,
+        };
+        this.sync_active_session(cx);
+        this
+    }
+
+    fn start(workspace: &mut Workspace, _: &Start, cx: &mut ViewContext<Workspace>) {
+        let mut adapter_names = ProjectSettings::get_global(cx)
+            .debuggers
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        adapter_names.sort();
+        match adapter_names.len() {
+            0 => workspace.show_error(
+                &anyhow!("no debuggers are configured in the `debuggers` setting"),
+                cx,
+            ),
+            1 => {
+                workspace.focus_panel::<Self>(cx);
+                Self::start_adapter(workspace.project(), adapter_names.remove(0), cx);
+            }
+            _ => {
+                let project = workspace.project().clone();
+                workspace
+                    .toggle_modal(cx, |cx| DebugAdapterPicker::new(project, adapter_names, cx));
+            }
+        }
+    }
+
+    pub(crate) fn start_adapter(
+        project: &Model<Project>,
+        adapter_name: Arc<str>,
+        cx: &mut WindowContext,
+    ) {
+        project
+            .update(cx, |project, cx| {
+                project.start_debug_session(adapter_name, cx)
+            })
+            .detach_and_log_err(cx);
+    }
+
+    fn stop(&mut self, cx: &mut ViewContext<Self>) {
+        if let Some(session) = self.active_session.clone() {
+            self.project
+                .update(cx, |project, cx| project.stop_debug_session(&session, cx));
+        }
+    }
+
+    fn update_active(
+        workspace: &mut Workspace,
+        cx: &mut ViewContext<Workspace>,
+        update: impl FnOnce(&mut Self, &mut ViewContext<Self>),
+    ) {
+        if let Some(panel) = workspace.panel::<Self>(cx) {
+            panel.update(cx, update);
+        }
+    }
+
+    fn update_session(
+        workspace: &mut Workspace,
+        cx: &mut ViewContext<Workspace>,
+        update: impl FnOnce(&mut DebugSession, &mut gpui::ModelContext<DebugSession>),
+    ) {
+        Self::update_active(workspace, cx, |panel, cx| {
+            if let Some(session) = panel.active_session.as_ref() {
+                session.update(cx, update);
+            }
+        });
+    }
+
+    /// Follows the most recently started session when the active one goes away.
+    fn sync_active_session(&mut self, cx: &mut ViewContext<Self>) {
+        let sessions = self.project.read(cx).debug_sessions();
+        if self
+            .active_session
+            .as_ref()
+            .map_or(false, |session| sessions.contains(session))
+        {
+            return;
+        }
+
+        self.active_session = sessions.last().cloned();
+        self.variables.clear();
+        self.variables_frame_id = None;
+        self.session_subscription = self
+            .active_session
+            .as_ref()
+            .map(|session| cx.subscribe(session, Self::handle_session_event));
+    }
+
+    fn handle_session_event(
+        &mut self,
+        session: Model<DebugSession>,
+        event: &DebugSessionEvent,
+        cx: &mut ViewContext<Self>,
+    ) {
+        match event {
+            DebugSessionEvent::Stopped => {
+                self.reveal_execution_position(&session, cx);
+                self.refresh_variables(&session, cx);
+            }
+            DebugSessionEvent::Continued | DebugSessionEvent::Terminated => {
+                self.variables.clear();
+                self.variables_frame_id = None;
+            }
+            DebugSessionEvent::Output | DebugSessionEvent::ThreadsChanged => {}
+        }
+        cx.notify();
+    }
+
+    fn reveal_execution_position(
+        &mut self,
+        session: &Model<DebugSession>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some((buffer, position)) = session.read(cx).execution_position().cloned() else {
+            return;
+        };
+        let point = position.to_point(&buffer.read(cx).snapshot());
+        self.workspace
+            .update(cx, |workspace, cx| {
+                let editor = workspace.open_project_item::<Editor>(buffer, cx);
+                editor.update(cx, |editor, cx| {
+                    editor.change_selections(Some(Autoscroll::center()), cx, |selections| {
+                        selections.select_ranges([point..point]);
+                    });
+                });
+            })
+            .log_err();
+    }
+
+    fn refresh_variables(&mut self, session: &Model<DebugSession>, cx: &mut ViewContext<Self>) {
+        let Some(frame_id) = session.read(cx).active_frame().map(|frame| frame.id) else {
+            return;
+        };
+        if self.variables_frame_id == Some(frame_id) {
+            return;
+        }
+        self.variables_frame_id = Some(frame_id);
+
+        let scopes = session.read(cx).scopes(frame_id, cx);
+        let session = session.downgrade();
+        self.load_variables = cx.spawn(|this, mut cx| {
+            async move {
+                let scopes = scopes.await?;
+                let mut variables = Vec::with_capacity(scopes.len());
+                for scope in scopes {
+                    if scope.expensive {
+                        variables.push((scope.name, Vec::new()));
+                        continue;
+                    }
+                    let scope_variables = session
+                        .update(&mut cx, |session, cx| {
+                            session.variables(scope.variables_reference, cx)
+                        })?
+                        .await?;
+                    variables.push((scope.name, scope_variables));
+                }
+                this.update(&mut cx, |this, cx| {
+                    if this.variables_frame_id == Some(frame_id) {
+                        this.variables = variables;
+                        cx.notify();
+                    }
+                })
+            }
+            .log_err()
+        });
+    }
+
+    fn evaluate(&mut self, _: &Confirm, cx: &mut ViewContext<Self>) {
+        let Some(session) = self.active_session.clone() else {
+            return;
+        };
+        let expression = self.repl_editor.read(cx).text(cx);
+        if expression.trim().is_empty() {
+            return;
+        }
+        self.repl_editor.update(cx, |editor, cx| editor.clear(cx));
+        session
+            .update(cx, |session, cx| session.evaluate(expression, cx))
+            .detach_and_log_err(cx);
+    }
+
+    fn serialize(&mut self, cx: &mut ViewContext<Self>) {
+        let width = self.width;
+        self.pending_serialization = cx.background_executor().spawn(
+            async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        DEBUGGER_PANEL_KEY.into(),
+                        serde_json::to_string(&SerializedDebuggerPanel { width })?,
+                    )
+                    .await?;
+                anyhow::Ok(())
+            }
+            .log_err(),
+        );
+    }
+
+    fn render_controls(
+        &self,
+        session: &Model<DebugSession>,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let stopped = session.read(cx).status() == DebugSessionStatus::Stopped;
+        let control = |id: &'static str,
+                       label: &'static str,
+                       enabled: bool,
+                       action: Box<dyn gpui::Action>| {
+            Button::new(id, label)
+                .disabled(!enabled)
+                .on_click(move |_, cx| cx.dispatch_action(action.boxed_clone()))
+        };
+        h_flex()
+            .gap_1()
+            .child(control("continue", "Continue", stopped, Box::new(Continue)))
+            .child(control("pause", "Pause", !stopped, Box::new(Pause)))
+            .child(control(
+                "step-over",
+                "Step Over",
+                stopped,
+                Box::new(StepOver),
+            ))
+            .child(control("step-in", "Step In", stopped, Box::new(StepIn)))
+            .child(control("step-out", "Step Out", stopped, Box::new(StepOut)))
+            .child(control("stop", "Stop", true, Box::new(Stop)))
+    }
+
+    fn render_session(
+        &self,
+        session: &Model<DebugSession>,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let session_handle = session.clone();
+        let session = session.read(cx);
+        let active_frame_id = session.active_frame().map(|frame| frame.id);
+
+        let threads = session.threads().map(|thread| {
+            let stopped = Some(thread.id) == session.stopped_thread_id();
+            ListItem::new(("thread", thread.id))
+                .selected(stopped)
+                .child(Label::new(thread.name.clone()))
+        });
+        let stack_frames = session
+            .stack_frames()
+            .iter()
+            .enumerate()
+            .map(|(ix, frame)| {
+                let location = frame
+                    .source
+                    .as_ref()
+                    .and_then(|source| source.name.clone().or_else(|| source.path.clone()))
+                    .map(|name| format!("{name}:{}", frame.line))
+                    .unwrap_or_default();
+                let session = session_handle.clone();
+                ListItem::new(("stack-frame", ix))
+                    .selected(Some(frame.id) == active_frame_id)
+                    .on_click(move |_, cx| {
+                        session.update(cx, |session, cx| session.select_stack_frame(ix, cx));
+                    })
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(Label::new(frame.name.clone()))
+                            .child(Label::new(location).color(Color::Muted)),
+                    )
+            });
+        let variables = self.variables.iter().flat_map(|(scope, variables)| {
+            Some(
+                ListItem::new(SharedString::from(format!("scope-{scope}")))
+                    .child(Label::new(scope.clone()).color(Color::Muted)),
+            )
+            .into_iter()
+            .chain(variables.iter().map(move |variable| {
+                ListItem::new(SharedString::from(format!("{scope}-{}", variable.name)))
+                    .indent_level(1)
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(Label::new(variable.name.clone()))
+                            .child(Label::new(variable.value.clone()).color(Color::Muted)),
+                    )
+            }))
+        });
+        let output = session
+            .output()
+            .iter()
+            .map(|line| Label::new(line.clone()).size(LabelSize::Small));
+
+        v_flex()
+            .size_full()
+            .gap_1()
+            .child(section_header("Threads"))
+            .children(threads)
+            .child(section_header("Stack Frames"))
+            .children(stack_frames)
+            .child(section_header("Variables"))
+            .children(variables)
+            .child(section_header("Output"))
+            .child(
+                v_flex()
+                    .id("debugger-output")
+                    .flex_1()
+                    .px_2()
+                    .overflow_y_scroll()
+                    .children(output),
+            )
+            .child(
+                div()
+                    .px_2()
+                    .py_1()
+                    .border_t_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(self.repl_editor.clone()),
+            )
+    }
+
+    fn render_adapters(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let mut adapter_names = ProjectSettings::get_global(cx)
+            .debuggers
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        adapter_names.sort();
+
+        v_flex()
+            .gap_2()
+            .p_4()
+            .when(adapter_names.is_empty(), |this| {
+                this.child(
+                    Label::new("Configure a debug adapter in the `debuggers` setting.")
+                        .color(Color::Muted)
+                        .size(LabelSize::Small),
+                )
+            })
+            .children(adapter_names.into_iter().map(|adapter_name| {
+                let project = self.project.clone();
+                Button::new(
+                    SharedString::from(format!("start-{adapter_name}")),
+                    format!("Start {adapter_name}"),
+                )
+                .style(ButtonStyle::Filled)
+                .full_width()
+                .on_click(move |_, cx| {
+                    Self::start_adapter(&project, adapter_name.clone(), cx);
+                })
+            }))
+    }
+}
+
+fn section_header(title: &'static str) -> impl IntoElement {
+    div()
+        .px_2()
+        .pt_2()
+        .child(Label::new(title).size(LabelSize::Small).color(Color::Muted))
+}
+
+impl Render for DebuggerPanel {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let active_session = self.active_session.clone();
+        v_flex()
+            .key_context("DebuggerPanel")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .on_action(cx.listener(Self::evaluate))
+            .child(
+                h_flex()
+                    .justify_between()
+                    .px_2()
+                    .py_1()
+                    // Match the height of the tab bar so they line up.
+                    .h(rems(ui::Tab::CONTAINER_HEIGHT_IN_REMS))
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(Label::new(
+                        active_session
+                            .as_ref()
+                            .map_or("Debugger".into(), |session| {
+                                session.read(cx).name().to_string()
+                            }),
+                    ))
+                    .children(
+                        active_session
+                            .as_ref()
+                            .map(|session| self.render_controls(session, cx)),
+                    ),
+            )
+            .map(|this| match active_session.as_ref() {
+                Some(session) => this.child(self.render_session(session, cx)),
+                None => this.child(self.render_adapters(cx)),
+            })
+    }
+}
+
+impl FocusableView for DebuggerPanel {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for DebuggerPanel {}
+
+impl Panel for DebuggerPanel {
+    fn persistent_name() -> &'static str {
+        "DebuggerPanel"
+    }
+
+    fn position(&self, cx: &WindowContext) -> DockPosition {
+        DebuggerPanelSettings::get_global(cx).dock
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, cx: &mut ViewContext<Self>) {
+        settings::update_settings_file::<DebuggerPanelSettings>(
+            self.fs.clone(),
+            cx,
+            move |settings| settings.dock = Some(position),
+        );
+    }
+
+    fn size(&self, cx: &WindowContext) -> Pixels {
+        self.width
+            .unwrap_or_else(|| DebuggerPanelSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, cx: &mut ViewContext<Self>) {
+        self.width = size;
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn icon(&self, cx: &WindowContext) -> Option<IconName> {
+        DebuggerPanelSettings::get_global(cx)
+            .button
+            .then(|| IconName::Debug)
+    }
+
+    fn icon_tooltip(&self, _cx: &WindowContext) -> Option<&'static str> {
+        Some("Debugger Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn gpui::Action> {
+        Box::new(ToggleFocus)
+    }
+}
@@ -0,0 +1,12 @@
+mod adapter_picker;
+pub mod debugger_panel;
+mod debugger_panel_settings;
+
+use debugger_panel_settings::DebuggerPanelSettings;
+use gpui::AppContext;
+use settings::Settings;
+
+pub fn init(cx: &mut AppContext) {
+    DebuggerPanelSettings::register(cx);
+    debugger_panel::init(cx);
+}
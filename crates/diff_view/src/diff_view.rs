@@ -0,0 +1,741 @@
+use collections::HashSet;
+use editor::{
+    deleted_lines_block,
+    display_map::{BlockContext, BlockDisposition, BlockId, BlockProperties, BlockStyle},
+    scroll::Autoscroll,
+    Anchor, Editor, EditorEvent, MultiBufferSnapshot,
+};
+use git::diff::{line_hunks, LineHunk};
+use gpui::{
+    actions, div, AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView, Hsla,
+    InteractiveElement, IntoElement, Model, ParentElement, PathPromptOptions, Render, Styled,
+    Subscription, Task, View, ViewContext, VisualContext, WindowContext,
+};
+use language::{language_settings::SoftWrap, Buffer, Capability, Point};
+use project::Project;
+use std::{mem, ops::Range, sync::Arc};
+use theme::StatusColors;
+use ui::prelude::*;
+use workspace::{
+    item::{Item, ItemEvent},
+    Workspace,
+};
+
+actions!(
+    diff_view,
+    [
+        CompareWithFile,
+        CompareWithClipboard,
+        CompareWithSaved,
+        ToggleUnified,
+        NextChange,
+        PreviousChange
+    ]
+);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(DiffView::register).detach();
+}
+
+/// Compares two buffers, either side by side with the rows of both sides aligned and their
+/// scroll positions locked together, or in a single editor that shows the removed lines
+/// above the lines that replaced them.
+///
+/// The diff is recomputed whenever either buffer changes.
+pub struct DiffView {
+    old: DiffSide,
+    new: DiffSide,
+    unified_editor: View<Editor>,
+    unified_blocks: Vec<BlockId>,
+    hunks: Vec<LineHunk>,
+    is_unified: bool,
+    focus_handle: FocusHandle,
+    recalculate_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+/// One of the two texts being compared.
+struct DiffSide {
+    title: SharedString,
+    buffer: Model<Buffer>,
+    editor: View<Editor>,
+    padding_blocks: Vec<BlockId>,
+}
+
+#[derive(Clone, Copy)]
+enum Side {
+    Old,
+    New,
+}
+
+impl DiffView {
+    fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+        workspace.register_action(Self::compare_with_file);
+        workspace.register_action(Self::compare_with_clipboard);
+        workspace.register_action(Self::compare_with_saved);
+    }
+
+    /// Compares a file chosen by the user with the active buffer.
+    fn compare_with_file(
+        workspace: &mut Workspace,
+        _: &CompareWithFile,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let Some(buffer) = active_buffer(workspace, cx) else {
+            return;
+        };
+        let project = workspace.project().clone();
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+        });
+        cx.spawn(|workspace, mut cx| async move {
+            let Some(path) = paths.await?.and_then(|paths| paths.into_iter().next()) else {
+                return Ok(());
+            };
+            let other = project
+                .update(&mut cx, |project, cx| project.open_local_buffer(path, cx))?
+                .await?;
+            workspace.update(&mut cx, |workspace, cx| {
+                let old_title = buffer_title(other.read(cx));
+                let new_title = buffer_title(buffer.read(cx));
+                Self::open((old_title, other), (new_title, buffer), workspace, cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Compares the clipboard's text with the active buffer.
+    fn compare_with_clipboard(
+        workspace: &mut Workspace,
+        _: &CompareWithClipboard,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let Some(buffer) = active_buffer(workspace, cx) else {
+            return;
+        };
+        let Some(text) = cx.read_from_clipboard().map(|item| item.text().clone()) else {
+            return;
+        };
+        let clipboard = read_only_buffer(text, &buffer, cx);
+        let title = buffer_title(buffer.read(cx));
+        Self::open(
+            ("Clipboard".into(), clipboard),
+            (title, buffer),
+            workspace,
+            cx,
+        );
+    }
+
+    /// Compares the active buffer's file as it is on disk with the buffer.
+    fn compare_with_saved(
+        workspace: &mut Workspace,
+        _: &CompareWithSaved,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let Some(buffer) = active_buffer(workspace, cx) else {
+            return;
+        };
+        let Some(file) = buffer.read(cx).file().and_then(|file| file.as_local()) else {
+            return;
+        };
+        let load = file.load(cx);
+        cx.spawn(|workspace, mut cx| async move {
+            let text = load.await?;
+            workspace.update(&mut cx, |workspace, cx| {
+                let saved = read_only_buffer(text, &buffer, cx);
+                let title = buffer_title(buffer.read(cx));
+                let saved_title = format!("{title} (on disk)").into();
+                Self::open((saved_title, saved), (title, buffer), workspace, cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn open(
+        old: (SharedString, Model<Buffer>),
+        new: (SharedString, Model<Buffer>),
+        workspace: &mut Workspace,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let project = workspace.project().clone();
+        let view = cx.new_view(|cx| DiffView::new(old, new, project, cx));
+        workspace.add_item(Box::new(view), cx);
+    }
+
+    fn new(
+        (old_title, old_buffer): (SharedString, Model<Buffer>),
+        (new_title, new_buffer): (SharedString, Model<Buffer>),
+        project: Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let old = DiffSide::new(old_title, old_buffer, project.clone(), cx);
+        let new = DiffSide::new(new_title, new_buffer, project.clone(), cx);
+        let unified_editor = cx.new_view(|cx| {
+            let mut editor = Editor::for_buffer(new.buffer.clone(), Some(project), cx);
+            editor.set_read_only(true);
+            editor
+        });
+
+        let focus_handle = cx.focus_handle();
+        let subscriptions = vec![
+            cx.subscribe(&old.buffer, |this, _, event, cx| {
+                this.handle_buffer_event(event, cx)
+            }),
+            cx.subscribe(&new.buffer, |this, _, event, cx| {
+                this.handle_buffer_event(event, cx)
+            }),
+            cx.subscribe(&old.editor, |this, _, event, cx| {
+                this.handle_editor_event(Side::Old, event, cx)
+            }),
+            cx.subscribe(&new.editor, |this, _, event, cx| {
+                this.handle_editor_event(Side::New, event, cx)
+            }),
+            cx.on_focus_in(&focus_handle, |this, cx| this.focus_in(cx)),
+        ];
+
+        let mut this = Self {
+            old,
+            new,
+            unified_editor,
+            unified_blocks: Vec::new(),
+            hunks: Vec::new(),
+            is_unified: false,
+            focus_handle,
+            recalculate_task: Task::ready(()),
+            _subscriptions: subscriptions,
+        };
+        this.recalculate(cx);
+        this
+    }
+
+    fn focus_in(&mut self, cx: &mut ViewContext<Self>) {
+        if self.focus_handle.is_focused(cx) {
+            self.active_editor().focus_handle(cx).focus(cx);
+        }
+    }
+
+    fn active_editor(&self) -> &View<Editor> {
+        if self.is_unified {
+            &self.unified_editor
+        } else {
+            &self.new.editor
+        }
+    }
+
+    fn handle_buffer_event(&mut self, event: &language::Event, cx: &mut ViewContext<Self>) {
+        match event {
+            language::Event::Edited | language::Event::Reloaded => self.recalculate(cx),
+            _ => {}
+        }
+    }
+
+    /// Keeps the scroll positions of both sides locked together. The rows of both sides
+    /// are aligned, so the same scroll position shows the corresponding rows.
+    fn handle_editor_event(&mut self, side: Side, event: &EditorEvent, cx: &mut ViewContext<Self>) {
+        let EditorEvent::ScrollPositionChanged { local: true, .. } = event else {
+            return;
+        };
+        let (source, target) = match side {
+            Side::Old => (&self.old.editor, &self.new.editor),
+            Side::New => (&self.new.editor, &self.old.editor),
+        };
+        let position = source.update(cx, |editor, cx| editor.scroll_position(cx));
+        target.update(cx, |editor, cx| {
+            if editor.scroll_position(cx) != position {
+                editor.set_scroll_position(position, cx);
+            }
+        });
+    }
+
+    fn recalculate(&mut self, cx: &mut ViewContext<Self>) {
+        let old_text = self.old.buffer.read(cx).text();
+        let new_text = self.new.buffer.read(cx).text();
+        let hunks = cx.background_executor().spawn(async move {
+            let hunks = line_hunks(&old_text, &new_text);
+            (hunks, old_text)
+        });
+        self.recalculate_task = cx.spawn(|this, mut cx| async move {
+            let (hunks, old_text) = hunks.await;
+            this.update(&mut cx, |this, cx| this.set_hunks(hunks, &old_text, cx))
+                .ok();
+        });
+    }
+
+    fn set_hunks(&mut self, hunks: Vec<LineHunk>, old_text: &str, cx: &mut ViewContext<Self>) {
+        let (old_padding, new_padding) = padding_rows(&hunks);
+        self.old.update(
+            hunks.iter().map(|hunk| hunk.old_rows.clone()),
+            old_padding,
+            |status| status.deleted_background,
+            cx,
+        );
+        self.new.update(
+            hunks.iter().map(|hunk| hunk.new_rows.clone()),
+            new_padding,
+            |status| status.created_background,
+            cx,
+        );
+
+        let old_lines = old_text.lines().collect::<Vec<_>>();
+        let unified_blocks = mem::take(&mut self.unified_blocks);
+        self.unified_blocks = self.unified_editor.update(cx, |editor, cx| {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            let added_rows = hunks
+                .iter()
+                .filter(|hunk| !hunk.new_rows.is_empty())
+                .map(|hunk| anchor_range_for_rows(&snapshot, hunk.new_rows.clone()))
+                .collect();
+            editor.highlight_rows_with_status::<Self>(
+                added_rows,
+                |status| status.created_background,
+                cx,
+            );
+
+            editor.remove_blocks(unified_blocks.into_iter().collect(), None, cx);
+            let deleted_blocks = hunks
+                .iter()
+                .filter(|hunk| !hunk.old_rows.is_empty())
+                .map(|hunk| {
+                    let rows = hunk.old_rows.start as usize..hunk.old_rows.end as usize;
+                    let deleted_lines = old_lines
+                        .get(rows)
+                        .unwrap_or_default()
+                        .iter()
+                        .map(|line| SharedString::from(line.to_string()))
+                        .collect();
+                    let (position, disposition) = block_position(&snapshot, hunk.new_rows.start);
+                    BlockProperties {
+                        disposition,
+                        ..deleted_lines_block(position, deleted_lines)
+                    }
+                })
+                .collect::<Vec<_>>();
+            editor.insert_blocks(deleted_blocks, None, cx)
+        });
+
+        self.hunks = hunks;
+        cx.notify();
+    }
+
+    fn toggle_unified(&mut self, _: &ToggleUnified, cx: &mut ViewContext<Self>) {
+        self.is_unified = !self.is_unified;
+        self.active_editor().focus_handle(cx).focus(cx);
+        cx.notify();
+    }
+
+    fn next_change(&mut self, _: &NextChange, cx: &mut ViewContext<Self>) {
+        self.go_to_change(true, cx);
+    }
+
+    fn previous_change(&mut self, _: &PreviousChange, cx: &mut ViewContext<Self>) {
+        self.go_to_change(false, cx);
+    }
+
+    /// Moves the cursor to the next or previous change, wrapping around at either end. When
+    /// shown side by side, the cursor of the other side is moved to the same change.
+    fn go_to_change(&mut self, forward: bool, cx: &mut ViewContext<Self>) {
+        let old_is_focused =
+            !self.is_unified && self.old.editor.focus_handle(cx).contains_focused(cx);
+        let (editor, side) = if old_is_focused {
+            (self.old.editor.clone(), Side::Old)
+        } else {
+            (self.active_editor().clone(), Side::New)
+        };
+        let rows = |hunk: &LineHunk, side: Side| match side {
+            Side::Old => hunk.old_rows.clone(),
+            Side::New => hunk.new_rows.clone(),
+        };
+
+        let cursor_row = editor.read(cx).selections.newest::<Point>(cx).head().row;
+        let hunk = if forward {
+            self.hunks
+                .iter()
+                .find(|hunk| rows(hunk, side).start > cursor_row)
+                .or(self.hunks.first())
+        } else {
+            self.hunks
+                .iter()
+                .rev()
+                .find(|hunk| rows(hunk, side).start < cursor_row)
+                .or(self.hunks.last())
+        };
+        let Some(hunk) = hunk.cloned() else {
+            return;
+        };
+
+        let select_row = |editor: &View<Editor>, row: u32, autoscroll, cx: &mut WindowContext| {
+            editor.update(cx, |editor, cx| {
+                editor.change_selections(autoscroll, cx, |selections| {
+                    selections.select_ranges([Point::new(row, 0)..Point::new(row, 0)])
+                });
+            });
+        };
+        select_row(
+            &editor,
+            rows(&hunk, side).start,
+            Some(Autoscroll::center()),
+            cx,
+        );
+        if !self.is_unified {
+            let (other_editor, other_side) = match side {
+                Side::Old => (&self.new.editor, Side::New),
+                Side::New => (&self.old.editor, Side::Old),
+            };
+            select_row(other_editor, rows(&hunk, other_side).start, None, cx);
+        }
+    }
+}
+
+impl DiffSide {
+    fn new(
+        title: SharedString,
+        buffer: Model<Buffer>,
+        project: Model<Project>,
+        cx: &mut ViewContext<DiffView>,
+    ) -> Self {
+        let editor = cx.new_view(|cx| {
+            let mut editor = Editor::for_buffer(buffer.clone(), Some(project), cx);
+            editor.set_read_only(true);
+            // Wrapped lines would break the alignment of both sides' rows.
+            editor.set_soft_wrap_mode(SoftWrap::None, cx);
+            editor
+        });
+        Self {
+            title,
+            buffer,
+            editor,
+            padding_blocks: Vec::new(),
+        }
+    }
+
+    /// Highlights the changed rows of this side and replaces its padding blocks.
+    fn update(
+        &mut self,
+        changed_rows: impl Iterator<Item = Range<u32>>,
+        padding: Vec<(u32, u32)>,
+        color: fn(&StatusColors) -> Hsla,
+        cx: &mut ViewContext<DiffView>,
+    ) {
+        let old_blocks = mem::take(&mut self.padding_blocks);
+        self.padding_blocks = self.editor.update(cx, |editor, cx| {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            let highlighted_rows = changed_rows
+                .filter(|rows| !rows.is_empty())
+                .map(|rows| anchor_range_for_rows(&snapshot, rows))
+                .collect();
+            editor.highlight_rows_with_status::<DiffView>(highlighted_rows, color, cx);
+
+            editor.remove_blocks(old_blocks.into_iter().collect::<HashSet<_>>(), None, cx);
+            let blocks = padding
+                .into_iter()
+                .flat_map(|(row, height)| {
+                    // Block heights are limited to `u8`, so tall padding is split up.
+                    let (position, disposition) = block_position(&snapshot, row);
+                    (0..height)
+                        .step_by(u8::MAX as usize)
+                        .map(move |start| BlockProperties {
+                            position,
+                            height: (height - start).min(u8::MAX as u32) as u8,
+                            style: BlockStyle::Flex,
+                            disposition,
+                            render: Arc::new(render_padding_block),
+                        })
+                })
+                .collect::<Vec<_>>();
+            editor.insert_blocks(blocks, None, cx)
+        });
+    }
+}
+
+/// The rows above which each side needs blank lines so that the rows of both sides stay
+/// aligned, along with the number of blank lines. The shorter side of every hunk is padded
+/// to the length of the longer one.
+fn padding_rows(hunks: &[LineHunk]) -> (Vec<(u32, u32)>, Vec<(u32, u32)>) {
+    let mut old_padding = Vec::new();
+    let mut new_padding = Vec::new();
+    for hunk in hunks {
+        let old_len = hunk.old_rows.end - hunk.old_rows.start;
+        let new_len = hunk.new_rows.end - hunk.new_rows.start;
+        if old_len > new_len {
+            new_padding.push((hunk.new_rows.end, old_len - new_len));
+        } else if new_len > old_len {
+            old_padding.push((hunk.old_rows.end, new_len - old_len));
+        }
+    }
+    (old_padding, new_padding)
+}
+
+fn anchor_range_for_rows(snapshot: &MultiBufferSnapshot, rows: Range<u32>) -> Range<Anchor> {
+    let max_point = snapshot.max_point();
+    snapshot.anchor_before(Point::new(rows.start, 0))
+        ..snapshot.anchor_before(Point::new(rows.end, 0).min(max_point))
+}
+
+/// Where to place a block that should appear above `row`, which may be one past the last
+/// row when the buffer doesn't end with a newline.
+fn block_position(snapshot: &MultiBufferSnapshot, row: u32) -> (Anchor, BlockDisposition) {
+    let max_point = snapshot.max_point();
+    if row > max_point.row {
+        (snapshot.anchor_after(max_point), BlockDisposition::Below)
+    } else {
+        (
+            snapshot.anchor_before(Point::new(row, 0)),
+            BlockDisposition::Above,
+        )
+    }
+}
+
+fn render_padding_block(cx: &mut BlockContext) -> AnyElement {
+    div()
+        .id(cx.block_id)
+        .w(cx.max_width + cx.gutter_width)
+        .h_full()
+        .bg(cx.theme().status().hidden_background)
+        .into_any_element()
+}
+
+fn active_buffer(workspace: &Workspace, cx: &AppContext) -> Option<Model<Buffer>> {
+    workspace
+        .active_item_as::<Editor>(cx)?
+        .read(cx)
+        .buffer()
+        .read(cx)
+        .as_singleton()
+}
+
+fn buffer_title(buffer: &Buffer) -> SharedString {
+    buffer
+        .file()
+        .map(|file| file.path().to_string_lossy().to_string().into())
+        .unwrap_or_else(|| "untitled".into())
+}
+
+/// A read-only buffer with the given text, in the same language as `like`.
+fn read_only_buffer(text: String, like: &Model<Buffer>, cx: &mut AppContext) -> Model<Buffer> {
+    let language = like.read(cx).language().cloned();
+    cx.new_model(|cx| {
+        let mut buffer = Buffer::new(0, cx.entity_id().as_u64(), text);
+        buffer.set_language(language, cx);
+        buffer.set_capability(Capability::ReadOnly, cx);
+        buffer
+    })
+}
+
+impl EventEmitter<ItemEvent> for DiffView {}
+
+impl FocusableView for DiffView {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for DiffView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let title = |title: SharedString| {
+            div()
+                .flex_1()
+                .px_2()
+                .py_1()
+                .child(Label::new(title).size(LabelSize::Small).color(Color::Muted))
+        };
+        let header = if self.is_unified {
+            h_flex().flex_1().child(title(
+                format!("{} → {}", self.old.title, self.new.title).into(),
+            ))
+        } else {
+            h_flex()
+                .flex_1()
+                .child(title(self.old.title.clone()))
+                .child(title(self.new.title.clone()))
+        };
+        let content = if self.is_unified {
+            div()
+                .flex_1()
+                .child(self.unified_editor.clone())
+                .into_any_element()
+        } else {
+            h_flex()
+                .flex_1()
+                .child(
+                    div()
+                        .flex_1()
+                        .h_full()
+                        .border_r_1()
+                        .border_color(cx.theme().colors().border)
+                        .child(self.old.editor.clone()),
+                )
+                .child(div().flex_1().h_full().child(self.new.editor.clone()))
+                .into_any_element()
+        };
+
+        v_flex()
+            .key_context("DiffView")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .on_action(cx.listener(Self::toggle_unified))
+            .on_action(cx.listener(Self::next_change))
+            .on_action(cx.listener(Self::previous_change))
+            .child(
+                h_flex()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(header)
+                    .child(
+                        div().px_2().child(
+                            Button::new(
+                                "toggle-unified",
+                                if self.is_unified {
+                                    "Side by Side"
+                                } else {
+                                    "Unified"
+                                },
+                            )
+                            .label_size(LabelSize::Small)
+                            .on_click(|_, cx| cx.dispatch_action(Box::new(ToggleUnified))),
+                        ),
+                    ),
+            )
+            .child(content)
+    }
+}
+
+impl Item for DiffView {
+    type Event = ItemEvent;
+
+    fn tab_tooltip_text(&self, _: &AppContext) -> Option<SharedString> {
+        Some(format!("{} ↔ {}", self.old.title, self.new.title).into())
+    }
+
+    fn tab_content(&self, _: Option<usize>, selected: bool, _: &WindowContext) -> AnyElement {
+        let file_name = |title: &SharedString| {
+            title
+                .rsplit(std::path::MAIN_SEPARATOR)
+                .next()
+                .unwrap_or_default()
+                .to_string()
+        };
+        Label::new(format!(
+            "{} ↔ {}",
+            file_name(&self.old.title),
+            file_name(&self.new.title)
+        ))
+        .color(if selected {
+            Color::Default
+        } else {
+            Color::Muted
+        })
+        .into_any_element()
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("diff view")
+    }
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{Context, TestAppContext};
+    use project::FakeFs;
+    use settings::SettingsStore;
+
+    #[gpui::test]
+    async fn test_diff_view_alignment(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let old = cx.new_model(|cx| {
+            Buffer::new(0, cx.entity_id().as_u64(), "one\ntwo\nthree\nfour\nfive\n")
+        });
+        let new = cx.new_model(|cx| {
+            Buffer::new(
+                0,
+                cx.entity_id().as_u64(),
+                "one\nTWO\nTWO AND A HALF\nthree\nfive\nsix\n",
+            )
+        });
+        let window = cx.add_window(|cx| {
+            DiffView::new(
+                ("old".into(), old),
+                ("new".into(), new.clone()),
+                project,
+                cx,
+            )
+        });
+        cx.run_until_parked();
+
+        let display_texts = |cx: &mut TestAppContext| {
+            window
+                .update(cx, |view, cx| {
+                    let mut text = |editor: &View<Editor>| {
+                        editor.update(cx, |editor, cx| editor.display_text(cx))
+                    };
+                    (
+                        text(&view.old.editor),
+                        text(&view.new.editor),
+                        text(&view.unified_editor),
+                    )
+                })
+                .unwrap()
+        };
+
+        // Both sides are padded so that unchanged rows line up, and the unified editor
+        // shows the deleted lines above the lines that replaced them.
+        let (old_text, new_text, unified_text) = display_texts(cx);
+        assert_eq!(old_text, "one\ntwo\n\nthree\nfour\nfive\n\n");
+        assert_eq!(new_text, "one\nTWO\nTWO AND A HALF\nthree\n\nfive\nsix\n");
+        assert_eq!(
+            unified_text,
+            "one\n\nTWO\nTWO AND A HALF\nthree\n\nfive\nsix\n"
+        );
+
+        // Editing either side recomputes the alignment.
+        new.update(cx, |buffer, cx| {
+            let offset = buffer.text().find("five").unwrap();
+            buffer.edit([(offset..offset, "four\n")], None, cx);
+        });
+        cx.run_until_parked();
+        let (old_text, new_text, unified_text) = display_texts(cx);
+        assert_eq!(old_text, "one\ntwo\n\nthree\nfour\nfive\n\n");
+        assert_eq!(
+            new_text,
+            "one\nTWO\nTWO AND A HALF\nthree\nfour\nfive\nsix\n"
+        );
+        assert_eq!(
+            unified_text,
+            "one\n\nTWO\nTWO AND A HALF\nthree\nfour\nfive\nsix\n"
+        );
+    }
+
+    #[test]
+    fn test_padding_rows() {
+        let hunks = line_hunks(
+            "one\ntwo\nthree\nfour\nfive\n",
+            "one\nTWO\nTWO AND A HALF\nthree\nfive\nsix\n",
+        );
+        // The replaced line gains a line on the new side, the deleted line is padded on
+        // the new side, and the appended line is padded on the old side.
+        assert_eq!(padding_rows(&hunks), (vec![(2, 1), (5, 1)], vec![(4, 1)]));
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings = SettingsStore::test(cx);
+            cx.set_global(settings);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            language::init(cx);
+            client::init_settings(cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+            editor::init(cx);
+        });
+    }
+}
@@ -76,6 +76,11 @@ pub struct FoldAt {
 pub struct UnfoldAt {
     pub buffer_row: u32,
 }
+
+#[derive(PartialEq, Clone, Deserialize, Default)]
+pub struct FoldAtLevel {
+    pub level: u32,
+}
 impl_actions!(
     editor,
     [
@@ -91,7 +96,8 @@ impl_actions!(
         ConfirmCodeAction,
         ToggleComments,
         FoldAt,
-        UnfoldAt
+        UnfoldAt,
+        FoldAtLevel
     ]
 );
 
@@ -132,12 +138,18 @@ gpui::actions!(
         ExpandMacroRecursively,
         FindAllReferences,
         Fold,
+        FoldAll,
+        FoldRecursive,
         FoldSelectedRanges,
         Format,
+        GoToDeclaration,
+        GoToDeclarationSplit,
         GoToDefinition,
         GoToDefinitionSplit,
         GoToDiagnostic,
         GoToHunk,
+        GoToImplementation,
+        GoToImplementationSplit,
         GoToPrevDiagnostic,
         GoToPrevHunk,
         GoToTypeDefinition,
@@ -170,22 +182,27 @@ gpui::actions!(
         NewlineAbove,
         NewlineBelow,
         NextScreen,
+        NextSignature,
         OpenExcerpts,
         Outdent,
         PageDown,
         PageUp,
         Paste,
+        PreviousSignature,
         Redo,
         RedoSelection,
         Rename,
         RestartLanguageServer,
         RevealInFinder,
         ReverseLines,
+        RevertHunk,
         ScrollCursorBottom,
         ScrollCursorCenter,
         ScrollCursorTop,
         SelectAll,
+        SelectClass,
         SelectDown,
+        SelectFunction,
         SelectLargerSyntaxNode,
         SelectLeft,
         SelectLine,
@@ -202,18 +219,26 @@ gpui::actions!(
         SelectUp,
         ShowCharacterPalette,
         ShowCompletions,
+        ShowSignatureHelp,
         ShuffleLines,
         SortLinesCaseInsensitive,
         SortLinesCaseSensitive,
         SplitSelectionIntoLines,
+        StageHunk,
         Tab,
         TabPrev,
+        ToggleBreakpoint,
+        ToggleGitBlame,
+        ToggleGitBlameInline,
+        ToggleHunkDiff,
         ToggleInlayHints,
         ToggleSoftWrap,
         Transpose,
         Undo,
         UndoSelection,
+        UnfoldAll,
         UnfoldLines,
+        UnstageHunk,
         DisplayCursorNames
     ]
 );
@@ -0,0 +1,244 @@
+use crate::{display_map::Inlay, Editor, InlayId, ToggleGitBlame, ToggleGitBlameInline};
+use chrono::{Local, NaiveDateTime, TimeZone};
+use gpui::{Model, Subscription, Task, ViewContext};
+use language::{Buffer, BufferSnapshot, Point};
+use project::repository::BlameEntry;
+use std::ops::Range;
+use ui::utils::{format_distance_from_now, DateTimeType};
+use util::{post_inc, truncate_and_trailoff, ResultExt};
+
+/// The width of the blame column in the gutter, in ems.
+pub(crate) const BLAME_GUTTER_WIDTH_IN_EMS: f32 = 24.;
+
+/// A range of lines in the buffer that were last changed by a commit.
+#[derive(Clone, Debug)]
+pub(crate) struct BlameHunk {
+    pub range: Range<text::Anchor>,
+    pub entry: BlameEntry,
+}
+
+/// The `git blame` of a singleton editor's buffer.
+///
+/// The blamed rows are stored as anchors so that they follow edits made after the blame was
+/// computed, and rows that were edited since then are treated as uncommitted rather than
+/// attributed to a commit that no longer matches their contents.
+pub(crate) struct GitBlame {
+    buffer: Model<Buffer>,
+    hunks: Vec<BlameHunk>,
+    version: clock::Global,
+    pub(crate) show_inline: bool,
+    pub(crate) show_in_gutter: bool,
+    inline_inlay: Option<InlayId>,
+    _refresh: Task<()>,
+    _subscription: Subscription,
+}
+
+impl GitBlame {
+    fn new(buffer: Model<Buffer>, cx: &mut ViewContext<Editor>) -> Self {
+        let subscription = cx.subscribe(&buffer, |editor, _, event, cx| match event {
+            language::Event::Saved
+            | language::Event::Reloaded
+            | language::Event::DiffBaseChanged => editor.refresh_git_blame(cx),
+            _ => {}
+        });
+        Self {
+            version: buffer.read(cx).version(),
+            buffer,
+            hunks: Vec::new(),
+            show_inline: false,
+            show_in_gutter: false,
+            inline_inlay: None,
+            _refresh: Task::ready(()),
+            _subscription: subscription,
+        }
+    }
+
+    /// Returns the blame for each of the given buffer rows.
+    pub(crate) fn hunks_for_rows<'a>(
+        &'a self,
+        rows: impl IntoIterator<Item = u32>,
+        snapshot: &BufferSnapshot,
+    ) -> Vec<Option<&'a BlameHunk>> {
+        let edited_rows = snapshot
+            .edits_since::<Point>(&self.version)
+            .map(|edit| {
+                // An edit ending at the start of a row, such as an inserted line, leaves that
+                // row's contents unchanged.
+                let end_row = if edit.new.end.column == 0 && edit.new.end.row > edit.new.start.row {
+                    edit.new.end.row
+                } else {
+                    edit.new.end.row + 1
+                };
+                edit.new.start.row..end_row
+            })
+            .collect::<Vec<_>>();
+        rows.into_iter()
+            .map(|row| {
+                if edited_rows.iter().any(|edited| edited.contains(&row)) {
+                    return None;
+                }
+                let ix = self.hunks.partition_point(|hunk| {
+                    snapshot.summary_for_anchor::<Point>(&hunk.range.start).row <= row
+                });
+                let hunk = self.hunks.get(ix.checked_sub(1)?)?;
+                (snapshot.summary_for_anchor::<Point>(&hunk.range.end).row > row).then_some(hunk)
+            })
+            .collect()
+    }
+}
+
+impl BlameHunk {
+    pub(crate) fn relative_time(&self) -> String {
+        NaiveDateTime::from_timestamp_opt(self.entry.author_time, 0)
+            .map(|time| {
+                format_distance_from_now(
+                    DateTimeType::Local(Local.from_utc_datetime(&time)),
+                    false,
+                    true,
+                    false,
+                )
+            })
+            .unwrap_or_default()
+    }
+
+    /// The author and date shown in the gutter.
+    pub(crate) fn gutter_text(&self) -> String {
+        let date = NaiveDateTime::from_timestamp_opt(self.entry.author_time, 0)
+            .map(|time| {
+                Local
+                    .from_utc_datetime(&time)
+                    .format("%Y-%m-%d")
+                    .to_string()
+            })
+            .unwrap_or_default();
+        format!(
+            "{:<16} {date}",
+            truncate_and_trailoff(&self.entry.author, 16)
+        )
+    }
+
+    fn inline_text(&self) -> String {
+        format!(
+            "    {}, {} • {}",
+            self.entry.author,
+            self.relative_time(),
+            self.entry.summary
+        )
+    }
+}
+
+impl Editor {
+    pub fn toggle_git_blame_inline(
+        &mut self,
+        _: &ToggleGitBlameInline,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.update_git_blame(cx, |blame| blame.show_inline = !blame.show_inline);
+    }
+
+    pub fn toggle_git_blame(&mut self, _: &ToggleGitBlame, cx: &mut ViewContext<Self>) {
+        self.update_git_blame(cx, |blame| blame.show_in_gutter = !blame.show_in_gutter);
+    }
+
+    fn update_git_blame(&mut self, cx: &mut ViewContext<Self>, update: impl FnOnce(&mut GitBlame)) {
+        if self.git_blame.is_none() {
+            let Some(buffer) = self.buffer.read(cx).as_singleton() else {
+                return;
+            };
+            self.git_blame = Some(GitBlame::new(buffer, cx));
+            self.refresh_git_blame(cx);
+        }
+
+        if let Some(blame) = self.git_blame.as_mut() {
+            update(blame);
+            if !blame.show_inline && !blame.show_in_gutter {
+                self.remove_inline_blame(cx);
+                self.git_blame = None;
+            } else {
+                self.refresh_inline_blame(cx);
+            }
+        }
+        cx.notify();
+    }
+
+    pub(crate) fn refresh_git_blame(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(project) = self.project.as_ref() else {
+            return;
+        };
+        let Some(blame) = self.git_blame.as_mut() else {
+            return;
+        };
+        let buffer = blame.buffer.clone();
+        let snapshot = buffer.read(cx).snapshot();
+        let entries = project.read(cx).blame_buffer(&buffer, cx);
+        blame._refresh = cx.spawn(|editor, mut cx| async move {
+            let Some(entries) = entries.await.log_err() else {
+                return;
+            };
+            let hunks = entries
+                .into_iter()
+                .map(|entry| BlameHunk {
+                    range: snapshot.anchor_before(Point::new(entry.rows.start, 0))
+                        ..snapshot.anchor_before(Point::new(entry.rows.end, 0)),
+                    entry,
+                })
+                .collect();
+            editor
+                .update(&mut cx, |editor, cx| {
+                    if let Some(blame) = editor.git_blame.as_mut() {
+                        blame.hunks = hunks;
+                        blame.version = snapshot.version().clone();
+                        editor.refresh_inline_blame(cx);
+                        cx.notify();
+                    }
+                })
+                .ok();
+        });
+    }
+
+    /// Shows the blame of the newest cursor's line at the end of that line.
+    pub(crate) fn refresh_inline_blame(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(blame) = self.git_blame.as_ref() else {
+            return;
+        };
+        if !blame.show_inline {
+            self.remove_inline_blame(cx);
+            return;
+        }
+
+        let row = self.selections.newest::<Point>(cx).head().row;
+        let buffer_snapshot = blame.buffer.read(cx).snapshot();
+        let text = blame
+            .hunks_for_rows([row], &buffer_snapshot)
+            .pop()
+            .flatten()
+            .map(BlameHunk::inline_text);
+
+        let to_remove = blame.inline_inlay.into_iter().collect::<Vec<_>>();
+        let mut to_insert = Vec::new();
+        let mut inline_inlay = None;
+        if let Some(text) = text {
+            let snapshot = self.buffer.read(cx).snapshot(cx);
+            let position = snapshot.anchor_after(Point::new(row, snapshot.line_len(row)));
+            let inlay = Inlay::blame(post_inc(&mut self.next_inlay_id), position, text);
+            inline_inlay = Some(inlay.id);
+            to_insert.push(inlay);
+        }
+        if let Some(blame) = self.git_blame.as_mut() {
+            blame.inline_inlay = inline_inlay;
+        }
+        if !to_remove.is_empty() || !to_insert.is_empty() {
+            self.splice_inlay_hints(to_remove, to_insert, cx);
+        }
+    }
+
+    fn remove_inline_blame(&mut self, cx: &mut ViewContext<Self>) {
+        if let Some(inlay_id) = self
+            .git_blame
+            .as_mut()
+            .and_then(|blame| blame.inline_inlay.take())
+        {
+            self.splice_inlay_hints(vec![inlay_id], Vec::new(), cx);
+        }
+    }
+}
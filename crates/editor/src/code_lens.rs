@@ -0,0 +1,322 @@
+use crate::{
+    display_map::{
+        BlockContext, BlockDisposition, BlockId, BlockProperties, BlockStyle, RenderBlock,
+    },
+    Editor, EditorMode, ToPoint as _,
+};
+use collections::{HashMap, HashSet};
+use futures::future;
+use gpui::{Model, Task};
+use language::{Bias, Buffer, Point, ToOffset, ToPoint};
+use project::CodeLens;
+use std::{mem, sync::Arc, time::Duration};
+use ui::prelude::*;
+use util::{ResultExt, TryFutureExt};
+
+const CODE_LENS_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(250);
+
+#[derive(Default)]
+pub(crate) struct CodeLensState {
+    /// The latest lenses of each buffer in the editor, by buffer id.
+    buffers: HashMap<u64, BufferCodeLenses>,
+    blocks: Vec<BlockId>,
+    refresh_task: Option<Task<Option<()>>>,
+    resolve_task: Option<Task<Option<()>>>,
+}
+
+struct BufferCodeLenses {
+    buffer: Model<Buffer>,
+    /// The version of the buffer that the lenses were requested for, or `None` when the
+    /// language server asked for them to be requested again.
+    version: Option<clock::Global>,
+    lenses: Vec<CodeLens>,
+}
+
+impl Editor {
+    /// Requests the code lenses of the editor's buffers that were edited since their lenses
+    /// were last requested, or of all the buffers when `refresh_all` is set, and shows them
+    /// above the lines they belong to.
+    pub(crate) fn refresh_code_lenses(&mut self, refresh_all: bool, cx: &mut ViewContext<Self>) {
+        if self.mode != EditorMode::Full {
+            return;
+        }
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+
+        let buffers = self.buffer.read(cx).all_buffers();
+        let buffer_ids = buffers
+            .iter()
+            .map(|buffer| buffer.read(cx).remote_id())
+            .collect::<HashSet<_>>();
+        let state = &mut self.code_lens_state;
+        state
+            .buffers
+            .retain(|buffer_id, _| buffer_ids.contains(buffer_id));
+        if refresh_all {
+            for buffer_lenses in state.buffers.values_mut() {
+                buffer_lenses.version = None;
+            }
+        }
+        let stale_buffers = buffers
+            .into_iter()
+            .filter(|buffer| {
+                let buffer = buffer.read(cx);
+                state
+                    .buffers
+                    .get(&buffer.remote_id())
+                    .and_then(|buffer_lenses| buffer_lenses.version.as_ref())
+                    .map_or(true, |version| buffer.version().changed_since(version))
+            })
+            .collect::<Vec<_>>();
+        if stale_buffers.is_empty() {
+            self.show_code_lenses(cx);
+            return;
+        }
+
+        self.code_lens_state.refresh_task = Some(cx.spawn(|editor, mut cx| {
+            async move {
+                cx.background_executor()
+                    .timer(CODE_LENS_DEBOUNCE_TIMEOUT)
+                    .await;
+                let requests = project.update(&mut cx, |project, cx| {
+                    stale_buffers
+                        .into_iter()
+                        .map(|buffer| {
+                            let version = buffer.read(cx).version();
+                            let request = project.code_lens(&buffer, cx);
+                            async move { (buffer, version, request.await) }
+                        })
+                        .collect::<Vec<_>>()
+                })?;
+                let responses = future::join_all(requests).await;
+
+                editor.update(&mut cx, |editor, cx| {
+                    for (buffer, version, lenses) in responses {
+                        if let Some(lenses) = lenses.log_err() {
+                            let buffer_id = buffer.read(cx).remote_id();
+                            editor.code_lens_state.buffers.insert(
+                                buffer_id,
+                                BufferCodeLenses {
+                                    buffer,
+                                    version: Some(version),
+                                    lenses,
+                                },
+                            );
+                        }
+                    }
+                    editor.show_code_lenses(cx);
+                    editor.resolve_visible_code_lenses(cx);
+                })
+            }
+            .log_err()
+        }));
+    }
+
+    /// Resolves the lenses on the visible lines that the language server sent without a
+    /// command, so that they can be shown.
+    pub(crate) fn resolve_visible_code_lenses(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        let Some(visible_line_count) = self.visible_line_count() else {
+            return;
+        };
+        if self.code_lens_state.buffers.is_empty() {
+            return;
+        }
+
+        let multi_buffer = self.buffer.read(cx);
+        let snapshot = multi_buffer.snapshot(cx);
+        let visible_start = self.scroll_manager.anchor().anchor.to_point(&snapshot);
+        let visible_end = snapshot.clip_point(
+            visible_start + Point::new(visible_line_count.ceil() as u32, 0),
+            Bias::Left,
+        );
+        let mut unresolved_lenses = Vec::new();
+        for (buffer, range, _) in
+            multi_buffer.range_to_buffer_ranges(visible_start..visible_end, cx)
+        {
+            let buffer = buffer.read(cx);
+            let Some(buffer_lenses) = self.code_lens_state.buffers.get(&buffer.remote_id()) else {
+                continue;
+            };
+            let start_ix = buffer_lenses
+                .lenses
+                .partition_point(|lens| lens.range.start.to_offset(buffer) < range.start);
+            for lens in &buffer_lenses.lenses[start_ix..] {
+                if lens.range.start.to_offset(buffer) > range.end {
+                    break;
+                }
+                if !lens.is_resolved() {
+                    unresolved_lenses.push((buffer_lenses.buffer.clone(), lens.clone()));
+                }
+            }
+        }
+        if unresolved_lenses.is_empty() {
+            return;
+        }
+
+        self.code_lens_state.resolve_task = Some(cx.spawn(|editor, mut cx| {
+            async move {
+                cx.background_executor()
+                    .timer(CODE_LENS_DEBOUNCE_TIMEOUT)
+                    .await;
+                let requests = project.update(&mut cx, |project, cx| {
+                    unresolved_lenses
+                        .into_iter()
+                        .map(|(buffer, lens)| {
+                            let buffer_id = buffer.read(cx).remote_id();
+                            let request = project.resolve_code_lens(buffer, lens, cx);
+                            async move { (buffer_id, request.await) }
+                        })
+                        .collect::<Vec<_>>()
+                })?;
+                let resolved_lenses = future::join_all(requests).await;
+
+                editor.update(&mut cx, |editor, cx| {
+                    for (buffer_id, resolved_lens) in resolved_lenses {
+                        let Some(resolved_lens) = resolved_lens.log_err() else {
+                            continue;
+                        };
+                        let Some(buffer_lenses) =
+                            editor.code_lens_state.buffers.get_mut(&buffer_id)
+                        else {
+                            continue;
+                        };
+                        if let Some(lens) = buffer_lenses.lenses.iter_mut().find(|lens| {
+                            !lens.is_resolved()
+                                && lens.lsp_lens.range == resolved_lens.lsp_lens.range
+                        }) {
+                            *lens = resolved_lens;
+                        }
+                    }
+                    editor.show_code_lenses(cx);
+                })
+            }
+            .log_err()
+        }));
+    }
+
+    /// Replaces the code lens blocks with one block per line that has resolved lenses.
+    fn show_code_lenses(&mut self, cx: &mut ViewContext<Self>) {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let mut blocks = Vec::new();
+        for (excerpt_id, buffer_snapshot, range) in snapshot.excerpts() {
+            let Some(buffer_lenses) = self
+                .code_lens_state
+                .buffers
+                .get(&buffer_snapshot.remote_id())
+            else {
+                continue;
+            };
+            let mut lenses_by_row = Vec::<(u32, Vec<CodeLens>)>::new();
+            for lens in &buffer_lenses.lenses {
+                let in_excerpt = lens
+                    .range
+                    .start
+                    .cmp(&range.context.start, buffer_snapshot)
+                    .is_ge()
+                    && lens
+                        .range
+                        .start
+                        .cmp(&range.context.end, buffer_snapshot)
+                        .is_lt();
+                if !in_excerpt || !lens.is_resolved() {
+                    continue;
+                }
+                let row = lens.range.start.to_point(buffer_snapshot).row;
+                match lenses_by_row.last_mut() {
+                    Some((last_row, row_lenses)) if *last_row == row => {
+                        row_lenses.push(lens.clone())
+                    }
+                    _ => lenses_by_row.push((row, vec![lens.clone()])),
+                }
+            }
+
+            for (row, lenses) in lenses_by_row {
+                let indent = buffer_snapshot.indent_size_for_line(row).len;
+                let position = buffer_snapshot.anchor_before(Point::new(row, indent));
+                blocks.push(BlockProperties {
+                    position: snapshot.anchor_in_excerpt(excerpt_id, position),
+                    height: 1,
+                    style: BlockStyle::Flex,
+                    disposition: BlockDisposition::Above,
+                    render: code_lens_block_renderer(buffer_lenses.buffer.clone(), lenses),
+                });
+            }
+        }
+
+        let old_blocks = mem::take(&mut self.code_lens_state.blocks);
+        if old_blocks.is_empty() && blocks.is_empty() {
+            return;
+        }
+        self.remove_blocks(old_blocks.into_iter().collect(), None, cx);
+        self.code_lens_state.blocks = self.insert_blocks(blocks, None, cx);
+        cx.notify();
+    }
+
+    /// Runs the command of a code lens and opens the edits it made, if any.
+    fn apply_code_lens(
+        &mut self,
+        buffer: Model<Buffer>,
+        lens: CodeLens,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        let Some(workspace) = self.workspace() else {
+            return;
+        };
+        let title = lens.title().unwrap_or_default().to_string();
+        let apply_code_lens =
+            project.update(cx, |project, cx| project.apply_code_lens(buffer, lens, cx));
+        let workspace = workspace.downgrade();
+        cx.spawn(|editor, cx| async move {
+            let project_transaction = apply_code_lens.await?;
+            Self::open_project_transaction(&editor, workspace, project_transaction, title, cx).await
+        })
+        .detach_and_log_err(cx);
+    }
+}
+
+fn code_lens_block_renderer(buffer: Model<Buffer>, lenses: Vec<CodeLens>) -> RenderBlock {
+    let lenses = Arc::new(lenses);
+    Arc::new(move |cx: &mut BlockContext| {
+        let editor = cx.view.clone();
+        let mut children = Vec::new();
+        for (ix, lens) in lenses.iter().enumerate() {
+            if ix > 0 {
+                children.push(
+                    Label::new("|")
+                        .size(LabelSize::XSmall)
+                        .color(Color::Disabled)
+                        .into_any_element(),
+                );
+            }
+            let editor = editor.clone();
+            let buffer = buffer.clone();
+            let lens = lens.clone();
+            children.push(
+                Button::new(ix, lens.title().unwrap_or_default().to_string())
+                    .label_size(LabelSize::XSmall)
+                    .color(Color::Muted)
+                    .on_click(move |_, cx| {
+                        editor.update(cx, |editor, cx| {
+                            editor.apply_code_lens(buffer.clone(), lens.clone(), cx)
+                        })
+                    })
+                    .into_any_element(),
+            );
+        }
+
+        h_flex()
+            .id(cx.block_id)
+            .h(cx.line_height)
+            .pl(cx.anchor_x)
+            .gap_1()
+            .children(children)
+            .into_any_element()
+    })
+}
@@ -66,6 +66,9 @@ pub trait ToDisplayPoint {
 
 type TextHighlights = TreeMap<Option<TypeId>, Arc<(HighlightStyle, Vec<Range<Anchor>>)>>;
 type InlayHighlights = BTreeMap<TypeId, HashMap<InlayId, (HighlightStyle, InlayHighlight)>>;
+/// Styles of ranges that are layered under the text highlights, ordered by position and
+/// without overlaps.
+type SemanticHighlights = Arc<[(Range<Anchor>, HighlightStyle)]>;
 
 pub struct DisplayMap {
     buffer: Model<MultiBuffer>,
@@ -77,6 +80,7 @@ pub struct DisplayMap {
     block_map: BlockMap,
     text_highlights: TextHighlights,
     inlay_highlights: InlayHighlights,
+    semantic_highlights: SemanticHighlights,
     pub clip_at_line_ends: bool,
 }
 
@@ -109,6 +113,7 @@ impl DisplayMap {
             block_map,
             text_highlights: Default::default(),
             inlay_highlights: Default::default(),
+            semantic_highlights: Arc::new([]),
             clip_at_line_ends: false,
         }
     }
@@ -134,6 +139,7 @@ impl DisplayMap {
             block_snapshot,
             text_highlights: self.text_highlights.clone(),
             inlay_highlights: self.inlay_highlights.clone(),
+            semantic_highlights: self.semantic_highlights.clone(),
             clip_at_line_ends: self.clip_at_line_ends,
         }
     }
@@ -254,6 +260,12 @@ impl DisplayMap {
         }
     }
 
+    /// Replaces the styles that are layered over the syntax highlighting, such as those of
+    /// semantic tokens. The ranges must be sorted and must not overlap.
+    pub fn set_semantic_highlights(&mut self, highlights: Vec<(Range<Anchor>, HighlightStyle)>) {
+        self.semantic_highlights = highlights.into();
+    }
+
     pub fn text_highlights(&self, type_id: TypeId) -> Option<(HighlightStyle, &[Range<Anchor>])> {
         let highlights = self.text_highlights.get(&Some(type_id))?;
         Some((highlights.0, &highlights.1))
@@ -329,6 +341,7 @@ impl DisplayMap {
 pub(crate) struct Highlights<'a> {
     pub text_highlights: Option<&'a TextHighlights>,
     pub inlay_highlights: Option<&'a InlayHighlights>,
+    pub semantic_highlights: Option<&'a [(Range<Anchor>, HighlightStyle)]>,
     pub inlay_highlight_style: Option<HighlightStyle>,
     pub suggestion_highlight_style: Option<HighlightStyle>,
 }
@@ -348,11 +361,11 @@ pub struct DisplaySnapshot {
     block_snapshot: block_map::BlockSnapshot,
     text_highlights: TextHighlights,
     inlay_highlights: InlayHighlights,
+    semantic_highlights: SemanticHighlights,
     clip_at_line_ends: bool,
 }
 
 impl DisplaySnapshot {
-    #[cfg(test)]
     pub fn fold_count(&self) -> usize {
         self.fold_snapshot.fold_count()
     }
@@ -515,6 +528,7 @@ impl DisplaySnapshot {
             Highlights {
                 text_highlights: Some(&self.text_highlights),
                 inlay_highlights: Some(&self.inlay_highlights),
+                semantic_highlights: Some(&self.semantic_highlights),
                 inlay_highlight_style,
                 suggestion_highlight_style,
             },
@@ -845,6 +859,11 @@ impl DisplaySnapshot {
     }
 
     pub fn is_foldable(self: &Self, buffer_row: u32) -> bool {
+        self.buffer_snapshot.syntax_fold_range(buffer_row).is_some()
+            || self.is_foldable_by_indent(buffer_row)
+    }
+
+    fn is_foldable_by_indent(self: &Self, buffer_row: u32) -> bool {
         let max_row = self.buffer_snapshot.max_buffer_row();
         if buffer_row >= max_row {
             return false;
@@ -867,9 +886,18 @@ impl DisplaySnapshot {
         false
     }
 
+    /// Returns the range to fold for the given row, preferring the range provided by
+    /// the language's `folds` query and falling back to the row's indentation.
     pub fn foldable_range(self: &Self, buffer_row: u32) -> Option<Range<Point>> {
+        if self.is_line_folded(buffer_row) {
+            return None;
+        }
+        if let Some(range) = self.buffer_snapshot.syntax_fold_range(buffer_row) {
+            return Some(range);
+        }
+
         let start = Point::new(buffer_row, self.buffer_snapshot.line_len(buffer_row));
-        if self.is_foldable(start.row) && !self.is_line_folded(start.row) {
+        if self.is_foldable_by_indent(start.row) {
             let (start_indent, _) = self.line_indent_for_buffer_row(buffer_row);
             let max_point = self.buffer_snapshot.max_point();
             let mut end = None;
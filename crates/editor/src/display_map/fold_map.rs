@@ -488,9 +488,8 @@ impl FoldSnapshot {
             .collect()
     }
 
-    #[cfg(test)]
     pub fn fold_count(&self) -> usize {
-        self.folds.items(&self.inlay_snapshot.buffer).len()
+        self.folds.summary().count
     }
 
     pub fn text_summary_for_range(&self, range: Range<FoldPoint>) -> TextSummary {
@@ -64,6 +64,14 @@ impl Inlay {
             text: text.into(),
         }
     }
+
+    pub fn blame<T: Into<Rope>>(id: usize, position: Anchor, text: T) -> Self {
+        Self {
+            id: InlayId::Blame(id),
+            position,
+            text: text.into(),
+        }
+    }
 }
 
 impl sum_tree::Item for Transform {
@@ -216,6 +224,7 @@ pub struct InlayChunks<'a> {
     suggestion_highlight_style: Option<HighlightStyle>,
     highlight_endpoints: Peekable<vec::IntoIter<HighlightEndpoint>>,
     active_highlights: BTreeMap<Option<TypeId>, HighlightStyle>,
+    semantic_highlights: Peekable<vec::IntoIter<(Range<InlayOffset>, HighlightStyle)>>,
     highlights: Highlights<'a>,
     snapshot: &'a InlaySnapshot,
 }
@@ -259,6 +268,21 @@ impl<'a> Iterator for InlayChunks<'a> {
             }
         }
 
+        let mut semantic_style = None;
+        while let Some((range, style)) = self.semantic_highlights.peek().cloned() {
+            if range.end <= self.output_offset {
+                self.semantic_highlights.next();
+            } else {
+                if range.start <= self.output_offset {
+                    semantic_style = Some(style);
+                    next_highlight_endpoint = cmp::min(next_highlight_endpoint, range.end);
+                } else {
+                    next_highlight_endpoint = cmp::min(next_highlight_endpoint, range.start);
+                }
+                break;
+            }
+        }
+
         let chunk = match self.transforms.item()? {
             Transform::Isomorphic(_) => {
                 let chunk = self
@@ -282,8 +306,8 @@ impl<'a> Iterator for InlayChunks<'a> {
                     text: prefix,
                     ..chunk.clone()
                 };
-                if !self.active_highlights.is_empty() {
-                    let mut highlight_style = HighlightStyle::default();
+                if semantic_style.is_some() || !self.active_highlights.is_empty() {
+                    let mut highlight_style = semantic_style.unwrap_or_default();
                     for active_highlight in self.active_highlights.values() {
                         highlight_style.highlight(*active_highlight);
                     }
@@ -304,7 +328,7 @@ impl<'a> Iterator for InlayChunks<'a> {
                 }
 
                 let mut highlight_style = match inlay.id {
-                    InlayId::Suggestion(_) => self.suggestion_highlight_style,
+                    InlayId::Suggestion(_) | InlayId::Blame(_) => self.suggestion_highlight_style,
                     InlayId::Hint(_) => self.inlay_highlight_style,
                 };
                 let next_inlay_highlight_endpoint;
@@ -1038,6 +1062,10 @@ impl InlaySnapshot {
             }
         }
         highlight_endpoints.sort();
+        let mut semantic_highlights = Vec::new();
+        if let Some(highlights) = highlights.semantic_highlights {
+            self.apply_semantic_highlights(&range, highlights, &mut semantic_highlights);
+        }
         let buffer_range = self.to_buffer_offset(range.start)..self.to_buffer_offset(range.end);
         let buffer_chunks = self.buffer.chunks(buffer_range, language_aware);
 
@@ -1053,6 +1081,7 @@ impl InlaySnapshot {
             suggestion_highlight_style: highlights.suggestion_highlight_style,
             highlight_endpoints: highlight_endpoints.into_iter().peekable(),
             active_highlights: Default::default(),
+            semantic_highlights: semantic_highlights.into_iter().peekable(),
             highlights,
             snapshot: self,
         }
@@ -1116,6 +1145,29 @@ impl InlaySnapshot {
         }
     }
 
+    fn apply_semantic_highlights(
+        &self,
+        range: &Range<InlayOffset>,
+        highlights: &[(Range<Anchor>, HighlightStyle)],
+        semantic_highlights: &mut Vec<(Range<InlayOffset>, HighlightStyle)>,
+    ) {
+        let start = self.buffer.anchor_after(self.to_buffer_offset(range.start));
+        let end = self.buffer.anchor_before(self.to_buffer_offset(range.end));
+        let start_ix =
+            highlights.partition_point(|(probe, _)| probe.end.cmp(&start, &self.buffer).is_le());
+        for (highlight_range, style) in &highlights[start_ix..] {
+            if highlight_range.start.cmp(&end, &self.buffer).is_ge() {
+                break;
+            }
+            let highlight_start = highlight_range.start.to_offset(&self.buffer);
+            let highlight_end = highlight_range.end.to_offset(&self.buffer);
+            semantic_highlights.push((
+                self.to_inlay_offset(highlight_start)..self.to_inlay_offset(highlight_end),
+                *style,
+            ));
+        }
+    }
+
     #[cfg(test)]
     pub fn text(&self) -> String {
         self.chunks(Default::default()..self.len(), false, Highlights::default())
@@ -1584,6 +1636,65 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    fn test_semantic_highlights(cx: &mut AppContext) {
+        let buffer = MultiBuffer::build_simple("let abc = def;", cx);
+        let buffer_snapshot = buffer.read(cx).snapshot(cx);
+        let (mut inlay_map, _) = InlayMap::new(buffer_snapshot.clone());
+        let (inlay_snapshot, _) = inlay_map.splice(
+            Vec::new(),
+            vec![Inlay {
+                id: InlayId::Hint(0),
+                position: buffer_snapshot.anchor_after(7),
+                text: ": i32".into(),
+            }],
+        );
+        assert_eq!(inlay_snapshot.text(), "let abc: i32 = def;");
+
+        let variable_style = HighlightStyle {
+            color: Some(gpui::red()),
+            ..Default::default()
+        };
+        let function_style = HighlightStyle {
+            color: Some(gpui::blue()),
+            ..Default::default()
+        };
+        let semantic_highlights = [
+            (
+                buffer_snapshot.anchor_after(4)..buffer_snapshot.anchor_before(7),
+                variable_style,
+            ),
+            (
+                buffer_snapshot.anchor_after(10)..buffer_snapshot.anchor_before(13),
+                function_style,
+            ),
+        ];
+        let mut chunks = Vec::<(String, Option<HighlightStyle>)>::new();
+        for chunk in inlay_snapshot.chunks(
+            InlayOffset(0)..inlay_snapshot.len(),
+            false,
+            Highlights {
+                semantic_highlights: Some(&semantic_highlights),
+                ..Default::default()
+            },
+        ) {
+            match chunks.last_mut() {
+                Some((text, style)) if *style == chunk.highlight_style => text.push_str(chunk.text),
+                _ => chunks.push((chunk.text.to_string(), chunk.highlight_style)),
+            }
+        }
+        assert_eq!(
+            chunks,
+            [
+                ("let ".to_string(), None),
+                ("abc".to_string(), Some(variable_style)),
+                (": i32 = ".to_string(), None),
+                ("def".to_string(), Some(function_style)),
+                (";".to_string(), None),
+            ]
+        );
+    }
+
     #[gpui::test(iterations = 100)]
     fn test_random_inlays(cx: &mut AppContext, mut rng: StdRng) {
         init_test(cx);
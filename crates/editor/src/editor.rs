@@ -13,7 +13,9 @@
 //!
 //! If you're looking to improve Vim mode, you should check out Vim crate that wraps Editor and overrides it's behaviour.
 pub mod actions;
+mod blame;
 mod blink_manager;
+mod code_lens;
 pub mod display_map;
 mod editor_settings;
 mod element;
@@ -24,12 +26,16 @@ mod highlight_matching_bracket;
 mod hover_popover;
 pub mod items;
 mod link_go_to_definition;
+mod linked_editing;
+mod merge_conflicts;
 mod mouse_context_menu;
 pub mod movement;
 mod persistence;
 mod rust_analyzer_ext;
 pub mod scroll;
 mod selections_collection;
+mod semantic_tokens;
+mod signature_help;
 
 #[cfg(test)]
 mod editor_tests;
@@ -39,9 +45,11 @@ use ::git::diff::DiffHunk;
 pub(crate) use actions::*;
 use aho_corasick::AhoCorasick;
 use anyhow::{anyhow, Context as _, Result};
+use blame::{GitBlame, BLAME_GUTTER_WIDTH_IN_EMS};
 use blink_manager::BlinkManager;
 use client::{Collaborator, ParticipantIndex};
 use clock::ReplicaId;
+use code_lens::CodeLensState;
 use collections::{BTreeMap, Bound, HashMap, HashSet, VecDeque};
 use convert_case::{Case, Casing};
 use copilot::Copilot;
@@ -52,7 +60,8 @@ use element::LineWithInvisibles;
 pub use element::{Cursor, EditorElement, HighlightedRange, HighlightedRangeLine};
 use futures::FutureExt;
 use fuzzy::{StringMatch, StringMatchCandidate};
-use git::diff_hunk_to_display;
+pub use git::deleted_lines_block;
+use git::{diff_hunk_to_display, ExpandedHunk};
 use gpui::{
     div, impl_actions, point, prelude::*, px, relative, rems, size, uniform_list, Action,
     AnyElement, AppContext, AsyncWindowContext, BackgroundExecutor, Bounds, ClipboardItem, Context,
@@ -72,11 +81,14 @@ use language::{
     language_settings::{self, all_language_settings, InlayHintSettings},
     markdown, point_from_lsp, AutoindentMode, BracketPair, Buffer, Capability, CodeAction,
     CodeLabel, Completion, CursorShape, Diagnostic, Documentation, IndentKind, IndentSize,
-    Language, LanguageServerName, OffsetRangeExt, Point, Selection, SelectionGoal, TransactionId,
+    Language, LanguageServerName, MergeConflict, OffsetRangeExt, Point, Runnable, Selection,
+    SelectionGoal, TextObject, TransactionId, Unclipped,
 };
 
 use link_go_to_definition::{GoToDefinitionLink, InlayHighlight, LinkGoToDefinitionState};
+use linked_editing::LinkedEditingState;
 use lsp::{DiagnosticSeverity, LanguageServerId};
+pub use merge_conflicts::ConflictResolution;
 use mouse_context_menu::MouseContextMenu;
 use movement::TextLayoutDetails;
 use multi_buffer::ToOffsetUtf16;
@@ -86,13 +98,19 @@ pub use multi_buffer::{
 };
 use ordered_float::OrderedFloat;
 use parking_lot::RwLock;
-use project::{FormatTrigger, Location, Project, ProjectPath, ProjectTransaction};
+use persistence::DB;
+use project::{
+    tasks::{TaskContext, TaskDefinition},
+    FormatTrigger, LanguageServerShowDocument, Location, Project, ProjectPath, ProjectTransaction,
+};
 use rand::prelude::*;
 use rpc::proto::*;
 use scroll::{Autoscroll, OngoingScroll, ScrollAnchor, ScrollManager, ScrollbarAutoHide};
 use selections_collection::{resolve_multiple, MutableSelectionsCollection, SelectionsCollection};
+use semantic_tokens::SemanticTokensState;
 use serde::{Deserialize, Serialize};
 use settings::{Settings, SettingsStore};
+use signature_help::{hide_signature_help, trigger_signature_help_on_input, SignatureHelpState};
 use smallvec::SmallVec;
 use snippet::Snippet;
 use std::{
@@ -119,7 +137,10 @@ use ui::{
     Tooltip,
 };
 use util::{post_inc, RangeExt, ResultExt, TryFutureExt};
-use workspace::{searchable::SearchEvent, ItemNavHistory, Pane, SplitDirection, ViewId, Workspace};
+use workspace::{
+    searchable::SearchEvent, ItemId, ItemNavHistory, Pane, SpawnTaskInTerminal, SplitDirection,
+    ViewId, Workspace, WorkspaceId,
+};
 
 const CURSOR_BLINK_INTERVAL: Duration = Duration::from_millis(500);
 const MAX_LINE_LEN: usize = 1024;
@@ -131,6 +152,8 @@ pub(crate) const CURSORS_VISIBLE_FOR: Duration = Duration::from_millis(2000);
 pub const CODE_ACTIONS_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(250);
 #[doc(hidden)]
 pub const DOCUMENT_HIGHLIGHTS_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(75);
+const SERIALIZE_FOLDS_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(500);
+pub(crate) const SELECTION_RANGES_TIMEOUT: Duration = Duration::from_millis(500);
 
 pub(crate) const FORMAT_TIMEOUT: Duration = Duration::from_secs(2);
 
@@ -201,6 +224,7 @@ pub fn render_parsed_markdown(
 pub(crate) enum InlayId {
     Suggestion(usize),
     Hint(usize),
+    Blame(usize),
 }
 
 impl InlayId {
@@ -208,6 +232,7 @@ impl InlayId {
         match self {
             Self::Suggestion(id) => *id,
             Self::Hint(id) => *id,
+            Self::Blame(id) => *id,
         }
     }
 }
@@ -233,9 +258,15 @@ pub fn init(cx: &mut AppContext) {
     workspace::register_followable_item::<Editor>(cx);
     workspace::register_deserializable_item::<Editor>(cx);
     cx.observe_new_views(
-        |workspace: &mut Workspace, _cx: &mut ViewContext<Workspace>| {
+        |workspace: &mut Workspace, cx: &mut ViewContext<Workspace>| {
             workspace.register_action(Editor::new_file);
             workspace.register_action(Editor::new_file_in_direction);
+            cx.subscribe(&workspace.project().clone(), |workspace, _, event, cx| {
+                if let project::Event::LanguageServerShowDocument(request) = event {
+                    Editor::show_document(workspace, request.clone(), cx);
+                }
+            })
+            .detach();
         },
     )
     .detach();
@@ -346,6 +377,7 @@ type CompletionId = usize;
 
 type BackgroundHighlight = (fn(&ThemeColors) -> Hsla, Vec<Range<Anchor>>);
 type InlayBackgroundHighlight = (fn(&ThemeColors) -> Hsla, Vec<InlayHighlight>);
+type RowHighlight = (fn(&StatusColors) -> Hsla, Vec<Range<Anchor>>);
 
 pub struct Editor {
     handle: WeakView<Self>,
@@ -362,6 +394,9 @@ pub struct Editor {
     autoclose_regions: Vec<AutocloseRegion>,
     snippet_stack: InvalidationStack<SnippetState>,
     select_larger_syntax_node_stack: Vec<Box<[Selection<usize>]>>,
+    selection_ranges_task: Option<Task<()>>,
+    /// How many times to expand the selections once `selection_ranges_task` resolves.
+    pending_selection_expansions: usize,
     ime_transaction: Option<TransactionId>,
     active_diagnostics: Option<ActiveDiagnosticGroup>,
     soft_wrap_mode_override: Option<language_settings::SoftWrap>,
@@ -379,6 +414,7 @@ pub struct Editor {
     highlighted_rows: Option<Range<u32>>,
     background_highlights: BTreeMap<TypeId, BackgroundHighlight>,
     inlay_background_highlights: TreeMap<Option<TypeId>, InlayBackgroundHighlight>,
+    row_highlights: BTreeMap<TypeId, RowHighlight>,
     nav_history: Option<ItemNavHistory>,
     context_menu: RwLock<Option<ContextMenu>>,
     mouse_context_menu: Option<MouseContextMenu>,
@@ -386,6 +422,7 @@ pub struct Editor {
     next_completion_id: CompletionId,
     available_code_actions: Option<(Model<Buffer>, Arc<[CodeAction]>)>,
     code_actions_task: Option<Task<()>>,
+    serialize_folds_task: Option<Task<()>>,
     document_highlights_task: Option<Task<()>>,
     pending_rename: Option<RenameState>,
     searchable: bool,
@@ -399,11 +436,19 @@ pub struct Editor {
     leader_peer_id: Option<PeerId>,
     remote_id: Option<ViewId>,
     hover_state: HoverState,
+    signature_help_state: SignatureHelpState,
     gutter_hovered: bool,
     link_go_to_definition_state: LinkGoToDefinitionState,
     copilot_state: CopilotState,
     inlay_hint_cache: InlayHintCache,
     next_inlay_id: usize,
+    semantic_tokens_state: SemanticTokensState,
+    code_lens_state: CodeLensState,
+    linked_editing_state: LinkedEditingState,
+    git_blame: Option<GitBlame>,
+    expanded_hunks: Vec<ExpandedHunk>,
+    merge_conflicts: Vec<MergeConflict<Anchor>>,
+    merge_conflict_blocks: Vec<BlockId>,
     _subscriptions: Vec<Subscription>,
     pixel_position_of_newest_cursor: Option<gpui::Point<Pixels>>,
     gutter_width: Pixels,
@@ -416,6 +461,7 @@ pub struct Editor {
 pub struct EditorSnapshot {
     pub mode: EditorMode,
     show_gutter: bool,
+    show_git_blame: bool,
     pub display_snapshot: DisplaySnapshot,
     pub placeholder_text: Option<Arc<str>>,
     is_focused: bool,
@@ -1245,7 +1291,7 @@ struct ActiveDiagnosticGroup {
     is_valid: bool,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ClipboardSelection {
     pub len: usize,
     pub is_entire_line: bool,
@@ -1260,9 +1306,24 @@ pub(crate) struct NavigationData {
     scroll_top_row: u32,
 }
 
+#[derive(Clone, Copy)]
 enum GotoDefinitionKind {
     Symbol,
     Type,
+    Implementation,
+    Declaration,
+}
+
+impl GotoDefinitionKind {
+    /// The title of the tab listing several definitions of this kind.
+    fn title(&self) -> &'static str {
+        match self {
+            GotoDefinitionKind::Symbol => "Definitions",
+            GotoDefinitionKind::Type => "Type Definitions",
+            GotoDefinitionKind::Implementation => "Implementations",
+            GotoDefinitionKind::Declaration => "Declarations",
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -1365,8 +1426,20 @@ impl Editor {
                     }));
                 }
                 project_subscriptions.push(cx.subscribe(project, |editor, _, event, cx| {
-                    if let project::Event::RefreshInlayHints = event {
-                        editor.refresh_inlay_hints(InlayHintRefreshReason::RefreshRequested, cx);
+                    match event {
+                        project::Event::RefreshInlayHints => {
+                            editor
+                                .refresh_inlay_hints(InlayHintRefreshReason::RefreshRequested, cx);
+                        }
+                        project::Event::RefreshSemanticTokens => {
+                            editor.refresh_semantic_tokens(true, cx);
+                        }
+                        project::Event::RefreshCodeLens => {
+                            editor.refresh_code_lenses(true, cx);
+                        }
+                        project::Event::BreakpointsChanged(_)
+                        | project::Event::DebugPositionChanged => cx.notify(),
+                        _ => {}
                     };
                 }));
             }
@@ -1397,6 +1470,8 @@ impl Editor {
             autoclose_regions: Default::default(),
             snippet_stack: Default::default(),
             select_larger_syntax_node_stack: Vec::new(),
+            selection_ranges_task: None,
+            pending_selection_expansions: 0,
             ime_transaction: Default::default(),
             active_diagnostics: None,
             soft_wrap_mode_override,
@@ -1412,14 +1487,20 @@ impl Editor {
             highlighted_rows: None,
             background_highlights: Default::default(),
             inlay_background_highlights: Default::default(),
+            row_highlights: Default::default(),
             nav_history: None,
             context_menu: RwLock::new(None),
             mouse_context_menu: None,
             completion_tasks: Default::default(),
             next_completion_id: 0,
             next_inlay_id: 0,
+            git_blame: None,
+            expanded_hunks: Vec::new(),
+            merge_conflicts: Vec::new(),
+            merge_conflict_blocks: Vec::new(),
             available_code_actions: Default::default(),
             code_actions_task: Default::default(),
+            serialize_folds_task: Default::default(),
             document_highlights_task: Default::default(),
             pending_rename: Default::default(),
             searchable: true,
@@ -1434,9 +1515,13 @@ impl Editor {
             leader_peer_id: None,
             remote_id: None,
             hover_state: Default::default(),
+            signature_help_state: Default::default(),
             link_go_to_definition_state: Default::default(),
             copilot_state: Default::default(),
             inlay_hint_cache: InlayHintCache::new(inlay_hint_settings),
+            semantic_tokens_state: Default::default(),
+            code_lens_state: Default::default(),
+            linked_editing_state: Default::default(),
             gutter_hovered: false,
             pixel_position_of_newest_cursor: None,
             gutter_width: Default::default(),
@@ -1470,6 +1555,9 @@ impl Editor {
 
         this.end_selection(cx);
         this.scroll_manager.show_scrollbar(cx);
+        this.refresh_merge_conflicts(cx);
+        this.refresh_semantic_tokens(false, cx);
+        this.refresh_code_lenses(false, cx);
 
         if mode == EditorMode::Full {
             let should_auto_hide_scrollbars = cx.should_auto_hide_scrollbars();
@@ -1505,6 +1593,9 @@ impl Editor {
                 None => {}
             }
         }
+        if self.signature_help_state.visible() {
+            key_context.add("showing_signature_help");
+        }
 
         for layer in self.keymap_context_layers.values() {
             key_context.extend(layer);
@@ -1561,6 +1652,40 @@ impl Editor {
         }
     }
 
+    /// Opens a file that a language server asked to show, selecting the requested range, and
+    /// tells the server whether the file could be opened.
+    fn show_document(
+        workspace: &mut Workspace,
+        request: LanguageServerShowDocument,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let previous_focus = cx.focused().filter(|_| !request.take_focus);
+        let open_task = workspace.open_abs_path(request.abs_path.clone(), true, cx);
+        cx.spawn(|_, mut cx| async move {
+            let item = open_task.await.log_err();
+            let success = item.is_some();
+            let editor = item.and_then(|item| item.downcast::<Editor>());
+            if let Some((selection, editor)) = request.selection.clone().zip(editor) {
+                editor
+                    .update(&mut cx, |editor, cx| {
+                        let snapshot = editor.buffer.read(cx).snapshot(cx);
+                        let start =
+                            snapshot.clip_point_utf16(Unclipped(selection.start), Bias::Left);
+                        let end = snapshot.clip_point_utf16(Unclipped(selection.end), Bias::Left);
+                        editor.change_selections(Some(Autoscroll::center()), cx, |s| {
+                            s.select_ranges([start..end])
+                        });
+                    })
+                    .ok();
+            }
+            if let Some(previous_focus) = previous_focus {
+                cx.update(|cx| cx.focus(&previous_focus)).ok();
+            }
+            request.respond(success).await;
+        })
+        .detach();
+    }
+
     pub fn replica_id(&self, cx: &AppContext) -> ReplicaId {
         self.buffer.read(cx).replica_id()
     }
@@ -1589,6 +1714,10 @@ impl Editor {
         EditorSnapshot {
             mode: self.mode,
             show_gutter: self.show_gutter,
+            show_git_blame: self
+                .git_blame
+                .as_ref()
+                .map_or(false, |blame| blame.show_in_gutter),
             display_snapshot: self.display_map.update(cx, |map, cx| map.snapshot(cx)),
             scroll_anchor: self.scroll_manager.anchor(),
             ongoing_scroll: self.scroll_manager.ongoing_scroll(),
@@ -1749,6 +1878,7 @@ impl Editor {
         self.snippet_stack
             .invalidate(&self.selections.disjoint_anchors(), buffer);
         self.take_rename(false, cx);
+        signature_help::selections_changed(self, cx);
 
         let new_cursor_position = self.selections.newest_anchor().head();
 
@@ -1821,10 +1951,13 @@ impl Editor {
             }
             self.refresh_code_actions(cx);
             self.refresh_document_highlights(cx);
+            self.refresh_linked_editing_ranges(cx);
             refresh_matching_bracket_highlights(self, cx);
             self.discard_copilot_suggestion(cx);
         }
 
+        self.refresh_inline_blame(cx);
+
         self.blink_manager.update(cx, BlinkManager::pause_blinking);
         cx.emit(EditorEvent::SelectionsChanged { local });
 
@@ -2230,6 +2363,10 @@ impl Editor {
             return;
         }
 
+        if hide_signature_help(self, cx) {
+            return;
+        }
+
         if self.hide_context_menu(cx).is_some() {
             return;
         }
@@ -2383,6 +2520,8 @@ impl Editor {
             edits.push((selection.start..selection.end, text.clone()));
         }
 
+        let linked_edits = self.linked_edits(&edits, &snapshot);
+        edits.extend(linked_edits);
         drop(snapshot);
         self.transact(cx, |this, cx| {
             this.buffer.update(cx, |buffer, cx| {
@@ -2450,6 +2589,7 @@ impl Editor {
                 this.trigger_completion_on_input(&text, cx);
                 this.refresh_copilot_suggestions(true, cx);
             }
+            trigger_signature_help_on_input(this, &text, cx);
         });
     }
 
@@ -2724,6 +2864,12 @@ impl Editor {
         let text: Arc<str> = text.into();
         self.transact(cx, |this, cx| {
             let old_selections = this.selections.all_adjusted(cx);
+            let mut edits = old_selections
+                .iter()
+                .map(|s| (s.start..s.end, text.clone()))
+                .collect::<Vec<_>>();
+            let linked_edits = this.linked_edits(&edits, &this.buffer.read(cx).snapshot(cx));
+            edits.extend(linked_edits);
             let selection_anchors = this.buffer.update(cx, |buffer, cx| {
                 let anchors = {
                     let snapshot = buffer.read(cx);
@@ -2735,13 +2881,7 @@ impl Editor {
                         })
                         .collect::<Vec<_>>()
                 };
-                buffer.edit(
-                    old_selections
-                        .iter()
-                        .map(|s| (s.start..s.end, text.clone())),
-                    autoindent_mode,
-                    cx,
-                );
+                buffer.edit(edits, autoindent_mode, cx);
                 anchors
             });
 
@@ -2876,6 +3016,20 @@ impl Editor {
         );
     }
 
+    pub fn toggle_breakpoint(&mut self, _: &ToggleBreakpoint, cx: &mut ViewContext<Self>) {
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        let head = self.selections.newest_anchor().head();
+        let Some((buffer, position)) = self.buffer.read(cx).text_anchor_for_position(head, cx)
+        else {
+            return;
+        };
+        project.update(cx, |project, cx| {
+            project.toggle_breakpoint(&buffer, position, cx);
+        });
+    }
+
     pub fn inlay_hints_enabled(&self) -> bool {
         self.inlay_hint_cache.enabled
     }
@@ -2960,6 +3114,7 @@ impl Editor {
             .current_inlays()
             .filter(move |inlay| {
                 Some(inlay.id) != self.copilot_state.suggestion.as_ref().map(|h| h.id)
+                    && !matches!(inlay.id, InlayId::Blame(_))
             })
             .cloned()
             .collect()
@@ -3923,6 +4078,21 @@ impl Editor {
         }
     }
 
+    pub fn render_runnable_indicator(
+        &self,
+        row: u32,
+        buffer: Model<Buffer>,
+        runnable: Runnable,
+        cx: &mut ViewContext<Self>,
+    ) -> IconButton {
+        IconButton::new(("runnable_indicator", row as usize), ui::IconName::Play)
+            .icon_size(IconSize::Small)
+            .icon_color(Color::Muted)
+            .on_click(cx.listener(move |editor, _e, cx| {
+                editor.spawn_runnable(&buffer, &runnable, cx);
+            }))
+    }
+
     pub fn render_fold_indicators(
         &self,
         fold_data: Vec<Option<(FoldStatus, u32, bool)>>,
@@ -5891,6 +6061,18 @@ impl Editor {
         }
     }
 
+    /// Records a jump from `origin` to the cursor in the navigation history. Selection changes
+    /// only record the moves that span at least `MIN_NAVIGATION_HISTORY_ROW_DELTA` rows.
+    pub fn record_jump(&mut self, origin: Anchor, cx: &mut ViewContext<Self>) {
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let origin_position = origin.to_point(&buffer);
+        let cursor_position = self.selections.newest_anchor().head().to_point(&buffer);
+        let row_delta = (cursor_position.row as i64 - origin_position.row as i64).abs();
+        if origin_position != cursor_position && row_delta < MIN_NAVIGATION_HISTORY_ROW_DELTA {
+            self.push_to_nav_history(origin, None, cx);
+        }
+    }
+
     pub fn select_to_end(&mut self, _: &SelectToEnd, cx: &mut ViewContext<Self>) {
         let buffer = self.buffer.read(cx).snapshot(cx);
         let mut selection = self.selections.first::<usize>(cx);
@@ -6658,26 +6840,169 @@ impl Editor {
         &mut self,
         _: &SelectLargerSyntaxNode,
         cx: &mut ViewContext<Self>,
+    ) {
+        // Expand once more when the ranges arrive rather than requesting them again.
+        if self.selection_ranges_task.is_some() {
+            self.pending_selection_expansions += 1;
+            return;
+        }
+
+        let old_selections = self.selections.all::<usize>(cx).into_boxed_slice();
+        let Some(selection_ranges) = self.lsp_selection_ranges(&old_selections, cx) else {
+            self.select_larger_node(old_selections, None, cx);
+            return;
+        };
+
+        self.pending_selection_expansions = 1;
+        let mut timeout = cx
+            .background_executor()
+            .timer(SELECTION_RANGES_TIMEOUT)
+            .fuse();
+        self.selection_ranges_task = Some(cx.spawn(|editor, mut cx| async move {
+            // Fall back to the syntax tree rather than leaving the selections as they are.
+            let selection_ranges = futures::select_biased! {
+                _ = timeout => {
+                    log::warn!("timed out waiting for selection ranges");
+                    None
+                }
+                selection_ranges = selection_ranges.fuse() => selection_ranges.log_err(),
+            };
+            editor
+                .update(&mut cx, |editor, cx| {
+                    editor.selection_ranges_task = None;
+                    let expansions = mem::take(&mut editor.pending_selection_expansions);
+
+                    // Ignore the response if the selections changed while waiting for it.
+                    let selections = editor.selections.all::<usize>(cx);
+                    if !selections
+                        .iter()
+                        .map(|selection| selection.range())
+                        .eq(old_selections.iter().map(|selection| selection.range()))
+                    {
+                        return;
+                    }
+
+                    let selection_count = old_selections.len();
+                    editor.select_larger_node(old_selections, selection_ranges.clone(), cx);
+                    for _ in 1..expansions {
+                        let old_selections = editor.selections.all::<usize>(cx);
+                        // Expanding can merge selections, after which the ranges no longer
+                        // line up with them.
+                        let selection_ranges = selection_ranges
+                            .clone()
+                            .filter(|_| old_selections.len() == selection_count);
+                        editor.select_larger_node(
+                            old_selections.into_boxed_slice(),
+                            selection_ranges,
+                            cx,
+                        );
+                    }
+                })
+                .ok();
+        }));
+    }
+
+    /// Requests the ranges the language server would expand the given selections to, if they
+    /// are all in one buffer that has selection ranges enabled. The ranges of each selection
+    /// are ordered from the innermost to the outermost one.
+    fn lsp_selection_ranges(
+        &self,
+        selections: &[Selection<usize>],
+        cx: &mut ViewContext<Self>,
+    ) -> Option<Task<Result<Vec<Vec<Range<usize>>>>>> {
+        let project = self.project.clone()?;
+        let multi_buffer = self.buffer.read(cx);
+        let snapshot = multi_buffer.snapshot(cx);
+        let mut buffer = None;
+        let mut positions = Vec::new();
+        let mut excerpt_ids = Vec::new();
+        for selection in selections {
+            let anchor = snapshot.anchor_before(selection.start);
+            let (selection_buffer, position) = multi_buffer.text_anchor_for_position(anchor, cx)?;
+            if *buffer.get_or_insert_with(|| selection_buffer.clone()) != selection_buffer {
+                return None;
+            }
+            positions.push(position);
+            excerpt_ids.push(anchor.excerpt_id);
+        }
+        let buffer = buffer?;
+        let enabled = {
+            let buffer = buffer.read(cx);
+            language_settings::language_settings(buffer.language(), buffer.file(), cx)
+                .selection_ranges
+        };
+        if !enabled {
+            return None;
+        }
+
+        let request = project.update(cx, |project, cx| {
+            project.selection_ranges(&buffer, positions, cx)
+        });
+        Some(cx.spawn(|editor, mut cx| async move {
+            let selection_ranges = request.await?;
+            editor.update(&mut cx, |editor, cx| {
+                let snapshot = editor.buffer.read(cx).snapshot(cx);
+                selection_ranges
+                    .into_iter()
+                    .zip(excerpt_ids)
+                    .map(|(ranges, excerpt_id)| {
+                        ranges
+                            .into_iter()
+                            .map(|range| {
+                                let start = snapshot.anchor_in_excerpt(excerpt_id, range.start);
+                                let end = snapshot.anchor_in_excerpt(excerpt_id, range.end);
+                                start.to_offset(&snapshot)..end.to_offset(&snapshot)
+                            })
+                            .collect()
+                    })
+                    .collect()
+            })
+        }))
+    }
+
+    /// Expands each selection to the smallest of its language server's selection ranges that
+    /// contains it, or to its syntax ancestor when there is no such range.
+    fn select_larger_node(
+        &mut self,
+        old_selections: Box<[Selection<usize>]>,
+        selection_ranges: Option<Vec<Vec<Range<usize>>>>,
+        cx: &mut ViewContext<Self>,
     ) {
         let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
         let buffer = self.buffer.read(cx).snapshot(cx);
-        let old_selections = self.selections.all::<usize>(cx).into_boxed_slice();
 
         let mut stack = mem::take(&mut self.select_larger_syntax_node_stack);
         let mut selected_larger_node = false;
         let new_selections = old_selections
             .iter()
-            .map(|selection| {
+            .enumerate()
+            .map(|(ix, selection)| {
                 let old_range = selection.start..selection.end;
+                let is_visible_expansion = |range: &Range<usize>| {
+                    range.start <= old_range.start
+                        && range.end >= old_range.end
+                        && *range != old_range
+                        && !display_map.intersects_fold(range.start)
+                        && !display_map.intersects_fold(range.end)
+                };
+                let selection_range = selection_ranges
+                    .as_ref()
+                    .and_then(|selection_ranges| selection_ranges.get(ix))
+                    .and_then(|ranges| ranges.iter().find(|range| is_visible_expansion(range)));
+
                 let mut new_range = old_range.clone();
-                while let Some(containing_range) =
-                    buffer.range_for_syntax_ancestor(new_range.clone())
-                {
-                    new_range = containing_range;
-                    if !display_map.intersects_fold(new_range.start)
-                        && !display_map.intersects_fold(new_range.end)
+                if let Some(selection_range) = selection_range {
+                    new_range = selection_range.clone();
+                } else {
+                    while let Some(containing_range) =
+                        buffer.range_for_syntax_ancestor(new_range.clone())
                     {
-                        break;
+                        new_range = containing_range;
+                        if !display_map.intersects_fold(new_range.start)
+                            && !display_map.intersects_fold(new_range.end)
+                        {
+                            break;
+                        }
                     }
                 }
 
@@ -6715,6 +7040,63 @@ impl Editor {
         self.select_larger_syntax_node_stack = stack;
     }
 
+    pub fn select_function(&mut self, _: &SelectFunction, cx: &mut ViewContext<Self>) {
+        self.select_enclosing_text_object(TextObject::AroundFunction, cx);
+    }
+
+    pub fn select_class(&mut self, _: &SelectClass, cx: &mut ViewContext<Self>) {
+        self.select_enclosing_text_object(TextObject::AroundClass, cx);
+    }
+
+    /// Expands each selection to the smallest range of the text object that contains it, as
+    /// captured by the language's `textobjects` query. Like selecting a larger syntax node,
+    /// the previous selections can be restored with `SelectSmallerSyntaxNode`.
+    fn select_enclosing_text_object(
+        &mut self,
+        text_object: TextObject,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let old_selections = self.selections.all::<usize>(cx).into_boxed_slice();
+
+        let mut stack = mem::take(&mut self.select_larger_syntax_node_stack);
+        let mut selected_text_object = false;
+        let new_selections = old_selections
+            .iter()
+            .map(|selection| {
+                let old_range = selection.range();
+                let new_range = buffer
+                    .text_object_ranges(old_range.clone(), &[text_object])
+                    .into_iter()
+                    .filter(|(range, _)| {
+                        range.start <= old_range.start
+                            && range.end >= old_range.end
+                            && *range != old_range
+                    })
+                    .map(|(range, _)| range)
+                    .min_by_key(|range| range.len())
+                    .unwrap_or_else(|| old_range.clone());
+
+                selected_text_object |= new_range != old_range;
+                Selection {
+                    id: selection.id,
+                    start: new_range.start,
+                    end: new_range.end,
+                    goal: SelectionGoal::None,
+                    reversed: selection.reversed,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        if selected_text_object {
+            stack.push(old_selections);
+            self.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.select(new_selections);
+            });
+        }
+        self.select_larger_syntax_node_stack = stack;
+    }
+
     pub fn move_to_enclosing_bracket(
         &mut self,
         _: &MoveToEnclosingBracket,
@@ -6952,6 +7334,35 @@ impl Editor {
         }
     }
 
+    fn stage_hunk(&mut self, _: &StageHunk, cx: &mut ViewContext<Self>) {
+        self.update_hunk_staging(true, cx);
+    }
+
+    fn unstage_hunk(&mut self, _: &UnstageHunk, cx: &mut ViewContext<Self>) {
+        self.update_hunk_staging(false, cx);
+    }
+
+    fn update_hunk_staging(&mut self, stage: bool, cx: &mut ViewContext<Self>) {
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        let head = self.selections.newest_anchor().head();
+        let Some((buffer, position)) = self.buffer.read(cx).text_anchor_for_position(head, cx)
+        else {
+            return;
+        };
+        let row = buffer.read(cx).summary_for_anchor::<Point>(&position).row;
+        project
+            .update(cx, |project, cx| {
+                if stage {
+                    project.stage_hunk(&buffer, row, cx)
+                } else {
+                    project.unstage_hunk(&buffer, row, cx)
+                }
+            })
+            .detach_and_log_err(cx);
+    }
+
     fn seek_in_direction(
         &mut self,
         snapshot: &DisplaySnapshot,
@@ -7005,6 +7416,30 @@ impl Editor {
         self.go_to_definition_of_kind(GotoDefinitionKind::Type, true, cx);
     }
 
+    pub fn go_to_implementation(&mut self, _: &GoToImplementation, cx: &mut ViewContext<Self>) {
+        self.go_to_definition_of_kind(GotoDefinitionKind::Implementation, false, cx);
+    }
+
+    pub fn go_to_implementation_split(
+        &mut self,
+        _: &GoToImplementationSplit,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.go_to_definition_of_kind(GotoDefinitionKind::Implementation, true, cx);
+    }
+
+    pub fn go_to_declaration(&mut self, _: &GoToDeclaration, cx: &mut ViewContext<Self>) {
+        self.go_to_definition_of_kind(GotoDefinitionKind::Declaration, false, cx);
+    }
+
+    pub fn go_to_declaration_split(
+        &mut self,
+        _: &GoToDeclarationSplit,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.go_to_definition_of_kind(GotoDefinitionKind::Declaration, true, cx);
+    }
+
     fn go_to_definition_of_kind(
         &mut self,
         kind: GotoDefinitionKind,
@@ -7026,6 +7461,8 @@ impl Editor {
         let definitions = project.update(cx, |project, cx| match kind {
             GotoDefinitionKind::Symbol => project.definition(&buffer, head, cx),
             GotoDefinitionKind::Type => project.type_definition(&buffer, head, cx),
+            GotoDefinitionKind::Implementation => project.implementation(&buffer, head, cx),
+            GotoDefinitionKind::Declaration => project.declaration(&buffer, head, cx),
         });
 
         cx.spawn(|editor, mut cx| async move {
@@ -7036,6 +7473,7 @@ impl Editor {
                         .into_iter()
                         .map(GoToDefinitionLink::Text)
                         .collect(),
+                    kind,
                     split,
                     cx,
                 );
@@ -7045,9 +7483,10 @@ impl Editor {
         .detach_and_log_err(cx);
     }
 
-    pub fn navigate_to_definitions(
+    pub(crate) fn navigate_to_definitions(
         &mut self,
         mut definitions: Vec<GoToDefinitionLink>,
+        kind: GotoDefinitionKind,
         split: bool,
         cx: &mut ViewContext<Editor>,
     ) {
@@ -7063,6 +7502,20 @@ impl Editor {
                 GoToDefinitionLink::InlayHint(lsp_location, server_id) => {
                     self.compute_target_location(lsp_location, server_id, cx)
                 }
+                GoToDefinitionLink::Url(url) => {
+                    cx.open_url(&url);
+                    return;
+                }
+                GoToDefinitionLink::File(project_path, position) => {
+                    link_go_to_definition::open_file_link(
+                        workspace,
+                        project_path,
+                        position,
+                        split,
+                        cx,
+                    );
+                    return;
+                }
             };
             cx.spawn(|editor, mut cx| async move {
                 let target = target_task.await.context("target resolution task")?;
@@ -7117,16 +7570,19 @@ impl Editor {
                                     link.origin.as_ref().map(|origin| {
                                         let buffer = origin.buffer.read(cx);
                                         format!(
-                                            "Definitions for {}",
+                                            "{} for {}",
+                                            kind.title(),
                                             buffer
                                                 .text_for_range(origin.range.clone())
                                                 .collect::<String>()
                                         )
                                     })
                                 }
-                                GoToDefinitionLink::InlayHint(_, _) => None,
+                                GoToDefinitionLink::InlayHint(_, _)
+                                | GoToDefinitionLink::Url(_)
+                                | GoToDefinitionLink::File(_, _) => None,
                             })
-                            .unwrap_or("Definitions".to_string());
+                            .unwrap_or_else(|| kind.title().to_string());
                         let location_tasks = definitions
                             .into_iter()
                             .map(|definition| match definition {
@@ -7136,6 +7592,9 @@ impl Editor {
                                 GoToDefinitionLink::InlayHint(lsp_location, server_id) => {
                                     editor.compute_target_location(lsp_location, server_id, cx)
                                 }
+                                GoToDefinitionLink::Url(_) | GoToDefinitionLink::File(_, _) => {
+                                    Task::Ready(Some(Ok(None)))
+                                }
                             })
                             .collect::<Vec<_>>();
                         (title, location_tasks)
@@ -7819,6 +8278,70 @@ impl Editor {
         self.fold_ranges(fold_ranges, true, cx);
     }
 
+    pub fn fold_recursive(&mut self, _: &actions::FoldRecursive, cx: &mut ViewContext<Self>) {
+        let mut fold_ranges = Vec::new();
+
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+
+        let selections = self.selections.all_adjusted(cx);
+        for selection in selections {
+            let range = selection.range().sorted();
+
+            let enclosing_range = (0..=range.start.row).rev().find_map(|row| {
+                display_map
+                    .foldable_range(row)
+                    .filter(|fold_range| fold_range.end.row >= range.start.row)
+            });
+            let Some(enclosing_range) = enclosing_range else {
+                continue;
+            };
+
+            for row in enclosing_range.start.row..=enclosing_range.end.row {
+                if let Some(fold_range) = display_map.foldable_range(row) {
+                    if fold_range.end <= enclosing_range.end {
+                        fold_ranges.push(fold_range);
+                    }
+                }
+            }
+        }
+
+        self.fold_ranges(fold_ranges, true, cx);
+    }
+
+    pub fn fold_all(&mut self, _: &actions::FoldAll, cx: &mut ViewContext<Self>) {
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        let fold_ranges = (0..=display_map.max_buffer_row())
+            .filter_map(|row| display_map.foldable_range(row))
+            .collect::<Vec<_>>();
+        self.fold_ranges(fold_ranges, true, cx);
+    }
+
+    /// Folds every foldable range nested `level` deep, where top-level ranges are at
+    /// level 1.
+    pub fn fold_at_level(&mut self, fold_at: &FoldAtLevel, cx: &mut ViewContext<Self>) {
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+
+        let mut fold_ranges = Vec::new();
+        let mut enclosing_ranges = Vec::<Range<Point>>::new();
+        for row in 0..=display_map.max_buffer_row() {
+            while enclosing_ranges
+                .last()
+                .map_or(false, |enclosing_range| enclosing_range.end.row < row)
+            {
+                enclosing_ranges.pop();
+            }
+
+            if let Some(fold_range) = display_map.foldable_range(row) {
+                if enclosing_ranges.len() as u32 + 1 == fold_at.level {
+                    fold_ranges.push(fold_range.clone());
+                }
+                enclosing_ranges.push(fold_range);
+            }
+        }
+
+        self.fold_ranges(fold_ranges, true, cx);
+    }
+
     pub fn fold_at(&mut self, fold_at: &FoldAt, cx: &mut ViewContext<Self>) {
         let buffer_row = fold_at.buffer_row;
         let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
@@ -7853,6 +8376,11 @@ impl Editor {
         self.unfold_ranges(ranges, true, true, cx);
     }
 
+    pub fn unfold_all(&mut self, _: &actions::UnfoldAll, cx: &mut ViewContext<Self>) {
+        let len = self.buffer.read(cx).len(cx);
+        self.unfold_ranges(std::iter::once(0..len), true, true, cx);
+    }
+
     pub fn unfold_at(&mut self, unfold_at: &UnfoldAt, cx: &mut ViewContext<Self>) {
         let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
 
@@ -7901,6 +8429,7 @@ impl Editor {
                 self.request_autoscroll(Autoscroll::fit(), cx);
             }
 
+            self.serialize_folds(cx);
             cx.notify();
         }
     }
@@ -7920,10 +8449,69 @@ impl Editor {
                 self.request_autoscroll(Autoscroll::fit(), cx);
             }
 
+            self.serialize_folds(cx);
             cx.notify();
         }
     }
 
+    /// Stores the folds of a singleton editor, so that they are restored along with
+    /// the workspace.
+    pub(crate) fn serialize_folds(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(workspace_id) = self.workspace.as_ref().map(|(_, id)| *id) else {
+            return;
+        };
+        if !self.buffer.read(cx).is_singleton() {
+            return;
+        }
+
+        let item_id = cx.view().item_id().as_u64() as ItemId;
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        let buffer = &display_map.buffer_snapshot;
+        let folds = display_map
+            .folds_in_range(0..buffer.len())
+            .map(|fold| fold.range.start.to_point(buffer)..fold.range.end.to_point(buffer))
+            .collect::<Vec<_>>();
+
+        cx.background_executor()
+            .spawn(async move { DB.save_folds(item_id, workspace_id, folds).await.log_err() })
+            .detach();
+    }
+
+    /// Stores the folds again once edits settle, since edits move them away from
+    /// their stored positions.
+    fn serialize_folds_after_edit(&mut self, cx: &mut ViewContext<Self>) {
+        if self.workspace.is_none() {
+            return;
+        }
+
+        self.serialize_folds_task = Some(cx.spawn(|this, mut cx| async move {
+            cx.background_executor()
+                .timer(SERIALIZE_FOLDS_DEBOUNCE_TIMEOUT)
+                .await;
+            this.update(&mut cx, |this, cx| this.serialize_folds(cx))
+                .log_err();
+        }));
+    }
+
+    pub(crate) fn read_folds_from_db(
+        &mut self,
+        item_id: ItemId,
+        workspace_id: WorkspaceId,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if let Ok(folds) = DB.get_folds(item_id, workspace_id) {
+            let buffer = self.buffer.read(cx).snapshot(cx);
+            let ranges = folds
+                .into_iter()
+                .map(|(start_row, start_column, end_row, end_column)| {
+                    buffer.clip_point(Point::new(start_row, start_column), Bias::Left)
+                        ..buffer.clip_point(Point::new(end_row, end_column), Bias::Right)
+                })
+                .collect::<Vec<_>>();
+            self.fold_ranges(ranges, false, cx);
+        }
+    }
+
     pub fn set_gutter_hovered(&mut self, hovered: bool, cx: &mut ViewContext<Self>) {
         if hovered != self.gutter_hovered {
             self.gutter_hovered = hovered;
@@ -8140,6 +8728,131 @@ impl Editor {
         self.highlighted_rows.clone()
     }
 
+    /// Returns the display rows within `range` that contain a breakpoint, along with
+    /// the row where a debug session is currently paused, if it is visible.
+    pub fn debugger_rows(
+        &self,
+        snapshot: &DisplaySnapshot,
+        range: Range<Anchor>,
+        cx: &AppContext,
+    ) -> (Vec<u32>, Option<u32>) {
+        use language::ToOffset as _;
+
+        let mut breakpoint_rows = Vec::new();
+        let mut execution_row = None;
+        let Some(project) = self.project.as_ref() else {
+            return (breakpoint_rows, execution_row);
+        };
+
+        let project = project.read(cx);
+        let execution_position = project.active_debug_position(cx);
+        for (buffer, buffer_range, excerpt_id) in
+            self.buffer.read(cx).range_to_buffer_ranges(range, cx)
+        {
+            let Some(buffer_snapshot) = snapshot.buffer_snapshot.buffer_for_excerpt(excerpt_id)
+            else {
+                continue;
+            };
+            let buffer_id = buffer.read(cx).remote_id();
+            let display_row = |anchor: &language::Anchor| {
+                let offset = anchor.to_offset(buffer_snapshot);
+                if !buffer_range.contains(&offset) {
+                    return None;
+                }
+                let row = snapshot
+                    .buffer_snapshot
+                    .anchor_in_excerpt(excerpt_id, *anchor)
+                    .to_display_point(snapshot)
+                    .row();
+                Some(row)
+            };
+
+            breakpoint_rows.extend(
+                project
+                    .breakpoints_for_buffer(buffer.read(cx), cx)
+                    .iter()
+                    .filter_map(|breakpoint| display_row(&breakpoint.position(buffer_snapshot))),
+            );
+            if let Some((_, position)) = execution_position
+                .as_ref()
+                .filter(|(paused_buffer_id, _)| *paused_buffer_id == buffer_id)
+            {
+                execution_row = execution_row.or_else(|| display_row(position));
+            }
+        }
+        breakpoint_rows.sort_unstable();
+        breakpoint_rows.dedup();
+        (breakpoint_rows, execution_row)
+    }
+
+    /// Returns the runnables that start within the given range, along with the
+    /// display row of each. Only the first runnable on each row is kept.
+    pub fn runnable_rows(
+        &self,
+        snapshot: &DisplaySnapshot,
+        range: Range<Anchor>,
+        cx: &AppContext,
+    ) -> Vec<(u32, Model<Buffer>, Runnable)> {
+        let mut rows = Vec::new();
+        if self.project.is_none() {
+            return rows;
+        }
+
+        for (buffer, buffer_range, excerpt_id) in
+            self.buffer.read(cx).range_to_buffer_ranges(range, cx)
+        {
+            let Some(buffer_snapshot) = snapshot.buffer_snapshot.buffer_for_excerpt(excerpt_id)
+            else {
+                continue;
+            };
+            for runnable in buffer_snapshot.runnables_in_range(buffer_range) {
+                let anchor = buffer_snapshot.anchor_after(runnable.run_range.start);
+                let row = snapshot
+                    .buffer_snapshot
+                    .anchor_in_excerpt(excerpt_id, anchor)
+                    .to_display_point(snapshot)
+                    .row();
+                rows.push((row, buffer.clone(), runnable));
+            }
+        }
+        rows.sort_by_key(|(row, _, _)| *row);
+        rows.dedup_by_key(|(row, _, _)| *row);
+        rows
+    }
+
+    fn spawn_runnable(
+        &mut self,
+        buffer: &Model<Buffer>,
+        runnable: &Runnable,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let buffer = buffer.read(cx);
+        let mut context = TaskContext {
+            row: Some(buffer.offset_to_point(runnable.run_range.start).row + 1),
+            symbol: runnable.symbol.clone(),
+            ..Default::default()
+        };
+        if let Some(file) = project::File::from_dyn(buffer.file()) {
+            let worktree_root = file.worktree.read(cx).abs_path();
+            context.file = Some(worktree_root.join(&file.path));
+            context.worktree_root = Some(worktree_root.to_path_buf());
+        }
+
+        let task = TaskDefinition {
+            label: runnable
+                .symbol
+                .clone()
+                .unwrap_or_else(|| runnable.command.command.clone()),
+            command: runnable.command.command.clone(),
+            args: runnable.command.args.clone(),
+            env: Default::default(),
+            cwd: None,
+            source_dir: None,
+        }
+        .resolve(&context);
+        cx.dispatch_action(Box::new(SpawnTaskInTerminal { task }));
+    }
+
     pub fn highlight_background<T: 'static>(
         &mut self,
         ranges: Vec<Range<Anchor>>,
@@ -8151,6 +8864,52 @@ impl Editor {
         cx.notify();
     }
 
+    /// Highlights the full width of every row touched by the given ranges with a status
+    /// color, such as the background of added or deleted lines.
+    pub fn highlight_rows_with_status<T: 'static>(
+        &mut self,
+        ranges: Vec<Range<Anchor>>,
+        color_fetcher: fn(&StatusColors) -> Hsla,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.row_highlights
+            .insert(TypeId::of::<T>(), (color_fetcher, ranges));
+        cx.notify();
+    }
+
+    pub fn clear_row_highlights<T: 'static>(&mut self, cx: &mut ViewContext<Self>) {
+        if self.row_highlights.remove(&TypeId::of::<T>()).is_some() {
+            cx.notify();
+        }
+    }
+
+    /// The display rows within `rows` that are highlighted with a status color.
+    pub(crate) fn status_highlighted_rows(
+        &self,
+        snapshot: &DisplaySnapshot,
+        rows: Range<u32>,
+        cx: &AppContext,
+    ) -> Vec<(Range<u32>, Hsla)> {
+        let status = cx.theme().status();
+        let mut highlighted_rows = Vec::new();
+        for (color_fetcher, ranges) in self.row_highlights.values() {
+            let color = color_fetcher(status);
+            for range in ranges {
+                let start_row = range.start.to_display_point(snapshot).row();
+                let end = range.end.to_display_point(snapshot);
+                let end_row = if end.column() > 0 || end.row() == start_row {
+                    end.row() + 1
+                } else {
+                    end.row()
+                };
+                if start_row < rows.end && end_row > rows.start {
+                    highlighted_rows.push((start_row..end_row, color));
+                }
+            }
+        }
+        highlighted_rows
+    }
+
     pub(crate) fn highlight_inlay_background<T: 'static>(
         &mut self,
         ranges: Vec<InlayHighlight>,
@@ -8407,6 +9166,10 @@ impl Editor {
             } => {
                 self.refresh_active_diagnostics(cx);
                 self.refresh_code_actions(cx);
+                self.refresh_inline_blame(cx);
+                self.refresh_semantic_tokens(false, cx);
+                self.refresh_code_lenses(false, cx);
+                self.collapse_edited_hunks(cx);
                 if self.has_active_copilot_suggestion(cx) {
                     self.update_visible_copilot_suggestion(cx);
                 }
@@ -8414,6 +9177,7 @@ impl Editor {
                 cx.emit(SearchEvent::MatchesInvalidated);
 
                 if *singleton_buffer_edited {
+                    self.serialize_folds_after_edit(cx);
                     if let Some(project) = &self.project {
                         let project = project.read(cx);
                         let languages_affected = multibuffer
@@ -8457,9 +9221,15 @@ impl Editor {
                     excerpts: excerpts.clone(),
                 });
                 self.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx);
+                self.refresh_merge_conflicts(cx);
+                self.refresh_semantic_tokens(false, cx);
+                self.refresh_code_lenses(false, cx);
             }
             multi_buffer::Event::ExcerptsRemoved { ids } => {
                 self.refresh_inlay_hints(InlayHintRefreshReason::ExcerptsRemoved(ids.clone()), cx);
+                self.refresh_merge_conflicts(cx);
+                self.refresh_semantic_tokens(false, cx);
+                self.refresh_code_lenses(false, cx);
                 cx.emit(EditorEvent::ExcerptsRemoved { ids: ids.clone() })
             }
             multi_buffer::Event::Reparsed => cx.emit(EditorEvent::Reparsed),
@@ -8468,7 +9238,11 @@ impl Editor {
             multi_buffer::Event::FileHandleChanged | multi_buffer::Event::Reloaded => {
                 cx.emit(EditorEvent::TitleChanged)
             }
-            multi_buffer::Event::DiffBaseChanged => cx.emit(EditorEvent::DiffBaseChanged),
+            multi_buffer::Event::DiffBaseChanged => {
+                self.collapse_expanded_hunks(cx);
+                cx.emit(EditorEvent::DiffBaseChanged)
+            }
+            multi_buffer::Event::MergeConflictsChanged => self.refresh_merge_conflicts(cx),
             multi_buffer::Event::Closed => cx.emit(EditorEvent::Closed),
             multi_buffer::Event::DiagnosticsUpdated => {
                 self.refresh_active_diagnostics(cx);
@@ -8491,6 +9265,7 @@ impl Editor {
             )),
             cx,
         );
+        self.refresh_semantic_tokens(false, cx);
         cx.notify();
     }
 
@@ -9102,8 +9877,11 @@ impl EditorSnapshot {
             let gutter_padding = (em_width * gutter_padding_factor).round();
             // Avoid flicker-like gutter resizes when the line number gains another digit and only resize the gutter on files with N*10^5 lines.
             let min_width_for_number_on_gutter = em_width * 4.0;
-            let gutter_width =
+            let mut gutter_width =
                 max_line_number_width.max(min_width_for_number_on_gutter) + gutter_padding * 2.0;
+            if self.show_git_blame {
+                gutter_width += em_width * BLAME_GUTTER_WIDTH_IN_EMS;
+            }
             let gutter_margin = -descent;
 
             GutterDimensions {
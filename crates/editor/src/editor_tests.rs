@@ -19,7 +19,7 @@ use language::{
 };
 use parking_lot::Mutex;
 use project::project_settings::{LspSettings, ProjectSettings};
-use project::FakeFs;
+use project::{repository::BlameEntry, FakeFs};
 use serde_json::{self, json};
 use std::sync::atomic;
 use std::sync::atomic::AtomicUsize;
@@ -800,6 +800,90 @@ fn test_fold_action(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+fn test_fold_all_and_fold_at_level(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let view = cx.add_window(|cx| {
+        let buffer = MultiBuffer::build_simple(
+            &"
+                impl Foo {
+                    fn a() {
+                        1
+                    }
+
+                    fn b() {
+                        2
+                    }
+                }
+            "
+            .unindent(),
+            cx,
+        );
+        build_editor(buffer.clone(), cx)
+    });
+
+    _ = view.update(cx, |view, cx| {
+        view.fold_at_level(&FoldAtLevel { level: 2 }, cx);
+        assert_eq!(
+            view.display_text(cx),
+            "
+                impl Foo {
+                    fn a() {⋯
+                    }
+
+                    fn b() {⋯
+                    }
+                }
+            "
+            .unindent(),
+        );
+
+        view.unfold_all(&UnfoldAll, cx);
+        assert_eq!(view.display_text(cx), view.buffer.read(cx).read(cx).text());
+
+        view.fold_all(&FoldAll, cx);
+        assert_eq!(
+            view.display_text(cx),
+            "
+                impl Foo {⋯
+                }
+            "
+            .unindent(),
+        );
+
+        view.unfold_all(&UnfoldAll, cx);
+        view.change_selections(None, cx, |s| {
+            s.select_display_ranges([DisplayPoint::new(0, 0)..DisplayPoint::new(0, 0)]);
+        });
+        view.fold_recursive(&FoldRecursive, cx);
+        assert_eq!(
+            view.display_text(cx),
+            "
+                impl Foo {⋯
+                }
+            "
+            .unindent(),
+        );
+
+        // Nested folds remain after unfolding the outermost one.
+        view.unfold_lines(&UnfoldLines, cx);
+        assert_eq!(
+            view.display_text(cx),
+            "
+                impl Foo {
+                    fn a() {⋯
+                    }
+
+                    fn b() {⋯
+                    }
+                }
+            "
+            .unindent(),
+        );
+    });
+}
+
 #[gpui::test]
 fn test_move_cursor(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -4098,6 +4182,80 @@ async fn test_select_larger_smaller_syntax_node(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_select_function_and_class(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let language = Arc::new(
+        Language::new(
+            LanguageConfig::default(),
+            Some(tree_sitter_rust::language()),
+        )
+        .with_text_object_query(indoc! {r#"
+            (function_item
+                body: (_) @function.inside) @function.around
+
+            (impl_item
+                body: (_) @class.inside) @class.around
+        "#})
+        .unwrap(),
+    );
+
+    let text = r#"
+        impl A {
+            fn a() {
+                let b = 1;
+            }
+        }
+    "#
+    .unindent();
+
+    let buffer = cx
+        .new_model(|cx| Buffer::new(0, cx.entity_id().as_u64(), text).with_language(language, cx));
+    let buffer = cx.new_model(|cx| MultiBuffer::singleton(buffer, cx));
+    let (view, cx) = cx.add_window_view(|cx| build_editor(buffer, cx));
+
+    view.condition::<crate::EditorEvent>(&cx, |view, cx| !view.buffer.read(cx).is_parsing(cx))
+        .await;
+
+    _ = view.update(cx, |view, cx| {
+        view.change_selections(None, cx, |s| {
+            s.select_display_ranges([DisplayPoint::new(2, 12)..DisplayPoint::new(2, 12)]);
+        });
+        view.select_function(&SelectFunction, cx);
+    });
+    assert_eq!(
+        view.update(cx, |view, cx| view.selections.display_ranges(cx)),
+        &[DisplayPoint::new(1, 4)..DisplayPoint::new(3, 5)]
+    );
+
+    // There's no function around the selected one.
+    _ = view.update(cx, |view, cx| {
+        view.select_function(&SelectFunction, cx);
+    });
+    assert_eq!(
+        view.update(cx, |view, cx| view.selections.display_ranges(cx)),
+        &[DisplayPoint::new(1, 4)..DisplayPoint::new(3, 5)]
+    );
+
+    _ = view.update(cx, |view, cx| {
+        view.select_class(&SelectClass, cx);
+    });
+    assert_eq!(
+        view.update(cx, |view, cx| view.selections.display_ranges(cx)),
+        &[DisplayPoint::new(0, 0)..DisplayPoint::new(4, 1)]
+    );
+
+    _ = view.update(cx, |view, cx| {
+        view.select_smaller_syntax_node(&SelectSmallerSyntaxNode, cx);
+        view.select_smaller_syntax_node(&SelectSmallerSyntaxNode, cx);
+    });
+    assert_eq!(
+        view.update(cx, |view, cx| view.selections.display_ranges(cx)),
+        &[DisplayPoint::new(2, 12)..DisplayPoint::new(2, 12)]
+    );
+}
+
 #[gpui::test]
 async fn test_autoindent_selections(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -5546,6 +5704,91 @@ async fn test_strip_whitespace_and_format_via_lsp(cx: &mut gpui::TestAppContext)
     );
 }
 
+#[gpui::test]
+async fn test_select_larger_syntax_node_when_selection_ranges_time_out(
+    cx: &mut gpui::TestAppContext,
+) {
+    init_test(cx, |settings| {
+        settings.defaults.selection_ranges = Some(true)
+    });
+
+    let mut cx = EditorLspTestContext::new_rust(
+        lsp::ServerCapabilities {
+            selection_range_provider: Some(lsp::SelectionRangeProviderCapability::Simple(true)),
+            ..Default::default()
+        },
+        cx,
+    )
+    .await;
+    cx.lsp
+        .handle_request::<lsp::request::SelectionRangeRequest, _, _>(|_, _| async move {
+            futures::future::pending::<()>().await;
+            Ok(None)
+        });
+
+    cx.set_state("fn main() { let vaˇlue = 1; }");
+    cx.executor().run_until_parked();
+    cx.update_editor(|editor, cx| editor.select_larger_syntax_node(&SelectLargerSyntaxNode, cx));
+    cx.executor().run_until_parked();
+    cx.assert_editor_state("fn main() { let vaˇlue = 1; }");
+
+    cx.executor().advance_clock(crate::SELECTION_RANGES_TIMEOUT);
+    cx.executor().run_until_parked();
+    cx.assert_editor_state("fn main() { let «valueˇ» = 1; }");
+}
+
+#[gpui::test]
+async fn test_linked_editing_ranges(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorLspTestContext::new_rust(
+        lsp::ServerCapabilities {
+            linked_editing_range_provider: Some(lsp::LinkedEditingRangeServerCapabilities::Simple(
+                true,
+            )),
+            ..Default::default()
+        },
+        cx,
+    )
+    .await;
+    cx.lsp
+        .handle_request::<lsp::request::LinkedEditingRange, _, _>(|params, _| async move {
+            let position = params.text_document_position_params.position;
+            if position.character > 4 {
+                return Ok(None);
+            }
+            Ok(Some(lsp::LinkedEditingRanges {
+                ranges: vec![
+                    lsp::Range::new(lsp::Position::new(0, 1), lsp::Position::new(0, 4)),
+                    lsp::Range::new(lsp::Position::new(0, 11), lsp::Position::new(0, 14)),
+                ],
+                word_pattern: None,
+            }))
+        });
+
+    // Edits within a linked range are applied to the other ranges.
+    cx.set_state("<divˇ>text</div>");
+    cx.executor()
+        .advance_clock(crate::linked_editing::LINKED_EDITING_DEBOUNCE_TIMEOUT);
+    cx.executor().run_until_parked();
+    cx.simulate_keystroke("x");
+    cx.assert_editor_state("<divxˇ>text</divx>");
+    cx.update_editor(|editor, cx| editor.backspace(&Backspace, cx));
+    cx.update_editor(|editor, cx| editor.backspace(&Backspace, cx));
+    cx.assert_editor_state("<diˇ>text</di>");
+
+    // The ranges are dropped once the cursor leaves them.
+    cx.update_editor(|editor, cx| {
+        editor.move_right(&MoveRight, cx);
+        editor.move_right(&MoveRight, cx);
+    });
+    cx.executor()
+        .advance_clock(crate::linked_editing::LINKED_EDITING_DEBOUNCE_TIMEOUT);
+    cx.executor().run_until_parked();
+    cx.simulate_keystroke("y");
+    cx.assert_editor_state("<di>tyˇext</di>");
+}
+
 #[gpui::test]
 async fn test_completion(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -7097,6 +7340,278 @@ async fn go_to_hunk(executor: BackgroundExecutor, cx: &mut gpui::TestAppContext)
     );
 }
 
+#[gpui::test]
+async fn test_inline_git_blame(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            ".git": {},
+            "a.txt": "one\ntwo\nthree\n",
+        }),
+    )
+    .await;
+    let blame_entry = |rows: Range<u32>, author: &str, summary: &str| BlameEntry {
+        rows,
+        sha: author.repeat(40 / author.len()),
+        author: author.into(),
+        author_time: 0,
+        summary: summary.into(),
+    };
+    fs.set_blame_for_repo(
+        Path::new("/dir/.git"),
+        vec![(
+            Path::new("a.txt"),
+            vec![
+                blame_entry(0..1, "Alice", "Add one"),
+                blame_entry(1..3, "Bob", "Add two and three"),
+            ],
+        )],
+    );
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/a.txt", cx)
+        })
+        .await
+        .unwrap();
+    let buffer = cx.new_model(|cx| MultiBuffer::singleton(buffer, cx));
+    let (editor, cx) =
+        cx.add_window_view(|cx| Editor::new(EditorMode::Full, buffer, Some(project.clone()), cx));
+    let inline_blame = |editor: &Editor, cx: &AppContext| {
+        editor
+            .display_map
+            .read(cx)
+            .current_inlays()
+            .find(|inlay| matches!(inlay.id, InlayId::Blame(_)))
+            .map(|inlay| (inlay.position, inlay.text.to_string()))
+    };
+
+    _ = editor.update(cx, |editor, cx| {
+        editor.toggle_git_blame_inline(&ToggleGitBlameInline, cx)
+    });
+    cx.executor().run_until_parked();
+    _ = editor.update(cx, |editor, cx| {
+        let (position, text) = inline_blame(editor, cx).unwrap();
+        let snapshot = editor.buffer.read(cx).snapshot(cx);
+        assert_eq!(position.to_point(&snapshot), Point::new(0, 3));
+        assert!(text.starts_with("    Alice, "), "{text:?}");
+        assert!(text.ends_with(" • Add one"), "{text:?}");
+
+        editor.change_selections(None, cx, |s| {
+            s.select_ranges([Point::new(2, 0)..Point::new(2, 0)])
+        });
+        let (_, text) = inline_blame(editor, cx).unwrap();
+        assert!(text.ends_with(" • Add two and three"), "{text:?}");
+
+        // Rows edited since the blame was computed are uncommitted, while the others keep
+        // their blame as they move.
+        editor.change_selections(None, cx, |s| {
+            s.select_ranges([Point::new(0, 0)..Point::new(0, 0)])
+        });
+        editor.handle_input("zero\n", cx);
+        let (_, text) = inline_blame(editor, cx).unwrap();
+        assert!(text.ends_with(" • Add one"), "{text:?}");
+
+        editor.change_selections(None, cx, |s| {
+            s.select_ranges([Point::new(0, 0)..Point::new(0, 0)])
+        });
+        assert_eq!(inline_blame(editor, cx).map(|(_, text)| text), None);
+
+        editor.change_selections(None, cx, |s| {
+            s.select_ranges([Point::new(3, 0)..Point::new(3, 0)])
+        });
+        let (position, text) = inline_blame(editor, cx).unwrap();
+        let snapshot = editor.buffer.read(cx).snapshot(cx);
+        assert_eq!(position.to_point(&snapshot), Point::new(3, 5));
+        assert!(text.ends_with(" • Add two and three"), "{text:?}");
+
+        editor.toggle_git_blame_inline(&ToggleGitBlameInline, cx);
+        assert_eq!(inline_blame(editor, cx).map(|(_, text)| text), None);
+        assert!(editor.git_blame.is_none());
+    });
+}
+
+#[gpui::test]
+async fn test_revert_and_toggle_hunk_diff(
+    executor: BackgroundExecutor,
+    cx: &mut gpui::TestAppContext,
+) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    let diff_base = "one\ntwo\nthree\nfour\n";
+    cx.set_state("one\nTWO\nthree\nˇfour\nfive\n");
+    cx.set_diff_base(Some(diff_base));
+    executor.run_until_parked();
+
+    // There is no hunk on the cursor's row.
+    cx.update_editor(|editor, cx| {
+        editor.toggle_hunk_diff(&ToggleHunkDiff, cx);
+        assert_eq!(editor.display_text(cx), "one\nTWO\nthree\nfour\nfive\n");
+    });
+
+    // The deleted line is shown in a block above the modified one.
+    cx.set_selections_state("one\nTˇWO\nthree\nfour\nfive\n");
+    cx.update_editor(|editor, cx| {
+        editor.toggle_hunk_diff(&ToggleHunkDiff, cx);
+        assert_eq!(editor.display_text(cx), "one\n\nTWO\nthree\nfour\nfive\n");
+        editor.toggle_hunk_diff(&ToggleHunkDiff, cx);
+        assert_eq!(editor.display_text(cx), "one\nTWO\nthree\nfour\nfive\n");
+        editor.toggle_hunk_diff(&ToggleHunkDiff, cx);
+    });
+
+    // Reverting the hunk restores its base text and hides its deleted lines.
+    cx.update_editor(|editor, cx| {
+        editor.revert_hunk(&RevertHunk, cx);
+        assert_eq!(editor.text(cx), "one\ntwo\nthree\nfour\nfive\n");
+        assert_eq!(editor.display_text(cx), "one\ntwo\nthree\nfour\nfive\n");
+    });
+    executor.run_until_parked();
+
+    // Both hunks touched by a selection are reverted at once.
+    cx.set_state("one\n«\nthree\nfour\nfiveˇ»\n");
+    cx.set_diff_base(Some(diff_base));
+    executor.run_until_parked();
+    cx.update_editor(|editor, cx| {
+        editor.revert_hunk(&RevertHunk, cx);
+        assert_eq!(editor.text(cx), "one\ntwo\nthree\nfour\n");
+        editor.undo(&Undo, cx);
+        assert_eq!(editor.text(cx), "one\n\nthree\nfour\nfive\n");
+    });
+}
+
+#[gpui::test]
+async fn test_toggle_hunk_diff_in_multibuffer(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let buffer_1 = cx.new_model(|cx| {
+        let mut buffer = Buffer::new(0, cx.entity_id().as_u64(), "aaa\nBBB\nccc");
+        buffer.set_diff_base(Some("aaa\nbbb\nccc".to_string()), cx);
+        buffer
+    });
+    let buffer_2 = cx.new_model(|cx| {
+        let mut buffer = Buffer::new(0, cx.entity_id().as_u64(), "ddd\nEEE\nfff");
+        buffer.set_diff_base(Some("ddd\neee\nfff".to_string()), cx);
+        buffer
+    });
+    cx.executor().run_until_parked();
+
+    let multibuffer = cx.new_model(|cx| {
+        let mut multibuffer = MultiBuffer::new(0, ReadWrite);
+        for buffer in [&buffer_1, &buffer_2] {
+            multibuffer.push_excerpts(
+                buffer.clone(),
+                [ExcerptRange {
+                    context: Point::new(0, 0)..Point::new(2, 3),
+                    primary: None,
+                }],
+                cx,
+            );
+        }
+        multibuffer
+    });
+    let (view, cx) = cx.add_window_view(|cx| build_editor(multibuffer, cx));
+    fn display_line_count(editor: &mut Editor, cx: &mut ViewContext<Editor>) -> usize {
+        editor.display_text(cx).lines().count()
+    }
+
+    // Hunks in both excerpts are expanded at once.
+    let initial_line_count = view.update(cx, |editor, cx| {
+        assert_eq!(editor.text(cx), "aaa\nBBB\nccc\nddd\nEEE\nfff");
+        let initial_line_count = display_line_count(editor, cx);
+        editor.change_selections(None, cx, |s| {
+            s.select_ranges([
+                Point::new(1, 0)..Point::new(1, 0),
+                Point::new(4, 0)..Point::new(4, 0),
+            ])
+        });
+        editor.toggle_hunk_diff(&ToggleHunkDiff, cx);
+        assert_eq!(editor.expanded_hunks.len(), 2);
+        assert_eq!(display_line_count(editor, cx), initial_line_count + 2);
+        initial_line_count
+    });
+
+    // Editing a buffer hides the deleted lines of its hunks only.
+    buffer_1.update(cx, |buffer, cx| {
+        buffer.edit([(Point::new(0, 0)..Point::new(0, 0), "X")], None, cx)
+    });
+    cx.executor().run_until_parked();
+    view.update(cx, |editor, cx| {
+        assert_eq!(editor.expanded_hunks.len(), 1);
+        assert_eq!(display_line_count(editor, cx), initial_line_count + 1);
+    });
+
+    // Reverting a hunk only edits the buffer it belongs to.
+    view.update(cx, |editor, cx| {
+        editor.change_selections(None, cx, |s| {
+            s.select_ranges([Point::new(4, 0)..Point::new(4, 0)])
+        });
+        editor.revert_hunk(&RevertHunk, cx);
+        assert_eq!(editor.text(cx), "Xaaa\nBBB\nccc\nddd\neee\nfff");
+        assert!(editor.expanded_hunks.is_empty());
+        assert_eq!(display_line_count(editor, cx), initial_line_count);
+    });
+    assert_eq!(
+        buffer_1.read_with(cx, |buffer, _| buffer.text()),
+        "Xaaa\nBBB\nccc"
+    );
+    assert_eq!(
+        buffer_2.read_with(cx, |buffer, _| buffer.text()),
+        "ddd\neee\nfff"
+    );
+}
+
+#[gpui::test]
+async fn test_resolve_merge_conflicts(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    let conflicted_text = indoc! {"
+        one
+        <<<<<<< HEAD
+        two
+        =======
+        TWO
+        >>>>>>> feature
+        three
+        <<<<<<< HEAD
+        four
+        =======
+        FOUR
+        >>>>>>> feature
+        ˇ"};
+    cx.set_state(conflicted_text);
+    cx.run_until_parked();
+
+    // Each conflict has a block with its resolution buttons above it.
+    cx.update_editor(|editor, cx| {
+        assert_eq!(editor.merge_conflicts.len(), 2);
+        assert_eq!(
+            editor.display_text(cx),
+            "one\n\n<<<<<<< HEAD\ntwo\n=======\nTWO\n>>>>>>> feature\nthree\n\n<<<<<<< HEAD\nfour\n=======\nFOUR\n>>>>>>> feature\n"
+        );
+
+        let conflict = editor.merge_conflicts[1].clone();
+        editor.resolve_merge_conflict(&conflict, ConflictResolution::Both, cx);
+    });
+    cx.run_until_parked();
+    cx.update_editor(|editor, cx| {
+        assert_eq!(editor.merge_conflicts.len(), 1);
+        let conflict = editor.merge_conflicts[0].clone();
+        editor.resolve_merge_conflict(&conflict, ConflictResolution::Incoming, cx);
+    });
+    cx.run_until_parked();
+    cx.assert_editor_state("one\nTWO\nthree\nfour\nFOUR\nˇ");
+    cx.update_editor(|editor, cx| {
+        assert!(editor.merge_conflicts.is_empty());
+        assert_eq!(editor.display_text(cx), "one\nTWO\nthree\nfour\nFOUR\n");
+    });
+}
+
 #[test]
 fn test_split_words() {
     fn split<'a>(text: &'a str) -> Vec<&'a str> {
@@ -16,9 +16,9 @@ use crate::{
     },
     mouse_context_menu,
     scroll::scroll_amount::ScrollAmount,
-    CursorShape, DisplayPoint, Editor, EditorMode, EditorSettings, EditorSnapshot, EditorStyle,
-    HalfPageDown, HalfPageUp, HoveredCursor, LineDown, LineUp, OpenExcerpts, PageDown, PageUp,
-    Point, SelectPhase, Selection, SoftWrap, ToPoint, CURSORS_VISIBLE_FOR, MAX_LINE_LEN,
+    signature_help, CursorShape, DisplayPoint, Editor, EditorMode, EditorSettings, EditorSnapshot,
+    EditorStyle, HalfPageDown, HalfPageUp, HoveredCursor, LineDown, LineUp, OpenExcerpts, PageDown,
+    PageUp, Point, SelectPhase, Selection, SoftWrap, ToPoint, CURSORS_VISIBLE_FOR, MAX_LINE_LEN,
 };
 use anyhow::Result;
 use collections::{BTreeMap, HashMap};
@@ -249,6 +249,8 @@ impl EditorElement {
         });
         register_action(view, cx, Editor::toggle_comments);
         register_action(view, cx, Editor::select_larger_syntax_node);
+        register_action(view, cx, Editor::select_function);
+        register_action(view, cx, Editor::select_class);
         register_action(view, cx, Editor::select_smaller_syntax_node);
         register_action(view, cx, Editor::move_to_enclosing_bracket);
         register_action(view, cx, Editor::undo_selection);
@@ -257,13 +259,25 @@ impl EditorElement {
         register_action(view, cx, Editor::go_to_prev_diagnostic);
         register_action(view, cx, Editor::go_to_hunk);
         register_action(view, cx, Editor::go_to_prev_hunk);
+        register_action(view, cx, Editor::stage_hunk);
+        register_action(view, cx, Editor::unstage_hunk);
+        register_action(view, cx, Editor::revert_hunk);
+        register_action(view, cx, Editor::toggle_hunk_diff);
         register_action(view, cx, Editor::go_to_definition);
         register_action(view, cx, Editor::go_to_definition_split);
         register_action(view, cx, Editor::go_to_type_definition);
         register_action(view, cx, Editor::go_to_type_definition_split);
+        register_action(view, cx, Editor::go_to_implementation);
+        register_action(view, cx, Editor::go_to_implementation_split);
+        register_action(view, cx, Editor::go_to_declaration);
+        register_action(view, cx, Editor::go_to_declaration_split);
         register_action(view, cx, Editor::fold);
+        register_action(view, cx, Editor::fold_all);
+        register_action(view, cx, Editor::fold_recursive);
         register_action(view, cx, Editor::fold_at);
+        register_action(view, cx, Editor::fold_at_level);
         register_action(view, cx, Editor::unfold_lines);
+        register_action(view, cx, Editor::unfold_all);
         register_action(view, cx, Editor::unfold_at);
         register_action(view, cx, Editor::fold_selected_ranges);
         register_action(view, cx, Editor::show_completions);
@@ -271,7 +285,13 @@ impl EditorElement {
         register_action(view, cx, Editor::open_excerpts);
         register_action(view, cx, Editor::toggle_soft_wrap);
         register_action(view, cx, Editor::toggle_inlay_hints);
+        register_action(view, cx, Editor::toggle_breakpoint);
+        register_action(view, cx, Editor::toggle_git_blame);
+        register_action(view, cx, Editor::toggle_git_blame_inline);
         register_action(view, cx, hover_popover::hover);
+        register_action(view, cx, signature_help::show_signature_help);
+        register_action(view, cx, signature_help::next_signature);
+        register_action(view, cx, signature_help::previous_signature);
         register_action(view, cx, Editor::reveal_in_finder);
         register_action(view, cx, Editor::copy_path);
         register_action(view, cx, Editor::copy_relative_path);
@@ -688,6 +708,29 @@ impl EditorElement {
                 cx.paint_quad(fill(Bounds { origin, size }, highlighted_line_bg));
             }
 
+            if let Some(row) = layout.debug_execution_row {
+                let origin = point(
+                    bounds.origin.x,
+                    bounds.origin.y + (layout.position_map.line_height * row as f32) - scroll_top,
+                );
+                let size = size(bounds.size.width, layout.position_map.line_height);
+                let execution_line_bg = cx.theme().status().warning_background;
+                cx.paint_quad(fill(Bounds { origin, size }, execution_line_bg));
+            }
+
+            for (rows, color) in &layout.status_highlighted_rows {
+                let origin = point(
+                    bounds.origin.x,
+                    bounds.origin.y + (layout.position_map.line_height * rows.start as f32)
+                        - scroll_top,
+                );
+                let size = size(
+                    bounds.size.width,
+                    layout.position_map.line_height * rows.len() as f32,
+                );
+                cx.paint_quad(fill(Bounds { origin, size }, *color));
+            }
+
             let scroll_left =
                 layout.position_map.snapshot.scroll_position().x * layout.position_map.em_width;
 
@@ -737,6 +780,27 @@ impl EditorElement {
             Self::paint_diff_hunks(bounds, layout, cx);
         }
 
+        let breakpoint_diameter = (line_height * 0.5).min(layout.gutter_padding);
+        let breakpoint_color = cx.theme().status().error;
+        for row in &layout.breakpoint_rows {
+            let origin = bounds.origin
+                + point(
+                    (layout.gutter_padding - breakpoint_diameter) / 2.,
+                    *row as f32 * line_height - scroll_top
+                        + (line_height - breakpoint_diameter) / 2.,
+                );
+            cx.paint_quad(quad(
+                Bounds {
+                    origin,
+                    size: size(breakpoint_diameter, breakpoint_diameter),
+                },
+                Corners::all(breakpoint_diameter / 2.),
+                breakpoint_color,
+                Edges::default(),
+                transparent_black(),
+            ));
+        }
+
         for (ix, line) in layout.line_numbers.iter().enumerate() {
             if let Some(line) = line {
                 let line_origin = bounds.origin
@@ -749,6 +813,18 @@ impl EditorElement {
             }
         }
 
+        for (ix, line) in layout.blame_lines.iter().enumerate() {
+            if let Some(line) = line {
+                let line_origin = bounds.origin
+                    + point(
+                        layout.gutter_padding,
+                        ix as f32 * line_height - (scroll_top % line_height),
+                    );
+
+                line.paint(line_origin, line_height, cx).log_err();
+            }
+        }
+
         cx.with_z_index(1, |cx| {
             for (ix, fold_indicator) in layout.fold_indicators.drain(..).enumerate() {
                 if let Some(fold_indicator) = fold_indicator {
@@ -773,6 +849,23 @@ impl EditorElement {
                 }
             }
 
+            for indicator in layout.runnable_indicators.drain(..) {
+                let mut button = indicator.button.into_any_element();
+                let available_space = size(
+                    AvailableSpace::MinContent,
+                    AvailableSpace::Definite(line_height),
+                );
+                let indicator_size = button.measure(available_space, cx);
+
+                let mut x = Pixels::ZERO;
+                let mut y = indicator.row as f32 * line_height - scroll_top;
+                // Center indicator.
+                x += ((layout.gutter_padding + layout.gutter_margin) - indicator_size.width) / 2.;
+                y += (line_height - indicator_size.height) / 2.;
+
+                button.draw(bounds.origin + point(x, y), available_space, cx);
+            }
+
             if let Some(indicator) = layout.code_actions_indicator.take() {
                 let mut button = indicator.button.into_any_element();
                 let available_space = size(
@@ -1256,6 +1349,34 @@ impl EditorElement {
             }
         }
 
+        if let Some((position, mut signature_help)) = layout.signature_help.take() {
+            let available_space = size(AvailableSpace::MinContent, AvailableSpace::MinContent);
+            let popover_size = signature_help.measure(available_space, cx);
+
+            // This is safe because we check on layout whether the cursor's row is available
+            let cursor_row_layout =
+                &layout.position_map.line_layouts[(position.row() - start_row) as usize].line;
+            let x = cursor_row_layout.x_for_index(position.column() as usize)
+                - layout.position_map.scroll_position.x;
+            let y = position.row() as f32 * layout.position_map.line_height
+                - layout.position_map.scroll_position.y;
+            let cursor_point = content_origin + point(x, y);
+
+            // Render above the cursor's line, so that the completions menu below it stays
+            // visible, unless there isn't enough space above.
+            let mut popover_origin = point(cursor_point.x, cursor_point.y - popover_size.height);
+            if popover_origin.y < text_bounds.origin.y {
+                popover_origin.y = cursor_point.y + layout.position_map.line_height;
+            }
+            let x_out_of_bounds =
+                text_bounds.upper_right().x - (popover_origin.x + popover_size.width);
+            if x_out_of_bounds < Pixels::ZERO {
+                popover_origin.x = popover_origin.x + x_out_of_bounds;
+            }
+
+            cx.break_content_mask(|cx| signature_help.draw(popover_origin, available_space, cx));
+        }
+
         if let Some(mouse_context_menu) = self.editor.read(cx).mouse_context_menu.as_ref() {
             let element = overlay()
                 .position(mouse_context_menu.position)
@@ -1768,6 +1889,58 @@ impl EditorElement {
         (shaped_line_numbers, fold_statuses)
     }
 
+    /// Shapes the author and date of the commit that last changed each visible row, showing
+    /// them only on the first visible row of each blame hunk.
+    fn shape_blame_lines(
+        &self,
+        rows: Range<u32>,
+        snapshot: &EditorSnapshot,
+        editor: &Editor,
+        cx: &ViewContext<Editor>,
+    ) -> Vec<Option<ShapedLine>> {
+        let Some(blame) = editor
+            .git_blame
+            .as_ref()
+            .filter(|blame| blame.show_in_gutter)
+        else {
+            return Vec::new();
+        };
+        let Some((_, _, buffer)) = snapshot.buffer_snapshot.as_singleton() else {
+            return Vec::new();
+        };
+
+        let buffer_rows = snapshot
+            .buffer_rows(rows.start)
+            .take((rows.end - rows.start) as usize)
+            .collect::<Vec<_>>();
+        let mut hunks = blame
+            .hunks_for_rows(buffer_rows.iter().flatten().copied(), buffer)
+            .into_iter();
+        let font_size = self.style.text.font_size.to_pixels(cx.rem_size());
+        let color = cx.theme().colors().editor_line_number;
+        let mut previous_hunk = None;
+        buffer_rows
+            .into_iter()
+            .map(|buffer_row| {
+                buffer_row?;
+                let hunk = hunks.next().flatten();
+                let is_new_hunk = hunk.map(|hunk| &hunk.range) != previous_hunk;
+                previous_hunk = hunk.map(|hunk| &hunk.range);
+                let text = hunk.filter(|_| is_new_hunk)?.gutter_text();
+                let run = TextRun {
+                    len: text.len(),
+                    font: self.style.text.font(),
+                    color,
+                    background_color: None,
+                    underline: None,
+                };
+                cx.text_system()
+                    .shape_line(text.into(), font_size, &[run])
+                    .log_err()
+            })
+            .collect()
+    }
+
     fn layout_lines(
         &self,
         rows: Range<u32>,
@@ -1910,6 +2083,15 @@ impl EditorElement {
             let is_singleton = editor.is_singleton(cx);
 
             let highlighted_rows = editor.highlighted_rows();
+            let (breakpoint_rows, debug_execution_row) =
+                editor.debugger_rows(&snapshot.display_snapshot, start_anchor..end_anchor, cx);
+            let mut status_highlighted_rows =
+                editor.merge_conflict_rows(&snapshot.display_snapshot, start_row..end_row, cx);
+            status_highlighted_rows.extend(editor.status_highlighted_rows(
+                &snapshot.display_snapshot,
+                start_row..end_row,
+                cx,
+            ));
             let highlighted_ranges = editor.background_highlights_in_range(
                 start_anchor..end_anchor,
                 &snapshot.display_snapshot,
@@ -2063,6 +2245,7 @@ impl EditorElement {
             );
 
             let display_hunks = self.layout_git_gutters(start_row..end_row, &snapshot);
+            let blame_lines = self.shape_blame_lines(start_row..end_row, &snapshot, editor, cx);
 
             let scrollbar_row_range = scroll_position.y..(scroll_position.y + height_in_lines);
 
@@ -2156,6 +2339,17 @@ impl EditorElement {
                 }
             }
 
+            let code_actions_row = code_actions_indicator.as_ref().map(|indicator| indicator.row);
+            let runnable_indicators = editor
+                .runnable_rows(&snapshot.display_snapshot, start_anchor..end_anchor, cx)
+                .into_iter()
+                .filter(|(row, _, _)| Some(*row) != code_actions_row)
+                .map(|(row, buffer, runnable)| RunnableIndicator {
+                    row,
+                    button: editor.render_runnable_indicator(row, buffer, runnable, cx),
+                })
+                .collect::<Vec<_>>();
+
             let visible_rows = start_row..start_row + line_layouts.len() as u32;
             let max_size = size(
                 (120. * em_width) // Default size
@@ -2172,13 +2366,28 @@ impl EditorElement {
                 editor.hover_state.render(
                 &snapshot,
                 &style,
-                visible_rows,
+                visible_rows.clone(),
                 max_size,
                 editor.workspace.as_ref().map(|(w, _)| w.clone()),
                 cx,
             )
             };
 
+            // Hover popovers are shown on request, so they take precedence over signature help.
+            let signature_help = if hover.is_some() {
+                None
+            } else {
+                editor.signature_help_state.render(
+                    &snapshot,
+                    &style,
+                    editor.selections.newest_anchor().head(),
+                    visible_rows,
+                    max_size,
+                    editor.workspace.as_ref().map(|(w, _)| w.clone()),
+                    cx,
+                )
+            };
+
             let editor_view = cx.view().clone();
             let fold_indicators = cx.with_element_context(|cx| {
 
@@ -2252,17 +2461,23 @@ impl EditorElement {
                 gutter_margin: gutter_dimensions.margin,
                 active_rows,
                 highlighted_rows,
+                breakpoint_rows,
+                debug_execution_row,
+                status_highlighted_rows,
                 highlighted_ranges,
                 line_numbers,
+                blame_lines,
                 display_hunks,
                 blocks,
                 selections,
                 context_menu,
                 code_actions_indicator,
+                runnable_indicators,
                 fold_indicators,
                 tab_invisible,
                 space_invisible,
                 hover_popovers: hover,
+                signature_help,
             }
         })
     }
@@ -3033,7 +3248,11 @@ pub struct LayoutState {
     visible_display_row_range: Range<u32>,
     active_rows: BTreeMap<u32, bool>,
     highlighted_rows: Option<Range<u32>>,
+    breakpoint_rows: Vec<u32>,
+    debug_execution_row: Option<u32>,
+    status_highlighted_rows: Vec<(Range<u32>, Hsla)>,
     line_numbers: Vec<Option<ShapedLine>>,
+    blame_lines: Vec<Option<ShapedLine>>,
     display_hunks: Vec<DisplayDiffHunk>,
     blocks: Vec<BlockLayout>,
     highlighted_ranges: Vec<(Range<DisplayPoint>, Hsla)>,
@@ -3044,7 +3263,9 @@ pub struct LayoutState {
     max_row: u32,
     context_menu: Option<(DisplayPoint, AnyElement)>,
     code_actions_indicator: Option<CodeActionsIndicator>,
+    runnable_indicators: Vec<RunnableIndicator>,
     hover_popovers: Option<(DisplayPoint, Vec<AnyElement>)>,
+    signature_help: Option<(DisplayPoint, AnyElement)>,
     fold_indicators: Vec<Option<IconButton>>,
     tab_invisible: ShapedLine,
     space_invisible: ShapedLine,
@@ -3055,6 +3276,11 @@ struct CodeActionsIndicator {
     button: IconButton,
 }
 
+struct RunnableIndicator {
+    row: u32,
+    button: IconButton,
+}
+
 struct PositionMap {
     size: Size<Pixels>,
     line_height: Pixels,
@@ -1,11 +1,18 @@
-use std::ops::Range;
+use std::{ops::Range, sync::Arc};
 
+use collections::{HashMap, HashSet};
 use git::diff::{DiffHunk, DiffHunkStatus};
-use language::Point;
+use gpui::{AppContext, EntityId, Model, StyledText};
+use language::{Buffer, Point, ToPoint};
+use multi_buffer::{Anchor, ExcerptId, MultiBufferSnapshot};
+use ui::prelude::*;
 
 use crate::{
-    display_map::{DisplaySnapshot, ToDisplayPoint},
-    AnchorRangeExt,
+    display_map::{
+        BlockContext, BlockDisposition, BlockId, BlockProperties, BlockStyle, DisplaySnapshot,
+        RenderBlock, ToDisplayPoint,
+    },
+    AnchorRangeExt, Editor, RevertHunk, ToggleHunkDiff,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -88,13 +95,330 @@ pub fn diff_hunk_to_display(hunk: DiffHunk<u32>, snapshot: &DisplaySnapshot) ->
     }
 }
 
+/// A diff hunk whose deleted base text is shown in a block above it.
+pub(crate) struct ExpandedHunk {
+    position: Anchor,
+    block: BlockId,
+    buffer_version: clock::Global,
+}
+
+impl Editor {
+    /// Restores the diff base text of every hunk touched by a selection.
+    pub fn revert_hunk(&mut self, _: &RevertHunk, cx: &mut ViewContext<Self>) {
+        let mut edits_by_buffer =
+            HashMap::<EntityId, (Model<Buffer>, Vec<(Range<Point>, String)>)>::default();
+        let mut reverted_positions = Vec::new();
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        for (excerpt_id, buffer, hunk) in self.diff_hunks_in_selections(cx) {
+            let Some(edit) = revert_edit(buffer.read(cx), &hunk) else {
+                continue;
+            };
+            reverted_positions.push(hunk_position(&snapshot, excerpt_id, &buffer, &hunk, cx));
+            edits_by_buffer
+                .entry(buffer.entity_id())
+                .or_insert_with(|| (buffer.clone(), Vec::new()))
+                .1
+                .push(edit);
+        }
+        if edits_by_buffer.is_empty() {
+            return;
+        }
+
+        self.transact(cx, |_, cx| {
+            for (buffer, edits) in edits_by_buffer.into_values() {
+                buffer.update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+            }
+        });
+
+        let mut blocks_to_remove = HashSet::default();
+        self.expanded_hunks.retain(|expanded| {
+            let reverted = reverted_positions
+                .iter()
+                .any(|position| position.cmp(&expanded.position, &snapshot).is_eq());
+            if reverted {
+                blocks_to_remove.insert(expanded.block);
+            }
+            !reverted
+        });
+        if !blocks_to_remove.is_empty() {
+            self.remove_blocks(blocks_to_remove, None, cx);
+        }
+    }
+
+    /// Shows or hides the deleted base text of every hunk touched by a selection.
+    pub fn toggle_hunk_diff(&mut self, _: &ToggleHunkDiff, cx: &mut ViewContext<Self>) {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let mut blocks_to_remove = HashSet::default();
+        let mut hunks_to_expand = Vec::new();
+        for (excerpt_id, buffer, hunk) in self.diff_hunks_in_selections(cx) {
+            let position = hunk_position(&snapshot, excerpt_id, &buffer, &hunk, cx);
+            if let Some(ix) = self
+                .expanded_hunks
+                .iter()
+                .position(|expanded| expanded.position.cmp(&position, &snapshot).is_eq())
+            {
+                blocks_to_remove.insert(self.expanded_hunks.remove(ix).block);
+            } else {
+                hunks_to_expand.push((position, buffer, hunk));
+            }
+        }
+
+        if !blocks_to_remove.is_empty() {
+            self.remove_blocks(blocks_to_remove, None, cx);
+        }
+        self.expand_hunks(hunks_to_expand, cx);
+    }
+
+    /// Shows the deleted base text of every hunk in the editor's excerpts.
+    pub fn expand_all_hunk_diffs(&mut self, cx: &mut ViewContext<Self>) {
+        let multi_buffer = self.buffer.read(cx);
+        let snapshot = multi_buffer.snapshot(cx);
+        let mut hunks_to_expand = Vec::new();
+        for (excerpt_id, buffer_snapshot, range) in snapshot.excerpts() {
+            let Some(buffer) = multi_buffer.buffer(buffer_snapshot.remote_id()) else {
+                continue;
+            };
+            let start_row = range.context.start.to_point(buffer_snapshot).row;
+            let end_row = range.context.end.to_point(buffer_snapshot).row;
+            for hunk in buffer_snapshot.git_diff_hunks_in_row_range(start_row..end_row + 1) {
+                let position = hunk_position(&snapshot, excerpt_id, &buffer, &hunk, cx);
+                let is_expanded = self
+                    .expanded_hunks
+                    .iter()
+                    .any(|expanded| expanded.position.cmp(&position, &snapshot).is_eq());
+                if !is_expanded {
+                    hunks_to_expand.push((position, buffer.clone(), hunk));
+                }
+            }
+        }
+        self.expand_hunks(hunks_to_expand, cx);
+    }
+
+    fn expand_hunks(
+        &mut self,
+        hunks: Vec<(Anchor, Model<Buffer>, DiffHunk<u32>)>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let mut blocks_to_insert = Vec::new();
+        let mut buffer_versions = Vec::new();
+        for (position, buffer, hunk) in hunks {
+            let buffer = buffer.read(cx);
+            let Some(diff_base) = buffer.diff_base() else {
+                continue;
+            };
+            let deleted_lines = diff_base[hunk.diff_base_byte_range.clone()]
+                .lines()
+                .map(|line| SharedString::from(line.to_string()))
+                .collect::<Vec<_>>();
+            if deleted_lines.is_empty() {
+                continue;
+            }
+            blocks_to_insert.push(deleted_lines_block(position, deleted_lines));
+            buffer_versions.push(buffer.version());
+        }
+        if blocks_to_insert.is_empty() {
+            return;
+        }
+
+        let positions = blocks_to_insert
+            .iter()
+            .map(|block| block.position)
+            .collect::<Vec<_>>();
+        let block_ids = self.insert_blocks(blocks_to_insert, None, cx);
+        self.expanded_hunks.extend(
+            positions
+                .into_iter()
+                .zip(block_ids)
+                .zip(buffer_versions)
+                .map(|((position, block), buffer_version)| ExpandedHunk {
+                    position,
+                    block,
+                    buffer_version,
+                }),
+        );
+    }
+
+    /// Hides the deleted lines of hunks whose buffer was edited since they were shown,
+    /// as the hunks no longer match the buffer until its diff is recalculated.
+    pub(crate) fn collapse_edited_hunks(&mut self, cx: &mut ViewContext<Self>) {
+        let multi_buffer = self.buffer.read(cx);
+        let mut blocks_to_remove = HashSet::default();
+        self.expanded_hunks.retain(|expanded| {
+            let edited = expanded
+                .position
+                .buffer_id
+                .and_then(|buffer_id| multi_buffer.buffer(buffer_id))
+                .map_or(true, |buffer| {
+                    buffer
+                        .read(cx)
+                        .version()
+                        .changed_since(&expanded.buffer_version)
+                });
+            if edited {
+                blocks_to_remove.insert(expanded.block);
+            }
+            !edited
+        });
+        if !blocks_to_remove.is_empty() {
+            self.remove_blocks(blocks_to_remove, None, cx);
+        }
+    }
+
+    pub(crate) fn collapse_expanded_hunks(&mut self, cx: &mut ViewContext<Self>) {
+        if self.expanded_hunks.is_empty() {
+            return;
+        }
+        let blocks = self
+            .expanded_hunks
+            .drain(..)
+            .map(|expanded| expanded.block)
+            .collect();
+        self.remove_blocks(blocks, None, cx);
+    }
+
+    /// The diff hunks touching each selection, with rows in the coordinates of their buffer.
+    fn diff_hunks_in_selections(
+        &self,
+        cx: &AppContext,
+    ) -> Vec<(ExcerptId, Model<Buffer>, DiffHunk<u32>)> {
+        let multi_buffer = self.buffer.read(cx);
+        let mut hunks = Vec::<(ExcerptId, Model<Buffer>, DiffHunk<u32>)>::new();
+        for selection in self.selections.all::<Point>(cx) {
+            let Some((buffer, start_offset, excerpt_id)) =
+                multi_buffer.point_to_buffer_offset(selection.start, cx)
+            else {
+                continue;
+            };
+            let buffer_snapshot = buffer.read(cx).snapshot();
+            let start_row = buffer_snapshot.offset_to_point(start_offset).row;
+            let end_row = match multi_buffer.point_to_buffer_offset(selection.end, cx) {
+                Some((_, end_offset, end_excerpt_id)) if end_excerpt_id == excerpt_id => {
+                    buffer_snapshot.offset_to_point(end_offset).row
+                }
+                _ => start_row,
+            };
+
+            for hunk in buffer_snapshot.git_diff_hunks_in_row_range(start_row..end_row + 1) {
+                let rows = &hunk.buffer_range;
+                let touches_selection = if rows.is_empty() {
+                    (start_row..=end_row).contains(&rows.start)
+                } else {
+                    rows.start <= end_row && rows.end > start_row
+                };
+                let is_new = !hunks.iter().any(|(_, other_buffer, other_hunk)| {
+                    *other_buffer == buffer && *other_hunk == hunk
+                });
+                if touches_selection && is_new {
+                    hunks.push((excerpt_id, buffer.clone(), hunk));
+                }
+            }
+        }
+        hunks
+    }
+}
+
+fn hunk_position(
+    snapshot: &MultiBufferSnapshot,
+    excerpt_id: ExcerptId,
+    buffer: &Model<Buffer>,
+    hunk: &DiffHunk<u32>,
+    cx: &AppContext,
+) -> Anchor {
+    let start = buffer
+        .read(cx)
+        .anchor_before(Point::new(hunk.buffer_range.start, 0));
+    snapshot.anchor_in_excerpt(excerpt_id, start)
+}
+
+/// The edit that replaces the hunk's rows with their diff base text.
+fn revert_edit(buffer: &Buffer, hunk: &DiffHunk<u32>) -> Option<(Range<Point>, String)> {
+    let diff_base = buffer.diff_base()?;
+    let max_point = buffer.max_point();
+    let row_start = |row: u32| {
+        if row > max_point.row {
+            max_point
+        } else {
+            Point::new(row, 0)
+        }
+    };
+    Some((
+        row_start(hunk.buffer_range.start)..row_start(hunk.buffer_range.end),
+        diff_base[hunk.diff_base_byte_range.clone()].to_string(),
+    ))
+}
+
+/// The most deleted lines a block shows. Blocks are at most `u8::MAX` lines tall, and
+/// the last line is kept for a footer counting the lines that aren't shown.
+const MAX_DELETED_LINES_SHOWN: usize = u8::MAX as usize - 1;
+
+/// A block showing lines that were deleted just above `position`, styled like the
+/// deleted lines of an expanded diff hunk.
+pub fn deleted_lines_block(
+    position: Anchor,
+    deleted_lines: Vec<SharedString>,
+) -> BlockProperties<Anchor> {
+    let hidden_line_count = deleted_lines.len().saturating_sub(MAX_DELETED_LINES_SHOWN);
+    let height =
+        deleted_lines.len().min(MAX_DELETED_LINES_SHOWN) + (hidden_line_count > 0) as usize;
+    BlockProperties {
+        position,
+        height: height as u8,
+        style: BlockStyle::Flex,
+        disposition: BlockDisposition::Above,
+        render: deleted_hunk_renderer(deleted_lines, hidden_line_count),
+    }
+}
+
+fn deleted_hunk_renderer(
+    deleted_lines: Vec<SharedString>,
+    hidden_line_count: usize,
+) -> RenderBlock {
+    Arc::new(move |cx: &mut BlockContext| {
+        let mut text_style = cx.editor_style.text.clone();
+        text_style.color = cx.theme().status().deleted;
+        let line_height = cx.line_height;
+
+        v_flex()
+            .id(cx.block_id)
+            .w(cx.max_width + cx.gutter_width)
+            .pl(cx.anchor_x)
+            .bg(cx.theme().status().deleted_background)
+            .children(
+                deleted_lines
+                    .iter()
+                    .take(MAX_DELETED_LINES_SHOWN)
+                    .map(|line| {
+                        div().h(line_height).child(
+                            StyledText::new(line.clone()).with_highlights(&text_style, Vec::new()),
+                        )
+                    }),
+            )
+            .when(hidden_line_count > 0, |this| {
+                this.child(
+                    div().h(line_height).child(
+                        Label::new(format!(
+                            "… {hidden_line_count} more deleted {}",
+                            if hidden_line_count == 1 {
+                                "line"
+                            } else {
+                                "lines"
+                            }
+                        ))
+                        .color(Color::Muted),
+                    ),
+                )
+            })
+            .into_any_element()
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use crate::editor_tests::init_test;
     use crate::Point;
-    use gpui::{Context, TestAppContext};
+    use gpui::{Context, SharedString, TestAppContext};
     use language::Capability::ReadWrite;
-    use multi_buffer::{ExcerptRange, MultiBuffer};
+    use multi_buffer::{Anchor, ExcerptRange, MultiBuffer};
     use project::{FakeFs, Project};
     use unindent::Unindent;
     #[gpui::test]
@@ -280,4 +604,26 @@ mod tests {
                 .as_slice(),
         );
     }
+
+    #[test]
+    fn test_deleted_lines_block_height() {
+        let lines = |count: usize| {
+            (0..count)
+                .map(|ix| SharedString::from(ix.to_string()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            super::deleted_lines_block(Anchor::min(), lines(3)).height,
+            3
+        );
+        // Long deletions are cut off with a footer line rather than truncated silently.
+        assert_eq!(
+            super::deleted_lines_block(Anchor::min(), lines(254)).height,
+            254
+        );
+        assert_eq!(
+            super::deleted_lines_block(Anchor::min(), lines(300)).height,
+            u8::MAX
+        );
+    }
 }
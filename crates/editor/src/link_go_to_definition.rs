@@ -2,19 +2,31 @@ use crate::{
     display_map::DisplaySnapshot,
     element::PointForPosition,
     hover_popover::{self, InlayHover},
-    Anchor, DisplayPoint, Editor, EditorSnapshot, GoToDefinition, GoToTypeDefinition, InlayId,
-    SelectPhase,
+    scroll::Autoscroll,
+    Anchor, DisplayPoint, Editor, EditorSnapshot, GoToDefinition, GoToTypeDefinition,
+    GotoDefinitionKind, InlayId, SelectPhase,
 };
-use gpui::{px, Task, ViewContext};
-use language::{Bias, ToOffset};
+use collections::HashMap;
+use gpui::{px, AsyncWindowContext, Model, Task, View, ViewContext, WeakView};
+use language::{Bias, Buffer, Point, ToOffset};
+use lazy_static::lazy_static;
 use lsp::LanguageServerId;
+use multi_buffer::{ExcerptId, MultiBufferSnapshot, ToPoint as _};
 use project::{
-    HoverBlock, HoverBlockKind, InlayHintLabelPartTooltip, InlayHintTooltip, LocationLink,
-    ResolveState,
+    DocumentLink, HoverBlock, HoverBlockKind, InlayHintLabelPartTooltip, InlayHintTooltip,
+    Item as _, LocationLink, Project, ProjectPath, ResolveState,
 };
-use std::ops::Range;
+use regex::Regex;
+use std::{ops::Range, path::Path};
 use theme::ActiveTheme as _;
-use util::TryFutureExt;
+use util::{ResultExt, TryFutureExt};
+use workspace::Workspace;
+
+lazy_static! {
+    static ref URL_REGEX: Regex = Regex::new(r#"https?://[^\s<>"'`]+"#).unwrap();
+    static ref PATH_REGEX: Regex =
+        Regex::new(r"(?P<path>[\w\-.~/]*[\w\-]+)(?::(?P<row>\d+)(?::(?P<column>\d+))?)?").unwrap();
+}
 
 #[derive(Debug, Default)]
 pub struct LinkGoToDefinitionState {
@@ -23,6 +35,8 @@ pub struct LinkGoToDefinitionState {
     pub kind: Option<LinkDefinitionKind>,
     pub definitions: Vec<GoToDefinitionLink>,
     pub task: Option<Task<Option<()>>>,
+    /// The document links of each buffer, along with the buffer version they were requested at.
+    document_links: HashMap<u64, (clock::Global, Vec<DocumentLink>)>,
 }
 
 #[derive(Debug, Eq, PartialEq, Clone)]
@@ -66,6 +80,20 @@ pub enum GoToDefinitionTrigger {
 pub enum GoToDefinitionLink {
     Text(LocationLink),
     InlayHint(lsp::Location, LanguageServerId),
+    /// A URL to open in the system browser.
+    Url(String),
+    /// A project file to open, optionally at a zero-based position.
+    File(ProjectPath, Option<Point>),
+}
+
+/// A URL or a file path written in the text of a buffer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TextLink {
+    Url(String),
+    Path {
+        path: String,
+        position: Option<Point>,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -396,41 +424,77 @@ pub fn show_link_definition(
     let task = cx.spawn(|this, mut cx| {
         async move {
             let result = match &trigger_point {
-                TriggerPoint::Text(_) => {
-                    // query the LSP for definition info
-                    project
-                        .update(&mut cx, |project, cx| match definition_kind {
-                            LinkDefinitionKind::Symbol => {
-                                project.definition(&buffer, buffer_position, cx)
-                            }
+                TriggerPoint::Text(trigger_anchor) => {
+                    let text_link = if definition_kind == LinkDefinitionKind::Symbol {
+                        text_link_at(&snapshot.buffer_snapshot, trigger_anchor)
+                    } else {
+                        None
+                    };
+                    if let Some((range, TextLink::Url(url))) = text_link {
+                        Some((
+                            Some(RangeInEditor::Text(range)),
+                            vec![GoToDefinitionLink::Url(url)],
+                        ))
+                    } else {
+                        // query the LSP for definition info
+                        let definitions = project
+                            .update(&mut cx, |project, cx| match definition_kind {
+                                LinkDefinitionKind::Symbol => {
+                                    project.definition(&buffer, buffer_position, cx)
+                                }
 
-                            LinkDefinitionKind::Type => {
-                                project.type_definition(&buffer, buffer_position, cx)
-                            }
-                        })?
-                        .await
-                        .ok()
-                        .map(|definition_result| {
-                            (
-                                definition_result.iter().find_map(|link| {
-                                    link.origin.as_ref().map(|origin| {
-                                        let start = snapshot.buffer_snapshot.anchor_in_excerpt(
-                                            excerpt_id.clone(),
-                                            origin.range.start,
-                                        );
-                                        let end = snapshot.buffer_snapshot.anchor_in_excerpt(
-                                            excerpt_id.clone(),
-                                            origin.range.end,
-                                        );
-                                        RangeInEditor::Text(start..end)
-                                    })
-                                }),
-                                definition_result
-                                    .into_iter()
-                                    .map(GoToDefinitionLink::Text)
-                                    .collect(),
+                                LinkDefinitionKind::Type => {
+                                    project.type_definition(&buffer, buffer_position, cx)
+                                }
+                            })?
+                            .await
+                            .ok()
+                            .map(|definition_result| {
+                                (
+                                    definition_result.iter().find_map(|link| {
+                                        link.origin.as_ref().map(|origin| {
+                                            let start = snapshot.buffer_snapshot.anchor_in_excerpt(
+                                                excerpt_id.clone(),
+                                                origin.range.start,
+                                            );
+                                            let end = snapshot.buffer_snapshot.anchor_in_excerpt(
+                                                excerpt_id.clone(),
+                                                origin.range.end,
+                                            );
+                                            RangeInEditor::Text(start..end)
+                                        })
+                                    }),
+                                    definition_result
+                                        .into_iter()
+                                        .map(GoToDefinitionLink::Text)
+                                        .collect::<Vec<_>>(),
+                                )
+                            });
+
+                        let has_definitions = definitions
+                            .as_ref()
+                            .map_or(false, |(_, definitions)| !definitions.is_empty());
+                        if !has_definitions && definition_kind == LinkDefinitionKind::Symbol {
+                            // Fall back to the document links of the language server, and then
+                            // to the file paths written in the buffer.
+                            match link_at(
+                                &this,
+                                &buffer,
+                                buffer_position,
+                                excerpt_id,
+                                text_link,
+                                &snapshot.buffer_snapshot,
+                                &mut cx,
                             )
-                        })
+                            .await
+                            {
+                                Some((range, link)) => Some((Some(range), vec![link])),
+                                None => definitions,
+                            }
+                        } else {
+                            definitions
+                        }
+                    }
                 }
                 TriggerPoint::InlayHint(highlight, lsp_location, server_id) => Some((
                     Some(RangeInEditor::Inlay(highlight.clone())),
@@ -481,7 +545,9 @@ pub fn show_link_definition(
                                         true
                                     }
                                 }
-                                GoToDefinitionLink::InlayHint(_, _) => true,
+                                GoToDefinitionLink::InlayHint(_, _)
+                                | GoToDefinitionLink::Url(_)
+                                | GoToDefinitionLink::File(_, _) => true,
                             }
                         });
 
@@ -588,7 +654,11 @@ fn go_to_fetched_definition_of_kind(
             cx.focus(&editor.focus_handle);
         }
 
-        editor.navigate_to_definitions(cached_definitions, split, cx);
+        let kind = match kind {
+            LinkDefinitionKind::Symbol => GotoDefinitionKind::Symbol,
+            LinkDefinitionKind::Type => GotoDefinitionKind::Type,
+        };
+        editor.navigate_to_definitions(cached_definitions, kind, split, cx);
     } else {
         editor.select(
             SelectPhase::Begin {
@@ -608,6 +678,196 @@ fn go_to_fetched_definition_of_kind(
     }
 }
 
+/// Finds the URL or file path written around `anchor`.
+fn text_link_at(
+    snapshot: &MultiBufferSnapshot,
+    anchor: &Anchor,
+) -> Option<(Range<Anchor>, TextLink)> {
+    let point = anchor.to_point(snapshot);
+    let line = snapshot
+        .text_for_range(
+            Point::new(point.row, 0)..Point::new(point.row, snapshot.line_len(point.row)),
+        )
+        .collect::<String>();
+    let (range, link) = find_link_in_line(&line, point.column as usize)?;
+    let start = snapshot.anchor_before(Point::new(point.row, range.start as u32));
+    let end = snapshot.anchor_after(Point::new(point.row, range.end as u32));
+    Some((start..end, link))
+}
+
+/// Finds the URL or file path in `line` that contains the byte offset `offset`. Paths may
+/// be followed by a one-based `:line:column` suffix.
+fn find_link_in_line(line: &str, offset: usize) -> Option<(Range<usize>, TextLink)> {
+    for url_match in URL_REGEX.find_iter(line) {
+        let url = url_match
+            .as_str()
+            .trim_end_matches(|c| matches!(c, '.' | ',' | ';' | ':' | '!' | '?' | ')' | ']'));
+        let range = url_match.start()..url_match.start() + url.len();
+        if range.contains(&offset) {
+            return Some((range, TextLink::Url(url.to_string())));
+        }
+    }
+
+    for captures in PATH_REGEX.captures_iter(line) {
+        let path_match = captures.get(0)?;
+        if !path_match.range().contains(&offset) {
+            continue;
+        }
+        let path = &captures["path"];
+        if !path.contains(|c| c == '/' || c == '.') {
+            return None;
+        }
+        let row = captures
+            .name("row")
+            .and_then(|row| row.as_str().parse::<u32>().ok());
+        let column = captures
+            .name("column")
+            .and_then(|column| column.as_str().parse::<u32>().ok());
+        let position =
+            row.map(|row| Point::new(row.saturating_sub(1), column.unwrap_or(1).saturating_sub(1)));
+        return Some((
+            path_match.range(),
+            TextLink::Path {
+                path: path.to_string(),
+                position,
+            },
+        ));
+    }
+
+    None
+}
+
+/// Finds the document link of the language server at `buffer_position`, or else an
+/// existing project file whose path is written there.
+async fn link_at(
+    editor: &WeakView<Editor>,
+    buffer: &Model<Buffer>,
+    buffer_position: text::Anchor,
+    excerpt_id: ExcerptId,
+    text_link: Option<(Range<Anchor>, TextLink)>,
+    snapshot: &MultiBufferSnapshot,
+    cx: &mut AsyncWindowContext,
+) -> Option<(RangeInEditor, GoToDefinitionLink)> {
+    let project = editor
+        .update(cx, |editor, _| editor.project.clone())
+        .ok()??;
+    let document_links = document_links(editor, &project, buffer, cx)
+        .await
+        .unwrap_or_default();
+    let document_link = buffer
+        .update(cx, |buffer, _| {
+            document_links.into_iter().find(|link| {
+                link.range.start.cmp(&buffer_position, buffer).is_le()
+                    && link.range.end.cmp(&buffer_position, buffer).is_ge()
+            })
+        })
+        .ok()?;
+    if let Some(document_link) = document_link {
+        let start = snapshot.anchor_in_excerpt(excerpt_id, document_link.range.start);
+        let end = snapshot.anchor_in_excerpt(excerpt_id, document_link.range.end);
+        let link = if document_link.target.scheme() == "file" {
+            let path = document_link.target.to_file_path().ok()?;
+            let project_path = project
+                .update(cx, |project, cx| {
+                    project.project_path_for_link(&path, None, cx)
+                })
+                .ok()??;
+            GoToDefinitionLink::File(project_path, None)
+        } else {
+            GoToDefinitionLink::Url(document_link.target.to_string())
+        };
+        return Some((RangeInEditor::Text(start..end), link));
+    }
+
+    let (range, TextLink::Path { path, position }) = text_link? else {
+        return None;
+    };
+    let project_path = project
+        .update(cx, |project, cx| {
+            let relative_to = buffer.read(cx).project_path(cx);
+            project.project_path_for_link(Path::new(&path), relative_to.as_ref(), cx)
+        })
+        .ok()??;
+    Some((
+        RangeInEditor::Text(range),
+        GoToDefinitionLink::File(project_path, position),
+    ))
+}
+
+/// Returns the document links of `buffer`, only requesting them again from the language
+/// server once the buffer has changed.
+async fn document_links(
+    editor: &WeakView<Editor>,
+    project: &Model<Project>,
+    buffer: &Model<Buffer>,
+    cx: &mut AsyncWindowContext,
+) -> Option<Vec<DocumentLink>> {
+    let (buffer_id, version) = buffer
+        .update(cx, |buffer, _| (buffer.remote_id(), buffer.version()))
+        .ok()?;
+    let cached_links = editor
+        .update(cx, |editor, _| {
+            let (links_version, links) = editor
+                .link_go_to_definition_state
+                .document_links
+                .get(&buffer_id)?;
+            (*links_version == version).then(|| links.clone())
+        })
+        .ok()?;
+    if cached_links.is_some() {
+        return cached_links;
+    }
+
+    let links = project
+        .update(cx, |project, cx| project.document_links(buffer, cx))
+        .ok()?
+        .await
+        .log_err()?;
+    editor
+        .update(cx, |editor, _| {
+            editor
+                .link_go_to_definition_state
+                .document_links
+                .insert(buffer_id, (version, links.clone()));
+        })
+        .ok()?;
+    Some(links)
+}
+
+/// Opens a file link in the workspace and moves the cursor to its position, if any.
+pub(crate) fn open_file_link(
+    workspace: View<Workspace>,
+    project_path: ProjectPath,
+    position: Option<Point>,
+    split: bool,
+    cx: &mut ViewContext<Editor>,
+) {
+    let open_task = workspace.update(cx, |workspace, cx| {
+        if split {
+            workspace.split_path(project_path, cx)
+        } else {
+            workspace.open_path(project_path, None, true, cx)
+        }
+    });
+    cx.spawn(|_, mut cx| async move {
+        let item = open_task.await?;
+        if let Some((position, editor)) = position.zip(item.downcast::<Editor>()) {
+            editor.update(&mut cx, |editor, cx| {
+                let position = editor
+                    .buffer()
+                    .read(cx)
+                    .snapshot(cx)
+                    .clip_point(position, Bias::Left);
+                editor.change_selections(Some(Autoscroll::center()), cx, |s| {
+                    s.select_ranges([position..position])
+                });
+            })?;
+        }
+        anyhow::Ok(())
+    })
+    .detach_and_log_err(cx);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -622,6 +882,10 @@ mod tests {
     use indoc::indoc;
     use language::language_settings::InlayHintSettings;
     use lsp::request::{GotoDefinition, GotoTypeDefinition};
+    use std::sync::{
+        atomic::{self, AtomicUsize},
+        Arc,
+    };
     use util::assert_set_eq;
 
     #[gpui::test]
@@ -1273,4 +1537,218 @@ mod tests {
                 }
             "});
     }
+
+    #[gpui::test]
+    async fn test_link_go_to_url_and_path(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+
+        let mut cx = EditorLspTestContext::new_rust(Default::default(), cx).await;
+        cx.set_state(indoc! {"
+                ˇ// See https://zed.dev/docs and file.rs:3:5
+                fn main() {
+                    test();
+                }
+            "});
+
+        // URLs are highlighted without asking the language server and open in the browser.
+        let hover_point = cx.display_point(indoc! {"
+                // See https://zed.dev/dˇocs and file.rs:3:5
+                fn main() {
+                    test();
+                }
+            "});
+        cx.update_editor(|editor, cx| {
+            update_go_to_definition_link(
+                editor,
+                Some(GoToDefinitionTrigger::Text(hover_point)),
+                true,
+                false,
+                cx,
+            );
+        });
+        cx.background_executor.run_until_parked();
+        cx.assert_editor_text_highlights::<LinkGoToDefinitionState>(indoc! {"
+                // See «https://zed.dev/docs» and file.rs:3:5
+                fn main() {
+                    test();
+                }
+            "});
+        cx.update_editor(|editor, cx| {
+            go_to_fetched_definition(editor, PointForPosition::valid(hover_point), false, cx);
+        });
+        assert_eq!(cx.opened_url(), Some("https://zed.dev/docs".to_string()));
+
+        // Paths are used when there is no definition, and open the file at their position.
+        let hover_point = cx.display_point(indoc! {"
+                // See https://zed.dev/docs and file.rˇs:3:5
+                fn main() {
+                    test();
+                }
+            "});
+        let mut requests =
+            cx.handle_request::<GotoDefinition, _, _>(|_, _, _| async move { Ok(None) });
+        cx.update_editor(|editor, cx| {
+            update_go_to_definition_link(
+                editor,
+                Some(GoToDefinitionTrigger::Text(hover_point)),
+                true,
+                false,
+                cx,
+            );
+        });
+        requests.next().await;
+        cx.background_executor.run_until_parked();
+        cx.assert_editor_text_highlights::<LinkGoToDefinitionState>(indoc! {"
+                // See https://zed.dev/docs and «file.rs:3:5»
+                fn main() {
+                    test();
+                }
+            "});
+        cx.update_editor(|editor, cx| {
+            go_to_fetched_definition(editor, PointForPosition::valid(hover_point), false, cx);
+        });
+        cx.background_executor.run_until_parked();
+        cx.assert_editor_state(indoc! {"
+                // See https://zed.dev/docs and file.rs:3:5
+                fn main() {
+                    ˇtest();
+                }
+            "});
+    }
+
+    #[gpui::test]
+    async fn test_link_go_to_document_link(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                document_link_provider: Some(lsp::DocumentLinkOptions {
+                    resolve_provider: None,
+                    work_done_progress_options: Default::default(),
+                }),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+        cx.set_state(indoc! {"
+                ˇ// Read the guide first.
+                fn main() {}
+            "});
+
+        let link_range = cx.lsp_range(indoc! {"
+                // Read the «guide» first.
+                fn main() {}
+            "});
+        let link_requests = Arc::new(AtomicUsize::new(0));
+        cx.lsp
+            .handle_request::<lsp::request::DocumentLinkRequest, _, _>({
+                let link_requests = link_requests.clone();
+                move |_, _| {
+                    link_requests.fetch_add(1, atomic::Ordering::SeqCst);
+                    async move {
+                        Ok(Some(vec![lsp::DocumentLink {
+                            range: link_range,
+                            target: Some(lsp::Url::parse("https://zed.dev/guide").unwrap()),
+                            tooltip: None,
+                            data: None,
+                        }]))
+                    }
+                }
+            });
+        cx.lsp
+            .handle_request::<GotoDefinition, _, _>(|_, _| async move { Ok(None) });
+
+        let link_point = cx.display_point(indoc! {"
+                // Read the guˇide first.
+                fn main() {}
+            "});
+        let other_point = cx.display_point(indoc! {"
+                // Read the guide fiˇrst.
+                fn main() {}
+            "});
+        let hover = |point: DisplayPoint, cx: &mut EditorLspTestContext| {
+            cx.update_editor(|editor, cx| {
+                update_go_to_definition_link(
+                    editor,
+                    Some(GoToDefinitionTrigger::Text(point)),
+                    true,
+                    false,
+                    cx,
+                );
+            });
+            cx.background_executor.run_until_parked();
+        };
+
+        hover(link_point, &mut cx);
+        cx.assert_editor_text_highlights::<LinkGoToDefinitionState>(indoc! {"
+                // Read the «guide» first.
+                fn main() {}
+            "});
+        assert_eq!(link_requests.load(atomic::Ordering::SeqCst), 1);
+
+        // The links are only requested again once the buffer changes.
+        hover(other_point, &mut cx);
+        hover(link_point, &mut cx);
+        cx.assert_editor_text_highlights::<LinkGoToDefinitionState>(indoc! {"
+                // Read the «guide» first.
+                fn main() {}
+            "});
+        assert_eq!(link_requests.load(atomic::Ordering::SeqCst), 1);
+
+        cx.update_editor(|editor, cx| editor.handle_input(" ", cx));
+        hover(other_point, &mut cx);
+        hover(link_point, &mut cx);
+        assert_eq!(link_requests.load(atomic::Ordering::SeqCst), 2);
+
+        cx.update_editor(|editor, cx| {
+            go_to_fetched_definition(editor, PointForPosition::valid(link_point), false, cx);
+        });
+        assert_eq!(cx.opened_url(), Some("https://zed.dev/guide".to_string()));
+    }
+
+    #[test]
+    fn test_find_link_in_line() {
+        let line = "See https://zed.dev/docs. for details";
+        assert_eq!(
+            find_link_in_line(line, 10),
+            Some((4..24, TextLink::Url("https://zed.dev/docs".to_string())))
+        );
+        assert_eq!(find_link_in_line(line, 30), None);
+
+        let line = "error at src/main.rs:10:5: expected `;`";
+        assert_eq!(
+            find_link_in_line(line, 12),
+            Some((
+                9..25,
+                TextLink::Path {
+                    path: "src/main.rs".to_string(),
+                    position: Some(Point::new(9, 4)),
+                }
+            ))
+        );
+
+        let line = "open ~/notes.md or ../README";
+        assert_eq!(
+            find_link_in_line(line, 6),
+            Some((
+                5..15,
+                TextLink::Path {
+                    path: "~/notes.md".to_string(),
+                    position: None,
+                }
+            ))
+        );
+        assert_eq!(
+            find_link_in_line(line, 20),
+            Some((
+                19..28,
+                TextLink::Path {
+                    path: "../README".to_string(),
+                    position: None,
+                }
+            ))
+        );
+        assert_eq!(find_link_in_line(line, 1), None);
+    }
 }
@@ -0,0 +1,159 @@
+use crate::{Editor, EditorMode};
+use gpui::{Task, ViewContext};
+use language::{language_settings::language_settings, Point};
+use multi_buffer::{Anchor, MultiBufferSnapshot, ToOffset, ToPoint};
+use std::{ops::Range, sync::Arc, time::Duration};
+use util::TryFutureExt;
+
+pub(crate) const LINKED_EDITING_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(50);
+
+#[derive(Default)]
+pub(crate) struct LinkedEditingState {
+    /// Ranges whose text is kept identical while any of them is edited, such as the names of
+    /// an opening and a closing tag.
+    ranges: Vec<Range<Anchor>>,
+    refresh_task: Option<Task<Option<()>>>,
+}
+
+impl Editor {
+    /// Keeps the linked editing ranges while every selection is within them, and otherwise
+    /// drops them and requests the ranges linked to the newest cursor.
+    pub(crate) fn refresh_linked_editing_ranges(&mut self, cx: &mut ViewContext<Self>) {
+        if self.mode != EditorMode::Full {
+            return;
+        }
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        if self.selections_within_linked_ranges(&snapshot) {
+            return;
+        }
+        self.linked_editing_state.ranges.clear();
+        self.linked_editing_state.refresh_task = None;
+
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        let cursor = self.selections.newest_anchor().head();
+        let Some((buffer, position)) = self.buffer.read(cx).text_anchor_for_position(cursor, cx)
+        else {
+            return;
+        };
+        let enabled = {
+            let buffer = buffer.read(cx);
+            language_settings(buffer.language(), buffer.file(), cx).linked_edits
+        };
+        if !enabled {
+            return;
+        }
+
+        let excerpt_id = cursor.excerpt_id;
+        self.linked_editing_state.refresh_task = Some(cx.spawn(|editor, mut cx| {
+            async move {
+                cx.background_executor()
+                    .timer(LINKED_EDITING_DEBOUNCE_TIMEOUT)
+                    .await;
+                let ranges = project
+                    .update(&mut cx, |project, cx| {
+                        project.linked_editing_ranges(&buffer, position, cx)
+                    })?
+                    .await?;
+
+                editor.update(&mut cx, |editor, cx| {
+                    let snapshot = editor.buffer.read(cx).snapshot(cx);
+                    editor.linked_editing_state.ranges = ranges
+                        .into_iter()
+                        .map(|range| {
+                            snapshot.anchor_in_excerpt(excerpt_id, range.start)
+                                ..snapshot.anchor_in_excerpt(excerpt_id, range.end)
+                        })
+                        .collect();
+                    if !editor.selections_within_linked_ranges(&snapshot) {
+                        editor.linked_editing_state.ranges.clear();
+                    }
+                })
+            }
+            .log_err()
+        }));
+    }
+
+    /// Returns the edits that apply `edits` to the other linked editing ranges too. No edits
+    /// are returned once the linked ranges no longer have the same text.
+    pub(crate) fn linked_edits(
+        &self,
+        edits: &[(Range<Point>, Arc<str>)],
+        snapshot: &MultiBufferSnapshot,
+    ) -> Vec<(Range<Point>, Arc<str>)> {
+        let ranges = self
+            .linked_editing_state
+            .ranges
+            .iter()
+            .map(|range| range.start.to_offset(snapshot)..range.end.to_offset(snapshot))
+            .collect::<Vec<_>>();
+        let Some(first_range) = ranges.first() else {
+            return Vec::new();
+        };
+        let text = snapshot
+            .text_for_range(first_range.clone())
+            .collect::<String>();
+        if ranges
+            .iter()
+            .any(|range| range.len() != text.len() || !snapshot.contains_str_at(range.start, &text))
+        {
+            return Vec::new();
+        }
+
+        let edits = edits
+            .iter()
+            .map(|(range, new_text)| {
+                (
+                    range.start.to_offset(snapshot)..range.end.to_offset(snapshot),
+                    new_text.clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+        let mut linked_edits = Vec::<(Range<usize>, Arc<str>)>::new();
+        for (edit_range, new_text) in &edits {
+            let Some(containing_ix) = ranges
+                .iter()
+                .position(|range| range.start <= edit_range.start && edit_range.end <= range.end)
+            else {
+                continue;
+            };
+            let start = edit_range.start - ranges[containing_ix].start;
+            let end = edit_range.end - ranges[containing_ix].start;
+            for (ix, range) in ranges.iter().enumerate() {
+                if ix == containing_ix {
+                    continue;
+                }
+                let linked_range = range.start + start..range.start + end;
+                // Leave the range alone if it's already being edited, e.g. by another cursor.
+                let overlaps_edit = edits.iter().chain(&linked_edits).any(|(other_range, _)| {
+                    other_range.start <= linked_range.end && linked_range.start <= other_range.end
+                });
+                if !overlaps_edit {
+                    linked_edits.push((linked_range, new_text.clone()));
+                }
+            }
+        }
+
+        linked_edits
+            .into_iter()
+            .map(|(range, new_text)| {
+                (
+                    range.start.to_point(snapshot)..range.end.to_point(snapshot),
+                    new_text,
+                )
+            })
+            .collect()
+    }
+
+    fn selections_within_linked_ranges(&self, snapshot: &MultiBufferSnapshot) -> bool {
+        let ranges = &self.linked_editing_state.ranges;
+        !ranges.is_empty()
+            && self.selections.disjoint_anchors().iter().all(|selection| {
+                ranges.iter().any(|range| {
+                    range.start.cmp(&selection.start, snapshot).is_le()
+                        && range.end.cmp(&selection.end, snapshot).is_ge()
+                })
+            })
+    }
+}
@@ -0,0 +1,162 @@
+use crate::{
+    display_map::{
+        BlockContext, BlockDisposition, BlockProperties, BlockStyle, DisplaySnapshot, RenderBlock,
+        ToDisplayPoint,
+    },
+    Anchor, Editor, EditorMode, MultiBufferSnapshot,
+};
+use gpui::{AppContext, Hsla};
+use language::MergeConflict;
+use std::{mem, ops::Range, sync::Arc};
+use ui::{prelude::*, Button};
+
+/// The version of a conflicted region that the user chose to keep.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConflictResolution {
+    Current,
+    Incoming,
+    Both,
+}
+
+impl Editor {
+    /// Rebuilds the highlights and the "Accept" blocks of the merge conflicts in the
+    /// editor's excerpts.
+    pub(crate) fn refresh_merge_conflicts(&mut self, cx: &mut ViewContext<Self>) {
+        if self.mode != EditorMode::Full {
+            return;
+        }
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let conflicts = merge_conflicts_in_excerpts(&snapshot);
+        if conflicts.is_empty() && self.merge_conflicts.is_empty() {
+            return;
+        }
+
+        let old_blocks = mem::take(&mut self.merge_conflict_blocks);
+        self.remove_blocks(old_blocks.into_iter().collect(), None, cx);
+        self.merge_conflict_blocks = self.insert_blocks(
+            conflicts.iter().map(|conflict| BlockProperties {
+                position: conflict.range.start,
+                height: 1,
+                style: BlockStyle::Flex,
+                disposition: BlockDisposition::Above,
+                render: merge_conflict_block_renderer(conflict.clone()),
+            }),
+            None,
+            cx,
+        );
+        self.merge_conflicts = conflicts;
+        cx.notify();
+    }
+
+    /// Replaces a conflicted region, including its markers, with the chosen version.
+    pub fn resolve_merge_conflict(
+        &mut self,
+        conflict: &MergeConflict<Anchor>,
+        resolution: ConflictResolution,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let text_for_range =
+            |range: &Range<Anchor>| snapshot.text_for_range(range.clone()).collect::<String>();
+        let text = match resolution {
+            ConflictResolution::Current => text_for_range(&conflict.ours),
+            ConflictResolution::Incoming => text_for_range(&conflict.theirs),
+            ConflictResolution::Both => {
+                text_for_range(&conflict.ours) + &text_for_range(&conflict.theirs)
+            }
+        };
+        self.transact(cx, |editor, cx| {
+            editor.buffer.update(cx, |buffer, cx| {
+                buffer.edit([(conflict.range.clone(), text)], None, cx)
+            });
+        });
+    }
+
+    /// The display rows of the visible conflicted regions, with the color to highlight
+    /// them in. The separator between both sides is left unhighlighted.
+    pub(crate) fn merge_conflict_rows(
+        &self,
+        snapshot: &DisplaySnapshot,
+        rows: Range<u32>,
+        cx: &AppContext,
+    ) -> Vec<(Range<u32>, Hsla)> {
+        let status = cx.theme().status();
+        let display_row = |anchor: &Anchor| anchor.to_display_point(snapshot).row();
+        let mut conflict_rows = Vec::new();
+        for conflict in &self.merge_conflicts {
+            let start_row = display_row(&conflict.range.start);
+            let end = conflict.range.end.to_display_point(snapshot);
+            let end_row = if end.column() > 0 {
+                end.row() + 1
+            } else {
+                end.row()
+            };
+            if start_row >= rows.end || end_row <= rows.start {
+                continue;
+            }
+
+            conflict_rows.push((
+                start_row..display_row(&conflict.ours.end),
+                status.created_background,
+            ));
+            if let Some(base) = &conflict.base {
+                conflict_rows.push((
+                    display_row(&conflict.ours.end)..display_row(&base.end),
+                    status.hidden_background,
+                ));
+            }
+            conflict_rows.push((
+                display_row(&conflict.theirs.start)..end_row,
+                status.info_background,
+            ));
+        }
+        conflict_rows
+    }
+}
+
+fn merge_conflicts_in_excerpts(snapshot: &MultiBufferSnapshot) -> Vec<MergeConflict<Anchor>> {
+    let mut conflicts = Vec::new();
+    for (excerpt_id, buffer, range) in snapshot.excerpts() {
+        for conflict in buffer.merge_conflicts() {
+            let in_excerpt = conflict
+                .range
+                .start
+                .cmp(&range.context.start, buffer)
+                .is_ge()
+                && conflict.range.end.cmp(&range.context.end, buffer).is_le();
+            if in_excerpt {
+                conflicts
+                    .push(conflict.map(|anchor| snapshot.anchor_in_excerpt(excerpt_id, *anchor)));
+            }
+        }
+    }
+    conflicts
+}
+
+fn merge_conflict_block_renderer(conflict: MergeConflict<Anchor>) -> RenderBlock {
+    Arc::new(move |cx: &mut BlockContext| {
+        let block_id = cx.block_id;
+        let editor = cx.view.clone();
+        let button = |label: &'static str, resolution: ConflictResolution| {
+            let editor = editor.clone();
+            let conflict = conflict.clone();
+            Button::new((label, block_id), label)
+                .label_size(LabelSize::Small)
+                .on_click(move |_, cx| {
+                    editor.update(cx, |editor, cx| {
+                        editor.resolve_merge_conflict(&conflict, resolution, cx)
+                    })
+                })
+        };
+
+        h_flex()
+            .id(block_id)
+            .h(cx.line_height)
+            .pl(cx.anchor_x)
+            .gap_1()
+            .child(button("Accept Current", ConflictResolution::Current))
+            .child(button("Accept Incoming", ConflictResolution::Incoming))
+            .child(button("Accept Both", ConflictResolution::Both))
+            .into_any_element()
+    })
+}
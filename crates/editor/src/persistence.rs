@@ -1,7 +1,8 @@
-use std::path::PathBuf;
+use std::{ops::Range, path::PathBuf};
 
 use db::sqlez_macros::sql;
 use db::{define_connection, query};
+use text::Point;
 
 use workspace::{ItemId, WorkspaceDb, WorkspaceId};
 
@@ -15,6 +16,15 @@ define_connection!(
     //   scroll_vertical_offset: f32,
     //   scroll_horizontal_offset: f32,
     // )
+    //
+    // editor_folds(
+    //   item_id: usize,
+    //   workspace_id: usize,
+    //   start_row: u32,
+    //   start_column: u32,
+    //   end_row: u32,
+    //   end_column: u32,
+    // )
     pub static ref DB: EditorDb<WorkspaceDb> =
         &[sql! (
             CREATE TABLE editors(
@@ -31,6 +41,19 @@ define_connection!(
             ALTER TABLE editors ADD COLUMN scroll_top_row INTEGER NOT NULL DEFAULT 0;
             ALTER TABLE editors ADD COLUMN scroll_horizontal_offset REAL NOT NULL DEFAULT 0;
             ALTER TABLE editors ADD COLUMN scroll_vertical_offset REAL NOT NULL DEFAULT 0;
+        ),
+        sql! (
+            CREATE TABLE editor_folds(
+                item_id INTEGER NOT NULL,
+                workspace_id INTEGER NOT NULL,
+                start_row INTEGER NOT NULL,
+                start_column INTEGER NOT NULL,
+                end_row INTEGER NOT NULL,
+                end_column INTEGER NOT NULL,
+                FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+                ON DELETE CASCADE
+                ON UPDATE CASCADE
+            ) STRICT;
         )];
 );
 
@@ -80,4 +103,46 @@ impl EditorDb {
             WHERE item_id = ?1 AND workspace_id = ?2
         }
     }
+
+    query! {
+        pub fn get_folds(item_id: ItemId, workspace_id: WorkspaceId) -> Result<Vec<(u32, u32, u32, u32)>> {
+            SELECT start_row, start_column, end_row, end_column
+            FROM editor_folds
+            WHERE item_id = ? AND workspace_id = ?
+        }
+    }
+
+    pub async fn save_folds(
+        &self,
+        item_id: ItemId,
+        workspace_id: WorkspaceId,
+        folds: Vec<Range<Point>>,
+    ) -> anyhow::Result<()> {
+        self.write(move |conn| {
+            conn.with_savepoint("update_folds", || {
+                conn.exec_bound(sql!(
+                    DELETE FROM editor_folds WHERE item_id = ? AND workspace_id = ?
+                ))?((item_id, workspace_id))?;
+
+                let mut insert = conn.exec_bound(sql!(
+                    INSERT INTO editor_folds
+                        (item_id, workspace_id, start_row, start_column, end_row, end_column)
+                    VALUES
+                        (?, ?, ?, ?, ?, ?)
+                ))?;
+                for fold in folds {
+                    insert((
+                        item_id,
+                        workspace_id,
+                        fold.start.row,
+                        fold.start.column,
+                        fold.end.row,
+                        fold.end.column,
+                    ))?;
+                }
+                Ok(())
+            })
+        })
+        .await
+    }
 }
@@ -0,0 +1,186 @@
+use crate::{Editor, EditorMode};
+use collections::{HashMap, HashSet};
+use futures::future;
+use gpui::{px, HighlightStyle, Task, UnderlineStyle, ViewContext};
+use language::{language_settings::language_settings, HighlightId};
+use project::{SemanticToken, SemanticTokens};
+use std::time::Duration;
+use theme::{ActiveTheme, SyntaxTheme};
+use util::{ResultExt, TryFutureExt};
+
+const SEMANTIC_TOKENS_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(200);
+
+#[derive(Default)]
+pub(crate) struct SemanticTokensState {
+    /// The latest tokens of each buffer in the editor, by buffer id.
+    buffers: HashMap<u64, BufferSemanticTokens>,
+    refresh_task: Option<Task<Option<()>>>,
+}
+
+struct BufferSemanticTokens {
+    /// The version of the buffer that the tokens were requested for, or `None` when the
+    /// language server asked for them to be requested again.
+    version: Option<clock::Global>,
+    tokens: SemanticTokens,
+}
+
+impl Editor {
+    /// Requests new semantic tokens for the editor's buffers that have them enabled and were
+    /// edited since their tokens were last requested, or for all of them when `refresh_all`
+    /// is set, and drops the tokens of the others.
+    pub(crate) fn refresh_semantic_tokens(
+        &mut self,
+        refresh_all: bool,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if self.mode != EditorMode::Full {
+            return;
+        }
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+
+        let buffers = self
+            .buffer
+            .read(cx)
+            .all_buffers()
+            .into_iter()
+            .filter(|buffer| {
+                let buffer = buffer.read(cx);
+                language_settings(buffer.language(), buffer.file(), cx).semantic_tokens
+            })
+            .collect::<Vec<_>>();
+        let buffer_ids = buffers
+            .iter()
+            .map(|buffer| buffer.read(cx).remote_id())
+            .collect::<HashSet<_>>();
+        let buffer_count = self.semantic_tokens_state.buffers.len();
+        self.semantic_tokens_state
+            .buffers
+            .retain(|buffer_id, _| buffer_ids.contains(buffer_id));
+        if self.semantic_tokens_state.buffers.len() != buffer_count {
+            self.update_semantic_highlights(cx);
+        }
+        if buffers.is_empty() {
+            self.semantic_tokens_state.refresh_task = None;
+            return;
+        }
+
+        if refresh_all {
+            for buffer_tokens in self.semantic_tokens_state.buffers.values_mut() {
+                buffer_tokens.version = None;
+            }
+        }
+        let stale_buffers = buffers
+            .into_iter()
+            .filter(|buffer| {
+                let buffer = buffer.read(cx);
+                self.semantic_tokens_state
+                    .buffers
+                    .get(&buffer.remote_id())
+                    .and_then(|buffer_tokens| buffer_tokens.version.as_ref())
+                    .map_or(true, |version| buffer.version().changed_since(version))
+            })
+            .collect::<Vec<_>>();
+        if stale_buffers.is_empty() {
+            return;
+        }
+
+        self.semantic_tokens_state.refresh_task = Some(cx.spawn(|editor, mut cx| {
+            async move {
+                cx.background_executor()
+                    .timer(SEMANTIC_TOKENS_DEBOUNCE_TIMEOUT)
+                    .await;
+                let requests = editor.update(&mut cx, |editor, cx| {
+                    stale_buffers
+                        .into_iter()
+                        .map(|buffer| {
+                            let buffer_id = buffer.read(cx).remote_id();
+                            let version = buffer.read(cx).version();
+                            let previous = editor
+                                .semantic_tokens_state
+                                .buffers
+                                .get(&buffer_id)
+                                .map(|buffer_tokens| &buffer_tokens.tokens);
+                            let request = project.update(cx, |project, cx| {
+                                project.semantic_tokens(&buffer, previous, cx)
+                            });
+                            async move { (buffer_id, version, request.await) }
+                        })
+                        .collect::<Vec<_>>()
+                })?;
+                let responses = future::join_all(requests).await;
+
+                editor.update(&mut cx, |editor, cx| {
+                    for (buffer_id, version, tokens) in responses {
+                        if let Some(Some(tokens)) = tokens.log_err() {
+                            editor.semantic_tokens_state.buffers.insert(
+                                buffer_id,
+                                BufferSemanticTokens {
+                                    version: Some(version),
+                                    tokens,
+                                },
+                            );
+                        }
+                    }
+                    editor.update_semantic_highlights(cx);
+                })
+            }
+            .log_err()
+        }));
+    }
+
+    /// Styles the semantic tokens that are within the editor's excerpts.
+    fn update_semantic_highlights(&mut self, cx: &mut ViewContext<Self>) {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let theme = cx.theme().syntax().clone();
+        let mut highlights = Vec::new();
+        for (excerpt_id, buffer, range) in snapshot.excerpts() {
+            let Some(tokens) = self
+                .semantic_tokens_state
+                .buffers
+                .get(&buffer.remote_id())
+                .map(|buffer_tokens| &buffer_tokens.tokens)
+            else {
+                continue;
+            };
+            let start_ix = tokens
+                .tokens
+                .partition_point(|token| token.range.end.cmp(&range.context.start, buffer).is_le());
+            for token in &tokens.tokens[start_ix..] {
+                if token.range.start.cmp(&range.context.end, buffer).is_ge() {
+                    break;
+                }
+                if let Some(style) = semantic_token_style(token, &theme) {
+                    let start = snapshot.anchor_in_excerpt(excerpt_id, token.range.start);
+                    let end = snapshot.anchor_in_excerpt(excerpt_id, token.range.end);
+                    highlights.push((start..end, style));
+                }
+            }
+        }
+
+        self.display_map.update(cx, |display_map, _| {
+            display_map.set_semantic_highlights(highlights)
+        });
+        cx.notify();
+    }
+}
+
+/// The syntax style of a token's type, with mutable variables underlined.
+fn semantic_token_style(token: &SemanticToken, theme: &SyntaxTheme) -> Option<HighlightStyle> {
+    let modifiers = token
+        .modifiers
+        .iter()
+        .map(|modifier| modifier.as_ref())
+        .collect::<Vec<_>>();
+    let mut style =
+        HighlightId::for_semantic_token(&token.token_type, &modifiers, theme).style(theme)?;
+    if modifiers.contains(&"mutable") {
+        style.underline = Some(UnderlineStyle {
+            thickness: px(1.),
+            color: style.color,
+            wavy: false,
+        });
+    }
+    Some(style)
+}
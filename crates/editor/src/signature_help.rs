@@ -0,0 +1,461 @@
+use crate::{
+    actions::{NextSignature, PreviousSignature, ShowSignatureHelp},
+    display_map::ToDisplayPoint,
+    hover_popover::parse_blocks,
+    Anchor, DisplayPoint, Editor, EditorSnapshot, EditorStyle,
+};
+use gpui::{
+    div, AnyElement, FontWeight, HighlightStyle, InteractiveElement, IntoElement, ParentElement,
+    Pixels, Size, StatefulInteractiveElement, Styled, StyledText, Task, ViewContext, WeakView,
+};
+use language::{Language, LanguageRegistry, ParsedMarkdown};
+use project::{HoverBlock, MarkupContent, SignatureHelp};
+use std::{ops::Range, sync::Arc};
+use ui::prelude::*;
+use util::TryFutureExt;
+use workspace::Workspace;
+
+/// Bindable action which requests signature help at the most recent selection head
+pub fn show_signature_help(
+    editor: &mut Editor,
+    _: &ShowSignatureHelp,
+    cx: &mut ViewContext<Editor>,
+) {
+    request_signature_help(editor, None, false, cx);
+}
+
+pub fn next_signature(editor: &mut Editor, _: &NextSignature, cx: &mut ViewContext<Editor>) {
+    cycle_signature(editor, true, cx);
+}
+
+pub fn previous_signature(
+    editor: &mut Editor,
+    _: &PreviousSignature,
+    cx: &mut ViewContext<Editor>,
+) {
+    cycle_signature(editor, false, cx);
+}
+
+/// Requests signature help after text was typed. The language server decides whether the
+/// text is one of its trigger characters, and signature help that is already shown is
+/// updated after every edit.
+pub fn trigger_signature_help_on_input(
+    editor: &mut Editor,
+    text: &str,
+    cx: &mut ViewContext<Editor>,
+) {
+    let is_retrigger = editor.signature_help_state.is_active();
+    if !is_retrigger && text.len() != 1 {
+        return;
+    }
+    request_signature_help(editor, Some(text.to_string()), is_retrigger, cx);
+}
+
+/// Hides the signature help popover when the cursor moves before the position it was
+/// requested from, which means it left the call's arguments.
+pub fn selections_changed(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    let Some(triggered_from) = editor.signature_help_state.triggered_from else {
+        return;
+    };
+    let buffer = editor.buffer.read(cx).snapshot(cx);
+    let head = editor.selections.newest_anchor().head();
+    if head.cmp(&triggered_from, &buffer).is_lt() {
+        hide_signature_help(editor, cx);
+    }
+}
+
+/// Hides the signature help popover and cancels any pending request.
+pub fn hide_signature_help(editor: &mut Editor, cx: &mut ViewContext<Editor>) -> bool {
+    let did_hide = editor.signature_help_state.popover.take().is_some();
+    editor.signature_help_state.task = None;
+    editor.signature_help_state.is_pending = false;
+    editor.signature_help_state.triggered_from = None;
+    if did_hide {
+        cx.notify();
+    }
+    did_hide
+}
+
+fn request_signature_help(
+    editor: &mut Editor,
+    trigger_character: Option<String>,
+    is_retrigger: bool,
+    cx: &mut ViewContext<Editor>,
+) {
+    if editor.pending_rename.is_some() {
+        return;
+    }
+    let Some(project) = editor.project.clone() else {
+        return;
+    };
+    let position = editor.selections.newest_anchor().head();
+    let Some((buffer, buffer_position)) = editor
+        .buffer
+        .read(cx)
+        .text_anchor_for_position(position, cx)
+    else {
+        return;
+    };
+
+    let request = project.update(cx, |project, cx| {
+        project.signature_help(
+            &buffer,
+            buffer_position,
+            trigger_character,
+            is_retrigger,
+            cx,
+        )
+    });
+    let language = buffer.read(cx).language().cloned();
+    let language_registry = project.read(cx).languages().clone();
+    editor.signature_help_state.is_pending = true;
+    editor.signature_help_state.task = Some(cx.spawn(|this, mut cx| {
+        async move {
+            let help = request.await;
+            let popover = match help {
+                Ok(Some(help)) => {
+                    Some(SignatureHelpPopover::new(help, &language_registry, language).await)
+                }
+                _ => None,
+            };
+
+            this.update(&mut cx, |this, cx| {
+                let state = &mut this.signature_help_state;
+                state.is_pending = false;
+                match popover {
+                    Some(mut popover) => {
+                        // Keep the overload the user cycled to while the call is being edited.
+                        if let Some(previous) = &state.popover {
+                            if previous.help.signatures.len() == popover.help.signatures.len() {
+                                popover.active_signature = previous.active_signature;
+                            }
+                        }
+                        if state.triggered_from.is_none() {
+                            state.triggered_from = Some(position);
+                        }
+                        state.popover = Some(popover);
+                    }
+                    None => {
+                        state.popover = None;
+                        state.triggered_from = None;
+                    }
+                }
+                cx.notify();
+            })
+        }
+        .log_err()
+    }));
+}
+
+fn cycle_signature(editor: &mut Editor, forward: bool, cx: &mut ViewContext<Editor>) {
+    let Some(popover) = editor.signature_help_state.popover.as_mut() else {
+        cx.propagate();
+        return;
+    };
+    let count = popover.help.signatures.len();
+    popover.active_signature = if forward {
+        (popover.active_signature + 1) % count
+    } else {
+        (popover.active_signature + count - 1) % count
+    };
+    cx.notify();
+}
+
+#[derive(Default)]
+pub struct SignatureHelpState {
+    popover: Option<SignatureHelpPopover>,
+    /// The cursor position that the shown signature help was first requested from.
+    triggered_from: Option<Anchor>,
+    is_pending: bool,
+    task: Option<Task<Option<()>>>,
+}
+
+impl SignatureHelpState {
+    pub fn visible(&self) -> bool {
+        self.popover.is_some()
+    }
+
+    fn is_active(&self) -> bool {
+        self.visible() || self.is_pending
+    }
+
+    pub fn render(
+        &mut self,
+        snapshot: &EditorSnapshot,
+        style: &EditorStyle,
+        cursor: Anchor,
+        visible_rows: Range<u32>,
+        max_size: Size<Pixels>,
+        workspace: Option<WeakView<Workspace>>,
+        cx: &mut ViewContext<Editor>,
+    ) -> Option<(DisplayPoint, AnyElement)> {
+        let popover = self.popover.as_ref()?;
+        let point = cursor.to_display_point(&snapshot.display_snapshot);
+        if !visible_rows.contains(&point.row()) {
+            return None;
+        }
+        Some((point, popover.render(style, max_size, workspace, cx)))
+    }
+}
+
+struct SignatureHelpPopover {
+    help: SignatureHelp,
+    active_signature: usize,
+    documentation: Vec<SignatureDocumentation>,
+}
+
+/// The parsed documentation of a signature and of each of its parameters.
+struct SignatureDocumentation {
+    signature: Option<ParsedMarkdown>,
+    parameters: Vec<Option<ParsedMarkdown>>,
+}
+
+impl SignatureHelpPopover {
+    async fn new(
+        help: SignatureHelp,
+        language_registry: &Arc<LanguageRegistry>,
+        language: Option<Arc<Language>>,
+    ) -> Self {
+        let parse = |documentation: Option<MarkupContent>| {
+            let language = language.clone();
+            async move {
+                let documentation = documentation.filter(|doc| !doc.value.trim().is_empty())?;
+                let blocks = [HoverBlock {
+                    text: documentation.value,
+                    kind: documentation.kind,
+                }];
+                Some(parse_blocks(&blocks, language_registry, language).await)
+            }
+        };
+
+        let mut documentation = Vec::new();
+        for signature in &help.signatures {
+            let mut parameters = Vec::new();
+            for parameter in &signature.parameters {
+                parameters.push(parse(parameter.documentation.clone()).await);
+            }
+            documentation.push(SignatureDocumentation {
+                signature: parse(signature.documentation.clone()).await,
+                parameters,
+            });
+        }
+
+        Self {
+            active_signature: help.active_signature,
+            help,
+            documentation,
+        }
+    }
+
+    fn render(
+        &self,
+        style: &EditorStyle,
+        max_size: Size<Pixels>,
+        workspace: Option<WeakView<Workspace>>,
+        cx: &mut ViewContext<Editor>,
+    ) -> AnyElement {
+        let signature = &self.help.signatures[self.active_signature];
+        let documentation = &self.documentation[self.active_signature];
+        let active_parameter = self.help.active_parameter(self.active_signature);
+
+        let highlights = active_parameter
+            .and_then(|ix| signature.parameters.get(ix)?.label_range.clone())
+            .map(|range| {
+                (
+                    range,
+                    HighlightStyle {
+                        color: Some(cx.theme().colors().text_accent),
+                        font_weight: Some(FontWeight::BOLD),
+                        ..Default::default()
+                    },
+                )
+            });
+        let parameter_documentation =
+            active_parameter.and_then(|ix| documentation.parameters.get(ix)?.as_ref());
+        let signature_count = self.help.signatures.len();
+
+        div()
+            .id("signature_help_popover")
+            .elevation_2(cx)
+            .p_2()
+            .overflow_y_scroll()
+            .max_w(max_size.width)
+            .max_h(max_size.height)
+            // Prevent a mouse move on the popover from being propagated to the editor,
+            // because that would show a hover popover over it.
+            .on_mouse_move(|_, cx| cx.stop_propagation())
+            .child(
+                v_flex()
+                    .gap_1()
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .when(signature_count > 1, |this| {
+                                this.child(
+                                    Label::new(format!(
+                                        "{}/{}",
+                                        self.active_signature + 1,
+                                        signature_count
+                                    ))
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                                )
+                            })
+                            .child(
+                                StyledText::new(signature.label.clone())
+                                    .with_highlights(&style.text, highlights),
+                            ),
+                    )
+                    .children(parameter_documentation.map(|parameter_documentation| {
+                        crate::render_parsed_markdown(
+                            "parameter_documentation",
+                            parameter_documentation,
+                            style,
+                            workspace.clone(),
+                            cx,
+                        )
+                    }))
+                    .children(
+                        documentation
+                            .signature
+                            .as_ref()
+                            .map(|signature_documentation| {
+                                crate::render_parsed_markdown(
+                                    "signature_documentation",
+                                    signature_documentation,
+                                    style,
+                                    workspace,
+                                    cx,
+                                )
+                            }),
+                    ),
+            )
+            .into_any_element()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        editor_tests::init_test, test::editor_lsp_test_context::EditorLspTestContext, Cancel,
+        MoveLeft,
+    };
+    use indoc::indoc;
+    use smol::stream::StreamExt;
+
+    #[gpui::test]
+    async fn test_signature_help_on_trigger_character(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                signature_help_provider: Some(lsp::SignatureHelpOptions {
+                    trigger_characters: Some(vec!["(".to_string(), ",".to_string()]),
+                    retrigger_characters: None,
+                    work_done_progress_options: Default::default(),
+                }),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+
+        cx.set_state(indoc! {"
+            fn main() { fooˇ }
+        "});
+        cx.simulate_keystroke("(");
+        cx.handle_request::<lsp::request::SignatureHelpRequest, _, _>(|_, params, _| async move {
+            let context = params.context.unwrap();
+            assert_eq!(
+                context.trigger_kind,
+                lsp::SignatureHelpTriggerKind::TRIGGER_CHARACTER
+            );
+            assert_eq!(context.trigger_character.as_deref(), Some("("));
+            assert!(!context.is_retrigger);
+            Ok(Some(lsp::SignatureHelp {
+                signatures: vec![
+                    lsp::SignatureInformation {
+                        label: "foo(a: usize, b: usize)".to_string(),
+                        documentation: None,
+                        parameters: Some(vec![
+                            lsp::ParameterInformation {
+                                label: lsp::ParameterLabel::Simple("a: usize".to_string()),
+                                documentation: None,
+                            },
+                            lsp::ParameterInformation {
+                                label: lsp::ParameterLabel::LabelOffsets([14, 22]),
+                                documentation: None,
+                            },
+                        ]),
+                        active_parameter: None,
+                    },
+                    lsp::SignatureInformation {
+                        label: "foo()".to_string(),
+                        documentation: None,
+                        parameters: None,
+                        active_parameter: None,
+                    },
+                ],
+                active_signature: Some(0),
+                active_parameter: Some(1),
+            }))
+        })
+        .next()
+        .await;
+
+        cx.condition(|editor, _| editor.signature_help_state.visible())
+            .await;
+        cx.editor(|editor, _| {
+            let popover = editor.signature_help_state.popover.as_ref().unwrap();
+            let signature = &popover.help.signatures[0];
+            let label_ranges = signature
+                .parameters
+                .iter()
+                .map(|parameter| parameter.label_range.clone())
+                .collect::<Vec<_>>();
+            assert_eq!(label_ranges, vec![Some(4..12), Some(14..22)]);
+            assert_eq!(popover.help.active_parameter(0), Some(1));
+        });
+
+        cx.update_editor(|editor, cx| next_signature(editor, &NextSignature, cx));
+        cx.editor(|editor, _| {
+            assert_eq!(
+                editor
+                    .signature_help_state
+                    .popover
+                    .as_ref()
+                    .unwrap()
+                    .active_signature,
+                1
+            );
+        });
+
+        // Moving the cursor out of the call's arguments hides the popover.
+        cx.update_editor(|editor, cx| editor.move_left(&MoveLeft, cx));
+        assert!(cx.editor(|editor, _| !editor.signature_help_state.visible()));
+
+        cx.update_editor(|editor, cx| show_signature_help(editor, &ShowSignatureHelp, cx));
+        cx.handle_request::<lsp::request::SignatureHelpRequest, _, _>(|_, params, _| async move {
+            let context = params.context.unwrap();
+            assert_eq!(context.trigger_kind, lsp::SignatureHelpTriggerKind::INVOKED);
+            Ok(Some(lsp::SignatureHelp {
+                signatures: vec![lsp::SignatureInformation {
+                    label: "foo()".to_string(),
+                    documentation: None,
+                    parameters: None,
+                    active_parameter: None,
+                }],
+                active_signature: None,
+                active_parameter: None,
+            }))
+        })
+        .next()
+        .await;
+        cx.condition(|editor, _| editor.signature_help_state.visible())
+            .await;
+
+        cx.update_editor(|editor, cx| editor.cancel(&Cancel, cx));
+        assert!(cx.editor(|editor, _| !editor.signature_help_state.visible()));
+    }
+}
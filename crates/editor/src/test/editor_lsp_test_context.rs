@@ -143,6 +143,17 @@ impl EditorLspTestContext {
                 ("<" @open ">" @close)
                 ("\"" @open "\"" @close)
                 (closure_parameters "|" @open "|" @close)"#})),
+            text_objects: Some(Cow::from(indoc! {r#"
+                (function_item
+                    body: (_) @function.inside) @function.around
+                (impl_item
+                    body: (_) @class.inside) @class.around
+                (line_comment) @comment.inside
+                (line_comment)+ @comment.around
+                (parameters
+                    (_) @argument)
+                (arguments
+                    (_) @argument)"#})),
             ..Default::default()
         })
         .expect("Could not parse queries");
@@ -24,7 +24,7 @@ use util::ResultExt;
 #[cfg(any(test, feature = "test-support"))]
 use collections::{btree_map, BTreeMap};
 #[cfg(any(test, feature = "test-support"))]
-use repository::{FakeGitRepositoryState, GitFileStatus};
+use repository::{BlameEntry, CommitFileDiff, FakeGitRepositoryState, GitFileStatus, LogEntry};
 #[cfg(any(test, feature = "test-support"))]
 use std::ffi::OsStr;
 
@@ -591,6 +591,41 @@ impl FakeFs {
         });
     }
 
+    pub fn set_head_for_repo(&self, dot_git: &Path, head_state: &[(&Path, String)]) {
+        self.with_git_state(dot_git, true, |state| {
+            state.head_contents.clear();
+            state.head_contents.extend(
+                head_state
+                    .iter()
+                    .map(|(path, content)| (path.to_path_buf(), content.clone())),
+            );
+        });
+    }
+
+    pub fn set_blame_for_repo(&self, dot_git: &Path, blames: Vec<(&Path, Vec<BlameEntry>)>) {
+        self.with_git_state(dot_git, true, |state| {
+            state.blames.clear();
+            state.blames.extend(
+                blames
+                    .into_iter()
+                    .map(|(path, entries)| (path.into(), entries)),
+            );
+        });
+    }
+
+    /// Sets the history of the repository, newest commit first, along with the files
+    /// each commit changed.
+    pub fn set_log_for_repo(&self, dot_git: &Path, commits: Vec<(LogEntry, Vec<CommitFileDiff>)>) {
+        self.with_git_state(dot_git, true, |state| {
+            state.log.clear();
+            state.commit_diffs.clear();
+            for (entry, diffs) in commits {
+                state.commit_diffs.insert(entry.sha.clone(), diffs);
+                state.log.push(entry);
+            }
+        });
+    }
+
     pub fn set_status_for_repo_via_working_copy_change(
         &self,
         dot_git: &Path,
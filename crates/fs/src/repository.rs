@@ -1,11 +1,12 @@
-use anyhow::Result;
-use collections::HashMap;
-use git2::{BranchType, StatusShow};
+use anyhow::{anyhow, Result};
+use collections::{HashMap, HashSet};
+use git2::{BranchType, IndexEntry, IndexTime, StatusShow};
 use parking_lot::Mutex;
 use serde_derive::{Deserialize, Serialize};
 use std::{
     cmp::Ordering,
     ffi::OsStr,
+    ops::Range,
     os::unix::prelude::OsStrExt,
     path::{Component, Path, PathBuf},
     sync::Arc,
@@ -16,6 +17,58 @@ use util::ResultExt;
 
 pub use git2::Repository as LibGitRepository;
 
+/// The status of a changed file, separately for its staged and unstaged changes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GitStatusEntry {
+    pub repo_path: RepoPath,
+    /// The status of the file in the index with respect to the HEAD commit.
+    pub staged: Option<GitFileStatus>,
+    /// The status of the file in the working directory with respect to the index.
+    pub unstaged: Option<GitFileStatus>,
+}
+
+impl GitStatusEntry {
+    /// Whether a merge left the file with unresolved conflicts.
+    pub fn is_conflicted(&self) -> bool {
+        self.staged == Some(GitFileStatus::Conflict)
+            || self.unstaged == Some(GitFileStatus::Conflict)
+    }
+}
+
+/// A range of lines that `git blame` attributes to a single commit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlameEntry {
+    /// The rows of the blamed content that were last changed by the commit.
+    pub rows: Range<u32>,
+    pub sha: String,
+    pub author: String,
+    /// The time the commit was authored, in seconds since the Unix epoch.
+    pub author_time: i64,
+    pub summary: String,
+}
+
+/// A commit in the history of a repository.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogEntry {
+    pub sha: String,
+    pub author: String,
+    /// The time the commit was authored, in seconds since the Unix epoch.
+    pub author_time: i64,
+    pub message: String,
+    /// The paths that the commit changed with respect to its first parent.
+    pub changed_paths: Vec<RepoPath>,
+}
+
+/// The contents of a file before and after a commit changed it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommitFileDiff {
+    pub path: RepoPath,
+    /// The file's contents in the commit's first parent, if it existed there as text.
+    pub old_text: Option<String>,
+    /// The file's contents in the commit, if it exists there as text.
+    pub new_text: Option<String>,
+}
+
 #[derive(Clone, Debug, Hash, PartialEq)]
 pub struct Branch {
     pub name: Box<str>,
@@ -26,6 +79,7 @@ pub struct Branch {
 pub trait GitRepository: Send {
     fn reload_index(&self);
     fn load_index_text(&self, relative_file_path: &Path) -> Option<String>;
+    fn load_head_text(&self, relative_file_path: &Path) -> Option<String>;
     fn branch_name(&self) -> Option<String>;
 
     /// Get the statuses of all of the files in the index that start with the given
@@ -46,9 +100,40 @@ pub trait GitRepository: Send {
     /// no need to consider the working directory file if the mtime matches.
     fn status(&self, path: &RepoPath, mtime: SystemTime) -> Option<GitFileStatus>;
 
+    /// Get the staged and unstaged status of every file in the repository that has
+    /// changes with respect to the HEAD commit or the index.
+    fn file_statuses(&self) -> Result<Vec<GitStatusEntry>>;
+
+    /// Attribute each line of a file to the commit that last changed it. When `content`
+    /// is given, it is blamed instead of the committed file, and lines that have not been
+    /// committed yet are omitted.
+    fn blame(&self, path: &RepoPath, content: Option<&str>) -> Result<Vec<BlameEntry>>;
+
+    /// List the commits reachable from HEAD, newest first, skipping the first `skip` of
+    /// them and returning at most `limit`. When `path` is given, only the commits that
+    /// changed it are listed, following it across renames.
+    fn log(&self, path: Option<&RepoPath>, skip: usize, limit: usize) -> Result<Vec<LogEntry>>;
+
+    /// Load the contents of every file changed by a commit, before and after the commit.
+    fn commit_diff(&self, sha: &str) -> Result<Vec<CommitFileDiff>>;
+
     fn branches(&self) -> Result<Vec<Branch>>;
     fn change_branch(&self, _: &str) -> Result<()>;
     fn create_branch(&self, _: &str) -> Result<()>;
+
+    /// Add the working directory contents of the given files to the index, removing
+    /// the files from the index if they no longer exist.
+    fn stage_paths(&self, paths: &[RepoPath]) -> Result<()>;
+
+    /// Reset the index entries of the given files to their state in the HEAD commit.
+    fn unstage_paths(&self, paths: &[RepoPath]) -> Result<()>;
+
+    /// Replace the contents of a file in the index, removing the file from the index
+    /// when `content` is `None`. This is used to stage and unstage individual hunks.
+    fn set_index_text(&self, path: &RepoPath, content: Option<String>) -> Result<()>;
+
+    /// Create a commit from the index on top of HEAD, returning the new commit's id.
+    fn commit(&self, message: &str) -> Result<String>;
 }
 
 impl std::fmt::Debug for dyn GitRepository {
@@ -88,6 +173,30 @@ impl GitRepository for LibGitRepository {
         None
     }
 
+    fn load_head_text(&self, relative_file_path: &Path) -> Option<String> {
+        fn logic(repo: &LibGitRepository, relative_file_path: &Path) -> Result<Option<String>> {
+            check_path_to_repo_path_errors(relative_file_path)?;
+
+            let tree = match repo.head() {
+                Ok(head) => head.peel_to_tree()?,
+                Err(_) => return Ok(None),
+            };
+            let entry = match tree.get_path(relative_file_path) {
+                Ok(entry) => entry,
+                Err(_) => return Ok(None),
+            };
+
+            let content = repo.find_blob(entry.id())?.content().to_owned();
+            Ok(Some(String::from_utf8(content)?))
+        }
+
+        match logic(self, relative_file_path) {
+            Ok(value) => return value,
+            Err(err) => log::error!("Error loading head text: {:?}", err),
+        }
+        None
+    }
+
     fn branch_name(&self) -> Option<String> {
         let head = self.head().log_err()?;
         let branch = String::from_utf8_lossy(head.shorthand_bytes());
@@ -155,6 +264,157 @@ impl GitRepository for LibGitRepository {
         status
     }
 
+    fn file_statuses(&self) -> Result<Vec<GitStatusEntry>> {
+        let mut options = git2::StatusOptions::new();
+        options.include_untracked(true);
+        options.recurse_untracked_dirs(true);
+        options.show(StatusShow::IndexAndWorkdir);
+
+        let statuses = self.statuses(Some(&mut options))?;
+        Ok(statuses
+            .iter()
+            .filter_map(|status| {
+                let repo_path = RepoPath(PathBuf::from(OsStr::from_bytes(status.path_bytes())));
+                let status = status.status();
+                if status.contains(git2::Status::IGNORED) {
+                    return None;
+                }
+                let index_status = status
+                    & (git2::Status::INDEX_NEW
+                        | git2::Status::INDEX_MODIFIED
+                        | git2::Status::INDEX_DELETED
+                        | git2::Status::INDEX_RENAMED
+                        | git2::Status::INDEX_TYPECHANGE);
+                let workdir_status = status - index_status;
+                let entry = GitStatusEntry {
+                    repo_path,
+                    staged: read_change_status(index_status, git2::Status::INDEX_DELETED),
+                    unstaged: read_change_status(workdir_status, git2::Status::WT_DELETED),
+                };
+                (entry.staged.is_some() || entry.unstaged.is_some()).then_some(entry)
+            })
+            .collect())
+    }
+
+    fn blame(&self, path: &RepoPath, content: Option<&str>) -> Result<Vec<BlameEntry>> {
+        check_path_to_repo_path_errors(path)?;
+        let file_blame = self.blame_file(path, None)?;
+        let buffer_blame;
+        let blame = match content {
+            Some(content) => {
+                buffer_blame = file_blame.blame_buffer(content.as_bytes())?;
+                &buffer_blame
+            }
+            None => &file_blame,
+        };
+
+        let mut summaries = HashMap::default();
+        let mut entries = Vec::new();
+        for hunk in blame.iter() {
+            let commit_id = hunk.final_commit_id();
+            if commit_id.is_zero() {
+                continue;
+            }
+            let summary = match summaries.entry(commit_id) {
+                collections::hash_map::Entry::Occupied(entry) => entry.get().clone(),
+                collections::hash_map::Entry::Vacant(entry) => {
+                    let commit = self.find_commit(commit_id)?;
+                    entry
+                        .insert(commit.summary().unwrap_or_default().to_string())
+                        .clone()
+                }
+            };
+            let signature = hunk.final_signature();
+            let start = hunk.final_start_line().saturating_sub(1) as u32;
+            entries.push(BlameEntry {
+                rows: start..start + hunk.lines_in_hunk() as u32,
+                sha: commit_id.to_string(),
+                author: signature.name().unwrap_or_default().to_string(),
+                author_time: signature.when().seconds(),
+                summary,
+            });
+        }
+        Ok(entries)
+    }
+
+    fn log(&self, path: Option<&RepoPath>, skip: usize, limit: usize) -> Result<Vec<LogEntry>> {
+        if let Some(path) = path {
+            check_path_to_repo_path_errors(path)?;
+        }
+        let mut revwalk = self.revwalk()?;
+        revwalk.push_head()?;
+        revwalk.set_sorting(git2::Sort::TIME)?;
+
+        // The path whose history is listed. It changes to the old path when walking
+        // past the commit that renamed it.
+        let mut followed_path = path.cloned();
+        let mut skipped = 0;
+        let mut entries = Vec::new();
+        for commit_id in revwalk {
+            if entries.len() >= limit {
+                break;
+            }
+
+            let commit = self.find_commit(commit_id?)?;
+            if let Some(followed_path) = followed_path.as_mut() {
+                let diff = commit_changes(self, &commit, Some(followed_path))?;
+                let Some(delta) = diff.deltas().next() else {
+                    continue;
+                };
+                if delta.status() == git2::Delta::Added {
+                    if let Some(old_path) = renamed_from(self, &commit, followed_path)? {
+                        *followed_path = old_path;
+                    }
+                }
+            }
+            if skipped < skip {
+                skipped += 1;
+                continue;
+            }
+
+            let changed_paths = commit_changes(self, &commit, None)?
+                .deltas()
+                .filter_map(|delta| {
+                    let path = delta.new_file().path().or(delta.old_file().path())?;
+                    Some(RepoPath(path.to_path_buf()))
+                })
+                .collect::<Vec<_>>();
+            let author = commit.author();
+            entries.push(LogEntry {
+                sha: commit.id().to_string(),
+                author: author.name().unwrap_or_default().to_string(),
+                author_time: author.when().seconds(),
+                message: commit.message().unwrap_or_default().to_string(),
+                changed_paths,
+            });
+        }
+        Ok(entries)
+    }
+
+    fn commit_diff(&self, sha: &str) -> Result<Vec<CommitFileDiff>> {
+        let commit = self.find_commit(git2::Oid::from_str(sha)?)?;
+        let blob_text = |file: git2::DiffFile| -> Result<Option<String>> {
+            if file.id().is_zero() {
+                return Ok(None);
+            }
+            let blob = self.find_blob(file.id())?;
+            Ok(String::from_utf8(blob.content().to_vec()).ok())
+        };
+
+        let mut diffs = Vec::new();
+        for delta in commit_changes(self, &commit, None)?.deltas() {
+            let Some(path) = delta.new_file().path().or(delta.old_file().path()) else {
+                continue;
+            };
+            diffs.push(CommitFileDiff {
+                path: RepoPath(path.to_path_buf()),
+                old_text: blob_text(delta.old_file())?,
+                new_text: blob_text(delta.new_file())?,
+            });
+        }
+        Ok(diffs)
+    }
+
     fn branches(&self) -> Result<Vec<Branch>> {
         let local_branches = self.branches(Some(BranchType::Local))?;
         let valid_branches = local_branches
@@ -195,6 +455,137 @@ impl GitRepository for LibGitRepository {
 
         Ok(())
     }
+
+    fn stage_paths(&self, paths: &[RepoPath]) -> Result<()> {
+        let workdir = self
+            .workdir()
+            .ok_or_else(|| anyhow!("cannot stage files in a bare repository"))?;
+        let mut index = self.index()?;
+        for path in paths {
+            check_path_to_repo_path_errors(path)?;
+            if workdir.join(path).exists() {
+                index.add_path(path)?;
+            } else {
+                index.remove_path(path)?;
+            }
+        }
+        index.write()?;
+        Ok(())
+    }
+
+    fn unstage_paths(&self, paths: &[RepoPath]) -> Result<()> {
+        match self.head() {
+            Ok(head) => {
+                let head = head.peel_to_commit()?;
+                self.reset_default(
+                    Some(head.as_object()),
+                    paths.iter().map(|path| path.as_path()),
+                )?;
+            }
+            // Before the first commit, unstaging a file removes it from the index.
+            Err(_) => {
+                let mut index = self.index()?;
+                for path in paths {
+                    index.remove_path(path)?;
+                }
+                index.write()?;
+            }
+        }
+        Ok(())
+    }
+
+    fn set_index_text(&self, path: &RepoPath, content: Option<String>) -> Result<()> {
+        check_path_to_repo_path_errors(path)?;
+        let mut index = self.index()?;
+        if let Some(content) = content {
+            let path_bytes = path.as_os_str().as_bytes().to_vec();
+            let mut entry = index.get_path(path, 0).unwrap_or_else(|| IndexEntry {
+                ctime: IndexTime::new(0, 0),
+                mtime: IndexTime::new(0, 0),
+                dev: 0,
+                ino: 0,
+                mode: 0o100644,
+                uid: 0,
+                gid: 0,
+                file_size: 0,
+                id: git2::Oid::zero(),
+                flags: path_bytes.len().min(0xfff) as u16,
+                flags_extended: 0,
+                path: path_bytes,
+            });
+            // Clear the cached stat data, so that the working directory file is compared
+            // against the new contents rather than assumed to match them.
+            entry.ctime = IndexTime::new(0, 0);
+            entry.mtime = IndexTime::new(0, 0);
+            entry.file_size = content.len() as u32;
+            entry.id = self.blob(content.as_bytes())?;
+            index.add(&entry)?;
+        } else {
+            index.remove_path(path)?;
+        }
+        index.write()?;
+        Ok(())
+    }
+
+    fn commit(&self, message: &str) -> Result<String> {
+        let signature = self.signature()?;
+        let tree_id = self.index()?.write_tree()?;
+        let tree = self.find_tree(tree_id)?;
+        let parent = match self.head() {
+            Ok(head) => Some(head.peel_to_commit()?),
+            Err(_) => None,
+        };
+        let parents = parent.iter().collect::<Vec<_>>();
+        let commit_id = LibGitRepository::commit(
+            self,
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parents,
+        )?;
+        Ok(commit_id.to_string())
+    }
+}
+
+/// Diffs a commit against its first parent, only looking at `path` when it is given.
+fn commit_changes<'a>(
+    repo: &'a LibGitRepository,
+    commit: &git2::Commit,
+    path: Option<&RepoPath>,
+) -> Result<git2::Diff<'a>> {
+    let parent_tree = if commit.parent_count() > 0 {
+        Some(commit.parent(0)?.tree()?)
+    } else {
+        None
+    };
+    let tree = commit.tree()?;
+    let mut options = git2::DiffOptions::new();
+    if let Some(path) = path {
+        options
+            .pathspec(path.0.as_path())
+            .disable_pathspec_match(true);
+    }
+    Ok(repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), Some(&mut options))?)
+}
+
+/// Returns the path that `commit` renamed to `path`, if it was renamed rather than added.
+fn renamed_from(
+    repo: &LibGitRepository,
+    commit: &git2::Commit,
+    path: &RepoPath,
+) -> Result<Option<RepoPath>> {
+    let mut diff = commit_changes(repo, commit, None)?;
+    diff.find_similar(Some(git2::DiffFindOptions::new().renames(true)))?;
+    let old_path = diff
+        .deltas()
+        .find(|delta| {
+            delta.status() == git2::Delta::Renamed
+                && delta.new_file().path() == Some(path.0.as_path())
+        })
+        .and_then(|delta| Some(RepoPath(delta.old_file().path()?.to_path_buf())));
+    Ok(old_path)
 }
 
 fn matches_index(repo: &LibGitRepository, path: &RepoPath, mtime: SystemTime) -> bool {
@@ -229,6 +620,16 @@ fn read_status(status: git2::Status) -> Option<GitFileStatus> {
     }
 }
 
+/// Like [`read_status`], but reports deleted files as modified, since there is no
+/// [`GitFileStatus`] for deletions.
+fn read_change_status(status: git2::Status, deleted: git2::Status) -> Option<GitFileStatus> {
+    read_status(status).or_else(|| {
+        status
+            .intersects(deleted)
+            .then_some(GitFileStatus::Modified)
+    })
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct FakeGitRepository {
     state: Arc<Mutex<FakeGitRepositoryState>>,
@@ -237,8 +638,17 @@ pub struct FakeGitRepository {
 #[derive(Debug, Clone, Default)]
 pub struct FakeGitRepositoryState {
     pub index_contents: HashMap<PathBuf, String>,
+    pub head_contents: HashMap<PathBuf, String>,
     pub worktree_statuses: HashMap<RepoPath, GitFileStatus>,
+    /// The paths in `worktree_statuses` whose changes have been staged.
+    pub staged_paths: HashSet<RepoPath>,
     pub branch_name: Option<String>,
+    /// The messages of the commits created with [`GitRepository::commit`].
+    pub commit_messages: Vec<String>,
+    pub blames: HashMap<RepoPath, Vec<BlameEntry>>,
+    /// The repository's history, newest first.
+    pub log: Vec<LogEntry>,
+    pub commit_diffs: HashMap<String, Vec<CommitFileDiff>>,
 }
 
 impl FakeGitRepository {
@@ -255,6 +665,11 @@ impl GitRepository for FakeGitRepository {
         state.index_contents.get(path).cloned()
     }
 
+    fn load_head_text(&self, path: &Path) -> Option<String> {
+        let state = self.state.lock();
+        state.head_contents.get(path).cloned()
+    }
+
     fn branch_name(&self) -> Option<String> {
         let state = self.state.lock();
         state.branch_name.clone()
@@ -280,6 +695,54 @@ impl GitRepository for FakeGitRepository {
         state.worktree_statuses.get(path).cloned()
     }
 
+    fn file_statuses(&self) -> Result<Vec<GitStatusEntry>> {
+        let state = self.state.lock();
+        let mut entries = state
+            .worktree_statuses
+            .iter()
+            .map(|(repo_path, status)| {
+                let is_staged = state.staged_paths.contains(repo_path);
+                GitStatusEntry {
+                    repo_path: repo_path.clone(),
+                    staged: is_staged.then_some(*status),
+                    unstaged: (!is_staged).then_some(*status),
+                }
+            })
+            .collect::<Vec<_>>();
+        entries.sort_by(|a, b| a.repo_path.cmp(&b.repo_path));
+        Ok(entries)
+    }
+
+    fn blame(&self, path: &RepoPath, _: Option<&str>) -> Result<Vec<BlameEntry>> {
+        let state = self.state.lock();
+        state
+            .blames
+            .get(path)
+            .cloned()
+            .ok_or_else(|| anyhow!("no blame for {path:?}"))
+    }
+
+    fn log(&self, path: Option<&RepoPath>, skip: usize, limit: usize) -> Result<Vec<LogEntry>> {
+        let state = self.state.lock();
+        Ok(state
+            .log
+            .iter()
+            .filter(|entry| path.map_or(true, |path| entry.changed_paths.contains(path)))
+            .skip(skip)
+            .take(limit)
+            .cloned()
+            .collect())
+    }
+
+    fn commit_diff(&self, sha: &str) -> Result<Vec<CommitFileDiff>> {
+        let state = self.state.lock();
+        state
+            .commit_diffs
+            .get(sha)
+            .cloned()
+            .ok_or_else(|| anyhow!("no commit {sha}"))
+    }
+
     fn branches(&self) -> Result<Vec<Branch>> {
         Ok(vec![])
     }
@@ -295,6 +758,52 @@ impl GitRepository for FakeGitRepository {
         state.branch_name = Some(name.to_owned());
         Ok(())
     }
+
+    fn stage_paths(&self, paths: &[RepoPath]) -> Result<()> {
+        let mut state = self.state.lock();
+        for path in paths {
+            if !state.worktree_statuses.contains_key(path) {
+                return Err(anyhow!("no changes to stage for {path:?}"));
+            }
+            state.staged_paths.insert(path.clone());
+        }
+        Ok(())
+    }
+
+    fn unstage_paths(&self, paths: &[RepoPath]) -> Result<()> {
+        let mut state = self.state.lock();
+        for path in paths {
+            state.staged_paths.remove(path);
+        }
+        Ok(())
+    }
+
+    fn set_index_text(&self, path: &RepoPath, content: Option<String>) -> Result<()> {
+        let mut state = self.state.lock();
+        match content {
+            Some(content) => state.index_contents.insert(path.0.clone(), content),
+            None => state.index_contents.remove(&path.0),
+        };
+        Ok(())
+    }
+
+    fn commit(&self, message: &str) -> Result<String> {
+        let mut state = self.state.lock();
+        if state.staged_paths.is_empty() {
+            return Err(anyhow!("nothing to commit"));
+        }
+
+        let staged_paths = std::mem::take(&mut state.staged_paths);
+        for path in &staged_paths {
+            state.worktree_statuses.remove(path);
+            match state.index_contents.get(&path.0).cloned() {
+                Some(content) => state.head_contents.insert(path.0.clone(), content),
+                None => state.head_contents.remove(&path.0),
+            };
+        }
+        state.commit_messages.push(message.to_owned());
+        Ok(format!("{:040x}", state.commit_messages.len()))
+    }
 }
 
 fn check_path_to_repo_path_errors(relative_file_path: &Path) -> Result<()> {
@@ -413,3 +922,65 @@ impl<'a> MapSeekTarget<RepoPath> for RepoPathDescendants<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_follows_renames() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = LibGitRepository::init(dir.path()).unwrap();
+        let mut time = 1_000_000;
+        let mut commit = |message: &str| {
+            let mut index = repo.index().unwrap();
+            index
+                .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+                .unwrap();
+            index.update_all(["*"], None).unwrap();
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let parent = repo.head().ok().map(|head| head.peel_to_commit().unwrap());
+            time += 60;
+            let signature =
+                git2::Signature::new("Test", "test@example.com", &git2::Time::new(time, 0))
+                    .unwrap();
+            LibGitRepository::commit(
+                &repo,
+                Some("HEAD"),
+                &signature,
+                &signature,
+                message,
+                &tree,
+                &parent.iter().collect::<Vec<_>>(),
+            )
+            .unwrap();
+        };
+
+        let contents = "one\ntwo\nthree\nfour\nfive\n";
+        std::fs::write(dir.path().join("a.txt"), contents).unwrap();
+        commit("add a");
+        std::fs::write(dir.path().join("other.txt"), "other\n").unwrap();
+        commit("add other");
+        std::fs::rename(dir.path().join("a.txt"), dir.path().join("b.txt")).unwrap();
+        commit("rename a to b");
+        std::fs::write(dir.path().join("b.txt"), format!("{contents}six\n")).unwrap();
+        commit("edit b");
+
+        let messages = |path: Option<&str>, skip: usize| {
+            let path = path.map(|path| RepoPath(path.into()));
+            GitRepository::log(&repo, path.as_ref(), skip, 10)
+                .unwrap()
+                .into_iter()
+                .map(|entry| entry.message)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            messages(Some("b.txt"), 0),
+            ["edit b", "rename a to b", "add a"]
+        );
+        assert_eq!(messages(Some("b.txt"), 1), ["rename a to b", "add a"]);
+        assert_eq!(messages(Some("other.txt"), 0), ["add other"]);
+        assert_eq!(messages(None, 1), ["rename a to b", "add other", "add a"]);
+    }
+}
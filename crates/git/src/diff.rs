@@ -275,6 +275,69 @@ impl BufferDiff {
     }
 }
 
+/// A region where two texts differ, in rows of each text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineHunk {
+    pub old_rows: Range<u32>,
+    pub new_rows: Range<u32>,
+}
+
+/// Computes the line-based hunks that turn `old` into `new`, without context lines.
+pub fn line_hunks(old: &str, new: &str) -> Vec<LineHunk> {
+    let Some(patch) = BufferDiff::diff(old, new) else {
+        return Vec::new();
+    };
+
+    (0..patch.num_hunks())
+        .filter_map(|hunk_index| {
+            let (hunk, _) = patch.hunk(hunk_index).ok()?;
+            Some(LineHunk {
+                old_rows: hunk_rows(hunk.old_start(), hunk.old_lines()),
+                new_rows: hunk_rows(hunk.new_start(), hunk.new_lines()),
+            })
+        })
+        .collect()
+}
+
+// Git reports the line *before* an empty range as its start.
+fn hunk_rows(start: u32, len: u32) -> Range<u32> {
+    if len == 0 {
+        start..start
+    } else {
+        start - 1..start - 1 + len
+    }
+}
+
+/// Returns the row in `old` that corresponds to `new_row`, given the hunks between them.
+/// Rows inside of a hunk are mapped to the start of that hunk's old rows.
+pub fn old_row_for_new_row(hunks: &[LineHunk], new_row: u32) -> u32 {
+    let mut delta = 0i64;
+    for hunk in hunks {
+        if hunk.new_rows.start > new_row {
+            break;
+        }
+        if hunk.new_rows.contains(&new_row) {
+            return hunk.old_rows.start;
+        }
+        delta = hunk.old_rows.end as i64 - hunk.new_rows.end as i64;
+    }
+    (new_row as i64 + delta) as u32
+}
+
+/// Applies a single hunk of the `old`..`new` diff to `old`, leaving the other hunks unapplied.
+pub fn apply_line_hunk(old: &str, new: &str, hunk: &LineHunk) -> String {
+    let old_lines = old.split_inclusive('\n').collect::<Vec<_>>();
+    let new_lines = new.split_inclusive('\n').collect::<Vec<_>>();
+    let old_rows = hunk.old_rows.start as usize..hunk.old_rows.end as usize;
+    let new_rows = hunk.new_rows.start as usize..hunk.new_rows.end as usize;
+
+    let mut text = String::with_capacity(old.len());
+    text.extend(&old_lines[..old_rows.start]);
+    text.extend(&new_lines[new_rows]);
+    text.extend(&old_lines[old_rows.end..]);
+    text
+}
+
 /// Range (crossing new lines), old, new
 #[cfg(any(test, feature = "test-support"))]
 #[track_caller]
@@ -317,6 +380,58 @@ mod tests {
     use text::Buffer;
     use unindent::Unindent as _;
 
+    #[test]
+    fn test_apply_line_hunk() {
+        let old = "
+            one
+            two
+            three
+            four
+        "
+        .unindent();
+        let new = "
+            zero
+            one
+            TWO
+            three
+        "
+        .unindent();
+
+        let hunks = line_hunks(&old, &new);
+        assert_eq!(
+            hunks,
+            [
+                LineHunk {
+                    old_rows: 0..0,
+                    new_rows: 0..1,
+                },
+                LineHunk {
+                    old_rows: 1..2,
+                    new_rows: 2..3,
+                },
+                LineHunk {
+                    old_rows: 3..4,
+                    new_rows: 4..4,
+                },
+            ]
+        );
+
+        assert_eq!(
+            apply_line_hunk(&old, &new, &hunks[0]),
+            "zero\none\ntwo\nthree\nfour\n"
+        );
+        assert_eq!(
+            apply_line_hunk(&old, &new, &hunks[1]),
+            "one\nTWO\nthree\nfour\n"
+        );
+        assert_eq!(apply_line_hunk(&old, &new, &hunks[2]), "one\ntwo\nthree\n");
+
+        assert_eq!(old_row_for_new_row(&hunks, 0), 0);
+        assert_eq!(old_row_for_new_row(&hunks, 1), 0);
+        assert_eq!(old_row_for_new_row(&hunks, 2), 1);
+        assert_eq!(old_row_for_new_row(&hunks, 3), 2);
+    }
+
     #[test]
     fn test_buffer_diff_simple() {
         let diff_base = "
@@ -0,0 +1,311 @@
+use crate::git_history::format_author_time;
+use editor::{Editor, EditorEvent, MultiBuffer};
+use gpui::{
+    div, AnyElement, AnyView, AppContext, EventEmitter, FocusHandle, FocusableView, IntoElement,
+    Model, ParentElement, Render, Styled, Task, View, ViewContext, VisualContext, WindowContext,
+};
+use language::{Buffer, Capability, LocalFile, Point};
+use project::{repository::LogEntry, Project, ProjectPath};
+use std::{
+    any::{Any, TypeId},
+    ffi::OsStr,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use ui::prelude::*;
+use util::{truncate_and_trailoff, TryFutureExt};
+use workspace::{
+    item::{BreadcrumbText, Item, ItemEvent},
+    ItemNavHistory, ToolbarItemLocation, Workspace,
+};
+
+const CONTEXT_LINE_COUNT: u32 = 3;
+
+/// A read-only multi-buffer of the changes made by a commit.
+///
+/// Each changed file is loaded as it was after the commit, with its contents before the
+/// commit as the diff base, and the deleted lines of every hunk are expanded.
+pub struct CommitView {
+    entry: LogEntry,
+    editor: View<Editor>,
+    excerpts: Model<MultiBuffer>,
+    _load_task: Task<Option<()>>,
+}
+
+/// A file as it was in a commit, so that excerpt headers show the file's path.
+struct CommitFile {
+    path: Arc<Path>,
+    worktree_id: usize,
+    mtime: SystemTime,
+    is_deleted: bool,
+}
+
+impl CommitView {
+    pub fn open(
+        entry: LogEntry,
+        work_directory: ProjectPath,
+        workspace: &mut Workspace,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let existing = workspace
+            .items_of_type::<CommitView>(cx)
+            .find(|view| view.read(cx).entry.sha == entry.sha);
+        if let Some(existing) = existing {
+            workspace.activate_item(&existing, cx);
+        } else {
+            let project = workspace.project().clone();
+            let view = cx.new_view(|cx| CommitView::new(entry, work_directory, project, cx));
+            workspace.add_item(Box::new(view), cx);
+        }
+    }
+
+    fn new(
+        entry: LogEntry,
+        work_directory: ProjectPath,
+        project: Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let replica_id = project.read(cx).replica_id();
+        let excerpts = cx.new_model(|_| MultiBuffer::new(replica_id, Capability::ReadOnly));
+        let editor = cx.new_view(|cx| {
+            let mut editor = Editor::for_multibuffer(excerpts.clone(), Some(project.clone()), cx);
+            editor.set_read_only(true);
+            editor
+        });
+        cx.subscribe(&editor, |_, _, event: &EditorEvent, cx| {
+            cx.emit(event.clone())
+        })
+        .detach();
+
+        let diffs = project
+            .read(cx)
+            .git_commit_diff(work_directory.clone(), entry.sha.clone(), cx);
+        let languages = project.read(cx).languages().clone();
+        let mtime = UNIX_EPOCH + Duration::from_secs(entry.author_time.max(0) as u64);
+        let load_task = cx.spawn(|this, mut cx| {
+            async move {
+                let mut buffers = Vec::new();
+                for diff in diffs.await? {
+                    // Binary files have no text to show.
+                    if diff.old_text.is_none() && diff.new_text.is_none() {
+                        continue;
+                    }
+                    let path = work_directory.path.join(&diff.path);
+                    let language = languages.language_for_file(&path, None).await.ok();
+                    let file = Arc::new(CommitFile {
+                        path: path.into(),
+                        worktree_id: work_directory.worktree_id.to_usize(),
+                        mtime,
+                        is_deleted: diff.new_text.is_none(),
+                    });
+                    let buffer = cx.new_model(|cx| {
+                        let mut buffer = Buffer::new(
+                            0,
+                            cx.entity_id().as_u64(),
+                            diff.new_text.unwrap_or_default(),
+                        );
+                        buffer.set_language(language, cx);
+                        buffer.file_updated(file, cx);
+                        buffer.set_diff_base(Some(diff.old_text.unwrap_or_default()), cx);
+                        buffer.set_capability(Capability::ReadOnly, cx);
+                        buffer
+                    })?;
+                    let recalc = buffer.update(&mut cx, |buffer, cx| buffer.git_diff_recalc(cx))?;
+                    if let Some(recalc) = recalc {
+                        recalc.await;
+                    }
+                    buffers.push(buffer);
+                }
+
+                this.update(&mut cx, |this, cx| {
+                    this.excerpts.update(cx, |excerpts, cx| {
+                        for buffer in buffers {
+                            let snapshot = buffer.read(cx).snapshot();
+                            let max_point = snapshot.max_point();
+                            let ranges = snapshot
+                                .git_diff_hunks_in_row_range(0..max_point.row + 1)
+                                .map(|hunk| {
+                                    Point::new(hunk.buffer_range.start, 0)
+                                        ..Point::new(hunk.buffer_range.end, 0).min(max_point)
+                                })
+                                .collect::<Vec<_>>();
+                            excerpts.push_excerpts_with_context_lines(
+                                buffer,
+                                ranges,
+                                CONTEXT_LINE_COUNT,
+                                cx,
+                            );
+                        }
+                    });
+                    this.editor
+                        .update(cx, |editor, cx| editor.expand_all_hunk_diffs(cx));
+                    cx.notify();
+                })
+            }
+            .log_err()
+        });
+
+        Self {
+            entry,
+            editor,
+            excerpts,
+            _load_task: load_task,
+        }
+    }
+
+    fn summary(&self) -> &str {
+        self.entry.message.lines().next().unwrap_or_default()
+    }
+}
+
+impl language::File for CommitFile {
+    fn as_local(&self) -> Option<&dyn LocalFile> {
+        None
+    }
+
+    fn mtime(&self) -> SystemTime {
+        self.mtime
+    }
+
+    fn path(&self) -> &Arc<Path> {
+        &self.path
+    }
+
+    fn full_path(&self, _: &AppContext) -> PathBuf {
+        self.path.to_path_buf()
+    }
+
+    fn file_name<'a>(&'a self, _: &'a AppContext) -> &'a OsStr {
+        self.path.file_name().unwrap_or_else(|| OsStr::new(""))
+    }
+
+    fn worktree_id(&self) -> usize {
+        self.worktree_id
+    }
+
+    fn is_deleted(&self) -> bool {
+        self.is_deleted
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn to_proto(&self) -> rpc::proto::File {
+        rpc::proto::File {
+            worktree_id: self.worktree_id as u64,
+            entry_id: None,
+            path: self.path.to_string_lossy().into(),
+            mtime: Some(self.mtime.into()),
+            is_deleted: self.is_deleted,
+        }
+    }
+}
+
+impl EventEmitter<EditorEvent> for CommitView {}
+
+impl FocusableView for CommitView {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl Render for CommitView {
+    fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+        div().size_full().child(self.editor.clone())
+    }
+}
+
+impl Item for CommitView {
+    type Event = EditorEvent;
+
+    fn to_item_events(event: &EditorEvent, f: impl FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn deactivated(&mut self, cx: &mut ViewContext<Self>) {
+        self.editor.update(cx, |editor, cx| editor.deactivated(cx));
+    }
+
+    fn navigate(&mut self, data: Box<dyn Any>, cx: &mut ViewContext<Self>) -> bool {
+        self.editor
+            .update(cx, |editor, cx| editor.navigate(data, cx))
+    }
+
+    fn tab_tooltip_text(&self, _: &AppContext) -> Option<SharedString> {
+        Some(
+            format!(
+                "{}\n{}, {}",
+                self.entry.message.trim_end(),
+                self.entry.author,
+                format_author_time(self.entry.author_time)
+            )
+            .into(),
+        )
+    }
+
+    fn tab_content(&self, _detail: Option<usize>, selected: bool, _: &WindowContext) -> AnyElement {
+        let short_sha = self.entry.sha.chars().take(7).collect::<String>();
+        Label::new(format!(
+            "{short_sha} {}",
+            truncate_and_trailoff(self.summary(), 40)
+        ))
+        .color(if selected {
+            Color::Default
+        } else {
+            Color::Muted
+        })
+        .into_any_element()
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("commit view")
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &AppContext,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::Item),
+    ) {
+        self.editor.for_each_project_item(cx, f)
+    }
+
+    fn is_singleton(&self, _: &AppContext) -> bool {
+        false
+    }
+
+    fn set_nav_history(&mut self, nav_history: ItemNavHistory, cx: &mut ViewContext<Self>) {
+        self.editor.update(cx, |editor, _| {
+            editor.set_nav_history(Some(nav_history));
+        });
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a View<Self>,
+        _: &'a AppContext,
+    ) -> Option<AnyView> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.to_any())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.editor.to_any())
+        } else {
+            None
+        }
+    }
+
+    fn breadcrumb_location(&self) -> ToolbarItemLocation {
+        ToolbarItemLocation::PrimaryLeft
+    }
+
+    fn breadcrumbs(&self, theme: &theme::Theme, cx: &AppContext) -> Option<Vec<BreadcrumbText>> {
+        self.editor.breadcrumbs(theme, cx)
+    }
+
+    fn added_to_workspace(&mut self, workspace: &mut Workspace, cx: &mut ViewContext<Self>) {
+        self.editor
+            .update(cx, |editor, cx| editor.added_to_workspace(workspace, cx));
+    }
+}
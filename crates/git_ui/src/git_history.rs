@@ -0,0 +1,332 @@
+use crate::commit_view::CommitView;
+use chrono::{Local, NaiveDateTime, TimeZone};
+use gpui::{
+    actions, AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView, InteractiveElement,
+    IntoElement, Model, ParentElement, Render, Styled, Task, View, ViewContext, VisualContext,
+    WeakView, WindowContext,
+};
+use project::{repository::LogEntry, Project, ProjectPath};
+use std::path::Path;
+use ui::{prelude::*, ListItem};
+use util::TryFutureExt;
+use workspace::{
+    item::{Item, ItemEvent},
+    Workspace, WorkspaceId,
+};
+
+actions!(git_history, [ShowRepositoryHistory, ShowFileHistory]);
+
+/// The number of commits loaded at a time.
+const PAGE_SIZE: usize = 100;
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(GitHistoryView::register).detach();
+}
+
+/// The commits of a repository, newest first, or only the commits that changed one of
+/// its files. Clicking a commit opens its diff.
+pub struct GitHistoryView {
+    project: Model<Project>,
+    workspace: WeakView<Workspace>,
+    focus_handle: FocusHandle,
+    /// The file whose history is shown, or the repository's work directory.
+    path: ProjectPath,
+    work_directory: ProjectPath,
+    entries: Vec<LogEntry>,
+    has_more: bool,
+    is_loading: bool,
+    load_task: Task<Option<()>>,
+}
+
+impl GitHistoryView {
+    fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+        workspace.register_action(Self::show_repository_history);
+        workspace.register_action(Self::show_file_history);
+    }
+
+    fn new(
+        project: Model<Project>,
+        workspace: WeakView<Workspace>,
+        path: ProjectPath,
+        work_directory: ProjectPath,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let mut this = Self {
+            project,
+            workspace,
+            focus_handle: cx.focus_handle(),
+            path,
+            work_directory,
+            entries: Vec::new(),
+            has_more: true,
+            is_loading: false,
+            load_task: Task::ready(None),
+        };
+        this.load_more(cx);
+        this
+    }
+
+    /// Shows the history of the repository containing the active item, or of the first
+    /// worktree's repository when no item is active.
+    fn show_repository_history(
+        workspace: &mut Workspace,
+        _: &ShowRepositoryHistory,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let project = workspace.project().read(cx);
+        let path = workspace
+            .active_item(cx)
+            .and_then(|item| item.project_path(cx))
+            .or_else(|| {
+                let worktree = project.visible_worktrees(cx).next()?;
+                Some(ProjectPath {
+                    worktree_id: worktree.read(cx).id(),
+                    path: Path::new("").into(),
+                })
+            });
+        let Some(work_directory) =
+            path.and_then(|path| work_directory_for_path(project, &path, cx))
+        else {
+            return;
+        };
+        Self::open(workspace, work_directory.clone(), work_directory, cx);
+    }
+
+    fn show_file_history(
+        workspace: &mut Workspace,
+        _: &ShowFileHistory,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let Some(path) = workspace
+            .active_item(cx)
+            .and_then(|item| item.project_path(cx))
+        else {
+            return;
+        };
+        let Some(work_directory) = work_directory_for_path(workspace.project().read(cx), &path, cx)
+        else {
+            return;
+        };
+        Self::open(workspace, path, work_directory, cx);
+    }
+
+    fn open(
+        workspace: &mut Workspace,
+        path: ProjectPath,
+        work_directory: ProjectPath,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let existing = workspace
+            .items_of_type::<GitHistoryView>(cx)
+            .find(|view| view.read(cx).path == path);
+        if let Some(existing) = existing {
+            workspace.activate_item(&existing, cx);
+        } else {
+            let project = workspace.project().clone();
+            let workspace_handle = cx.view().downgrade();
+            let view = cx.new_view(|cx| {
+                GitHistoryView::new(project, workspace_handle, path, work_directory, cx)
+            });
+            workspace.add_item(Box::new(view), cx);
+        }
+    }
+
+    fn is_file_history(&self) -> bool {
+        self.path != self.work_directory
+    }
+
+    fn load_more(&mut self, cx: &mut ViewContext<Self>) {
+        if self.is_loading || !self.has_more {
+            return;
+        }
+        let log =
+            self.project
+                .read(cx)
+                .git_log(self.path.clone(), self.entries.len(), PAGE_SIZE, cx);
+        let workspace = self.workspace.clone();
+        self.is_loading = true;
+        self.load_task = cx.spawn(|this, mut cx| {
+            async move {
+                let result = log.await;
+                this.update(&mut cx, |this, cx| {
+                    this.is_loading = false;
+                    match result {
+                        Ok(entries) => {
+                            this.has_more = entries.len() == PAGE_SIZE;
+                            this.entries.extend(entries);
+                        }
+                        Err(error) => {
+                            this.has_more = false;
+                            workspace
+                                .update(cx, |workspace, cx| workspace.show_error(&error, cx))
+                                .ok();
+                        }
+                    }
+                    cx.notify();
+                })
+            }
+            .log_err()
+        });
+    }
+
+    fn open_commit(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        let Some(entry) = self.entries.get(ix).cloned() else {
+            return;
+        };
+        let work_directory = self.work_directory.clone();
+        self.workspace
+            .update(cx, |workspace, cx| {
+                CommitView::open(entry, work_directory, workspace, cx);
+            })
+            .ok();
+    }
+
+    fn render_entry(&self, ix: usize, entry: &LogEntry, cx: &mut ViewContext<Self>) -> ListItem {
+        let summary = entry.message.lines().next().unwrap_or_default().to_string();
+        let short_sha = entry.sha.chars().take(7).collect::<String>();
+        ListItem::new(ix)
+            .on_click(cx.listener(move |this, _, cx| this.open_commit(ix, cx)))
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(Label::new(short_sha).color(Color::Muted))
+                    .child(Label::new(summary)),
+            )
+            .end_slot(
+                Label::new(format!(
+                    "{}, {}",
+                    entry.author,
+                    format_author_time(entry.author_time)
+                ))
+                .size(LabelSize::Small)
+                .color(Color::Muted),
+            )
+    }
+}
+
+fn work_directory_for_path(
+    project: &Project,
+    path: &ProjectPath,
+    cx: &AppContext,
+) -> Option<ProjectPath> {
+    let worktree = project.worktree_for_id(path.worktree_id, cx)?;
+    let (work_directory, _) = worktree
+        .read(cx)
+        .repository_and_work_directory_for_path(&path.path)?;
+    Some(ProjectPath {
+        worktree_id: path.worktree_id,
+        path: work_directory.as_ref().into(),
+    })
+}
+
+pub(crate) fn format_author_time(author_time: i64) -> String {
+    NaiveDateTime::from_timestamp_opt(author_time, 0)
+        .map(|time| {
+            Local
+                .from_utc_datetime(&time)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_default()
+}
+
+impl EventEmitter<ItemEvent> for GitHistoryView {}
+
+impl FocusableView for GitHistoryView {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for GitHistoryView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let entries = self
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(ix, entry)| self.render_entry(ix, entry, cx))
+            .collect::<Vec<_>>();
+        let is_loading = self.is_loading;
+
+        v_flex()
+            .id("git-history")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .overflow_y_scroll()
+            .bg(cx.theme().colors().editor_background)
+            .children(entries)
+            .when(self.entries.is_empty() && !is_loading, |this| {
+                this.child(
+                    div()
+                        .p_4()
+                        .child(Label::new("No commits").color(Color::Muted)),
+                )
+            })
+            .when(self.has_more && !self.entries.is_empty(), |this| {
+                this.child(
+                    div().p_2().child(
+                        Button::new("load-more", "Load More")
+                            .disabled(is_loading)
+                            .on_click(cx.listener(|this, _, cx| this.load_more(cx))),
+                    ),
+                )
+            })
+    }
+}
+
+impl Item for GitHistoryView {
+    type Event = ItemEvent;
+
+    fn tab_tooltip_text(&self, _: &AppContext) -> Option<SharedString> {
+        Some(self.path.path.to_string_lossy().to_string().into())
+    }
+
+    fn tab_content(&self, _: Option<usize>, selected: bool, _: &WindowContext) -> AnyElement {
+        let label = if self.is_file_history() {
+            let file_name = self
+                .path
+                .path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            format!("History of {file_name}")
+        } else {
+            "Repository History".to_string()
+        };
+        Label::new(label)
+            .color(if selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("git history")
+    }
+
+    fn show_toolbar(&self) -> bool {
+        false
+    }
+
+    fn clone_on_split(
+        &self,
+        _workspace_id: WorkspaceId,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<View<Self>> {
+        Some(cx.new_view(|cx| {
+            GitHistoryView::new(
+                self.project.clone(),
+                self.workspace.clone(),
+                self.path.clone(),
+                self.work_directory.clone(),
+                cx,
+            )
+        }))
+    }
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+}
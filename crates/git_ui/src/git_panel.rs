@@ -0,0 +1,422 @@
+use crate::git_panel_settings::GitPanelSettings;
+use anyhow::Result;
+use db::kvp::KEY_VALUE_STORE;
+use editor::Editor;
+use gpui::{
+    actions, div, AppContext, AsyncWindowContext, EventEmitter, FocusHandle, FocusableView,
+    InteractiveElement, IntoElement, Model, ParentElement, Pixels, Render, Styled, Subscription,
+    Task, View, ViewContext, VisualContext, WeakView, WindowContext,
+};
+use project::{
+    git_repository::GitStatus, repository::GitFileStatus, Event as ProjectEvent, Fs, Project,
+    ProjectPath,
+};
+use serde::{Deserialize, Serialize};
+use settings::Settings;
+use std::sync::Arc;
+use ui::{prelude::*, ListItem, Tooltip};
+use util::{ResultExt, TryFutureExt};
+use workspace::{
+    dock::{DockPosition, Panel, PanelEvent},
+    Workspace,
+};
+
+const GIT_PANEL_KEY: &'static str = "GitPanel";
+
+actions!(git_panel, [ToggleFocus, Commit, StageAll, UnstageAll]);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(
+        |workspace: &mut Workspace, _: &mut ViewContext<Workspace>| {
+            workspace.register_action(|workspace, _: &ToggleFocus, cx| {
+                workspace.toggle_panel_focus::<GitPanel>(cx);
+            });
+        },
+    )
+    .detach();
+}
+
+pub struct GitPanel {
+    project: Model<Project>,
+    workspace: WeakView<Workspace>,
+    fs: Arc<dyn Fs>,
+    focus_handle: FocusHandle,
+    width: Option<Pixels>,
+    commit_editor: View<Editor>,
+    statuses: Vec<GitStatus>,
+    pending_serialization: Task<Option<()>>,
+    load_statuses: Task<Option<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedGitPanel {
+    width: Option<Pixels>,
+}
+
+impl GitPanel {
+    pub fn load(
+        workspace: WeakView<Workspace>,
+        cx: AsyncWindowContext,
+    ) -> Task<Result<View<Self>>> {
+        cx.spawn(|mut cx| async move {
+            let serialized_panel = if let Some(panel) = cx
+                .background_executor()
+                .spawn(async move { KEY_VALUE_STORE.read_kvp(GIT_PANEL_KEY) })
+                .await
+                .log_err()
+                .flatten()
+            {
+                Some(serde_json::from_str::<SerializedGitPanel>(&panel)?)
+            } else {
+                None
+            };
+
+            workspace.update(&mut cx, |workspace, cx| {
+                let panel = cx.new_view(|cx| Self::new(workspace, cx));
+                if let Some(serialized_panel) = serialized_panel {
+                    panel.update(cx, |panel, cx| {
+                        panel.width = serialized_panel.width;
+                        cx.notify();
+                    });
+                }
+                panel
+            })
+        })
+    }
+
+    fn new(workspace: &Workspace, cx: &mut ViewContext<Self>) -> Self {
+        let project = workspace.project().clone();
+        let commit_editor = cx.new_view(|cx| {
+            let mut editor = Editor::auto_height(8, cx);
+            editor.set_placeholder_text("Commit message (⌘⏎ to commit)", cx);
+            editor
+        });
+        let subscriptions = vec![cx.subscribe(&project, |this, _, event, cx| match event {
+            ProjectEvent::GitRepositoriesUpdated(_)
+            | ProjectEvent::WorktreeAdded
+            | ProjectEvent::WorktreeRemoved(_) => this.refresh_statuses(cx),
+            _ => {}
+        })];
+
+        let mut this = Self {
+            project,
+            workspace: workspace.weak_handle(),
+            fs: workspace.app_state().fs.clone(),
+            focus_handle: cx.focus_handle(),
+            width: None,
+            commit_editor,
+            statuses: Vec::new(),
+            pending_serialization: Task::ready(None),
+            load_statuses: Task::ready(None),
+            _subscriptions: subscriptions,
+        };
+        this.refresh_statuses(cx);
+        this
+    }
+
+    fn refresh_statuses(&mut self, cx: &mut ViewContext<Self>) {
+        let statuses = self.project.read(cx).git_statuses(cx);
+        self.load_statuses = cx.spawn(|this, mut cx| {
+            async move {
+                let mut statuses = statuses.await?;
+                statuses.sort_by(|a, b| a.project_path.path.cmp(&b.project_path.path));
+                this.update(&mut cx, |this, cx| {
+                    this.statuses = statuses;
+                    cx.notify();
+                })
+            }
+            .log_err()
+        });
+    }
+
+    /// Runs a git operation and refreshes the statuses once it finishes. `on_success` is
+    /// only called when the operation succeeds.
+    fn run_git_operation(
+        &mut self,
+        operation: impl FnOnce(&Project, &AppContext) -> Task<Result<()>>,
+        on_success: impl FnOnce(&mut Self, &mut ViewContext<Self>) + 'static,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let task = operation(self.project.read(cx), cx);
+        let workspace = self.workspace.clone();
+        cx.spawn(|this, mut cx| async move {
+            let result = task.await;
+            if let Err(error) = &result {
+                workspace
+                    .update(&mut cx, |workspace, cx| workspace.show_error(error, cx))
+                    .ok();
+            }
+            this.update(&mut cx, |this, cx| {
+                if result.is_ok() {
+                    on_success(this, cx);
+                }
+                this.refresh_statuses(cx)
+            })?;
+            result
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn stage_paths(&mut self, paths: Vec<ProjectPath>, cx: &mut ViewContext<Self>) {
+        self.run_git_operation(|project, cx| project.stage_paths(paths, cx), |_, _| {}, cx);
+    }
+
+    fn unstage_paths(&mut self, paths: Vec<ProjectPath>, cx: &mut ViewContext<Self>) {
+        self.run_git_operation(
+            |project, cx| project.unstage_paths(paths, cx),
+            |_, _| {},
+            cx,
+        );
+    }
+
+    fn stage_all(&mut self, _: &StageAll, cx: &mut ViewContext<Self>) {
+        let paths = self
+            .statuses
+            .iter()
+            .filter(|status| status.entry.unstaged.is_some())
+            .map(|status| status.project_path.clone())
+            .collect();
+        self.stage_paths(paths, cx);
+    }
+
+    fn unstage_all(&mut self, _: &UnstageAll, cx: &mut ViewContext<Self>) {
+        let paths = self
+            .statuses
+            .iter()
+            .filter(|status| status.entry.staged.is_some())
+            .map(|status| status.project_path.clone())
+            .collect();
+        self.unstage_paths(paths, cx);
+    }
+
+    fn commit(&mut self, _: &Commit, cx: &mut ViewContext<Self>) {
+        let message = self.commit_editor.read(cx).text(cx);
+        if message.trim().is_empty() {
+            return;
+        }
+        // Keep the message around when the commit fails, so that it can be retried.
+        self.run_git_operation(
+            |project, cx| project.commit(message, cx),
+            |this, cx| this.commit_editor.update(cx, |editor, cx| editor.clear(cx)),
+            cx,
+        );
+    }
+
+    fn open_path(&mut self, project_path: ProjectPath, cx: &mut ViewContext<Self>) {
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace
+                    .open_path(project_path, None, true, cx)
+                    .detach_and_log_err(cx);
+            })
+            .log_err();
+    }
+
+    fn serialize(&mut self, cx: &mut ViewContext<Self>) {
+        let width = self.width;
+        self.pending_serialization = cx.background_executor().spawn(
+            async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        GIT_PANEL_KEY.into(),
+                        serde_json::to_string(&SerializedGitPanel { width })?,
+                    )
+                    .await?;
+                anyhow::Ok(())
+            }
+            .log_err(),
+        );
+    }
+
+    fn render_entries(&self, staged: bool, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let entries = self.statuses.iter().filter_map(|status| {
+            let file_status = if staged {
+                status.entry.staged
+            } else {
+                status.entry.unstaged
+            }?;
+            let project_path = status.project_path.clone();
+            let (label, color) = match file_status {
+                GitFileStatus::Added => ("A", Color::Created),
+                GitFileStatus::Modified => ("M", Color::Modified),
+                GitFileStatus::Conflict => ("C", Color::Conflict),
+            };
+            let id = SharedString::from(format!(
+                "{}-{}-{}",
+                if staged { "staged" } else { "unstaged" },
+                project_path.worktree_id.to_usize(),
+                project_path.path.to_string_lossy()
+            ));
+            let toggle_staged = {
+                let project_path = project_path.clone();
+                cx.listener(move |this, _, cx| {
+                    if staged {
+                        this.unstage_paths(vec![project_path.clone()], cx);
+                    } else {
+                        this.stage_paths(vec![project_path.clone()], cx);
+                    }
+                })
+            };
+            Some(
+                ListItem::new(id.clone())
+                    .on_click(cx.listener(move |this, _, cx| {
+                        this.open_path(project_path.clone(), cx);
+                    }))
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(Label::new(label).color(color))
+                            .child(Label::new(
+                                status.project_path.path.to_string_lossy().to_string(),
+                            )),
+                    )
+                    .end_slot(
+                        IconButton::new(
+                            id,
+                            if staged {
+                                IconName::Dash
+                            } else {
+                                IconName::Plus
+                            },
+                        )
+                        .icon_size(IconSize::Small)
+                        .tooltip(move |cx| {
+                            Tooltip::text(if staged { "Unstage" } else { "Stage" }, cx)
+                        })
+                        .on_click(toggle_staged),
+                    ),
+            )
+        });
+        v_flex().children(entries)
+    }
+}
+
+fn section_header(title: &'static str, action: impl IntoElement) -> impl IntoElement {
+    h_flex()
+        .justify_between()
+        .px_2()
+        .pt_2()
+        .child(Label::new(title).size(LabelSize::Small).color(Color::Muted))
+        .child(action)
+}
+
+impl Render for GitPanel {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let has_staged_changes = self
+            .statuses
+            .iter()
+            .any(|status| status.entry.staged.is_some());
+        let has_unstaged_changes = self
+            .statuses
+            .iter()
+            .any(|status| status.entry.unstaged.is_some());
+
+        v_flex()
+            .key_context("GitPanel")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .on_action(cx.listener(Self::commit))
+            .on_action(cx.listener(Self::stage_all))
+            .on_action(cx.listener(Self::unstage_all))
+            .child(
+                v_flex()
+                    .gap_1()
+                    .p_2()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(self.commit_editor.clone())
+                    .child(
+                        Button::new("commit", "Commit")
+                            .style(ButtonStyle::Filled)
+                            .full_width()
+                            .disabled(!has_staged_changes)
+                            .on_click(|_, cx| cx.dispatch_action(Box::new(Commit))),
+                    ),
+            )
+            .child(
+                v_flex()
+                    .id("git-panel-entries")
+                    .flex_1()
+                    .overflow_y_scroll()
+                    .child(section_header(
+                        "Staged Changes",
+                        Button::new("unstage-all", "Unstage All")
+                            .label_size(LabelSize::Small)
+                            .disabled(!has_staged_changes)
+                            .on_click(|_, cx| cx.dispatch_action(Box::new(UnstageAll))),
+                    ))
+                    .child(self.render_entries(true, cx))
+                    .child(section_header(
+                        "Changes",
+                        Button::new("stage-all", "Stage All")
+                            .label_size(LabelSize::Small)
+                            .disabled(!has_unstaged_changes)
+                            .on_click(|_, cx| cx.dispatch_action(Box::new(StageAll))),
+                    ))
+                    .child(self.render_entries(false, cx)),
+            )
+            .when(self.statuses.is_empty(), |this| {
+                this.child(
+                    div().p_4().child(
+                        Label::new("There are no changes in the project's git repositories.")
+                            .color(Color::Muted)
+                            .size(LabelSize::Small),
+                    ),
+                )
+            })
+    }
+}
+
+impl FocusableView for GitPanel {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for GitPanel {}
+
+impl Panel for GitPanel {
+    fn persistent_name() -> &'static str {
+        "GitPanel"
+    }
+
+    fn position(&self, cx: &WindowContext) -> DockPosition {
+        GitPanelSettings::get_global(cx).dock
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, cx: &mut ViewContext<Self>) {
+        settings::update_settings_file::<GitPanelSettings>(self.fs.clone(), cx, move |settings| {
+            settings.dock = Some(position)
+        });
+    }
+
+    fn size(&self, cx: &WindowContext) -> Pixels {
+        self.width
+            .unwrap_or_else(|| GitPanelSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, cx: &mut ViewContext<Self>) {
+        self.width = size;
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn icon(&self, cx: &WindowContext) -> Option<IconName> {
+        GitPanelSettings::get_global(cx)
+            .button
+            .then(|| IconName::FileGit)
+    }
+
+    fn icon_tooltip(&self, _cx: &WindowContext) -> Option<&'static str> {
+        Some("Git Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn gpui::Action> {
+        Box::new(ToggleFocus)
+    }
+}
@@ -0,0 +1,16 @@
+pub mod commit_view;
+pub mod git_history;
+pub mod git_panel;
+mod git_panel_settings;
+pub mod merge_conflicts;
+
+use git_panel_settings::GitPanelSettings;
+use gpui::AppContext;
+use settings::Settings;
+
+pub fn init(cx: &mut AppContext) {
+    GitPanelSettings::register(cx);
+    git_panel::init(cx);
+    git_history::init(cx);
+    merge_conflicts::init(cx);
+}
@@ -0,0 +1,323 @@
+use anyhow::Result;
+use editor::{Editor, EditorEvent, MultiBuffer};
+use gpui::{
+    actions, div, AnyElement, AnyView, AppContext, EventEmitter, FocusHandle, FocusableView,
+    InteractiveElement, IntoElement, Model, ParentElement, Render, Styled, Subscription, Task,
+    View, ViewContext, VisualContext, WeakView, WindowContext,
+};
+use project::{Event as ProjectEvent, Project, ProjectPath};
+use std::{
+    any::{Any, TypeId},
+    path::PathBuf,
+};
+use ui::{prelude::*, Label};
+use util::TryFutureExt;
+use workspace::{
+    item::{BreadcrumbText, Item, ItemEvent},
+    ItemNavHistory, ToolbarItemLocation, Workspace,
+};
+
+actions!(merge_conflicts, [Deploy]);
+
+const CONTEXT_LINE_COUNT: u32 = 1;
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(MergeConflictsEditor::register)
+        .detach();
+}
+
+/// A multi-buffer of the conflicted regions of every file that git reports as conflicted.
+///
+/// Files whose conflict markers were already removed are left out, and the excerpts are
+/// only rebuilt when the set of conflicted files changes, so that resolving a conflict
+/// doesn't move the user's place.
+pub struct MergeConflictsEditor {
+    project: Model<Project>,
+    workspace: WeakView<Workspace>,
+    focus_handle: FocusHandle,
+    editor: View<Editor>,
+    excerpts: Model<MultiBuffer>,
+    conflicted_paths: Vec<ProjectPath>,
+    update_excerpts_task: Task<Option<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl EventEmitter<EditorEvent> for MergeConflictsEditor {}
+
+impl Render for MergeConflictsEditor {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let child = if self.conflicted_paths.is_empty() {
+            div()
+                .bg(cx.theme().colors().editor_background)
+                .flex()
+                .items_center()
+                .justify_center()
+                .size_full()
+                .child(Label::new("No merge conflicts"))
+        } else {
+            div().size_full().child(self.editor.clone())
+        };
+
+        div()
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .child(child)
+    }
+}
+
+impl MergeConflictsEditor {
+    fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+        workspace.register_action(Self::deploy);
+    }
+
+    fn new(
+        project: Model<Project>,
+        workspace: WeakView<Workspace>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let project_subscription = cx.subscribe(&project, |this, _, event, cx| {
+            if let ProjectEvent::GitRepositoriesUpdated(_) = event {
+                this.update_excerpts(cx);
+            }
+        });
+
+        let focus_handle = cx.focus_handle();
+        let focus_in_subscription = cx.on_focus_in(&focus_handle, |this, cx| this.focus_in(cx));
+
+        let excerpts = cx.new_model(|cx| {
+            MultiBuffer::new(project.read(cx).replica_id(), project.read(cx).capability())
+        });
+        let editor = cx.new_view(|cx| {
+            let mut editor = Editor::for_multibuffer(excerpts.clone(), Some(project.clone()), cx);
+            editor.set_vertical_scroll_margin(5, cx);
+            editor
+        });
+        let editor_subscription = cx.subscribe(&editor, |this, _, event: &EditorEvent, cx| {
+            cx.emit(event.clone());
+            if event == &EditorEvent::Focused && this.conflicted_paths.is_empty() {
+                cx.focus(&this.focus_handle);
+            }
+        });
+
+        let mut this = Self {
+            project,
+            workspace,
+            focus_handle,
+            editor,
+            excerpts,
+            conflicted_paths: Vec::new(),
+            update_excerpts_task: Task::ready(None),
+            _subscriptions: vec![
+                project_subscription,
+                editor_subscription,
+                focus_in_subscription,
+            ],
+        };
+        this.update_excerpts(cx);
+        this
+    }
+
+    fn deploy(workspace: &mut Workspace, _: &Deploy, cx: &mut ViewContext<Workspace>) {
+        if let Some(existing) = workspace.item_of_type::<MergeConflictsEditor>(cx) {
+            workspace.activate_item(&existing, cx);
+        } else {
+            let workspace_handle = cx.view().downgrade();
+            let conflicts = cx.new_view(|cx| {
+                MergeConflictsEditor::new(workspace.project().clone(), workspace_handle, cx)
+            });
+            workspace.add_item(Box::new(conflicts), cx);
+        }
+    }
+
+    fn focus_in(&mut self, cx: &mut ViewContext<Self>) {
+        if self.focus_handle.is_focused(cx) && !self.conflicted_paths.is_empty() {
+            self.editor.focus_handle(cx).focus(cx)
+        }
+    }
+
+    fn update_excerpts(&mut self, cx: &mut ViewContext<Self>) {
+        let statuses = self.project.read(cx).git_statuses(cx);
+        let project = self.project.clone();
+        self.update_excerpts_task = cx.spawn(|this, mut cx| {
+            async move {
+                let conflicted_paths = statuses
+                    .await?
+                    .into_iter()
+                    .filter(|status| status.entry.is_conflicted())
+                    .map(|status| status.project_path)
+                    .collect::<Vec<_>>();
+                let unchanged =
+                    this.update(&mut cx, |this, _| this.conflicted_paths == conflicted_paths)?;
+                if unchanged {
+                    return Ok(());
+                }
+
+                let mut buffers = Vec::new();
+                for path in &conflicted_paths {
+                    let buffer = project
+                        .update(&mut cx, |project, cx| project.open_buffer(path.clone(), cx))?
+                        .await?;
+                    buffers.push(buffer);
+                }
+
+                this.update(&mut cx, |this, cx| {
+                    this.excerpts.update(cx, |excerpts, cx| {
+                        excerpts.clear(cx);
+                        for buffer in buffers {
+                            let ranges = buffer
+                                .read(cx)
+                                .snapshot()
+                                .merge_conflicts()
+                                .iter()
+                                .map(|conflict| conflict.range.clone())
+                                .collect::<Vec<_>>();
+                            excerpts.push_excerpts_with_context_lines(
+                                buffer,
+                                ranges,
+                                CONTEXT_LINE_COUNT,
+                                cx,
+                            );
+                        }
+                    });
+                    this.conflicted_paths = conflicted_paths;
+                    cx.notify();
+                })
+            }
+            .log_err()
+        });
+    }
+}
+
+impl FocusableView for MergeConflictsEditor {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for MergeConflictsEditor {
+    type Event = EditorEvent;
+
+    fn to_item_events(event: &EditorEvent, f: impl FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn deactivated(&mut self, cx: &mut ViewContext<Self>) {
+        self.editor.update(cx, |editor, cx| editor.deactivated(cx));
+    }
+
+    fn navigate(&mut self, data: Box<dyn Any>, cx: &mut ViewContext<Self>) -> bool {
+        self.editor
+            .update(cx, |editor, cx| editor.navigate(data, cx))
+    }
+
+    fn tab_tooltip_text(&self, _: &AppContext) -> Option<SharedString> {
+        Some("Merge Conflicts".into())
+    }
+
+    fn tab_content(&self, _detail: Option<usize>, selected: bool, _: &WindowContext) -> AnyElement {
+        let label = match self.conflicted_paths.len() {
+            0 => "No merge conflicts".to_string(),
+            1 => "1 conflicted file".to_string(),
+            count => format!("{count} conflicted files"),
+        };
+        Label::new(label)
+            .color(if selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("merge conflicts")
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &AppContext,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::Item),
+    ) {
+        self.editor.for_each_project_item(cx, f)
+    }
+
+    fn is_singleton(&self, _: &AppContext) -> bool {
+        false
+    }
+
+    fn set_nav_history(&mut self, nav_history: ItemNavHistory, cx: &mut ViewContext<Self>) {
+        self.editor.update(cx, |editor, _| {
+            editor.set_nav_history(Some(nav_history));
+        });
+    }
+
+    fn clone_on_split(
+        &self,
+        _workspace_id: workspace::WorkspaceId,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<View<Self>>
+    where
+        Self: Sized,
+    {
+        Some(cx.new_view(|cx| {
+            MergeConflictsEditor::new(self.project.clone(), self.workspace.clone(), cx)
+        }))
+    }
+
+    fn is_dirty(&self, cx: &AppContext) -> bool {
+        self.excerpts.read(cx).is_dirty(cx)
+    }
+
+    fn has_conflict(&self, cx: &AppContext) -> bool {
+        self.excerpts.read(cx).has_conflict(cx)
+    }
+
+    fn can_save(&self, _: &AppContext) -> bool {
+        true
+    }
+
+    fn save(&mut self, project: Model<Project>, cx: &mut ViewContext<Self>) -> Task<Result<()>> {
+        self.editor.save(project, cx)
+    }
+
+    fn save_as(
+        &mut self,
+        _: Model<Project>,
+        _: PathBuf,
+        _: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        unreachable!()
+    }
+
+    fn reload(&mut self, project: Model<Project>, cx: &mut ViewContext<Self>) -> Task<Result<()>> {
+        self.editor.reload(project, cx)
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a View<Self>,
+        _: &'a AppContext,
+    ) -> Option<AnyView> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.to_any())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.editor.to_any())
+        } else {
+            None
+        }
+    }
+
+    fn breadcrumb_location(&self) -> ToolbarItemLocation {
+        ToolbarItemLocation::PrimaryLeft
+    }
+
+    fn breadcrumbs(&self, theme: &theme::Theme, cx: &AppContext) -> Option<Vec<BreadcrumbText>> {
+        self.editor.breadcrumbs(theme, cx)
+    }
+
+    fn added_to_workspace(&mut self, workspace: &mut Workspace, cx: &mut ViewContext<Self>) {
+        self.editor
+            .update(cx, |editor, cx| editor.added_to_workspace(workspace, cx));
+    }
+}
@@ -1174,6 +1174,33 @@ impl<'a> WindowContext<'a> {
         !self.app.propagate_event
     }
 
+    /// Dispatch a keystroke as though the user had typed it: to the key bindings and key
+    /// listeners first, and otherwise as text input to the focused element's input handler.
+    pub fn dispatch_keystroke(&mut self, keystroke: Keystroke) -> bool {
+        // Keystrokes are matched against the key contexts of the last frame, which must reflect
+        // the effects of any keystrokes dispatched before this one.
+        if self.window.dirty || self.window.focus_invalidated {
+            self.draw();
+        }
+
+        if self.dispatch_event(PlatformInput::KeyDown(KeyDownEvent {
+            keystroke: keystroke.clone(),
+            is_held: false,
+        })) {
+            return true;
+        }
+
+        if let Some(input) = keystroke.ime_key {
+            if let Some(mut input_handler) = self.window.platform_window.take_input_handler() {
+                input_handler.flush_pending_input(&input, self);
+                self.window.platform_window.set_input_handler(input_handler);
+                return true;
+            }
+        }
+
+        false
+    }
+
     fn dispatch_mouse_event(&mut self, event: &dyn Any) {
         if let Some(mut handlers) = self
             .window
@@ -0,0 +1,728 @@
+mod hierarchy_panel_settings;
+
+use anyhow::Result;
+use collections::HashMap;
+use db::kvp::KEY_VALUE_STORE;
+use editor::{scroll::Autoscroll, Editor, MultiBuffer};
+use gpui::{
+    actions, div, AppContext, AsyncWindowContext, ClickEvent, EventEmitter, FocusHandle,
+    FocusableView, InteractiveElement, IntoElement, KeyContext, Model, ParentElement, Pixels,
+    Render, Styled, Task, View, ViewContext, VisualContext, WeakView, WindowContext,
+};
+use hierarchy_panel_settings::HierarchyPanelSettings;
+use language::{Capability, OffsetRangeExt};
+use menu::{Confirm, SelectNext, SelectPrev};
+use project::{CallHierarchyCall, CallHierarchyItem, Fs, Location, Project, TypeHierarchyItem};
+use serde::{Deserialize, Serialize};
+use settings::Settings;
+use std::sync::Arc;
+use ui::{prelude::*, ListItem};
+use util::{ResultExt, TryFutureExt};
+use workspace::{
+    dock::{DockPosition, Panel, PanelEvent},
+    Workspace,
+};
+
+const HIERARCHY_PANEL_KEY: &'static str = "HierarchyPanel";
+const CONTEXT_LINE_COUNT: u32 = 2;
+
+actions!(
+    hierarchy_panel,
+    [
+        ToggleFocus,
+        ShowIncomingCalls,
+        ShowOutgoingCalls,
+        ShowSupertypes,
+        ShowSubtypes,
+        ExpandSelectedNode,
+        CollapseSelectedNode,
+    ]
+);
+
+pub fn init(cx: &mut AppContext) {
+    HierarchyPanelSettings::register(cx);
+    cx.observe_new_views(
+        |workspace: &mut Workspace, _: &mut ViewContext<Workspace>| {
+            workspace.register_action(|workspace, _: &ToggleFocus, cx| {
+                workspace.toggle_panel_focus::<HierarchyPanel>(cx);
+            });
+            workspace.register_action(|workspace, _: &ShowIncomingCalls, cx| {
+                HierarchyPanel::show(HierarchyKind::IncomingCalls, workspace, cx);
+            });
+            workspace.register_action(|workspace, _: &ShowOutgoingCalls, cx| {
+                HierarchyPanel::show(HierarchyKind::OutgoingCalls, workspace, cx);
+            });
+            workspace.register_action(|workspace, _: &ShowSupertypes, cx| {
+                HierarchyPanel::show(HierarchyKind::Supertypes, workspace, cx);
+            });
+            workspace.register_action(|workspace, _: &ShowSubtypes, cx| {
+                HierarchyPanel::show(HierarchyKind::Subtypes, workspace, cx);
+            });
+        },
+    )
+    .detach();
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HierarchyKind {
+    IncomingCalls,
+    OutgoingCalls,
+    Supertypes,
+    Subtypes,
+}
+
+impl HierarchyKind {
+    fn is_call_hierarchy(self) -> bool {
+        matches!(self, Self::IncomingCalls | Self::OutgoingCalls)
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::IncomingCalls => "Incoming Calls",
+            Self::OutgoingCalls => "Outgoing Calls",
+            Self::Supertypes => "Supertypes",
+            Self::Subtypes => "Subtypes",
+        }
+    }
+
+    /// The kind showing the other direction of the same hierarchy.
+    fn reversed(self) -> Self {
+        match self {
+            Self::IncomingCalls => Self::OutgoingCalls,
+            Self::OutgoingCalls => Self::IncomingCalls,
+            Self::Supertypes => Self::Subtypes,
+            Self::Subtypes => Self::Supertypes,
+        }
+    }
+}
+
+#[derive(Clone)]
+enum NodeItem {
+    Call(CallHierarchyItem),
+    Type(TypeHierarchyItem),
+}
+
+impl NodeItem {
+    fn name(&self) -> &str {
+        match self {
+            Self::Call(item) => &item.name,
+            Self::Type(item) => &item.name,
+        }
+    }
+
+    fn detail(&self) -> Option<&str> {
+        match self {
+            Self::Call(item) => item.detail.as_deref(),
+            Self::Type(item) => item.detail.as_deref(),
+        }
+    }
+
+    fn location(&self) -> &Location {
+        match self {
+            Self::Call(item) => &item.location,
+            Self::Type(item) => &item.location,
+        }
+    }
+
+    fn selection_range(&self) -> &std::ops::Range<language::Anchor> {
+        match self {
+            Self::Call(item) => &item.selection_range,
+            Self::Type(item) => &item.selection_range,
+        }
+    }
+}
+
+enum NodeChildren {
+    NotLoaded,
+    Loading,
+    Loaded(Vec<usize>),
+}
+
+/// An item in the tree. Nodes are stored in a flat list and refer to their children by
+/// index, and their children are only requested from the language server when they're
+/// first expanded.
+struct HierarchyNode {
+    item: NodeItem,
+    /// The calls linking this node to its parent, which are shown in the preview.
+    call_sites: Vec<Location>,
+    depth: usize,
+    children: NodeChildren,
+    is_expanded: bool,
+}
+
+/// A tree of the callers or callees of a function, or of the supertypes or subtypes of a
+/// type, with a preview of the selected node's call sites.
+pub struct HierarchyPanel {
+    project: Model<Project>,
+    workspace: WeakView<Workspace>,
+    fs: Arc<dyn Fs>,
+    focus_handle: FocusHandle,
+    width: Option<Pixels>,
+    kind: HierarchyKind,
+    nodes: Vec<HierarchyNode>,
+    roots: Vec<usize>,
+    selected_node: Option<usize>,
+    preview: View<Editor>,
+    preview_excerpts: Model<MultiBuffer>,
+    pending_serialization: Task<Option<()>>,
+    load_tasks: HashMap<usize, Task<Option<()>>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedHierarchyPanel {
+    width: Option<Pixels>,
+}
+
+impl HierarchyPanel {
+    pub fn load(
+        workspace: WeakView<Workspace>,
+        cx: AsyncWindowContext,
+    ) -> Task<Result<View<Self>>> {
+        cx.spawn(|mut cx| async move {
+            let serialized_panel = if let Some(panel) = cx
+                .background_executor()
+                .spawn(async move { KEY_VALUE_STORE.read_kvp(HIERARCHY_PANEL_KEY) })
+                .await
+                .log_err()
+                .flatten()
+            {
+                Some(serde_json::from_str::<SerializedHierarchyPanel>(&panel)?)
+            } else {
+                None
+            };
+
+            workspace.update(&mut cx, |workspace, cx| {
+                let panel = cx.new_view(|cx| Self::new(workspace, cx));
+                if let Some(serialized_panel) = serialized_panel {
+                    panel.update(cx, |panel, cx| {
+                        panel.width = serialized_panel.width;
+                        cx.notify();
+                    });
+                }
+                panel
+            })
+        })
+    }
+
+    fn new(workspace: &Workspace, cx: &mut ViewContext<Self>) -> Self {
+        let project = workspace.project().clone();
+        let preview_excerpts = cx
+            .new_model(|cx| MultiBuffer::new(project.read(cx).replica_id(), Capability::ReadOnly));
+        let preview = cx.new_view(|cx| {
+            let mut editor =
+                Editor::for_multibuffer(preview_excerpts.clone(), Some(project.clone()), cx);
+            editor.set_read_only(true);
+            editor
+        });
+
+        Self {
+            project,
+            workspace: workspace.weak_handle(),
+            fs: workspace.app_state().fs.clone(),
+            focus_handle: cx.focus_handle(),
+            width: None,
+            kind: HierarchyKind::IncomingCalls,
+            nodes: Vec::new(),
+            roots: Vec::new(),
+            selected_node: None,
+            preview,
+            preview_excerpts,
+            pending_serialization: Task::ready(None),
+            load_tasks: HashMap::default(),
+        }
+    }
+
+    /// Prepares the hierarchy of the symbol under the active editor's newest cursor and shows
+    /// it in the panel.
+    fn show(kind: HierarchyKind, workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+        let Some(editor) = workspace
+            .active_item(cx)
+            .and_then(|item| item.act_as::<Editor>(cx))
+        else {
+            return;
+        };
+        let Some((buffer, position)) = editor.update(cx, |editor, cx| {
+            let head = editor.selections.newest::<usize>(cx).head();
+            editor.buffer().read(cx).text_anchor_for_position(head, cx)
+        }) else {
+            return;
+        };
+
+        let project = workspace.project().clone();
+        let roots: Task<Result<Vec<NodeItem>>> = if kind.is_call_hierarchy() {
+            let items = project.update(cx, |project, cx| {
+                project.prepare_call_hierarchy(&buffer, position, cx)
+            });
+            cx.spawn(
+                |_, _| async move { Ok(items.await?.into_iter().map(NodeItem::Call).collect()) },
+            )
+        } else {
+            let items = project.update(cx, |project, cx| {
+                project.prepare_type_hierarchy(&buffer, position, cx)
+            });
+            cx.spawn(
+                |_, _| async move { Ok(items.await?.into_iter().map(NodeItem::Type).collect()) },
+            )
+        };
+
+        cx.spawn(|workspace, mut cx| async move {
+            let roots = roots.await?;
+            workspace.update(&mut cx, |workspace, cx| {
+                if let Some(panel) = workspace.focus_panel::<HierarchyPanel>(cx) {
+                    panel.update(cx, |panel, cx| panel.set_roots(kind, roots, cx));
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn set_roots(&mut self, kind: HierarchyKind, roots: Vec<NodeItem>, cx: &mut ViewContext<Self>) {
+        self.kind = kind;
+        self.nodes.clear();
+        self.load_tasks.clear();
+        self.roots = roots
+            .into_iter()
+            .enumerate()
+            .map(|(ix, item)| {
+                self.nodes.push(HierarchyNode {
+                    item,
+                    call_sites: Vec::new(),
+                    depth: 0,
+                    children: NodeChildren::NotLoaded,
+                    is_expanded: false,
+                });
+                ix
+            })
+            .collect();
+        self.selected_node = self.roots.first().copied();
+        if let Some(root) = self.selected_node {
+            self.set_expanded(root, true, cx);
+        }
+        self.update_preview(cx);
+        cx.notify();
+    }
+
+    /// Shows the other direction of the hierarchy, starting from the same roots.
+    fn reverse(&mut self, cx: &mut ViewContext<Self>) {
+        let roots = self
+            .roots
+            .iter()
+            .map(|&ix| self.nodes[ix].item.clone())
+            .collect();
+        self.set_roots(self.kind.reversed(), roots, cx);
+    }
+
+    fn set_expanded(&mut self, ix: usize, is_expanded: bool, cx: &mut ViewContext<Self>) {
+        let Some(node) = self.nodes.get_mut(ix) else {
+            return;
+        };
+        node.is_expanded = is_expanded;
+        if is_expanded && matches!(node.children, NodeChildren::NotLoaded) {
+            node.children = NodeChildren::Loading;
+            let item = node.item.clone();
+            let children = self.request_children(&item, cx);
+            let task = cx.spawn(|this, mut cx| {
+                async move {
+                    let children = children.await;
+                    this.update(&mut cx, |this, cx| {
+                        this.load_tasks.remove(&ix);
+                        let depth = this.nodes[ix].depth + 1;
+                        let children = children
+                            .log_err()
+                            .unwrap_or_default()
+                            .into_iter()
+                            .map(|(item, call_sites)| {
+                                this.nodes.push(HierarchyNode {
+                                    item,
+                                    call_sites,
+                                    depth,
+                                    children: NodeChildren::NotLoaded,
+                                    is_expanded: false,
+                                });
+                                this.nodes.len() - 1
+                            })
+                            .collect();
+                        this.nodes[ix].children = NodeChildren::Loaded(children);
+                        cx.notify();
+                    })
+                }
+                .log_err()
+            });
+            self.load_tasks.insert(ix, task);
+        }
+        cx.notify();
+    }
+
+    fn request_children(
+        &self,
+        item: &NodeItem,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<Vec<(NodeItem, Vec<Location>)>>> {
+        fn calls_to_children(calls: Vec<CallHierarchyCall>) -> Vec<(NodeItem, Vec<Location>)> {
+            calls
+                .into_iter()
+                .map(|call| (NodeItem::Call(call.item), call.call_sites))
+                .collect()
+        }
+
+        let calls = match (self.kind, item) {
+            (HierarchyKind::IncomingCalls, NodeItem::Call(item)) => self
+                .project
+                .update(cx, |project, cx| project.incoming_calls(item, cx)),
+            (HierarchyKind::OutgoingCalls, NodeItem::Call(item)) => self
+                .project
+                .update(cx, |project, cx| project.outgoing_calls(item, cx)),
+            (HierarchyKind::Supertypes, NodeItem::Type(item)) => {
+                let types = self
+                    .project
+                    .update(cx, |project, cx| project.supertypes(item, cx));
+                return cx.spawn(|_, _| async move {
+                    Ok(types
+                        .await?
+                        .into_iter()
+                        .map(|item| (NodeItem::Type(item), Vec::new()))
+                        .collect())
+                });
+            }
+            (HierarchyKind::Subtypes, NodeItem::Type(item)) => {
+                let types = self
+                    .project
+                    .update(cx, |project, cx| project.subtypes(item, cx));
+                return cx.spawn(|_, _| async move {
+                    Ok(types
+                        .await?
+                        .into_iter()
+                        .map(|item| (NodeItem::Type(item), Vec::new()))
+                        .collect())
+                });
+            }
+            _ => return Task::ready(Ok(Vec::new())),
+        };
+        cx.spawn(|_, _| async move { Ok(calls_to_children(calls.await?)) })
+    }
+
+    /// The nodes that are currently shown, in the order they're rendered.
+    fn visible_nodes(&self) -> Vec<usize> {
+        let mut visible_nodes = Vec::new();
+        let mut stack = self.roots.iter().rev().copied().collect::<Vec<_>>();
+        while let Some(ix) = stack.pop() {
+            visible_nodes.push(ix);
+            let node = &self.nodes[ix];
+            if let (true, NodeChildren::Loaded(children)) = (node.is_expanded, &node.children) {
+                stack.extend(children.iter().rev().copied());
+            }
+        }
+        visible_nodes
+    }
+
+    fn select_node(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        if self.selected_node != Some(ix) {
+            self.selected_node = Some(ix);
+            self.update_preview(cx);
+            cx.notify();
+        }
+    }
+
+    fn select_next(&mut self, _: &SelectNext, cx: &mut ViewContext<Self>) {
+        let visible_nodes = self.visible_nodes();
+        let next = match self
+            .selected_node
+            .and_then(|selected| visible_nodes.iter().position(|&ix| ix == selected))
+        {
+            Some(position) => visible_nodes.get(position + 1),
+            None => visible_nodes.first(),
+        };
+        if let Some(&ix) = next {
+            self.select_node(ix, cx);
+        }
+    }
+
+    fn select_prev(&mut self, _: &SelectPrev, cx: &mut ViewContext<Self>) {
+        let visible_nodes = self.visible_nodes();
+        let prev = match self
+            .selected_node
+            .and_then(|selected| visible_nodes.iter().position(|&ix| ix == selected))
+        {
+            Some(position) => position.checked_sub(1).and_then(|ix| visible_nodes.get(ix)),
+            None => visible_nodes.last(),
+        };
+        if let Some(&ix) = prev {
+            self.select_node(ix, cx);
+        }
+    }
+
+    fn expand_selected_node(&mut self, _: &ExpandSelectedNode, cx: &mut ViewContext<Self>) {
+        if let Some(ix) = self.selected_node {
+            self.set_expanded(ix, true, cx);
+        }
+    }
+
+    fn collapse_selected_node(&mut self, _: &CollapseSelectedNode, cx: &mut ViewContext<Self>) {
+        if let Some(ix) = self.selected_node {
+            self.set_expanded(ix, false, cx);
+        }
+    }
+
+    fn confirm(&mut self, _: &Confirm, cx: &mut ViewContext<Self>) {
+        if let Some(ix) = self.selected_node {
+            self.open_node(ix, cx);
+        }
+    }
+
+    /// Opens the node's definition, with its name selected.
+    fn open_node(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        let Some(node) = self.nodes.get(ix) else {
+            return;
+        };
+        let buffer = node.item.location().buffer.clone();
+        let range = node.item.selection_range().to_offset(buffer.read(cx));
+        self.workspace
+            .update(cx, |workspace, cx| {
+                let editor = workspace.open_project_item::<Editor>(buffer, cx);
+                editor.update(cx, |editor, cx| {
+                    editor.change_selections(Some(Autoscroll::center()), cx, |s| {
+                        s.select_ranges([range]);
+                    });
+                });
+            })
+            .log_err();
+    }
+
+    /// Shows the selected node's call sites, or the definition of a root or a type.
+    fn update_preview(&mut self, cx: &mut ViewContext<Self>) {
+        let locations = match self.selected_node.map(|ix| &self.nodes[ix]) {
+            Some(node) if node.call_sites.is_empty() => vec![node.item.location().clone()],
+            Some(node) => node.call_sites.clone(),
+            None => Vec::new(),
+        };
+        let highlights = self.preview_excerpts.update(cx, |excerpts, cx| {
+            excerpts.clear(cx);
+            let mut highlights = Vec::new();
+            let mut locations = locations.into_iter().peekable();
+            while let Some(location) = locations.next() {
+                let buffer = location.buffer;
+                let mut ranges = vec![location.range];
+                while let Some(next) = locations.next_if(|next| next.buffer == buffer) {
+                    ranges.push(next.range);
+                }
+                highlights.extend(excerpts.push_excerpts_with_context_lines(
+                    buffer,
+                    ranges,
+                    CONTEXT_LINE_COUNT,
+                    cx,
+                ));
+            }
+            highlights
+        });
+        let highlight_call_sites = self
+            .selected_node
+            .map_or(false, |ix| !self.nodes[ix].call_sites.is_empty());
+        self.preview.update(cx, |editor, cx| {
+            if highlight_call_sites {
+                editor.highlight_background::<Self>(
+                    highlights,
+                    |theme| theme.editor_highlighted_line_background,
+                    cx,
+                );
+            } else {
+                editor.clear_background_highlights::<Self>(cx);
+            }
+        });
+    }
+
+    fn serialize(&mut self, cx: &mut ViewContext<Self>) {
+        let width = self.width;
+        self.pending_serialization = cx.background_executor().spawn(
+            async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        HIERARCHY_PANEL_KEY.into(),
+                        serde_json::to_string(&SerializedHierarchyPanel { width })?,
+                    )
+                    .await?;
+                anyhow::Ok(())
+            }
+            .log_err(),
+        );
+    }
+
+    fn dispatch_context(&self) -> KeyContext {
+        let mut dispatch_context = KeyContext::default();
+        dispatch_context.add("HierarchyPanel");
+        dispatch_context.add("menu");
+        dispatch_context
+    }
+
+    fn render_node(&self, ix: usize, cx: &mut ViewContext<Self>) -> ListItem {
+        let node = &self.nodes[ix];
+        let toggle = match &node.children {
+            NodeChildren::Loaded(children) if children.is_empty() => None,
+            _ => Some(node.is_expanded),
+        };
+        let file_name = node
+            .item
+            .location()
+            .buffer
+            .read(cx)
+            .file()
+            .map(|file| file.file_name(cx).to_string_lossy().to_string());
+        let is_expanded = node.is_expanded;
+
+        ListItem::new(ix)
+            .indent_level(node.depth)
+            .indent_step_size(px(12.))
+            .selected(self.selected_node == Some(ix))
+            .toggle(toggle)
+            .on_toggle(cx.listener(move |this, _, cx| this.set_expanded(ix, !is_expanded, cx)))
+            .on_click(cx.listener(move |this, event: &ClickEvent, cx| {
+                this.select_node(ix, cx);
+                if event.up.click_count > 1 {
+                    this.open_node(ix, cx);
+                }
+            }))
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(Label::new(node.item.name().to_string()))
+                    .when_some(node.item.detail(), |this, detail| {
+                        this.child(
+                            Label::new(detail.to_string())
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                    })
+                    .when(matches!(node.children, NodeChildren::Loading), |this| {
+                        this.child(
+                            Label::new("Loading…")
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                    }),
+            )
+            .end_slot(file_name.map(|file_name| {
+                Label::new(file_name)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted)
+            }))
+    }
+}
+
+impl Render for HierarchyPanel {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let nodes = self
+            .visible_nodes()
+            .into_iter()
+            .map(|ix| self.render_node(ix, cx))
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .key_context(self.dispatch_context())
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_prev))
+            .on_action(cx.listener(Self::expand_selected_node))
+            .on_action(cx.listener(Self::collapse_selected_node))
+            .on_action(cx.listener(Self::confirm))
+            .child(
+                h_flex()
+                    .justify_between()
+                    .px_2()
+                    .py_1()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(
+                        Label::new(self.kind.label())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(
+                        Button::new("reverse-hierarchy", self.kind.reversed().label())
+                            .label_size(LabelSize::Small)
+                            .disabled(self.roots.is_empty())
+                            .on_click(cx.listener(|this, _, cx| this.reverse(cx))),
+                    ),
+            )
+            .map(|this| {
+                if self.roots.is_empty() {
+                    this.child(
+                        div().p_4().child(
+                            Label::new("Show the calls or types of a symbol to explore them here.")
+                                .color(Color::Muted)
+                                .size(LabelSize::Small),
+                        ),
+                    )
+                } else {
+                    this.child(
+                        v_flex()
+                            .id("hierarchy-panel-nodes")
+                            .flex_1()
+                            .overflow_y_scroll()
+                            .children(nodes),
+                    )
+                    .child(
+                        div()
+                            .flex_1()
+                            .border_t_1()
+                            .border_color(cx.theme().colors().border)
+                            .child(self.preview.clone()),
+                    )
+                }
+            })
+    }
+}
+
+impl FocusableView for HierarchyPanel {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for HierarchyPanel {}
+
+impl Panel for HierarchyPanel {
+    fn persistent_name() -> &'static str {
+        "HierarchyPanel"
+    }
+
+    fn position(&self, cx: &WindowContext) -> DockPosition {
+        HierarchyPanelSettings::get_global(cx).dock
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, cx: &mut ViewContext<Self>) {
+        settings::update_settings_file::<HierarchyPanelSettings>(
+            self.fs.clone(),
+            cx,
+            move |settings| settings.dock = Some(position),
+        );
+    }
+
+    fn size(&self, cx: &WindowContext) -> Pixels {
+        self.width
+            .unwrap_or_else(|| HierarchyPanelSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, cx: &mut ViewContext<Self>) {
+        self.width = size;
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn icon(&self, cx: &WindowContext) -> Option<IconName> {
+        HierarchyPanelSettings::get_global(cx)
+            .button
+            .then(|| IconName::FileTree)
+    }
+
+    fn icon_tooltip(&self, _cx: &WindowContext) -> Option<&'static str> {
+        Some("Hierarchy Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn gpui::Action> {
+        Box::new(ToggleFocus)
+    }
+}
@@ -8,12 +8,13 @@ use crate::{
     diagnostic_set::{DiagnosticEntry, DiagnosticGroup},
     language_settings::{language_settings, LanguageSettings},
     markdown::parse_markdown,
+    merge_conflict::{merge_conflicts, MergeConflict},
     outline::OutlineItem,
     syntax_map::{
         SyntaxLayer, SyntaxMap, SyntaxMapCapture, SyntaxMapCaptures, SyntaxMapMatches,
         SyntaxSnapshot, ToTreeSitterPoint,
     },
-    CodeLabel, LanguageScope, Outline,
+    CodeLabel, LanguageScope, Outline, RunnableCommand, TextObject,
 };
 use anyhow::{anyhow, Result};
 pub use clock::ReplicaId;
@@ -80,6 +81,11 @@ pub struct Buffer {
     text: TextBuffer,
     diff_base: Option<String>,
     git_diff: git::diff::BufferDiff,
+    merge_conflicts: Arc<[MergeConflict]>,
+    merge_conflicts_task: Option<Task<()>>,
+    syntax_folds: Arc<[Range<Anchor>]>,
+    syntax_folds_update_count: usize,
+    syntax_folds_task: Option<Task<()>>,
     file: Option<Arc<dyn File>>,
     /// The mtime of the file when this buffer was last loaded from
     /// or saved to disk.
@@ -117,6 +123,7 @@ pub struct Buffer {
 pub struct BufferSnapshot {
     text: text::BufferSnapshot,
     git_diff: git::diff::BufferDiff,
+    merge_conflicts: Arc<[MergeConflict]>,
     pub(crate) syntax: SyntaxSnapshot,
     file: Option<Arc<dyn File>>,
     diagnostics: SmallVec<[(LanguageServerId, DiagnosticSet); 2]>,
@@ -127,6 +134,8 @@ pub struct BufferSnapshot {
     selections_update_count: usize,
     language: Option<Arc<Language>>,
     parse_count: usize,
+    syntax_folds: Arc<[Range<Anchor>]>,
+    syntax_folds_update_count: usize,
 }
 
 /// The kind and amount of indentation in a particular line. For now,
@@ -277,6 +286,17 @@ pub struct CodeAction {
     pub lsp_action: lsp::CodeAction,
 }
 
+/// A test, benchmark or entry point found by a language's `runnables` query.
+#[derive(Clone, Debug)]
+pub struct Runnable {
+    /// The range of the buffer that was captured as `@run`.
+    pub run_range: Range<usize>,
+    /// The text captured as `@name`, if any.
+    pub symbol: Option<String>,
+    /// The command configured for the tag of the matching pattern.
+    pub command: RunnableCommand,
+}
+
 /// An operation used to synchronize this buffer with its other replicas.
 #[derive(Clone, Debug, PartialEq)]
 pub enum Operation {
@@ -333,6 +353,8 @@ pub enum Event {
     Reloaded,
     /// The buffer's diff_base changed.
     DiffBaseChanged,
+    /// The buffer's merge conflict regions changed.
+    MergeConflictsChanged,
     /// The buffer's language was changed.
     LanguageChanged,
     /// The buffer's syntax trees were updated.
@@ -666,6 +688,11 @@ impl Buffer {
             reload_task: None,
             transaction_depth: 0,
             was_dirty_before_starting_transaction: None,
+            merge_conflicts: Arc::from([]),
+            merge_conflicts_task: None,
+            syntax_folds: Arc::from([]),
+            syntax_folds_update_count: 0,
+            syntax_folds_task: None,
             text: buffer,
             diff_base,
             git_diff: git::diff::BufferDiff::new(),
@@ -703,6 +730,7 @@ impl Buffer {
             text,
             syntax,
             git_diff: self.git_diff.clone(),
+            merge_conflicts: self.merge_conflicts.clone(),
             file: self.file.clone(),
             remote_selections: self.remote_selections.clone(),
             diagnostics: self.diagnostics.clone(),
@@ -712,6 +740,8 @@ impl Buffer {
             language: self.language.clone(),
             parse_count: self.parse_count,
             selections_update_count: self.selections_update_count,
+            syntax_folds: self.syntax_folds.clone(),
+            syntax_folds_update_count: self.syntax_folds_update_count,
         }
     }
 
@@ -973,6 +1003,11 @@ impl Buffer {
         self.git_diff_update_count
     }
 
+    /// The number of times the syntax folds were collected.
+    pub fn syntax_folds_update_count(&self) -> usize {
+        self.syntax_folds_update_count
+    }
+
     /// Whether the buffer is being parsed in the background.
     #[cfg(any(test, feature = "test-support"))]
     pub fn is_parsing(&self) -> bool {
@@ -1020,6 +1055,8 @@ impl Buffer {
         let language = if let Some(language) = self.language.clone() {
             language
         } else {
+            self.refresh_merge_conflicts(cx);
+            self.refresh_syntax_folds(cx);
             return;
         };
 
@@ -1083,10 +1120,56 @@ impl Buffer {
         self.parse_count += 1;
         self.syntax_map.lock().did_parse(syntax_snapshot);
         self.request_autoindent(cx);
+        self.refresh_merge_conflicts(cx);
+        self.refresh_syntax_folds(cx);
         cx.emit(Event::Reparsed);
         cx.notify();
     }
 
+    /// Scans the buffer for conflict markers on the background executor, replacing any
+    /// scan that is still running. Buffers are rescanned whenever they're reparsed.
+    pub fn refresh_merge_conflicts(&mut self, cx: &mut ModelContext<Self>) {
+        let snapshot = self.text.snapshot();
+        let scan = cx
+            .background_executor()
+            .spawn(async move { merge_conflicts(&snapshot) });
+        self.merge_conflicts_task = Some(cx.spawn(|this, mut cx| async move {
+            let conflicts = scan.await;
+            this.update(&mut cx, |this, cx| {
+                let snapshot = this.text.snapshot();
+                let changed = conflicts.len() != this.merge_conflicts.len()
+                    || conflicts
+                        .iter()
+                        .zip(this.merge_conflicts.iter())
+                        .any(|(new, old)| new.to_offset(&snapshot) != old.to_offset(&snapshot));
+                if changed {
+                    this.merge_conflicts = conflicts.into();
+                    cx.emit(Event::MergeConflictsChanged);
+                    cx.notify();
+                }
+            })
+            .ok();
+        }));
+    }
+
+    /// Collects the folds of the `folds` query on the background executor, replacing any
+    /// collection that is still running.
+    fn refresh_syntax_folds(&mut self, cx: &mut ModelContext<Self>) {
+        let snapshot = self.snapshot();
+        let folds = cx
+            .background_executor()
+            .spawn(async move { snapshot.compute_syntax_folds() });
+        self.syntax_folds_task = Some(cx.spawn(|this, mut cx| async move {
+            let folds = folds.await;
+            this.update(&mut cx, |this, cx| {
+                this.syntax_folds = folds.into();
+                this.syntax_folds_update_count += 1;
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
     /// Assign to the buffer a set of diagnostics created by a given language server.
     pub fn update_diagnostics(
         &mut self,
@@ -2831,6 +2914,201 @@ impl BufferSnapshot {
         self.syntax.matches(range, self, query)
     }
 
+    /// Returns the range to fold for the outermost node captured as `@fold` by the
+    /// language's `folds` query that starts on the given row.
+    ///
+    /// The range starts at the end of the row. When the node's last line only holds
+    /// closing delimiters, the range ends before that line so that they stay visible.
+    /// The folds are collected in the background after each parse, so until then they
+    /// follow the edits made since the last one.
+    pub fn syntax_fold_range(&self, row: u32) -> Option<Range<Point>> {
+        let ix = self
+            .syntax_folds
+            .binary_search_by_key(&row, |fold| fold.start.to_point(self).row)
+            .ok()?;
+        let end = self.syntax_folds[ix].end.to_point(self);
+        (end.row > row).then(|| Point::new(row, self.line_len(row))..end)
+    }
+
+    /// Collects the outermost fold starting on each row, from the start of the row to
+    /// the end of the fold, sorted by row.
+    ///
+    /// Consecutive single-line nodes captured as `@fold.run` that start their line,
+    /// such as line comments, are folded together as one range.
+    fn compute_syntax_folds(&self) -> Vec<Range<Anchor>> {
+        let mut matches = self.syntax.matches(0..self.len(), &self.text, |grammar| {
+            grammar.folds_config.as_ref().map(|c| &c.query)
+        });
+        let configs = matches
+            .grammars()
+            .iter()
+            .map(|grammar| grammar.folds_config.as_ref().unwrap())
+            .collect::<Vec<_>>();
+
+        let mut folds = BTreeMap::<u32, Point>::new();
+        let mut run: Option<Range<Point>> = None;
+        let mut runs = Vec::new();
+        while let Some(mat) = matches.peek() {
+            let config = &configs[mat.grammar_index];
+            for capture in mat.captures {
+                let start = Point::new(
+                    capture.node.start_position().row as u32,
+                    capture.node.start_position().column as u32,
+                );
+                let node_end = Point::new(
+                    capture.node.end_position().row as u32,
+                    capture.node.end_position().column as u32,
+                );
+                if Some(capture.index) == config.fold_run_capture_ix {
+                    if start.row != node_end.row
+                        || self.indent_size_for_line(start.row).len != start.column
+                    {
+                        continue;
+                    }
+                    if let Some(run) = run.as_mut().filter(|run| start.row <= run.end.row + 1) {
+                        run.end = run.end.max(node_end);
+                    } else {
+                        runs.extend(run.replace(start..node_end));
+                    }
+                    continue;
+                }
+                if capture.index != config.fold_capture_ix {
+                    continue;
+                }
+
+                let ends_with_delimiters = self
+                    .text_for_range(Point::new(node_end.row, 0)..node_end)
+                    .flat_map(|chunk| chunk.chars())
+                    .all(|c| !c.is_alphanumeric());
+                let end = if ends_with_delimiters {
+                    (node_end.row > start.row + 1)
+                        .then(|| Point::new(node_end.row - 1, self.line_len(node_end.row - 1)))
+                } else {
+                    (node_end.row > start.row).then_some(node_end)
+                };
+                if let Some(end) = end {
+                    let fold_end = folds.entry(start.row).or_insert(end);
+                    *fold_end = (*fold_end).max(end);
+                }
+            }
+            matches.advance();
+        }
+        runs.extend(run);
+
+        for run in runs {
+            if run.end.row > run.start.row {
+                let fold_end = folds.entry(run.start.row).or_insert(run.end);
+                *fold_end = (*fold_end).max(run.end);
+            }
+        }
+        folds
+            .into_iter()
+            .map(|(row, end)| self.anchor_after(Point::new(row, 0))..self.anchor_before(end))
+            .collect()
+    }
+
+    /// Returns the runnables whose `@run` capture starts within the given range.
+    ///
+    /// Matches whose pattern has no `tag`, or whose tag has no command configured
+    /// for the language, are skipped.
+    pub fn runnables_in_range(&self, range: Range<usize>) -> Vec<Runnable> {
+        let mut matches = self.syntax.matches(range.clone(), &self.text, |grammar| {
+            grammar.runnable_config.as_ref().map(|c| &c.query)
+        });
+        let configs = matches
+            .grammars()
+            .iter()
+            .map(|grammar| grammar.runnable_config.as_ref().unwrap())
+            .collect::<Vec<_>>();
+
+        let mut runnables = Vec::new();
+        while let Some(mat) = matches.peek() {
+            let config = &configs[mat.grammar_index];
+            let run_range = mat
+                .captures
+                .iter()
+                .find(|capture| capture.index == config.run_capture_ix)
+                .map(|capture| capture.node.byte_range());
+            let symbol = mat
+                .captures
+                .iter()
+                .find(|capture| Some(capture.index) == config.name_capture_ix)
+                .map(|capture| self.text_for_range(capture.node.byte_range()).collect());
+            let tag = config.pattern_tags[mat.pattern_index].as_deref();
+            matches.advance();
+
+            let Some((run_range, tag)) = run_range.zip(tag) else {
+                continue;
+            };
+            if !range.contains(&run_range.start) {
+                continue;
+            }
+            let command = self
+                .language_at(run_range.start)
+                .and_then(|language| language.config.runnables.get(tag));
+            if let Some(command) = command {
+                runnables.push(Runnable {
+                    run_range,
+                    symbol,
+                    command: command.clone(),
+                });
+            }
+        }
+        runnables
+    }
+
+    /// Returns the ranges of the given text objects from the language's `textobjects` query
+    /// that overlap the given range, along with the text object that each one is.
+    ///
+    /// Captures of the same text object within a match, such as a run of line comments, are
+    /// combined into one range.
+    pub fn text_object_ranges<T: ToOffset>(
+        &self,
+        range: Range<T>,
+        text_objects: &[TextObject],
+    ) -> Vec<(Range<usize>, TextObject)> {
+        let range = range.start.to_offset(self)..range.end.to_offset(self);
+        let mut ranges = Vec::<(Range<usize>, TextObject)>::new();
+        let mut matches = self.syntax.matches(range.clone(), &self.text, |grammar| {
+            grammar
+                .text_object_config
+                .as_ref()
+                .map(|config| &config.query)
+        });
+        let configs = matches
+            .grammars()
+            .iter()
+            .map(|grammar| grammar.text_object_config.as_ref().unwrap())
+            .collect::<Vec<_>>();
+        while let Some(mat) = matches.peek() {
+            let captures = configs[mat.grammar_index]
+                .captures
+                .iter()
+                .filter(|capture| {
+                    text_objects.contains(&capture.text_object)
+                        && capture.pattern_ixs.contains(&mat.pattern_index)
+                });
+            for capture in captures {
+                let node_range = mat
+                    .captures
+                    .iter()
+                    .filter(|mat_capture| mat_capture.index == capture.capture_ix)
+                    .map(|mat_capture| mat_capture.node.byte_range())
+                    .reduce(|a, b| a.start.min(b.start)..a.end.max(b.end));
+                if let Some(node_range) = node_range {
+                    if node_range.start <= range.end && node_range.end >= range.start {
+                        ranges.push((node_range, capture.text_object));
+                    }
+                }
+            }
+            matches.advance();
+        }
+        // The same node can be matched by several layers or patterns.
+        ranges.sort_by_key(|(range, _)| (range.start, range.end));
+        ranges.dedup();
+        ranges
+    }
+
     /// Returns bracket range pairs overlapping or adjacent to `range`
     pub fn bracket_ranges<'a, T: ToOffset>(
         &'a self,
@@ -2918,6 +3196,11 @@ impl BufferSnapshot {
             })
     }
 
+    /// The regions of the buffer delimited by git merge conflict markers, in order.
+    pub fn merge_conflicts(&self) -> &[MergeConflict] {
+        &self.merge_conflicts
+    }
+
     /// Whether the buffer contains any git changes.
     pub fn has_git_diff(&self) -> bool {
         !self.git_diff.is_empty()
@@ -3068,6 +3351,11 @@ impl BufferSnapshot {
     pub fn git_diff_update_count(&self) -> usize {
         self.git_diff_update_count
     }
+
+    /// The number of times the syntax folds were collected.
+    pub fn syntax_folds_update_count(&self) -> usize {
+        self.syntax_folds_update_count
+    }
 }
 
 fn indent_size_for_line(text: &text::BufferSnapshot, row: u32) -> IndentSize {
@@ -3095,6 +3383,7 @@ impl Clone for BufferSnapshot {
         Self {
             text: self.text.clone(),
             git_diff: self.git_diff.clone(),
+            merge_conflicts: self.merge_conflicts.clone(),
             syntax: self.syntax.clone(),
             file: self.file.clone(),
             remote_selections: self.remote_selections.clone(),
@@ -3105,6 +3394,8 @@ impl Clone for BufferSnapshot {
             git_diff_update_count: self.git_diff_update_count,
             language: self.language.clone(),
             parse_count: self.parse_count,
+            syntax_folds: self.syntax_folds.clone(),
+            syntax_folds_update_count: self.syntax_folds_update_count,
         }
     }
 }
@@ -440,6 +440,87 @@ async fn test_reparse(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_merge_conflicts(cx: &mut gpui::TestAppContext) {
+    let text = "
+        fn a() {}
+        <<<<<<< HEAD
+        fn b() {}
+        ||||||| base
+        fn c() {}
+        =======
+        fn d() {}
+        >>>>>>> feature
+        <<<<<<< HEAD
+        =======
+        fn e() {}
+        >>>>>>> feature
+        ======== not a marker
+    "
+    .unindent();
+    let buffer = cx.new_model(|cx| {
+        let mut buffer = Buffer::new(0, cx.entity_id().as_u64(), text.clone());
+        buffer.refresh_merge_conflicts(cx);
+        buffer
+    });
+    // The buffer is scanned in the background.
+    buffer.update(cx, |buffer, _| {
+        assert!(buffer.snapshot().merge_conflicts().is_empty())
+    });
+    cx.executor().run_until_parked();
+
+    let conflict_texts = |buffer: &Buffer| {
+        let snapshot = buffer.snapshot();
+        snapshot
+            .merge_conflicts()
+            .iter()
+            .map(|conflict| {
+                let conflict = conflict.to_offset(&snapshot);
+                (
+                    snapshot.text_for_range(conflict.ours).collect::<String>(),
+                    conflict
+                        .base
+                        .map(|base| snapshot.text_for_range(base).collect::<String>()),
+                    snapshot.text_for_range(conflict.theirs).collect::<String>(),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+
+    buffer.update(cx, |buffer, cx| {
+        assert_eq!(
+            conflict_texts(buffer),
+            [
+                (
+                    "fn b() {}\n".to_string(),
+                    Some("fn c() {}\n".to_string()),
+                    "fn d() {}\n".to_string()
+                ),
+                (String::new(), None, "fn e() {}\n".to_string()),
+            ]
+        );
+        let conflict = buffer.snapshot().merge_conflicts()[0].to_offset(&buffer.snapshot());
+        assert_eq!(
+            buffer.text_for_range(conflict.range).collect::<String>(),
+            "<<<<<<< HEAD\nfn b() {}\n||||||| base\nfn c() {}\n=======\nfn d() {}\n>>>>>>> feature\n"
+        );
+
+        // Resolving a conflict removes its region once the buffer is rescanned.
+        let start = text.find("<<<<<<<").unwrap();
+        let end = text.find("fn d").unwrap();
+        buffer.edit([(start..end, "")], None, cx);
+        let end = buffer.text().find(">>>>>>> feature\n").unwrap();
+        buffer.edit([(end..end + ">>>>>>> feature\n".len(), "")], None, cx);
+    });
+    cx.executor().run_until_parked();
+    buffer.update(cx, |buffer, _| {
+        assert_eq!(
+            conflict_texts(buffer),
+            [(String::new(), None, "fn e() {}\n".to_string())]
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_resetting_language(cx: &mut gpui::TestAppContext) {
     let buffer = cx.new_model(|cx| {
@@ -643,6 +724,237 @@ async fn test_outline_with_extra_context(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_syntax_fold_range(cx: &mut gpui::TestAppContext) {
+    let language = Language::new(
+        LanguageConfig {
+            name: "Rust".into(),
+            path_suffixes: vec!["rs".to_string()],
+            ..Default::default()
+        },
+        Some(tree_sitter_rust::language()),
+    )
+    .with_folds_query(
+        r#"
+        (_ "{" "}") @fold
+        (_ "(" ")") @fold
+        (string_literal) @fold
+        (line_comment) @fold.run
+        "#,
+    )
+    .unwrap();
+
+    let text = r#"
+        fn a() {
+            let s = "
+                one
+                two";
+            b(1,
+                2)
+        }
+
+        // one
+        // two
+        // three
+        fn c() {} // trailing
+    "#
+    .unindent();
+
+    let buffer = cx.new_model(|cx| {
+        Buffer::new(0, cx.entity_id().as_u64(), text).with_language(Arc::new(language), cx)
+    });
+    // The folds are collected in the background.
+    assert_eq!(
+        buffer.update(cx, |buffer, _| buffer.snapshot().syntax_fold_range(0)),
+        None
+    );
+    cx.run_until_parked();
+    let snapshot = buffer.update(cx, |buffer, _| buffer.snapshot());
+
+    // Closing delimiters on their own line stay visible.
+    assert_eq!(
+        snapshot.syntax_fold_range(0),
+        Some(Point::new(0, 8)..Point::new(5, 10))
+    );
+    // Otherwise the whole node is folded.
+    assert_eq!(
+        snapshot.syntax_fold_range(1),
+        Some(Point::new(1, 13)..Point::new(3, 12))
+    );
+    // Nodes spanning only two lines are folded when they don't end with delimiters.
+    assert_eq!(
+        snapshot.syntax_fold_range(4),
+        Some(Point::new(4, 8)..Point::new(5, 10))
+    );
+    assert_eq!(snapshot.syntax_fold_range(2), None);
+    // Consecutive line comments are folded together.
+    assert_eq!(
+        snapshot.syntax_fold_range(8),
+        Some(Point::new(8, 6)..Point::new(10, 8))
+    );
+    assert_eq!(snapshot.syntax_fold_range(9), None);
+    assert_eq!(snapshot.syntax_fold_range(11), None);
+}
+
+#[gpui::test]
+async fn test_text_object_ranges(cx: &mut gpui::TestAppContext) {
+    let language = Language::new(
+        LanguageConfig {
+            name: "Rust".into(),
+            path_suffixes: vec!["rs".to_string()],
+            ..Default::default()
+        },
+        Some(tree_sitter_rust::language()),
+    )
+    .with_text_object_query(
+        r#"
+        (function_item
+            body: (_) @function.inside) @function.around
+        (line_comment)+ @comment.around
+        "#,
+    )
+    .unwrap();
+
+    let text = r#"
+        // a
+        // b
+        fn c() {
+            d();
+        }
+    "#
+    .unindent();
+
+    let buffer = cx.new_model(|cx| {
+        Buffer::new(0, cx.entity_id().as_u64(), text).with_language(Arc::new(language), cx)
+    });
+    let snapshot = buffer.update(cx, |buffer, _| buffer.snapshot());
+    let ranges = |range: Range<Point>, text_objects: &[TextObject]| {
+        let range = range.to_offset(&snapshot);
+        let mut ranges = snapshot
+            .text_object_ranges(range, text_objects)
+            .into_iter()
+            .map(|(range, text_object)| (range.to_point(&snapshot), text_object))
+            .collect::<Vec<_>>();
+        ranges.sort_by_key(|(range, _)| (range.start, range.end));
+        ranges
+    };
+
+    let all = [
+        TextObject::InsideFunction,
+        TextObject::AroundFunction,
+        TextObject::AroundComment,
+    ];
+
+    // A run of line comments is a single text object.
+    assert_eq!(
+        ranges(Point::new(1, 0)..Point::new(1, 1), &all),
+        [(
+            Point::new(0, 0)..Point::new(1, 4),
+            TextObject::AroundComment
+        )]
+    );
+    assert_eq!(
+        ranges(Point::new(3, 4)..Point::new(3, 5), &all),
+        [
+            (
+                Point::new(2, 0)..Point::new(4, 1),
+                TextObject::AroundFunction
+            ),
+            (
+                Point::new(2, 7)..Point::new(4, 1),
+                TextObject::InsideFunction
+            ),
+        ]
+    );
+
+    // Only the patterns of the given text objects are matched.
+    assert_eq!(
+        ranges(
+            Point::new(3, 4)..Point::new(3, 5),
+            &[TextObject::InsideFunction]
+        ),
+        [(
+            Point::new(2, 7)..Point::new(4, 1),
+            TextObject::InsideFunction
+        )]
+    );
+}
+
+#[gpui::test]
+async fn test_runnables(cx: &mut gpui::TestAppContext) {
+    let language = Language::new(
+        LanguageConfig {
+            name: "Rust".into(),
+            path_suffixes: vec!["rs".to_string()],
+            runnables: [(
+                "rust-test".to_string(),
+                RunnableCommand {
+                    command: "cargo".into(),
+                    args: vec!["test".into(), "--".into(), "$SYMBOL".into()],
+                },
+            )]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        },
+        Some(tree_sitter_rust::language()),
+    )
+    .with_runnable_query(
+        r#"
+        (
+            (attribute_item (attribute (identifier) @_attribute))
+            .
+            (function_item
+                name: (_) @name) @run
+            (#eq? @_attribute "test")
+            (#set! "tag" "rust-test")
+        )
+        (
+            (function_item
+                name: (identifier) @name) @run
+            (#eq? @name "main")
+            (#set! "tag" "rust-main")
+        )
+        "#,
+    )
+    .unwrap();
+
+    let text = r#"
+        fn main() {}
+
+        #[test]
+        fn it_works() {}
+
+        fn helper() {}
+    "#
+    .unindent();
+
+    let buffer = cx.new_model(|cx| {
+        Buffer::new(0, cx.entity_id().as_u64(), text).with_language(Arc::new(language), cx)
+    });
+    let snapshot = buffer.update(cx, |buffer, _| buffer.snapshot());
+
+    // `main` is matched, but has no command configured for its tag.
+    let runnables = snapshot.runnables_in_range(0..snapshot.len());
+    assert_eq!(
+        runnables
+            .iter()
+            .map(|runnable| (
+                snapshot.offset_to_point(runnable.run_range.start).row,
+                runnable.symbol.as_deref(),
+                runnable.command.command.as_str(),
+            ))
+            .collect::<Vec<_>>(),
+        &[(3, Some("it_works"), "cargo")]
+    );
+
+    // Runnables starting outside of the range are excluded.
+    let start = snapshot.point_to_offset(Point::new(4, 0));
+    assert!(snapshot
+        .runnables_in_range(start..snapshot.len())
+        .is_empty());
+}
+
 #[gpui::test]
 async fn test_symbols_containing(cx: &mut gpui::TestAppContext) {
     let text = r#"
@@ -12,32 +12,10 @@ const DEFAULT_SYNTAX_HIGHLIGHT_ID: HighlightId = HighlightId(u32::MAX);
 
 impl HighlightMap {
     pub(crate) fn new(capture_names: &[&str], theme: &SyntaxTheme) -> Self {
-        // For each capture name in the highlight query, find the longest
-        // key in the theme's syntax styles that matches all of the
-        // dot-separated components of the capture name.
         HighlightMap(
             capture_names
                 .iter()
-                .map(|capture_name| {
-                    theme
-                        .highlights
-                        .iter()
-                        .enumerate()
-                        .filter_map(|(i, (key, _))| {
-                            let mut len = 0;
-                            let capture_parts = capture_name.split('.');
-                            for key_part in key.split('.') {
-                                if capture_parts.clone().any(|part| part == key_part) {
-                                    len += 1;
-                                } else {
-                                    return None;
-                                }
-                            }
-                            Some((i, len))
-                        })
-                        .max_by_key(|(_, len)| *len)
-                        .map_or(DEFAULT_SYNTAX_HIGHLIGHT_ID, |(i, _)| HighlightId(i as u32))
-                })
+                .map(|capture_name| HighlightId::for_capture_name(capture_name, theme))
                 .collect(),
         )
     }
@@ -55,6 +33,63 @@ impl HighlightId {
         *self == DEFAULT_SYNTAX_HIGHLIGHT_ID
     }
 
+    fn for_capture_name(capture_name: &str, theme: &SyntaxTheme) -> Self {
+        // Find the longest key in the theme's syntax styles that matches all
+        // of the dot-separated components of the capture name.
+        theme
+            .highlights
+            .iter()
+            .enumerate()
+            .filter_map(|(i, (key, _))| {
+                let mut len = 0;
+                let capture_parts = capture_name.split('.');
+                for key_part in key.split('.') {
+                    if capture_parts.clone().any(|part| part == key_part) {
+                        len += 1;
+                    } else {
+                        return None;
+                    }
+                }
+                Some((i, len))
+            })
+            .max_by_key(|(_, len)| *len)
+            .map_or(DEFAULT_SYNTAX_HIGHLIGHT_ID, |(i, _)| HighlightId(i as u32))
+    }
+
+    /// Finds the theme's style for a semantic token reported by a language server,
+    /// by translating the token's type and modifiers into the names used by
+    /// tree-sitter highlight queries.
+    pub fn for_semantic_token(token_type: &str, modifiers: &[&str], theme: &SyntaxTheme) -> Self {
+        let has_modifier = |modifier: &str| modifiers.contains(&modifier);
+        let capture_name = match token_type {
+            "type" | "class" | "struct" | "interface" | "typeParameter" | "typeAlias"
+            | "builtinType" | "selfTypeKeyword" => "type",
+            "enum" => "enum",
+            "enumMember" => "variant",
+            "variable" | "parameter" if has_modifier("readonly") && has_modifier("static") => {
+                "constant"
+            }
+            "variable" | "parameter" => "variable",
+            "selfKeyword" => "variable.special",
+            "property" => "property",
+            "function" => "function",
+            "method" => "function.method",
+            "macro" => "function.special",
+            "keyword" => "keyword",
+            "comment" if has_modifier("documentation") => "comment.doc",
+            "comment" => "comment",
+            "string" => "string",
+            "number" => "number",
+            "boolean" => "boolean",
+            "regexp" => "string.regex",
+            "operator" => "operator",
+            "decorator" | "attribute" => "attribute",
+            "label" | "lifetime" => "label",
+            _ => return DEFAULT_SYNTAX_HIGHLIGHT_ID,
+        };
+        Self::for_capture_name(capture_name, theme)
+    }
+
     pub fn style(&self, theme: &SyntaxTheme) -> Option<HighlightStyle> {
         theme.highlights.get(self.0 as usize).map(|entry| entry.1)
     }
@@ -108,4 +143,29 @@ mod tests {
         assert_eq!(map.get(1).name(&theme), Some("function.async"));
         assert_eq!(map.get(2).name(&theme), Some("variable.builtin"));
     }
+
+    #[test]
+    fn test_semantic_token_highlight_ids() {
+        let theme = SyntaxTheme {
+            highlights: [
+                ("function", rgba(0x100000ff)),
+                ("type", rgba(0x200000ff)),
+                ("variable", rgba(0x300000ff)),
+                ("constant", rgba(0x400000ff)),
+            ]
+            .iter()
+            .map(|(name, color)| (name.to_string(), (*color).into()))
+            .collect(),
+        };
+
+        let name = |token_type, modifiers| {
+            HighlightId::for_semantic_token(token_type, modifiers, &theme).name(&theme)
+        };
+        assert_eq!(name("method", &[]), Some("function"));
+        assert_eq!(name("macro", &[]), Some("function"));
+        assert_eq!(name("struct", &[]), Some("type"));
+        assert_eq!(name("variable", &["mutable"]), Some("variable"));
+        assert_eq!(name("variable", &["readonly", "static"]), Some("constant"));
+        assert_eq!(name("namespace", &[]), None);
+    }
 }
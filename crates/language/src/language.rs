@@ -10,6 +10,7 @@ mod buffer;
 mod diagnostic_set;
 mod highlight_map;
 pub mod language_settings;
+mod merge_conflict;
 mod outline;
 pub mod proto;
 mod syntax_map;
@@ -52,7 +53,7 @@ use std::{
 };
 use syntax_map::SyntaxSnapshot;
 use theme::{SyntaxTheme, Theme};
-use tree_sitter::{self, Query};
+use tree_sitter::{self, CaptureQuantifier, Query};
 use unicase::UniCase;
 use util::{http::HttpClient, paths::PathExt};
 use util::{post_inc, ResultExt, TryFutureExt as _, UnwrapFuture};
@@ -61,6 +62,7 @@ pub use buffer::Operation;
 pub use buffer::*;
 pub use diagnostic_set::DiagnosticEntry;
 pub use lsp::LanguageServerId;
+pub use merge_conflict::MergeConflict;
 pub use outline::{Outline, OutlineItem};
 pub use syntax_map::{OwnedSyntaxLayer, SyntaxLayer};
 pub use text::LineEnding;
@@ -435,6 +437,21 @@ pub struct LanguageConfig {
     /// The name of a Prettier parser that should be used for this language.
     #[serde(default)]
     pub prettier_parser_name: Option<String>,
+    /// Commands for the runnables captured by the `runnables` query, keyed by the
+    /// `tag` property set on each pattern of that query.
+    #[serde(default)]
+    pub runnables: HashMap<String, RunnableCommand>,
+}
+
+/// A command that runs a test, benchmark or binary found by the `runnables` query.
+///
+/// The command and its arguments may reference the same variables as tasks, with
+/// `$SYMBOL` set to the name captured by the query.
+#[derive(Clone, Debug, Deserialize)]
+pub struct RunnableCommand {
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
 }
 
 /// Tree-sitter language queries for a given language.
@@ -445,6 +462,9 @@ pub struct LanguageQueries {
     pub indents: Option<Cow<'static, str>>,
     pub outline: Option<Cow<'static, str>>,
     pub embedding: Option<Cow<'static, str>>,
+    pub runnables: Option<Cow<'static, str>>,
+    pub folds: Option<Cow<'static, str>>,
+    pub text_objects: Option<Cow<'static, str>>,
     pub injections: Option<Cow<'static, str>>,
     pub overrides: Option<Cow<'static, str>>,
 }
@@ -514,6 +534,7 @@ impl Default for LanguageConfig {
             word_characters: Default::default(),
             prettier_parser_name: None,
             collapsed_placeholder: Default::default(),
+            runnables: Default::default(),
         }
     }
 }
@@ -620,6 +641,9 @@ pub struct Grammar {
     pub(crate) indents_config: Option<IndentConfig>,
     pub outline_config: Option<OutlineConfig>,
     pub embedding_config: Option<EmbeddingConfig>,
+    pub runnable_config: Option<RunnableConfig>,
+    pub(crate) folds_config: Option<FoldsConfig>,
+    pub(crate) text_object_config: Option<TextObjectConfig>,
     pub(crate) injection_config: Option<InjectionConfig>,
     pub(crate) override_config: Option<OverrideConfig>,
     pub(crate) highlight_map: Mutex<HighlightMap>,
@@ -651,6 +675,66 @@ pub struct EmbeddingConfig {
     pub keep_capture_ix: Option<u32>,
 }
 
+pub struct RunnableConfig {
+    pub query: Query,
+    pub run_capture_ix: u32,
+    pub name_capture_ix: Option<u32>,
+    /// The `tag` property of each pattern in the query.
+    pub pattern_tags: Vec<Option<Box<str>>>,
+}
+
+struct FoldsConfig {
+    query: Query,
+    fold_capture_ix: u32,
+    fold_run_capture_ix: Option<u32>,
+}
+
+/// A syntax node that a language's `textobjects` query captures, named after its capture.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextObject {
+    /// `@function.inside`, usually the body of a function.
+    InsideFunction,
+    /// `@function.around`, the whole function.
+    AroundFunction,
+    /// `@class.inside`, usually the body of a class, struct or impl.
+    InsideClass,
+    /// `@class.around`, the whole class, struct or impl.
+    AroundClass,
+    /// `@comment.inside`, a single comment.
+    InsideComment,
+    /// `@comment.around`, a comment together with the comments next to it.
+    AroundComment,
+    /// `@argument`, a single parameter or argument in a list of them.
+    Argument,
+}
+
+impl TextObject {
+    pub fn from_capture_name(name: &str) -> Option<TextObject> {
+        match name {
+            "function.inside" => Some(TextObject::InsideFunction),
+            "function.around" => Some(TextObject::AroundFunction),
+            "class.inside" => Some(TextObject::InsideClass),
+            "class.around" => Some(TextObject::AroundClass),
+            "comment.inside" => Some(TextObject::InsideComment),
+            "comment.around" => Some(TextObject::AroundComment),
+            "argument" => Some(TextObject::Argument),
+            _ => None,
+        }
+    }
+}
+
+struct TextObjectConfig {
+    query: Query,
+    /// The capture of each text object in the query, along with the patterns that capture it.
+    captures: Vec<TextObjectCapture>,
+}
+
+struct TextObjectCapture {
+    text_object: TextObject,
+    capture_ix: u32,
+    pattern_ixs: Vec<usize>,
+}
+
 struct InjectionConfig {
     query: Query,
     content_capture_ix: u32,
@@ -1281,6 +1365,9 @@ impl Language {
                     brackets_config: None,
                     outline_config: None,
                     embedding_config: None,
+                    runnable_config: None,
+                    folds_config: None,
+                    text_object_config: None,
                     indents_config: None,
                     injection_config: None,
                     override_config: None,
@@ -1330,6 +1417,21 @@ impl Language {
                 .with_embedding_query(query.as_ref())
                 .context("Error loading embedding query")?;
         }
+        if let Some(query) = queries.runnables {
+            self = self
+                .with_runnable_query(query.as_ref())
+                .context("Error loading runnables query")?;
+        }
+        if let Some(query) = queries.folds {
+            self = self
+                .with_folds_query(query.as_ref())
+                .context("Error loading folds query")?;
+        }
+        if let Some(query) = queries.text_objects {
+            self = self
+                .with_text_object_query(query.as_ref())
+                .context("Error loading textobjects query")?;
+        }
         if let Some(query) = queries.injections {
             self = self
                 .with_injection_query(query.as_ref())
@@ -1408,6 +1510,83 @@ impl Language {
         Ok(self)
     }
 
+    pub fn with_runnable_query(mut self, source: &str) -> Result<Self> {
+        let grammar = self.grammar_mut();
+        let query = Query::new(&grammar.ts_language, source)?;
+        let mut run_capture_ix = None;
+        let mut name_capture_ix = None;
+        get_capture_indices(
+            &query,
+            &mut [("run", &mut run_capture_ix), ("name", &mut name_capture_ix)],
+        );
+        let pattern_tags = (0..query.pattern_count())
+            .map(|ix| {
+                query
+                    .property_settings(ix)
+                    .iter()
+                    .find(|setting| setting.key.as_ref() == "tag")
+                    .and_then(|setting| setting.value.clone())
+            })
+            .collect();
+        if let Some(run_capture_ix) = run_capture_ix {
+            grammar.runnable_config = Some(RunnableConfig {
+                query,
+                run_capture_ix,
+                name_capture_ix,
+                pattern_tags,
+            });
+        }
+        Ok(self)
+    }
+
+    pub fn with_folds_query(mut self, source: &str) -> Result<Self> {
+        let grammar = self.grammar_mut();
+        let query = Query::new(&grammar.ts_language, source)?;
+        let mut fold_capture_ix = None;
+        let mut fold_run_capture_ix = None;
+        get_capture_indices(
+            &query,
+            &mut [
+                ("fold", &mut fold_capture_ix),
+                ("fold.run", &mut fold_run_capture_ix),
+            ],
+        );
+        if let Some(fold_capture_ix) = fold_capture_ix {
+            grammar.folds_config = Some(FoldsConfig {
+                query,
+                fold_capture_ix,
+                fold_run_capture_ix,
+            });
+        }
+        Ok(self)
+    }
+
+    pub fn with_text_object_query(mut self, source: &str) -> Result<Self> {
+        let grammar = self.grammar_mut();
+        let query = Query::new(&grammar.ts_language, source)?;
+        let captures = query
+            .capture_names()
+            .iter()
+            .enumerate()
+            .filter_map(|(capture_ix, name)| {
+                Some(TextObjectCapture {
+                    text_object: TextObject::from_capture_name(name)?,
+                    capture_ix: capture_ix as u32,
+                    pattern_ixs: (0..query.pattern_count())
+                        .filter(|&pattern_ix| {
+                            query.capture_quantifiers(pattern_ix)[capture_ix]
+                                != CaptureQuantifier::Zero
+                        })
+                        .collect(),
+                })
+            })
+            .collect::<Vec<_>>();
+        if !captures.is_empty() {
+            grammar.text_object_config = Some(TextObjectConfig { query, captures });
+        }
+        Ok(self)
+    }
+
     pub fn with_brackets_query(mut self, source: &str) -> Result<Self> {
         let grammar = self.grammar_mut();
         let query = Query::new(&grammar.ts_language, source)?;
@@ -93,6 +93,15 @@ pub struct LanguageSettings {
     pub inlay_hints: InlayHintSettings,
     /// Whether to automatically close brackets.
     pub use_autoclose: bool,
+    /// Whether to highlight code using the semantic tokens provided by language
+    /// servers, on top of the tree-sitter highlighting.
+    pub semantic_tokens: bool,
+    /// Whether to expand and shrink selections using the selection ranges
+    /// provided by language servers, instead of the syntax tree.
+    pub selection_ranges: bool,
+    /// Whether to edit ranges linked by language servers together, such as
+    /// the names of matching HTML tags.
+    pub linked_edits: bool,
 }
 
 /// The settings for [GitHub Copilot](https://github.com/features/copilot).
@@ -215,6 +224,21 @@ pub struct LanguageSettingsContent {
     ///
     /// Default: true
     pub use_autoclose: Option<bool>,
+    /// Whether to highlight code using the semantic tokens provided by language
+    /// servers, on top of the tree-sitter highlighting.
+    ///
+    /// Default: true
+    pub semantic_tokens: Option<bool>,
+    /// Whether to expand and shrink selections using the selection ranges
+    /// provided by language servers, instead of the syntax tree.
+    ///
+    /// Default: false
+    pub selection_ranges: Option<bool>,
+    /// Whether to edit ranges linked by language servers together, such as
+    /// the names of matching HTML tags.
+    ///
+    /// Default: true
+    pub linked_edits: Option<bool>,
 }
 
 /// The contents of the GitHub Copilot settings.
@@ -548,6 +572,9 @@ fn merge_settings(settings: &mut LanguageSettings, src: &LanguageSettingsContent
     merge(&mut settings.hard_tabs, src.hard_tabs);
     merge(&mut settings.soft_wrap, src.soft_wrap);
     merge(&mut settings.use_autoclose, src.use_autoclose);
+    merge(&mut settings.semantic_tokens, src.semantic_tokens);
+    merge(&mut settings.selection_ranges, src.selection_ranges);
+    merge(&mut settings.linked_edits, src.linked_edits);
     merge(&mut settings.show_wrap_guides, src.show_wrap_guides);
     merge(&mut settings.wrap_guides, src.wrap_guides.clone());
 
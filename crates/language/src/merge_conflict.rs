@@ -0,0 +1,120 @@
+use std::ops::Range;
+use text::{Anchor, BufferSnapshot, Point, ToOffset};
+
+/// A region of a buffer delimited by the conflict markers that git leaves behind
+/// when it can't merge two versions of a file.
+///
+/// The `ours`, `base` and `theirs` ranges contain whole lines, excluding the marker lines.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MergeConflict<T = Anchor> {
+    /// The whole region, from the start of the `<<<<<<<` line through the newline
+    /// ending the `>>>>>>>` line.
+    pub range: Range<T>,
+    /// The lines after the `<<<<<<<` marker.
+    pub ours: Range<T>,
+    /// The lines after the `|||||||` marker, only present in `diff3`-style conflicts.
+    pub base: Option<Range<T>>,
+    /// The lines after the `=======` marker.
+    pub theirs: Range<T>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Marker {
+    Ours,
+    Base,
+    Separator,
+    Theirs,
+}
+
+impl<T> MergeConflict<T> {
+    /// Converts every position in the conflict, for example from buffer anchors
+    /// to multi-buffer anchors.
+    pub fn map<U>(&self, mut f: impl FnMut(&T) -> U) -> MergeConflict<U> {
+        let mut map_range = |range: &Range<T>| f(&range.start)..f(&range.end);
+        MergeConflict {
+            range: map_range(&self.range),
+            ours: map_range(&self.ours),
+            base: self.base.as_ref().map(&mut map_range),
+            theirs: map_range(&self.theirs),
+        }
+    }
+}
+
+impl MergeConflict<Anchor> {
+    pub fn to_offset(&self, buffer: &BufferSnapshot) -> MergeConflict<usize> {
+        self.map(|anchor| anchor.to_offset(buffer))
+    }
+}
+
+/// Finds every complete conflict region in the buffer. Markers that aren't part of a
+/// well-formed region are ignored.
+pub(crate) fn merge_conflicts(buffer: &BufferSnapshot) -> Vec<MergeConflict> {
+    let mut conflicts = Vec::new();
+    let mut start_row = None;
+    let mut base_row = None;
+    let mut separator_row = None;
+
+    for row in 0..=buffer.max_point().row {
+        let Some(marker) = marker_at_row(buffer, row) else {
+            continue;
+        };
+        match marker {
+            Marker::Ours => {
+                start_row = Some(row);
+                base_row = None;
+                separator_row = None;
+            }
+            Marker::Base if start_row.is_some() && separator_row.is_none() => {
+                base_row = Some(row);
+            }
+            Marker::Separator if start_row.is_some() && separator_row.is_none() => {
+                separator_row = Some(row);
+            }
+            Marker::Theirs => {
+                if let Some((start_row, separator_row)) = start_row.zip(separator_row) {
+                    let line_start = |row: u32| buffer.anchor_before(Point::new(row, 0));
+                    let ours_end = base_row.unwrap_or(separator_row);
+                    let end = if row < buffer.max_point().row {
+                        line_start(row + 1)
+                    } else {
+                        buffer.anchor_after(Point::new(row, buffer.line_len(row)))
+                    };
+                    conflicts.push(MergeConflict {
+                        range: line_start(start_row)..end,
+                        ours: line_start(start_row + 1)..line_start(ours_end),
+                        base: base_row
+                            .map(|base_row| line_start(base_row + 1)..line_start(separator_row)),
+                        theirs: line_start(separator_row + 1)..line_start(row),
+                    });
+                }
+                start_row = None;
+                base_row = None;
+                separator_row = None;
+            }
+            _ => {}
+        }
+    }
+
+    conflicts
+}
+
+fn marker_at_row(buffer: &BufferSnapshot, row: u32) -> Option<Marker> {
+    let mut chars = buffer.chars_at(Point::new(row, 0));
+    let first = chars.next()?;
+    let marker = match first {
+        '<' => Marker::Ours,
+        '|' => Marker::Base,
+        '=' => Marker::Separator,
+        '>' => Marker::Theirs,
+        _ => return None,
+    };
+    for _ in 0..6 {
+        if chars.next() != Some(first) {
+            return None;
+        }
+    }
+    match chars.next() {
+        None | Some(' ') | Some('\n') => Some(marker),
+        Some(_) => None,
+    }
+}
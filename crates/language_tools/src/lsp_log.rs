@@ -11,6 +11,7 @@ use lsp::IoKind;
 use project::{search::SearchQuery, Project};
 use std::{borrow::Cow, sync::Arc};
 use ui::{popover_menu, prelude::*, Button, Checkbox, ContextMenu, Label, Selection};
+use util::ResultExt;
 use workspace::{
     item::{Item, ItemHandle},
     searchable::{SearchEvent, SearchableItem, SearchableItemHandle},
@@ -36,6 +37,7 @@ struct LanguageServerState {
     rpc_state: Option<LanguageServerRpcState>,
     _io_logs_subscription: Option<lsp::Subscription>,
     _lsp_logs_subscription: Option<lsp::Subscription>,
+    _lsp_traces_subscription: Option<lsp::Subscription>,
 }
 
 struct LanguageServerRpcState {
@@ -166,6 +168,7 @@ impl LogStore {
                 log_messages: VecDeque::with_capacity(MAX_STORED_LOG_ENTRIES),
                 _io_logs_subscription: None,
                 _lsp_logs_subscription: None,
+                _lsp_traces_subscription: None,
             }
         });
 
@@ -188,6 +191,25 @@ impl LogStore {
         });
         let this = cx.handle().downgrade();
         let weak_project = project.downgrade();
+        server_state._lsp_traces_subscription = server.as_ref().map(|server| {
+            let server_id = server.server_id();
+            server.on_notification::<lsp::notification::LogTrace, _>({
+                let this = this.clone();
+                let weak_project = weak_project.clone();
+                move |params, mut cx| {
+                    if let Some((project, this)) = weak_project.upgrade().zip(this.upgrade()) {
+                        let message = match params.verbose {
+                            Some(verbose) => format!("trace: {}\n{}", params.message, verbose),
+                            None => format!("trace: {}", params.message),
+                        };
+                        this.update(&mut cx, |this, cx| {
+                            this.add_language_server_log(&project, server_id, &message, cx);
+                        })
+                        .ok();
+                    }
+                }
+            })
+        });
         server_state._lsp_logs_subscription = server.map(|server| {
             let server_id = server.server_id();
             server.on_notification::<lsp::notification::LogMessage, _>({
@@ -583,6 +605,17 @@ impl LspLogView {
         enabled: bool,
         cx: &mut ViewContext<Self>,
     ) {
+        // Servers only send `$/logTrace` notifications while tracing is on.
+        if let Some(server) = self.project.read(cx).language_server_for_id(server_id) {
+            let value = if enabled {
+                lsp::TraceValue::Verbose
+            } else {
+                lsp::TraceValue::Off
+            };
+            server
+                .notify::<lsp::notification::SetTrace>(lsp::SetTraceParams { value })
+                .log_err();
+        }
         self.log_store.update(cx, |log_store, cx| {
             if enabled {
                 log_store.enable_rpc_trace_for_language_server(&self.project, server_id);
@@ -93,6 +93,19 @@ async fn test_lsp_logs(cx: &mut TestAppContext) {
         );
         assert_eq!(view.editor.read(cx).text(cx), "hello from the server\n");
     });
+
+    language_server.notify::<lsp::notification::LogTrace>(lsp::LogTraceParams {
+        message: "received request".into(),
+        verbose: Some("params: {}".into()),
+    });
+    cx.executor().run_until_parked();
+
+    log_view.update(&mut cx, |view, cx| {
+        assert_eq!(
+            view.editor.read(cx).text(cx),
+            "hello from the server\ntrace: received request\nparams: {}\n"
+        );
+    });
 }
 
 fn init_test(cx: &mut gpui::TestAppContext) {
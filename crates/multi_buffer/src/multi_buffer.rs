@@ -13,7 +13,7 @@ use language::{
     language_settings::{language_settings, LanguageSettings},
     AutoindentMode, Buffer, BufferChunks, BufferSnapshot, Capability, CharKind, Chunk, CursorShape,
     DiagnosticEntry, File, IndentSize, Language, LanguageScope, OffsetRangeExt, OffsetUtf16,
-    Outline, OutlineItem, Point, PointUtf16, Selection, TextDimension, ToOffset as _,
+    Outline, OutlineItem, Point, PointUtf16, Selection, TextDimension, TextObject, ToOffset as _,
     ToOffsetUtf16 as _, ToPoint as _, ToPointUtf16 as _, TransactionId, Unclipped,
 };
 use std::{
@@ -79,6 +79,7 @@ pub enum Event {
     },
     Reloaded,
     DiffBaseChanged,
+    MergeConflictsChanged,
     LanguageChanged,
     CapabilityChanged,
     Reparsed,
@@ -131,6 +132,7 @@ struct BufferState {
     last_diagnostics_update_count: usize,
     last_file_update_count: usize,
     last_git_diff_update_count: usize,
+    last_syntax_folds_update_count: usize,
     excerpts: Vec<Locator>,
     _subscriptions: [gpui::Subscription; 2],
 }
@@ -260,6 +262,7 @@ impl MultiBuffer {
                     last_diagnostics_update_count: buffer_state.last_diagnostics_update_count,
                     last_file_update_count: buffer_state.last_file_update_count,
                     last_git_diff_update_count: buffer_state.last_git_diff_update_count,
+                    last_syntax_folds_update_count: buffer_state.last_syntax_folds_update_count,
                     excerpts: buffer_state.excerpts.clone(),
                     _subscriptions: [
                         new_cx.observe(&buffer_state.buffer, |_, _, cx| cx.notify()),
@@ -1034,6 +1037,7 @@ impl MultiBuffer {
             last_diagnostics_update_count: buffer_snapshot.diagnostics_update_count(),
             last_file_update_count: buffer_snapshot.file_update_count(),
             last_git_diff_update_count: buffer_snapshot.git_diff_update_count(),
+            last_syntax_folds_update_count: buffer_snapshot.syntax_folds_update_count(),
             excerpts: Default::default(),
             _subscriptions: [
                 cx.observe(&buffer, |_, _, cx| cx.notify()),
@@ -1418,6 +1422,7 @@ impl MultiBuffer {
             language::Event::FileHandleChanged => Event::FileHandleChanged,
             language::Event::Reloaded => Event::Reloaded,
             language::Event::DiffBaseChanged => Event::DiffBaseChanged,
+            language::Event::MergeConflictsChanged => Event::MergeConflictsChanged,
             language::Event::LanguageChanged => Event::LanguageChanged,
             language::Event::Reparsed => Event::Reparsed,
             language::Event::DiagnosticsUpdated => Event::DiagnosticsUpdated,
@@ -1549,6 +1554,7 @@ impl MultiBuffer {
             let diagnostics_update_count = buffer.diagnostics_update_count();
             let file_update_count = buffer.file_update_count();
             let git_diff_update_count = buffer.git_diff_update_count();
+            let syntax_folds_update_count = buffer.syntax_folds_update_count();
 
             let buffer_edited = version.changed_since(&buffer_state.last_version);
             let buffer_reparsed = parse_count > buffer_state.last_parse_count;
@@ -1559,12 +1565,15 @@ impl MultiBuffer {
             let buffer_file_updated = file_update_count > buffer_state.last_file_update_count;
             let buffer_git_diff_updated =
                 git_diff_update_count > buffer_state.last_git_diff_update_count;
+            let buffer_syntax_folds_updated =
+                syntax_folds_update_count > buffer_state.last_syntax_folds_update_count;
             if buffer_edited
                 || buffer_reparsed
                 || buffer_selections_updated
                 || buffer_diagnostics_updated
                 || buffer_file_updated
                 || buffer_git_diff_updated
+                || buffer_syntax_folds_updated
             {
                 buffer_state.last_version = version;
                 buffer_state.last_parse_count = parse_count;
@@ -1572,6 +1581,7 @@ impl MultiBuffer {
                 buffer_state.last_diagnostics_update_count = diagnostics_update_count;
                 buffer_state.last_file_update_count = file_update_count;
                 buffer_state.last_git_diff_update_count = git_diff_update_count;
+                buffer_state.last_syntax_folds_update_count = syntax_folds_update_count;
                 excerpts_to_edit.extend(
                     buffer_state
                         .excerpts
@@ -3186,6 +3196,63 @@ impl MultiBufferSnapshot {
             })
     }
 
+    /// Returns the fold range that the excerpt's language `folds` query provides for
+    /// the given row, or `None` if the range does not fit within a single excerpt.
+    pub fn syntax_fold_range(&self, row: u32) -> Option<Range<Point>> {
+        let start = self.point_to_offset(Point::new(row, 0));
+        let end = self.point_to_offset(Point::new(row, self.line_len(row)));
+        let (excerpt, excerpt_offset) = self.excerpt_containing(start..end)?;
+        let excerpt_buffer_start = excerpt.range.context.start.to_offset(&excerpt.buffer);
+        let excerpt_buffer_end = excerpt_buffer_start + excerpt.text_summary.len;
+
+        let buffer_row = excerpt
+            .buffer
+            .offset_to_point(excerpt_buffer_start + (start - excerpt_offset))
+            .row;
+        let fold_range = excerpt.buffer.syntax_fold_range(buffer_row)?;
+        let fold_start = excerpt.buffer.point_to_offset(fold_range.start);
+        let fold_end = excerpt.buffer.point_to_offset(fold_range.end);
+        if fold_start < excerpt_buffer_start || fold_end > excerpt_buffer_end {
+            return None;
+        }
+
+        let start = excerpt_offset + (fold_start - excerpt_buffer_start);
+        let end = excerpt_offset + (fold_end - excerpt_buffer_start);
+        Some(self.offset_to_point(start)..self.offset_to_point(end))
+    }
+
+    /// Returns the ranges of the given text objects that the excerpt's language `textobjects`
+    /// query captures around the given range, or nothing if the range does not fit within a
+    /// single excerpt. Ranges that extend past the excerpt are skipped.
+    pub fn text_object_ranges<T: ToOffset>(
+        &self,
+        range: Range<T>,
+        text_objects: &[TextObject],
+    ) -> Vec<(Range<usize>, TextObject)> {
+        let range = range.start.to_offset(self)..range.end.to_offset(self);
+        let Some((excerpt, excerpt_offset)) = self.excerpt_containing(range.clone()) else {
+            return Vec::new();
+        };
+        let excerpt_buffer_start = excerpt.range.context.start.to_offset(&excerpt.buffer);
+        let excerpt_buffer_end = excerpt_buffer_start + excerpt.text_summary.len;
+
+        let start_in_buffer = excerpt_buffer_start + range.start.saturating_sub(excerpt_offset);
+        let end_in_buffer = excerpt_buffer_start + range.end.saturating_sub(excerpt_offset);
+        excerpt
+            .buffer
+            .text_object_ranges(start_in_buffer..end_in_buffer, text_objects)
+            .into_iter()
+            .filter_map(|(range, text_object)| {
+                if range.start < excerpt_buffer_start || range.end > excerpt_buffer_end {
+                    return None;
+                }
+                let start = excerpt_offset + (range.start - excerpt_buffer_start);
+                let end = excerpt_offset + (range.end - excerpt_buffer_start);
+                Some((start..end, text_object))
+            })
+            .collect()
+    }
+
     pub fn outline(&self, theme: Option<&SyntaxTheme>) -> Option<Outline<Anchor>> {
         let (excerpt_id, _, buffer) = self.as_singleton()?;
         let outline = buffer.outline(theme)?;
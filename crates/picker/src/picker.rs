@@ -226,8 +226,10 @@ impl<D: PickerDelegate> Picker<D> {
     }
 
     pub fn set_query(&self, query: impl Into<Arc<str>>, cx: &mut ViewContext<Self>) {
-        self.editor
-            .update(cx, |editor, cx| editor.set_text(query, cx));
+        self.editor.update(cx, |editor, cx| {
+            editor.set_text(query, cx);
+            editor.move_to_end(&Default::default(), cx);
+        });
     }
 }
 
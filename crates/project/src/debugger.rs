@@ -0,0 +1,759 @@
+use crate::{
+    project_settings::{DebugRequestKind, ProjectSettings},
+    Event, File, Project, ProjectPath,
+};
+use anyhow::{anyhow, Result};
+use collections::{BTreeMap, HashMap};
+use dap::{
+    DebugAdapterClient, DebugAdapterClientId, EvaluateArguments, EvaluateResponse, Scope,
+    ScopesArguments, SourceBreakpoint, StackFrame, StackTraceArguments, ThreadArguments, Variable,
+    VariablesArguments,
+};
+use futures::StreamExt;
+use gpui::{
+    AppContext, AsyncAppContext, Context, EventEmitter, Model, ModelContext, Task, WeakModel,
+};
+use language::{Buffer, BufferSnapshot, Point};
+use settings::Settings;
+use std::{path::PathBuf, sync::Arc};
+use text::{Anchor, Bias, ToPoint};
+use util::{post_inc, ResultExt};
+
+/// A breakpoint set by the user. It's anchored in its file's buffer while the buffer is open,
+/// so that it follows edits, and otherwise kept as a row to restore it from.
+#[derive(Clone, Debug)]
+pub struct Breakpoint {
+    anchor: Option<Anchor>,
+    row: u32,
+}
+
+impl Breakpoint {
+    pub fn position(&self, snapshot: &BufferSnapshot) -> Anchor {
+        self.anchor.unwrap_or_else(|| {
+            snapshot.anchor_before(snapshot.clip_point(Point::new(self.row, 0), Bias::Left))
+        })
+    }
+
+    pub fn row(&self, snapshot: &BufferSnapshot) -> u32 {
+        self.position(snapshot).to_point(snapshot).row
+    }
+}
+
+#[derive(Default)]
+pub struct Debugger {
+    breakpoints: HashMap<ProjectPath, Vec<Breakpoint>>,
+    sessions: Vec<Model<DebugSession>>,
+    next_client_id: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DebugSessionStatus {
+    Starting,
+    Running,
+    Stopped,
+    Terminated,
+}
+
+pub enum DebugSessionEvent {
+    Stopped,
+    Continued,
+    Output,
+    ThreadsChanged,
+    Terminated,
+}
+
+/// Events received from the adapter before the session model exists are buffered through this.
+enum AdapterEvent {
+    Initialized,
+    Stopped(dap::StoppedEventBody),
+    Continued(dap::ContinuedEventBody),
+    Exited(dap::ExitedEventBody),
+    Terminated,
+    Thread(dap::ThreadEventBody),
+    Output(dap::OutputEventBody),
+}
+
+/// A single running debuggee, driven through a debug adapter.
+pub struct DebugSession {
+    name: Arc<str>,
+    client: Arc<DebugAdapterClient>,
+    project: WeakModel<Project>,
+    status: DebugSessionStatus,
+    threads: BTreeMap<u64, dap::Thread>,
+    stopped_thread_id: Option<u64>,
+    stack_frames: Vec<StackFrame>,
+    active_frame_ix: Option<usize>,
+    execution_position: Option<(Model<Buffer>, Anchor)>,
+    output: Vec<String>,
+    _maintain_events: Task<()>,
+    _subscriptions: Vec<dap::Subscription>,
+}
+
+/// The path that the breakpoints of a buffer are kept under, so that they outlive the buffer.
+fn breakpoints_path(buffer: &Buffer, cx: &AppContext) -> Option<ProjectPath> {
+    let file = File::from_dyn(buffer.file())?;
+    Some(ProjectPath {
+        worktree_id: file.worktree_id(cx),
+        path: file.path.clone(),
+    })
+}
+
+impl Project {
+    pub fn breakpoints_for_buffer(&self, buffer: &Buffer, cx: &AppContext) -> &[Breakpoint] {
+        breakpoints_path(buffer, cx)
+            .and_then(|path| self.debugger.breakpoints.get(&path))
+            .map_or(&[], |breakpoints| breakpoints.as_slice())
+    }
+
+    /// Adds a breakpoint on the row containing `position`, or removes it if that row already has one.
+    pub fn toggle_breakpoint(
+        &mut self,
+        buffer: &Model<Buffer>,
+        position: Anchor,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(path) = breakpoints_path(buffer.read(cx), cx) else {
+            return;
+        };
+        let snapshot = buffer.read(cx).snapshot();
+        let row = position.to_point(&snapshot).row;
+        let breakpoints = self.debugger.breakpoints.entry(path.clone()).or_default();
+        if let Some(ix) = breakpoints
+            .iter()
+            .position(|breakpoint| breakpoint.row(&snapshot) == row)
+        {
+            breakpoints.remove(ix);
+        } else {
+            let ix = breakpoints
+                .binary_search_by_key(&row, |breakpoint| breakpoint.row(&snapshot))
+                .unwrap_or_else(|ix| ix);
+            let anchor = snapshot.anchor_before(Point::new(row, 0));
+            breakpoints.insert(
+                ix,
+                Breakpoint {
+                    anchor: Some(anchor),
+                    row,
+                },
+            );
+        }
+        if breakpoints.is_empty() {
+            self.debugger.breakpoints.remove(&path);
+        }
+
+        if let Some((abs_path, breakpoints)) = self.source_breakpoints(&path, cx) {
+            for session in &self.debugger.sessions {
+                session.update(cx, |session, cx| {
+                    session.set_breakpoints(abs_path.clone(), breakpoints.clone(), cx)
+                });
+            }
+        }
+        cx.emit(Event::BreakpointsChanged(snapshot.remote_id()));
+    }
+
+    /// Anchors the breakpoints of the buffer's file in the buffer once it's opened.
+    pub(crate) fn anchor_breakpoints(&mut self, buffer: &Buffer, cx: &AppContext) {
+        let Some(breakpoints) =
+            breakpoints_path(buffer, cx).and_then(|path| self.debugger.breakpoints.get_mut(&path))
+        else {
+            return;
+        };
+        let snapshot = buffer.snapshot();
+        for breakpoint in breakpoints {
+            breakpoint.anchor = Some(breakpoint.position(&snapshot));
+        }
+    }
+
+    /// Keeps the rows of the breakpoints of a buffer that's released, to restore them from when
+    /// its file is reopened.
+    pub(crate) fn unanchor_breakpoints(&mut self, buffer: &Buffer, cx: &AppContext) {
+        let Some(breakpoints) =
+            breakpoints_path(buffer, cx).and_then(|path| self.debugger.breakpoints.get_mut(&path))
+        else {
+            return;
+        };
+        let snapshot = buffer.snapshot();
+        for breakpoint in breakpoints {
+            breakpoint.row = breakpoint.row(&snapshot);
+            breakpoint.anchor = None;
+        }
+    }
+
+    pub fn debug_sessions(&self) -> &[Model<DebugSession>] {
+        &self.debugger.sessions
+    }
+
+    /// The location where the first stopped debug session is currently paused.
+    pub fn active_debug_position(&self, cx: &AppContext) -> Option<(u64, Anchor)> {
+        self.debugger.sessions.iter().find_map(|session| {
+            let (buffer, position) = session.read(cx).execution_position.as_ref()?;
+            Some((buffer.read(cx).remote_id(), *position))
+        })
+    }
+
+    /// Launches the debug adapter configured under `adapter_name` in the `debuggers` settings.
+    pub fn start_debug_session(
+        &mut self,
+        adapter_name: Arc<str>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Model<DebugSession>>> {
+        if self.is_remote() {
+            return Task::ready(Err(anyhow!("debugging as a guest is not supported yet")));
+        }
+        let Some(settings) = ProjectSettings::get_global(cx)
+            .debuggers
+            .get(&adapter_name)
+            .cloned()
+        else {
+            return Task::ready(Err(anyhow!(
+                "no debugger named {adapter_name:?} is configured"
+            )));
+        };
+        let Some(working_dir) = self
+            .visible_worktrees(cx)
+            .next()
+            .map(|worktree| worktree.read(cx).abs_path().to_path_buf())
+        else {
+            return Task::ready(Err(anyhow!("no worktree to debug")));
+        };
+
+        let client_id = DebugAdapterClientId(post_inc(&mut self.debugger.next_client_id));
+        cx.spawn(move |project, mut cx| async move {
+            let client = DebugAdapterClient::new(
+                client_id,
+                adapter_name.to_string(),
+                settings.binary.clone(),
+                &working_dir,
+                cx.clone(),
+            )
+            .await?;
+            let (session, client) =
+                Self::initialize_debug_session(project, adapter_name, client, &mut cx).await?;
+
+            // Some adapters only answer `launch` once configuration is done, so don't block on it.
+            let request = match settings.request {
+                DebugRequestKind::Launch => client.request::<dap::Launch>(settings.configuration),
+                DebugRequestKind::Attach => client.request::<dap::Attach>(settings.configuration),
+            };
+            cx.background_executor()
+                .spawn(async move { request.await.log_err() })
+                .detach();
+
+            Ok(session)
+        })
+    }
+
+    /// Starts a debug session with a fake adapter, without launching anything.
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn start_fake_debug_session(
+        &mut self,
+        adapter_name: Arc<str>,
+        capabilities: dap::Capabilities,
+        cx: &mut ModelContext<Self>,
+    ) -> (dap::FakeDebugAdapter, Task<Result<Model<DebugSession>>>) {
+        let (client, fake) =
+            dap::FakeDebugAdapter::new(adapter_name.to_string(), capabilities, cx.to_async());
+        let session = cx.spawn(move |project, mut cx| async move {
+            let (session, _) =
+                Self::initialize_debug_session(project, adapter_name, client, &mut cx).await?;
+            Ok(session)
+        });
+        (fake, session)
+    }
+
+    async fn initialize_debug_session(
+        project: WeakModel<Self>,
+        adapter_name: Arc<str>,
+        client: DebugAdapterClient,
+        cx: &mut AsyncAppContext,
+    ) -> Result<(Model<DebugSession>, Arc<DebugAdapterClient>)> {
+        let (events_tx, events_rx) = futures::channel::mpsc::unbounded();
+        let subscriptions = vec![
+            client.on_event::<dap::Initialized, _>({
+                let events_tx = events_tx.clone();
+                move |_, _| {
+                    events_tx.unbounded_send(AdapterEvent::Initialized).ok();
+                }
+            }),
+            client.on_event::<dap::Stopped, _>({
+                let events_tx = events_tx.clone();
+                move |body, _| {
+                    events_tx.unbounded_send(AdapterEvent::Stopped(body)).ok();
+                }
+            }),
+            client.on_event::<dap::Continued, _>({
+                let events_tx = events_tx.clone();
+                move |body, _| {
+                    events_tx.unbounded_send(AdapterEvent::Continued(body)).ok();
+                }
+            }),
+            client.on_event::<dap::Exited, _>({
+                let events_tx = events_tx.clone();
+                move |body, _| {
+                    events_tx.unbounded_send(AdapterEvent::Exited(body)).ok();
+                }
+            }),
+            client.on_event::<dap::Terminated, _>({
+                let events_tx = events_tx.clone();
+                move |_, _| {
+                    events_tx.unbounded_send(AdapterEvent::Terminated).ok();
+                }
+            }),
+            client.on_event::<dap::ThreadEvent, _>({
+                let events_tx = events_tx.clone();
+                move |body, _| {
+                    events_tx.unbounded_send(AdapterEvent::Thread(body)).ok();
+                }
+            }),
+            client.on_event::<dap::Output, _>(move |body, _| {
+                events_tx.unbounded_send(AdapterEvent::Output(body)).ok();
+            }),
+        ];
+
+        let client = client.initialize(adapter_name.to_string()).await?;
+        let session = project.update(cx, |project, cx| {
+            let project_handle = cx.weak_model();
+            let session = cx.new_model(|cx| {
+                DebugSession::new(
+                    adapter_name,
+                    client.clone(),
+                    project_handle,
+                    events_rx,
+                    subscriptions,
+                    cx,
+                )
+            });
+            cx.subscribe(&session, |project, session, event, cx| match event {
+                DebugSessionEvent::Stopped | DebugSessionEvent::Continued => {
+                    cx.emit(Event::DebugPositionChanged)
+                }
+                DebugSessionEvent::Terminated => {
+                    project
+                        .debugger
+                        .sessions
+                        .retain(|existing| existing != &session);
+                    cx.emit(Event::DebugPositionChanged);
+                    cx.notify();
+                }
+                DebugSessionEvent::Output | DebugSessionEvent::ThreadsChanged => {}
+            })
+            .detach();
+            project.debugger.sessions.push(session.clone());
+            cx.notify();
+            session
+        })?;
+
+        Ok((session, client))
+    }
+
+    pub fn stop_debug_session(
+        &mut self,
+        session: &Model<DebugSession>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        self.debugger
+            .sessions
+            .retain(|existing| existing != session);
+        session.update(cx, |session, cx| session.terminate(cx));
+        cx.emit(Event::DebugPositionChanged);
+        cx.notify();
+    }
+
+    /// The breakpoints of a file as the debug adapter takes them, using the rows in its buffer
+    /// when it's open.
+    fn source_breakpoints(
+        &self,
+        path: &ProjectPath,
+        cx: &AppContext,
+    ) -> Option<(PathBuf, Vec<SourceBreakpoint>)> {
+        let abs_path = self.absolute_path(path, cx)?;
+        let snapshot = self
+            .local_buffer_ids_by_path
+            .get(path)
+            .and_then(|buffer_id| self.opened_buffers.get(buffer_id)?.upgrade())
+            .map(|buffer| buffer.read(cx).snapshot());
+        let breakpoints = self
+            .debugger
+            .breakpoints
+            .get(path)
+            .into_iter()
+            .flatten()
+            .map(|breakpoint| {
+                let row = snapshot
+                    .as_ref()
+                    .map_or(breakpoint.row, |snapshot| breakpoint.row(snapshot));
+                SourceBreakpoint {
+                    line: row as u64 + 1,
+                    ..Default::default()
+                }
+            })
+            .collect();
+        Some((abs_path, breakpoints))
+    }
+
+    fn all_source_breakpoints(&self, cx: &AppContext) -> Vec<(PathBuf, Vec<SourceBreakpoint>)> {
+        self.debugger
+            .breakpoints
+            .keys()
+            .filter_map(|path| self.source_breakpoints(path, cx))
+            .collect()
+    }
+}
+
+impl EventEmitter<DebugSessionEvent> for DebugSession {}
+
+impl DebugSession {
+    fn new(
+        name: Arc<str>,
+        client: Arc<DebugAdapterClient>,
+        project: WeakModel<Project>,
+        mut events_rx: futures::channel::mpsc::UnboundedReceiver<AdapterEvent>,
+        subscriptions: Vec<dap::Subscription>,
+        cx: &mut ModelContext<Self>,
+    ) -> Self {
+        let maintain_events = cx.spawn(|this, mut cx| async move {
+            while let Some(event) = events_rx.next().await {
+                if this
+                    .update(&mut cx, |this, cx| this.handle_adapter_event(event, cx))
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        });
+        Self {
+            name,
+            client,
+            project,
+            status: DebugSessionStatus::Starting,
+            threads: Default::default(),
+            stopped_thread_id: None,
+            stack_frames: Vec::new(),
+            active_frame_ix: None,
+            execution_position: None,
+            output: Vec::new(),
+            _maintain_events: maintain_events,
+            _subscriptions: subscriptions,
+        }
+    }
+
+    pub fn name(&self) -> &Arc<str> {
+        &self.name
+    }
+
+    pub fn status(&self) -> DebugSessionStatus {
+        self.status
+    }
+
+    pub fn threads(&self) -> impl Iterator<Item = &dap::Thread> {
+        self.threads.values()
+    }
+
+    pub fn stopped_thread_id(&self) -> Option<u64> {
+        self.stopped_thread_id
+    }
+
+    pub fn stack_frames(&self) -> &[StackFrame] {
+        &self.stack_frames
+    }
+
+    pub fn active_frame(&self) -> Option<&StackFrame> {
+        self.stack_frames.get(self.active_frame_ix?)
+    }
+
+    pub fn output(&self) -> &[String] {
+        &self.output
+    }
+
+    pub fn execution_position(&self) -> Option<&(Model<Buffer>, Anchor)> {
+        self.execution_position.as_ref()
+    }
+
+    fn handle_adapter_event(&mut self, event: AdapterEvent, cx: &mut ModelContext<Self>) {
+        match event {
+            AdapterEvent::Initialized => {
+                if let Some(project) = self.project.upgrade() {
+                    for (path, breakpoints) in project.read(cx).all_source_breakpoints(cx) {
+                        self.set_breakpoints(path, breakpoints, cx);
+                    }
+                }
+                self.status = DebugSessionStatus::Running;
+                if self
+                    .client
+                    .capabilities()
+                    .supports_configuration_done_request
+                    .unwrap_or(false)
+                {
+                    let request = self.client.request::<dap::ConfigurationDone>(None);
+                    cx.background_executor()
+                        .spawn(async move { request.await.log_err() })
+                        .detach();
+                }
+                self.refresh_threads(cx);
+            }
+            AdapterEvent::Stopped(body) => {
+                self.status = DebugSessionStatus::Stopped;
+                self.stopped_thread_id = body.thread_id.or(self.stopped_thread_id);
+                if let Some(description) = body.description.or(body.text) {
+                    self.output.push(description);
+                }
+                self.refresh_threads(cx);
+                if let Some(thread_id) = self.stopped_thread_id {
+                    self.refresh_stack_frames(thread_id, cx);
+                }
+                cx.emit(DebugSessionEvent::Stopped);
+            }
+            AdapterEvent::Continued(body) => {
+                if body.all_threads_continued.unwrap_or(true)
+                    || Some(body.thread_id) == self.stopped_thread_id
+                {
+                    self.mark_running(cx);
+                }
+            }
+            AdapterEvent::Exited(body) => {
+                self.output
+                    .push(format!("Process exited with code {}", body.exit_code));
+                cx.emit(DebugSessionEvent::Output);
+            }
+            AdapterEvent::Terminated => {
+                self.status = DebugSessionStatus::Terminated;
+                self.stack_frames.clear();
+                self.active_frame_ix = None;
+                self.execution_position = None;
+                cx.emit(DebugSessionEvent::Terminated);
+            }
+            AdapterEvent::Thread(body) => {
+                if body.reason == "exited" {
+                    self.threads.remove(&body.thread_id);
+                    cx.emit(DebugSessionEvent::ThreadsChanged);
+                } else {
+                    self.refresh_threads(cx);
+                }
+            }
+            AdapterEvent::Output(body) => {
+                if body.category.as_deref() != Some("telemetry") {
+                    self.output.push(body.output);
+                    cx.emit(DebugSessionEvent::Output);
+                }
+            }
+        }
+        cx.notify();
+    }
+
+    fn mark_running(&mut self, cx: &mut ModelContext<Self>) {
+        self.status = DebugSessionStatus::Running;
+        self.stack_frames.clear();
+        self.active_frame_ix = None;
+        self.execution_position = None;
+        cx.emit(DebugSessionEvent::Continued);
+        cx.notify();
+    }
+
+    fn refresh_threads(&mut self, cx: &mut ModelContext<Self>) {
+        let request = self.client.request::<dap::Threads>(None);
+        cx.spawn(|this, mut cx| async move {
+            let response = request.await?;
+            this.update(&mut cx, |this, cx| {
+                this.threads = response
+                    .threads
+                    .into_iter()
+                    .map(|thread| (thread.id, thread))
+                    .collect();
+                cx.emit(DebugSessionEvent::ThreadsChanged);
+                cx.notify();
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn refresh_stack_frames(&mut self, thread_id: u64, cx: &mut ModelContext<Self>) {
+        let request = self.client.request::<dap::StackTrace>(StackTraceArguments {
+            thread_id,
+            start_frame: None,
+            levels: None,
+        });
+        cx.spawn(|this, mut cx| async move {
+            let response = request.await?;
+            this.update(&mut cx, |this, cx| {
+                this.stack_frames = response.stack_frames;
+                if this.stack_frames.is_empty() {
+                    this.active_frame_ix = None;
+                    cx.notify();
+                } else {
+                    this.select_stack_frame(0, cx);
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Makes the given frame the one whose location is shown in the editor and whose scopes are inspected.
+    pub fn select_stack_frame(&mut self, ix: usize, cx: &mut ModelContext<Self>) {
+        let Some(frame) = self.stack_frames.get(ix) else {
+            return;
+        };
+        self.active_frame_ix = Some(ix);
+        self.execution_position = None;
+        cx.notify();
+
+        let Some(path) = frame
+            .source
+            .as_ref()
+            .and_then(|source| source.path.as_ref())
+            .map(PathBuf::from)
+        else {
+            return;
+        };
+        let row = frame.line.saturating_sub(1) as u32;
+        let Some(project) = self.project.upgrade() else {
+            return;
+        };
+        let open_buffer = project.update(cx, |project, cx| project.open_local_buffer(path, cx));
+        cx.spawn(|this, mut cx| async move {
+            let buffer = open_buffer.await?;
+            this.update(&mut cx, |this, cx| {
+                if this.active_frame_ix == Some(ix) {
+                    let position = buffer.read(cx).anchor_before(Point::new(row, 0));
+                    this.execution_position = Some((buffer, position));
+                    cx.emit(DebugSessionEvent::Stopped);
+                    cx.notify();
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn set_breakpoints(
+        &mut self,
+        path: PathBuf,
+        breakpoints: Vec<SourceBreakpoint>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        if self.status == DebugSessionStatus::Terminated {
+            return;
+        }
+        let request = self
+            .client
+            .request::<dap::SetBreakpoints>(dap::SetBreakpointsArguments {
+                source: dap::Source {
+                    name: path
+                        .file_name()
+                        .map(|name| name.to_string_lossy().into_owned()),
+                    path: Some(path.to_string_lossy().into_owned()),
+                    source_reference: None,
+                },
+                breakpoints,
+                source_modified: None,
+            });
+        cx.background_executor()
+            .spawn(async move { request.await.log_err() })
+            .detach();
+    }
+
+    pub fn continue_thread(&mut self, cx: &mut ModelContext<Self>) {
+        self.thread_request::<dap::Continue>(cx);
+    }
+
+    pub fn step_over(&mut self, cx: &mut ModelContext<Self>) {
+        self.thread_request::<dap::Next>(cx);
+    }
+
+    pub fn step_in(&mut self, cx: &mut ModelContext<Self>) {
+        self.thread_request::<dap::StepIn>(cx);
+    }
+
+    pub fn step_out(&mut self, cx: &mut ModelContext<Self>) {
+        self.thread_request::<dap::StepOut>(cx);
+    }
+
+    pub fn pause(&mut self, cx: &mut ModelContext<Self>) {
+        let Some(thread_id) = self
+            .stopped_thread_id
+            .or_else(|| self.threads.keys().next().copied())
+        else {
+            return;
+        };
+        let request = self
+            .client
+            .request::<dap::Pause>(ThreadArguments { thread_id });
+        cx.background_executor()
+            .spawn(async move { request.await.log_err() })
+            .detach();
+    }
+
+    fn thread_request<R>(&mut self, cx: &mut ModelContext<Self>)
+    where
+        R: dap::Request<Arguments = ThreadArguments>,
+    {
+        if self.status != DebugSessionStatus::Stopped {
+            return;
+        }
+        let Some(thread_id) = self.stopped_thread_id else {
+            return;
+        };
+        let request = self.client.request::<R>(ThreadArguments { thread_id });
+        self.mark_running(cx);
+        cx.background_executor()
+            .spawn(async move { request.await.log_err() })
+            .detach();
+    }
+
+    pub fn scopes(&self, frame_id: u64, cx: &AppContext) -> Task<Result<Vec<Scope>>> {
+        let request = self
+            .client
+            .request::<dap::Scopes>(ScopesArguments { frame_id });
+        cx.background_executor()
+            .spawn(async move { Ok(request.await?.scopes) })
+    }
+
+    /// Fetches the children of a scope or of a structured variable.
+    pub fn variables(
+        &self,
+        variables_reference: u64,
+        cx: &AppContext,
+    ) -> Task<Result<Vec<Variable>>> {
+        let request = self.client.request::<dap::Variables>(VariablesArguments {
+            variables_reference,
+        });
+        cx.background_executor()
+            .spawn(async move { Ok(request.await?.variables) })
+    }
+
+    /// Evaluates an expression typed into the REPL in the context of the active stack frame.
+    pub fn evaluate(
+        &mut self,
+        expression: String,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<EvaluateResponse>> {
+        self.output.push(format!("> {expression}"));
+        cx.notify();
+        let request = self.client.request::<dap::Evaluate>(EvaluateArguments {
+            expression,
+            frame_id: self.active_frame().map(|frame| frame.id),
+            context: Some("repl".into()),
+        });
+        cx.spawn(|this, mut cx| async move {
+            let result = request.await;
+            this.update(&mut cx, |this, cx| {
+                match &result {
+                    Ok(response) => this.output.push(response.result.clone()),
+                    Err(error) => this.output.push(error.to_string()),
+                }
+                cx.emit(DebugSessionEvent::Output);
+                cx.notify();
+            })?;
+            result
+        })
+    }
+
+    fn terminate(&mut self, cx: &mut ModelContext<Self>) {
+        if let Some(shutdown) = self.client.shutdown() {
+            cx.background_executor().spawn(shutdown).detach();
+        }
+        self.status = DebugSessionStatus::Terminated;
+        self.stack_frames.clear();
+        self.active_frame_ix = None;
+        self.execution_position = None;
+        cx.emit(DebugSessionEvent::Terminated);
+        cx.notify();
+    }
+}
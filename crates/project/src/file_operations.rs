@@ -0,0 +1,274 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::{anyhow, Result};
+use futures::FutureExt;
+use globset::GlobBuilder;
+use gpui::{AsyncAppContext, Model, ModelContext, Task};
+use language::CachedLspAdapter;
+use lsp::LanguageServer;
+use util::ResultExt;
+
+use crate::{Entry, Project, ProjectEntryId, Worktree, WorktreeId};
+
+/// How long to wait for a server's edits before performing a file operation without them.
+const FILE_OPERATION_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Selects the filters that a server registered for one kind of file operation.
+type FileOperationOptions = fn(
+    &lsp::WorkspaceFileOperationsServerCapabilities,
+) -> Option<&lsp::FileOperationRegistrationOptions>;
+
+impl Project {
+    /// Creates an entry in a local worktree, after applying the edits that language servers
+    /// want to make for the new file.
+    pub(crate) fn create_local_entry(
+        &mut self,
+        worktree: Model<Worktree>,
+        path: Arc<Path>,
+        is_directory: bool,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Option<Entry>>> {
+        let worktree_id = worktree.read(cx).id();
+        let abs_path = match worktree.read(cx).absolutize(&path) {
+            Ok(abs_path) => abs_path,
+            Err(error) => return Task::ready(Err(error)),
+        };
+
+        cx.spawn(move |this, mut cx| async move {
+            let this = this.upgrade().ok_or_else(|| anyhow!("project dropped"))?;
+            Self::will_operate_on_files::<lsp::request::WillCreateFiles>(
+                &this,
+                worktree_id,
+                |operations| operations.will_create.as_ref(),
+                &[&abs_path],
+                is_directory,
+                || lsp::CreateFilesParams {
+                    files: vec![lsp::FileCreate {
+                        uri: file_uri(&abs_path),
+                    }],
+                },
+                &mut cx,
+            )
+            .await;
+            worktree
+                .update(&mut cx, |worktree, cx| {
+                    worktree
+                        .as_local_mut()
+                        .unwrap()
+                        .create_entry(path, is_directory, cx)
+                })?
+                .await
+        })
+    }
+
+    /// Renames an entry in a local worktree, after applying the edits that language servers
+    /// want to make for the new path, such as updated imports, and then tells them about the
+    /// rename.
+    pub(crate) fn rename_local_entry(
+        &mut self,
+        worktree: Model<Worktree>,
+        entry_id: ProjectEntryId,
+        new_path: Arc<Path>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Option<Entry>>> {
+        let snapshot = worktree.read(cx);
+        let worktree_id = snapshot.id();
+        let Some(entry) = snapshot.entry_for_id(entry_id) else {
+            return Task::ready(Ok(None));
+        };
+        let is_dir = entry.is_dir();
+        let abs_paths = snapshot
+            .absolutize(&entry.path)
+            .and_then(|old_abs_path| Ok((old_abs_path, snapshot.absolutize(&new_path)?)));
+        let (old_abs_path, new_abs_path) = match abs_paths {
+            Ok(abs_paths) => abs_paths,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        let abs_paths = [old_abs_path.clone(), new_abs_path.clone()];
+        let rename_params = move || lsp::RenameFilesParams {
+            files: vec![lsp::FileRename {
+                old_uri: file_uri(&old_abs_path),
+                new_uri: file_uri(&new_abs_path),
+            }],
+        };
+
+        cx.spawn(move |this, mut cx| async move {
+            let this = this.upgrade().ok_or_else(|| anyhow!("project dropped"))?;
+            let abs_paths = abs_paths.iter().map(PathBuf::as_path).collect::<Vec<_>>();
+            Self::will_operate_on_files::<lsp::request::WillRenameFiles>(
+                &this,
+                worktree_id,
+                |operations| operations.will_rename.as_ref(),
+                &abs_paths,
+                is_dir,
+                &rename_params,
+                &mut cx,
+            )
+            .await;
+            let entry = worktree
+                .update(&mut cx, |worktree, cx| {
+                    worktree
+                        .as_local_mut()
+                        .unwrap()
+                        .rename_entry(entry_id, new_path, cx)
+                })?
+                .await?;
+            this.update(&mut cx, |this, _| {
+                let servers = this.file_operation_servers(
+                    worktree_id,
+                    |operations| operations.did_rename.as_ref(),
+                    &abs_paths,
+                    is_dir,
+                );
+                for (_, server) in servers {
+                    server
+                        .notify::<lsp::notification::DidRenameFiles>(rename_params())
+                        .log_err();
+                }
+            })?;
+            Ok(entry)
+        })
+    }
+
+    /// Deletes an entry from a local worktree, after applying the edits that language servers
+    /// want to make before the file is gone.
+    pub(crate) fn delete_local_entry(
+        &mut self,
+        worktree: Model<Worktree>,
+        entry_id: ProjectEntryId,
+        cx: &mut ModelContext<Self>,
+    ) -> Option<Task<Result<()>>> {
+        let snapshot = worktree.read(cx);
+        let worktree_id = snapshot.id();
+        let entry = snapshot.entry_for_id(entry_id)?;
+        let is_dir = entry.is_dir();
+        let abs_path = snapshot.absolutize(&entry.path).log_err()?;
+
+        Some(cx.spawn(move |this, mut cx| async move {
+            let this = this.upgrade().ok_or_else(|| anyhow!("project dropped"))?;
+            Self::will_operate_on_files::<lsp::request::WillDeleteFiles>(
+                &this,
+                worktree_id,
+                |operations| operations.will_delete.as_ref(),
+                &[&abs_path],
+                is_dir,
+                || lsp::DeleteFilesParams {
+                    files: vec![lsp::FileDelete {
+                        uri: file_uri(&abs_path),
+                    }],
+                },
+                &mut cx,
+            )
+            .await;
+            worktree
+                .update(&mut cx, |worktree, cx| {
+                    worktree
+                        .as_local_mut()
+                        .unwrap()
+                        .delete_entry(entry_id, cx)
+                        .ok_or_else(|| anyhow!("invalid entry"))
+                })??
+                .await
+        }))
+    }
+
+    /// Sends a `workspace/will*Files` request to each server whose filters match the paths, and
+    /// applies the workspace edits they respond with. Servers that don't respond in time are
+    /// skipped, so that they can't hold up the file operation.
+    async fn will_operate_on_files<R>(
+        this: &Model<Self>,
+        worktree_id: WorktreeId,
+        options: FileOperationOptions,
+        abs_paths: &[&Path],
+        is_dir: bool,
+        params: impl Fn() -> R::Params,
+        cx: &mut AsyncAppContext,
+    ) where
+        R: lsp::request::Request<Result = Option<lsp::WorkspaceEdit>>,
+    {
+        let Ok(servers) = this.update(cx, |this, _| {
+            this.file_operation_servers(worktree_id, options, abs_paths, is_dir)
+        }) else {
+            return;
+        };
+        for (adapter, server) in servers {
+            let request = server.request::<R>(params());
+            let edit = futures::select! {
+                response = request.fuse() => response.log_err().flatten(),
+                _ = cx.background_executor().timer(FILE_OPERATION_TIMEOUT).fuse() => {
+                    log::warn!("{} did not respond to {} in time", server.name(), R::METHOD);
+                    None
+                }
+            };
+            if let Some(edit) = edit {
+                Self::deserialize_workspace_edit(this.clone(), edit, true, adapter, server, cx)
+                    .await
+                    .log_err();
+            }
+        }
+    }
+
+    /// The running language servers of a worktree that registered for a file operation on any
+    /// of the paths.
+    fn file_operation_servers(
+        &self,
+        worktree_id: WorktreeId,
+        options: FileOperationOptions,
+        abs_paths: &[&Path],
+        is_dir: bool,
+    ) -> Vec<(Arc<CachedLspAdapter>, Arc<LanguageServer>)> {
+        self.language_servers_for_worktree(worktree_id)
+            .filter(|(_, _, server)| {
+                let Some(options) = server
+                    .capabilities()
+                    .workspace
+                    .as_ref()
+                    .and_then(|workspace| workspace.file_operations.as_ref())
+                    .and_then(options)
+                else {
+                    return false;
+                };
+                options.filters.iter().any(|filter| {
+                    abs_paths
+                        .iter()
+                        .any(|abs_path| filter_matches(filter, abs_path, is_dir))
+                })
+            })
+            .map(|(adapter, _, server)| (adapter.clone(), server.clone()))
+            .collect()
+    }
+}
+
+fn filter_matches(filter: &lsp::FileOperationFilter, abs_path: &Path, is_dir: bool) -> bool {
+    if filter
+        .scheme
+        .as_ref()
+        .map_or(false, |scheme| scheme != "file")
+    {
+        return false;
+    }
+    let pattern = &filter.pattern;
+    match pattern.matches {
+        Some(lsp::FileOperationPatternKind::File) if is_dir => return false,
+        Some(lsp::FileOperationPatternKind::Folder) if !is_dir => return false,
+        _ => {}
+    }
+    let ignore_case = pattern
+        .options
+        .as_ref()
+        .and_then(|options| options.ignore_case)
+        .unwrap_or(false);
+    GlobBuilder::new(&pattern.glob)
+        .case_insensitive(ignore_case)
+        .build()
+        .log_err()
+        .map_or(false, |glob| glob.compile_matcher().is_match(abs_path))
+}
+
+fn file_uri(abs_path: &Path) -> String {
+    lsp::Url::from_file_path(abs_path).unwrap().to_string()
+}
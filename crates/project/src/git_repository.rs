@@ -0,0 +1,295 @@
+use crate::{worktree::File, Project, ProjectPath};
+use anyhow::{anyhow, Context as _, Result};
+use fs::repository::{
+    BlameEntry, CommitFileDiff, GitRepository, GitStatusEntry, LogEntry, RepoPath,
+};
+use git::diff::{apply_line_hunk, line_hunks, old_row_for_new_row, LineHunk};
+use gpui::{AppContext, Model, Task};
+use language::Buffer;
+use parking_lot::Mutex;
+use std::{ops::Range, sync::Arc};
+
+/// A changed file in one of the project's git repositories.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GitStatus {
+    pub project_path: ProjectPath,
+    pub entry: GitStatusEntry,
+}
+
+impl Project {
+    /// The changed files of every git repository in the project's local worktrees.
+    pub fn git_statuses(&self, cx: &AppContext) -> Task<Result<Vec<GitStatus>>> {
+        let mut repositories = Vec::new();
+        for worktree in self.visible_worktrees(cx) {
+            let worktree = worktree.read(cx);
+            let Some(local_worktree) = worktree.as_local() else {
+                continue;
+            };
+            for (work_directory, repository) in local_worktree.repositories() {
+                if let Some(local_repo) = local_worktree.get_local_repo(repository) {
+                    repositories.push((
+                        worktree.id(),
+                        work_directory.clone(),
+                        local_repo.repo_ptr.clone(),
+                    ));
+                }
+            }
+        }
+
+        cx.background_executor().spawn(async move {
+            let mut statuses = Vec::new();
+            for (worktree_id, work_directory, repo) in repositories {
+                for entry in repo.lock().file_statuses()? {
+                    statuses.push(GitStatus {
+                        project_path: ProjectPath {
+                            worktree_id,
+                            path: work_directory.join(&entry.repo_path.0).into(),
+                        },
+                        entry,
+                    });
+                }
+            }
+            Ok(statuses)
+        })
+    }
+
+    /// Adds the current contents of the given files to their repositories' index.
+    pub fn stage_paths(&self, paths: Vec<ProjectPath>, cx: &AppContext) -> Task<Result<()>> {
+        let repo_paths = match self.repo_paths(paths, cx) {
+            Ok(repo_paths) => repo_paths,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        cx.background_executor().spawn(async move {
+            for (repo, repo_path) in repo_paths {
+                repo.lock().stage_paths(&[repo_path])?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Resets the given files in their repositories' index to their contents at `HEAD`.
+    pub fn unstage_paths(&self, paths: Vec<ProjectPath>, cx: &AppContext) -> Task<Result<()>> {
+        let repo_paths = match self.repo_paths(paths, cx) {
+            Ok(repo_paths) => repo_paths,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        cx.background_executor().spawn(async move {
+            for (repo, repo_path) in repo_paths {
+                repo.lock().unstage_paths(&[repo_path])?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Stages the unstaged change of `buffer` that touches `row`, leaving its other changes
+    /// unstaged.
+    pub fn stage_hunk(
+        &self,
+        buffer: &Model<Buffer>,
+        row: u32,
+        cx: &AppContext,
+    ) -> Task<Result<()>> {
+        let (repo, repo_path) = match self.repo_path_for_buffer(buffer, cx) {
+            Ok(repo_path) => repo_path,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        let buffer = buffer.read(cx);
+        let index_text = buffer.diff_base().map(ToString::to_string);
+        let buffer_text = buffer.text();
+
+        cx.background_executor().spawn(async move {
+            let new_index_text = match index_text {
+                Some(index_text) => {
+                    let hunks = line_hunks(&index_text, &buffer_text);
+                    let hunk = hunk_containing_row(&hunks, row, |hunk| &hunk.new_rows)
+                        .context("no unstaged change at the given row")?;
+                    apply_line_hunk(&index_text, &buffer_text, hunk)
+                }
+                None => buffer_text,
+            };
+            repo.lock().set_index_text(&repo_path, Some(new_index_text))
+        })
+    }
+
+    /// Unstages the staged change of `buffer` that touches `row`, leaving its other changes
+    /// staged.
+    pub fn unstage_hunk(
+        &self,
+        buffer: &Model<Buffer>,
+        row: u32,
+        cx: &AppContext,
+    ) -> Task<Result<()>> {
+        let (repo, repo_path) = match self.repo_path_for_buffer(buffer, cx) {
+            Ok(repo_path) => repo_path,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        let buffer_text = buffer.read(cx).text();
+
+        cx.background_executor().spawn(async move {
+            let repo = repo.lock();
+            let index_text = repo
+                .load_index_text(&repo_path)
+                .context("file is not staged")?;
+            let Some(head_text) = repo.load_head_text(&repo_path) else {
+                return repo.unstage_paths(&[repo_path]);
+            };
+
+            let index_row = old_row_for_new_row(&line_hunks(&index_text, &buffer_text), row);
+            let hunks = line_hunks(&index_text, &head_text);
+            let hunk = hunk_containing_row(&hunks, index_row, |hunk| &hunk.old_rows)
+                .context("no staged change at the given row")?;
+            repo.set_index_text(
+                &repo_path,
+                Some(apply_line_hunk(&index_text, &head_text, hunk)),
+            )
+        })
+    }
+
+    /// Blames the current contents of `buffer`, so that the returned rows match the buffer
+    /// even when it has unsaved edits. Rows that haven't been committed have no entry.
+    pub fn blame_buffer(
+        &self,
+        buffer: &Model<Buffer>,
+        cx: &AppContext,
+    ) -> Task<Result<Vec<BlameEntry>>> {
+        let (repo, repo_path) = match self.repo_path_for_buffer(buffer, cx) {
+            Ok(repo_path) => repo_path,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        let buffer_text = buffer.read(cx).text();
+        cx.background_executor()
+            .spawn(async move { repo.lock().blame(&repo_path, Some(&buffer_text)) })
+    }
+
+    /// Lists the commits of the repository containing `path`, newest first. When `path` is
+    /// the repository's work directory, the whole history is listed, otherwise only the
+    /// commits that changed the file at `path`, following it across renames.
+    pub fn git_log(
+        &self,
+        path: ProjectPath,
+        skip: usize,
+        limit: usize,
+        cx: &AppContext,
+    ) -> Task<Result<Vec<LogEntry>>> {
+        let (repo, repo_path) = match self.repo_paths(vec![path], cx) {
+            Ok(mut repo_paths) => repo_paths.remove(0),
+            Err(error) => return Task::ready(Err(error)),
+        };
+        cx.background_executor().spawn(async move {
+            let path = (!repo_path.0.as_os_str().is_empty()).then_some(&repo_path);
+            repo.lock().log(path, skip, limit)
+        })
+    }
+
+    /// Loads the files changed by the commit `sha` in the repository containing `path`.
+    pub fn git_commit_diff(
+        &self,
+        path: ProjectPath,
+        sha: String,
+        cx: &AppContext,
+    ) -> Task<Result<Vec<CommitFileDiff>>> {
+        let (repo, _) = match self.repo_paths(vec![path], cx) {
+            Ok(mut repo_paths) => repo_paths.remove(0),
+            Err(error) => return Task::ready(Err(error)),
+        };
+        cx.background_executor()
+            .spawn(async move { repo.lock().commit_diff(&sha) })
+    }
+
+    /// Commits the staged changes of every repository in the project that has any.
+    pub fn commit(&self, message: String, cx: &AppContext) -> Task<Result<()>> {
+        if self.is_remote() {
+            return Task::ready(Err(anyhow!(
+                "git operations are not supported in remote projects"
+            )));
+        }
+
+        let repositories = self
+            .visible_worktrees(cx)
+            .filter_map(|worktree| {
+                let worktree = worktree.read(cx).as_local()?;
+                Some(
+                    worktree
+                        .repositories()
+                        .filter_map(|(_, repository)| {
+                            Some(worktree.get_local_repo(repository)?.repo_ptr.clone())
+                        })
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .flatten()
+            .collect::<Vec<_>>();
+
+        cx.background_executor().spawn(async move {
+            let mut committed = false;
+            for repo in repositories {
+                let repo = repo.lock();
+                let has_staged_changes = repo
+                    .file_statuses()?
+                    .iter()
+                    .any(|entry| entry.staged.is_some());
+                if has_staged_changes {
+                    repo.commit(&message)?;
+                    committed = true;
+                }
+            }
+            if committed {
+                Ok(())
+            } else {
+                Err(anyhow!("there are no staged changes to commit"))
+            }
+        })
+    }
+
+    fn repo_paths(
+        &self,
+        paths: Vec<ProjectPath>,
+        cx: &AppContext,
+    ) -> Result<Vec<(Arc<Mutex<dyn GitRepository>>, RepoPath)>> {
+        paths
+            .into_iter()
+            .map(|project_path| {
+                let worktree = self
+                    .worktree_for_id(project_path.worktree_id, cx)
+                    .context("worktree was removed")?;
+                let worktree = worktree.read(cx);
+                let worktree = worktree
+                    .as_local()
+                    .context("git operations are not supported in remote projects")?;
+                let (work_directory, repo) = worktree
+                    .local_repo_for_path(&project_path.path)
+                    .with_context(|| {
+                        format!("{:?} is not in a git repository", project_path.path)
+                    })?;
+                let repo_path = project_path.path.strip_prefix(&work_directory)?;
+                Ok((repo.repo_ptr.clone(), repo_path.into()))
+            })
+            .collect()
+    }
+
+    fn repo_path_for_buffer(
+        &self,
+        buffer: &Model<Buffer>,
+        cx: &AppContext,
+    ) -> Result<(Arc<Mutex<dyn GitRepository>>, RepoPath)> {
+        let file = File::from_dyn(buffer.read(cx).file()).context("buffer has no file")?;
+        let project_path = ProjectPath {
+            worktree_id: file.worktree_id(cx),
+            path: file.path.clone(),
+        };
+        self.repo_paths(vec![project_path], cx)?
+            .pop()
+            .context("buffer is not in a git repository")
+    }
+}
+
+fn hunk_containing_row(
+    hunks: &[LineHunk],
+    row: u32,
+    rows: impl Fn(&LineHunk) -> &Range<u32>,
+) -> Option<&LineHunk> {
+    hunks.iter().find(|hunk| {
+        let hunk_rows = rows(hunk);
+        hunk_rows.contains(&row) || (hunk_rows.is_empty() && hunk_rows.start == row)
+    })
+}
@@ -1,7 +1,9 @@
 use crate::{
-    DocumentHighlight, Hover, HoverBlock, HoverBlockKind, InlayHint, InlayHintLabel,
-    InlayHintLabelPart, InlayHintLabelPartTooltip, InlayHintTooltip, Location, LocationLink,
-    MarkupContent, Project, ProjectTransaction, ResolveState,
+    CallHierarchyCall, CallHierarchyItem, CodeLens, DocumentHighlight, DocumentLink, HierarchyItem,
+    Hover, HoverBlock, HoverBlockKind, InlayHint, InlayHintLabel, InlayHintLabelPart,
+    InlayHintLabelPartTooltip, InlayHintTooltip, Location, LocationLink, MarkupContent,
+    ParameterInformation, Project, ProjectTransaction, ResolveState, SemanticToken, SemanticTokens,
+    SignatureHelp, SignatureInformation, TypeHierarchyItem,
 };
 use anyhow::{anyhow, Context, Result};
 use async_trait::async_trait;
@@ -20,8 +22,10 @@ use lsp::{
     CompletionListItemDefaultsEditRange, DocumentHighlightKind, LanguageServer, LanguageServerId,
     OneOf, ServerCapabilities,
 };
+use serde::{de::DeserializeOwned, Serialize};
 use std::{cmp::Reverse, ops::Range, path::Path, sync::Arc};
 use text::LineEnding;
+use util::ResultExt;
 
 pub fn lsp_formatting_options(tab_size: u32) -> lsp::FormattingOptions {
     lsp::FormattingOptions {
@@ -105,6 +109,14 @@ pub(crate) struct GetTypeDefinition {
     pub position: PointUtf16,
 }
 
+pub(crate) struct GetImplementation {
+    pub position: PointUtf16,
+}
+
+pub(crate) struct GetDeclaration {
+    pub position: PointUtf16,
+}
+
 pub(crate) struct GetReferences {
     pub position: PointUtf16,
 }
@@ -117,6 +129,50 @@ pub(crate) struct GetHover {
     pub position: PointUtf16,
 }
 
+pub(crate) struct GetSignatureHelp {
+    pub position: PointUtf16,
+    pub trigger_character: Option<String>,
+    pub is_retrigger: bool,
+}
+
+pub(crate) struct PrepareCallHierarchy {
+    pub position: PointUtf16,
+}
+
+pub(crate) struct GetIncomingCalls {
+    pub item: lsp::CallHierarchyItem,
+}
+
+pub(crate) struct GetOutgoingCalls {
+    pub item: lsp::CallHierarchyItem,
+}
+
+pub(crate) struct PrepareTypeHierarchy {
+    pub position: PointUtf16,
+}
+
+pub(crate) struct GetSupertypes {
+    pub item: lsp::TypeHierarchyItem,
+}
+
+pub(crate) struct GetSubtypes {
+    pub item: lsp::TypeHierarchyItem,
+}
+
+pub(crate) struct GetSemanticTokens;
+
+pub(crate) struct GetCodeLens;
+
+pub(crate) struct GetDocumentLinks;
+
+pub(crate) struct GetSelectionRanges {
+    pub positions: Vec<PointUtf16>,
+}
+
+pub(crate) struct GetLinkedEditingRanges {
+    pub position: PointUtf16,
+}
+
 pub(crate) struct GetCompletions {
     pub position: PointUtf16,
 }
@@ -592,6 +648,208 @@ impl LspCommand for GetTypeDefinition {
     }
 }
 
+#[async_trait(?Send)]
+impl LspCommand for GetImplementation {
+    type Response = Vec<LocationLink>;
+    type LspRequest = lsp::request::GotoImplementation;
+    type ProtoRequest = proto::GetImplementation;
+
+    fn check_capabilities(&self, capabilities: &ServerCapabilities) -> bool {
+        match &capabilities.implementation_provider {
+            None => false,
+            Some(lsp::ImplementationProviderCapability::Simple(false)) => false,
+            _ => true,
+        }
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &AppContext,
+    ) -> lsp::GotoImplementationParams {
+        lsp::GotoImplementationParams {
+            text_document_position_params: lsp::TextDocumentPositionParams {
+                text_document: lsp::TextDocumentIdentifier {
+                    uri: lsp::Url::from_file_path(path).unwrap(),
+                },
+                position: point_to_lsp(self.position),
+            },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        }
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<lsp::GotoImplementationResponse>,
+        project: Model<Project>,
+        buffer: Model<Buffer>,
+        server_id: LanguageServerId,
+        cx: AsyncAppContext,
+    ) -> Result<Vec<LocationLink>> {
+        location_links_from_lsp(message, project, buffer, server_id, cx).await
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetImplementation {
+        proto::GetImplementation {
+            project_id,
+            buffer_id: buffer.remote_id(),
+            position: Some(language::proto::serialize_anchor(
+                &buffer.anchor_before(self.position),
+            )),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetImplementation,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Self> {
+        let position = message
+            .position
+            .and_then(deserialize_anchor)
+            .ok_or_else(|| anyhow!("invalid position"))?;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self {
+            position: buffer.update(&mut cx, |buffer, _| position.to_point_utf16(buffer))?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<LocationLink>,
+        project: &mut Project,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut AppContext,
+    ) -> proto::GetImplementationResponse {
+        let links = location_links_to_proto(response, project, peer_id, cx);
+        proto::GetImplementationResponse { links }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetImplementationResponse,
+        project: Model<Project>,
+        _: Model<Buffer>,
+        cx: AsyncAppContext,
+    ) -> Result<Vec<LocationLink>> {
+        location_links_from_proto(message.links, project, cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetImplementation) -> u64 {
+        message.buffer_id
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetDeclaration {
+    type Response = Vec<LocationLink>;
+    type LspRequest = lsp::request::GotoDeclaration;
+    type ProtoRequest = proto::GetDeclaration;
+
+    fn check_capabilities(&self, capabilities: &ServerCapabilities) -> bool {
+        match &capabilities.declaration_provider {
+            None => false,
+            Some(lsp::DeclarationCapability::Simple(false)) => false,
+            _ => true,
+        }
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &AppContext,
+    ) -> lsp::GotoDeclarationParams {
+        lsp::GotoDeclarationParams {
+            text_document_position_params: lsp::TextDocumentPositionParams {
+                text_document: lsp::TextDocumentIdentifier {
+                    uri: lsp::Url::from_file_path(path).unwrap(),
+                },
+                position: point_to_lsp(self.position),
+            },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        }
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<lsp::GotoDeclarationResponse>,
+        project: Model<Project>,
+        buffer: Model<Buffer>,
+        server_id: LanguageServerId,
+        cx: AsyncAppContext,
+    ) -> Result<Vec<LocationLink>> {
+        location_links_from_lsp(message, project, buffer, server_id, cx).await
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetDeclaration {
+        proto::GetDeclaration {
+            project_id,
+            buffer_id: buffer.remote_id(),
+            position: Some(language::proto::serialize_anchor(
+                &buffer.anchor_before(self.position),
+            )),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetDeclaration,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Self> {
+        let position = message
+            .position
+            .and_then(deserialize_anchor)
+            .ok_or_else(|| anyhow!("invalid position"))?;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self {
+            position: buffer.update(&mut cx, |buffer, _| position.to_point_utf16(buffer))?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<LocationLink>,
+        project: &mut Project,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut AppContext,
+    ) -> proto::GetDeclarationResponse {
+        let links = location_links_to_proto(response, project, peer_id, cx);
+        proto::GetDeclarationResponse { links }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetDeclarationResponse,
+        project: Model<Project>,
+        _: Model<Buffer>,
+        cx: AsyncAppContext,
+    ) -> Result<Vec<LocationLink>> {
+        location_links_from_proto(message.links, project, cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetDeclaration) -> u64 {
+        message.buffer_id
+    }
+}
+
 fn language_server_for_buffer(
     project: &Model<Project>,
     buffer: &Model<Buffer>,
@@ -1313,6 +1571,2014 @@ impl LspCommand for GetHover {
     }
 }
 
+#[async_trait(?Send)]
+impl LspCommand for GetSignatureHelp {
+    type Response = Option<SignatureHelp>;
+    type LspRequest = lsp::request::SignatureHelpRequest;
+    type ProtoRequest = proto::GetSignatureHelp;
+
+    fn check_capabilities(&self, capabilities: &ServerCapabilities) -> bool {
+        let Some(options) = &capabilities.signature_help_provider else {
+            return false;
+        };
+        // Any edit updates signature help that is already shown, but only the server's
+        // trigger characters show it in the first place.
+        match &self.trigger_character {
+            Some(character) if !self.is_retrigger => options
+                .trigger_characters
+                .as_ref()
+                .map_or(false, |characters| characters.contains(character)),
+            _ => true,
+        }
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        language_server: &Arc<LanguageServer>,
+        _: &AppContext,
+    ) -> lsp::SignatureHelpParams {
+        let options = &language_server.capabilities().signature_help_provider;
+        let is_trigger_character = |character: &String| {
+            options.as_ref().map_or(false, |options| {
+                [&options.trigger_characters, &options.retrigger_characters]
+                    .into_iter()
+                    .flatten()
+                    .any(|characters| characters.contains(character))
+            })
+        };
+        let (trigger_kind, trigger_character) = match &self.trigger_character {
+            Some(character) if is_trigger_character(character) => (
+                lsp::SignatureHelpTriggerKind::TRIGGER_CHARACTER,
+                Some(character.clone()),
+            ),
+            Some(_) => (lsp::SignatureHelpTriggerKind::CONTENT_CHANGE, None),
+            None => (lsp::SignatureHelpTriggerKind::INVOKED, None),
+        };
+
+        lsp::SignatureHelpParams {
+            context: Some(lsp::SignatureHelpContext {
+                trigger_kind,
+                trigger_character,
+                is_retrigger: self.is_retrigger,
+                active_signature_help: None,
+            }),
+            text_document_position_params: lsp::TextDocumentPositionParams {
+                text_document: lsp::TextDocumentIdentifier {
+                    uri: lsp::Url::from_file_path(path).unwrap(),
+                },
+                position: point_to_lsp(self.position),
+            },
+            work_done_progress_params: Default::default(),
+        }
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<lsp::SignatureHelp>,
+        _: Model<Project>,
+        _: Model<Buffer>,
+        _: LanguageServerId,
+        _: AsyncAppContext,
+    ) -> Result<Self::Response> {
+        let Some(help) = message else {
+            return Ok(None);
+        };
+        if help.signatures.is_empty() {
+            return Ok(None);
+        }
+
+        fn markup_content_from_lsp(documentation: lsp::Documentation) -> MarkupContent {
+            match documentation {
+                lsp::Documentation::String(value) => MarkupContent {
+                    kind: HoverBlockKind::PlainText,
+                    value,
+                },
+                lsp::Documentation::MarkupContent(markup_content) => MarkupContent {
+                    kind: match markup_content.kind {
+                        lsp::MarkupKind::PlainText => HoverBlockKind::PlainText,
+                        lsp::MarkupKind::Markdown => HoverBlockKind::Markdown,
+                    },
+                    value: markup_content.value,
+                },
+            }
+        }
+
+        let signatures = help
+            .signatures
+            .into_iter()
+            .map(|signature| {
+                // Parameters given as plain strings are searched for in order, so that a
+                // parameter whose name also appears earlier in the label is found correctly.
+                let mut search_start = 0;
+                let parameters = signature
+                    .parameters
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|parameter| {
+                        let label_range = match parameter.label {
+                            lsp::ParameterLabel::Simple(label) => {
+                                signature.label[search_start..].find(&label).map(|start| {
+                                    let start = search_start + start;
+                                    start..start + label.len()
+                                })
+                            }
+                            lsp::ParameterLabel::LabelOffsets([start, end]) => {
+                                utf16_offset_to_byte_offset(&signature.label, start).and_then(
+                                    |start| {
+                                        let end =
+                                            utf16_offset_to_byte_offset(&signature.label, end)?;
+                                        Some(start..end)
+                                    },
+                                )
+                            }
+                        };
+                        if let Some(label_range) = &label_range {
+                            search_start = label_range.end;
+                        }
+                        ParameterInformation {
+                            label_range,
+                            documentation: parameter.documentation.map(markup_content_from_lsp),
+                        }
+                    })
+                    .collect();
+                SignatureInformation {
+                    label: signature.label,
+                    documentation: signature.documentation.map(markup_content_from_lsp),
+                    parameters,
+                    active_parameter: signature.active_parameter.map(|ix| ix as usize),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let active_signature = help
+            .active_signature
+            .map_or(0, |ix| ix as usize)
+            .min(signatures.len() - 1);
+        Ok(Some(SignatureHelp {
+            signatures,
+            active_signature,
+            active_parameter: help.active_parameter.map(|ix| ix as usize),
+        }))
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> Self::ProtoRequest {
+        proto::GetSignatureHelp {
+            project_id,
+            buffer_id: buffer.remote_id(),
+            position: Some(language::proto::serialize_anchor(
+                &buffer.anchor_before(self.position),
+            )),
+            trigger_character: self.trigger_character.clone(),
+            is_retrigger: self.is_retrigger,
+            version: serialize_version(&buffer.version),
+        }
+    }
+
+    async fn from_proto(
+        message: Self::ProtoRequest,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Self> {
+        let position = message
+            .position
+            .and_then(deserialize_anchor)
+            .ok_or_else(|| anyhow!("invalid position"))?;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self {
+            position: buffer.update(&mut cx, |buffer, _| position.to_point_utf16(buffer))?,
+            trigger_character: message.trigger_character,
+            is_retrigger: message.is_retrigger,
+        })
+    }
+
+    fn response_to_proto(
+        response: Self::Response,
+        _: &mut Project,
+        _: PeerId,
+        _: &clock::Global,
+        _: &mut AppContext,
+    ) -> proto::GetSignatureHelpResponse {
+        fn markup_content_to_proto(markup_content: MarkupContent) -> proto::MarkupContent {
+            proto::MarkupContent {
+                is_markdown: markup_content.kind == HoverBlockKind::Markdown,
+                value: markup_content.value,
+            }
+        }
+
+        let Some(response) = response else {
+            return proto::GetSignatureHelpResponse {
+                signatures: Vec::new(),
+                active_signature: 0,
+                active_parameter: None,
+            };
+        };
+        proto::GetSignatureHelpResponse {
+            signatures: response
+                .signatures
+                .into_iter()
+                .map(|signature| proto::SignatureInformation {
+                    label: signature.label,
+                    documentation: signature.documentation.map(markup_content_to_proto),
+                    parameters: signature
+                        .parameters
+                        .into_iter()
+                        .map(|parameter| proto::ParameterInformation {
+                            label_start: parameter
+                                .label_range
+                                .as_ref()
+                                .map(|range| range.start as u64),
+                            label_end: parameter.label_range.map(|range| range.end as u64),
+                            documentation: parameter.documentation.map(markup_content_to_proto),
+                        })
+                        .collect(),
+                    active_parameter: signature.active_parameter.map(|ix| ix as u64),
+                })
+                .collect(),
+            active_signature: response.active_signature as u64,
+            active_parameter: response.active_parameter.map(|ix| ix as u64),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetSignatureHelpResponse,
+        _: Model<Project>,
+        _: Model<Buffer>,
+        _: AsyncAppContext,
+    ) -> Result<Self::Response> {
+        fn markup_content_from_proto(markup_content: proto::MarkupContent) -> MarkupContent {
+            MarkupContent {
+                kind: if markup_content.is_markdown {
+                    HoverBlockKind::Markdown
+                } else {
+                    HoverBlockKind::PlainText
+                },
+                value: markup_content.value,
+            }
+        }
+
+        if message.signatures.is_empty() {
+            return Ok(None);
+        }
+        let signatures = message
+            .signatures
+            .into_iter()
+            .map(|signature| {
+                let label_len = signature.label.len();
+                SignatureInformation {
+                    parameters: signature
+                        .parameters
+                        .into_iter()
+                        .map(|parameter| ParameterInformation {
+                            label_range: parameter
+                                .label_start
+                                .zip(parameter.label_end)
+                                .map(|(start, end)| start as usize..end as usize)
+                                .filter(|range| range.start <= range.end && range.end <= label_len),
+                            documentation: parameter.documentation.map(markup_content_from_proto),
+                        })
+                        .collect(),
+                    label: signature.label,
+                    documentation: signature.documentation.map(markup_content_from_proto),
+                    active_parameter: signature.active_parameter.map(|ix| ix as usize),
+                }
+            })
+            .collect::<Vec<_>>();
+        Ok(Some(SignatureHelp {
+            active_signature: (message.active_signature as usize).min(signatures.len() - 1),
+            active_parameter: message.active_parameter.map(|ix| ix as usize),
+            signatures,
+        }))
+    }
+
+    fn buffer_id_from_proto(message: &Self::ProtoRequest) -> u64 {
+        message.buffer_id
+    }
+}
+
+/// Converts an offset in UTF-16 code units into a byte offset, if it falls on a character
+/// boundary of `text`.
+fn utf16_offset_to_byte_offset(text: &str, utf16_offset: u32) -> Option<usize> {
+    let mut utf16_len = 0;
+    for (byte_offset, character) in text.char_indices() {
+        if utf16_len == utf16_offset as usize {
+            return Some(byte_offset);
+        }
+        utf16_len += character.len_utf16();
+    }
+    (utf16_len == utf16_offset as usize).then_some(text.len())
+}
+
+/// The fields shared by the items of call hierarchies and type hierarchies.
+trait LspHierarchyItem: Clone + Serialize + DeserializeOwned {
+    fn name(&self) -> &str;
+    fn kind(&self) -> lsp::SymbolKind;
+    fn detail(&self) -> Option<&str>;
+    fn uri(&self) -> &lsp::Url;
+    fn range(&self) -> lsp::Range;
+    fn selection_range(&self) -> lsp::Range;
+}
+
+impl LspHierarchyItem for lsp::CallHierarchyItem {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn kind(&self) -> lsp::SymbolKind {
+        self.kind
+    }
+
+    fn detail(&self) -> Option<&str> {
+        self.detail.as_deref()
+    }
+
+    fn uri(&self) -> &lsp::Url {
+        &self.uri
+    }
+
+    fn range(&self) -> lsp::Range {
+        self.range
+    }
+
+    fn selection_range(&self) -> lsp::Range {
+        self.selection_range
+    }
+}
+
+impl LspHierarchyItem for lsp::TypeHierarchyItem {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn kind(&self) -> lsp::SymbolKind {
+        self.kind
+    }
+
+    fn detail(&self) -> Option<&str> {
+        self.detail.as_deref()
+    }
+
+    fn uri(&self) -> &lsp::Url {
+        &self.uri
+    }
+
+    fn range(&self) -> lsp::Range {
+        self.range
+    }
+
+    fn selection_range(&self) -> lsp::Range {
+        self.selection_range
+    }
+}
+
+fn anchor_range_from_lsp(buffer: &Buffer, range: lsp::Range) -> Range<Anchor> {
+    let start = buffer.clip_point_utf16(point_from_lsp(range.start), Bias::Left);
+    let end = buffer.clip_point_utf16(point_from_lsp(range.end), Bias::Left);
+    buffer.anchor_after(start)..buffer.anchor_before(end)
+}
+
+fn deserialize_anchor_ranges(
+    starts: Vec<proto::Anchor>,
+    ends: Vec<proto::Anchor>,
+) -> Result<Vec<Range<Anchor>>> {
+    starts
+        .into_iter()
+        .zip(ends)
+        .map(|(start, end)| {
+            let start = deserialize_anchor(start).context("invalid start")?;
+            let end = deserialize_anchor(end).context("invalid end")?;
+            Ok(start..end)
+        })
+        .collect()
+}
+
+async fn hierarchy_items_from_lsp<T: LspHierarchyItem>(
+    lsp_items: Vec<T>,
+    project: &Model<Project>,
+    buffer: &Model<Buffer>,
+    server_id: LanguageServerId,
+    cx: &mut AsyncAppContext,
+) -> Result<Vec<HierarchyItem<T>>> {
+    let (lsp_adapter, language_server) =
+        language_server_for_buffer(project, buffer, server_id, cx)?;
+    let mut items = Vec::new();
+    for lsp_item in lsp_items {
+        let item_buffer = project
+            .update(cx, |this, cx| {
+                this.open_local_buffer_via_lsp(
+                    lsp_item.uri().clone(),
+                    language_server.server_id(),
+                    lsp_adapter.name.clone(),
+                    cx,
+                )
+            })?
+            .await?;
+        let (range, selection_range) = item_buffer.update(cx, |item_buffer, _| {
+            (
+                anchor_range_from_lsp(item_buffer, lsp_item.range()),
+                anchor_range_from_lsp(item_buffer, lsp_item.selection_range()),
+            )
+        })?;
+        items.push(HierarchyItem {
+            name: lsp_item.name().to_string(),
+            kind: lsp_item.kind(),
+            detail: lsp_item.detail().map(ToString::to_string),
+            location: Location {
+                buffer: item_buffer,
+                range,
+            },
+            selection_range,
+            lsp_item,
+        });
+    }
+    Ok(items)
+}
+
+fn location_to_proto(
+    location: Location,
+    project: &mut Project,
+    peer_id: PeerId,
+    cx: &mut AppContext,
+) -> proto::Location {
+    let buffer_id = project.create_buffer_for_peer(&location.buffer, peer_id, cx);
+    proto::Location {
+        start: Some(serialize_anchor(&location.range.start)),
+        end: Some(serialize_anchor(&location.range.end)),
+        buffer_id,
+    }
+}
+
+async fn location_from_proto(
+    location: proto::Location,
+    project: &Model<Project>,
+    cx: &mut AsyncAppContext,
+) -> Result<Location> {
+    let buffer = project
+        .update(cx, |this, cx| {
+            this.wait_for_remote_buffer(location.buffer_id, cx)
+        })?
+        .await?;
+    let start = location
+        .start
+        .and_then(deserialize_anchor)
+        .ok_or_else(|| anyhow!("missing location start"))?;
+    let end = location
+        .end
+        .and_then(deserialize_anchor)
+        .ok_or_else(|| anyhow!("missing location end"))?;
+    buffer
+        .update(cx, |buffer, _| buffer.wait_for_anchors([start, end]))?
+        .await?;
+    Ok(Location {
+        buffer,
+        range: start..end,
+    })
+}
+
+fn hierarchy_item_to_proto<T: LspHierarchyItem>(
+    item: HierarchyItem<T>,
+    project: &mut Project,
+    peer_id: PeerId,
+    cx: &mut AppContext,
+) -> proto::HierarchyItem {
+    proto::HierarchyItem {
+        location: Some(location_to_proto(item.location, project, peer_id, cx)),
+        selection_start: Some(serialize_anchor(&item.selection_range.start)),
+        selection_end: Some(serialize_anchor(&item.selection_range.end)),
+        lsp_item: serde_json::to_string(&item.lsp_item).unwrap_or_default(),
+    }
+}
+
+async fn hierarchy_item_from_proto<T: LspHierarchyItem>(
+    item: proto::HierarchyItem,
+    project: &Model<Project>,
+    cx: &mut AsyncAppContext,
+) -> Result<HierarchyItem<T>> {
+    let lsp_item: T = serde_json::from_str(&item.lsp_item)?;
+    let location = location_from_proto(
+        item.location.ok_or_else(|| anyhow!("missing location"))?,
+        project,
+        cx,
+    )
+    .await?;
+    let start = item
+        .selection_start
+        .and_then(deserialize_anchor)
+        .ok_or_else(|| anyhow!("missing selection start"))?;
+    let end = item
+        .selection_end
+        .and_then(deserialize_anchor)
+        .ok_or_else(|| anyhow!("missing selection end"))?;
+    location
+        .buffer
+        .update(cx, |buffer, _| buffer.wait_for_anchors([start, end]))?
+        .await?;
+    Ok(HierarchyItem {
+        name: lsp_item.name().to_string(),
+        kind: lsp_item.kind(),
+        detail: lsp_item.detail().map(ToString::to_string),
+        location,
+        selection_range: start..end,
+        lsp_item,
+    })
+}
+
+fn call_hierarchy_supported(capabilities: &ServerCapabilities) -> bool {
+    match &capabilities.call_hierarchy_provider {
+        None => false,
+        Some(lsp::CallHierarchyServerCapability::Simple(false)) => false,
+        _ => true,
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for PrepareCallHierarchy {
+    type Response = Vec<CallHierarchyItem>;
+    type LspRequest = lsp::request::CallHierarchyPrepare;
+    type ProtoRequest = proto::PrepareCallHierarchy;
+
+    fn check_capabilities(&self, capabilities: &ServerCapabilities) -> bool {
+        call_hierarchy_supported(capabilities)
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &AppContext,
+    ) -> lsp::CallHierarchyPrepareParams {
+        lsp::CallHierarchyPrepareParams {
+            text_document_position_params: lsp::TextDocumentPositionParams {
+                text_document: lsp::TextDocumentIdentifier {
+                    uri: lsp::Url::from_file_path(path).unwrap(),
+                },
+                position: point_to_lsp(self.position),
+            },
+            work_done_progress_params: Default::default(),
+        }
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::CallHierarchyItem>>,
+        project: Model<Project>,
+        buffer: Model<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<CallHierarchyItem>> {
+        hierarchy_items_from_lsp(
+            message.unwrap_or_default(),
+            &project,
+            &buffer,
+            server_id,
+            &mut cx,
+        )
+        .await
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::PrepareCallHierarchy {
+        proto::PrepareCallHierarchy {
+            project_id,
+            buffer_id: buffer.remote_id(),
+            position: Some(language::proto::serialize_anchor(
+                &buffer.anchor_before(self.position),
+            )),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::PrepareCallHierarchy,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Self> {
+        let position = message
+            .position
+            .and_then(deserialize_anchor)
+            .ok_or_else(|| anyhow!("invalid position"))?;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self {
+            position: buffer.update(&mut cx, |buffer, _| position.to_point_utf16(buffer))?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<CallHierarchyItem>,
+        project: &mut Project,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut AppContext,
+    ) -> proto::HierarchyItemsResponse {
+        proto::HierarchyItemsResponse {
+            items: response
+                .into_iter()
+                .map(|item| hierarchy_item_to_proto(item, project, peer_id, cx))
+                .collect(),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::HierarchyItemsResponse,
+        project: Model<Project>,
+        _: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<CallHierarchyItem>> {
+        let mut items = Vec::new();
+        for item in message.items {
+            items.push(hierarchy_item_from_proto(item, &project, &mut cx).await?);
+        }
+        Ok(items)
+    }
+
+    fn buffer_id_from_proto(message: &proto::PrepareCallHierarchy) -> u64 {
+        message.buffer_id
+    }
+}
+
+async fn call_hierarchy_calls_from_proto(
+    message: proto::CallHierarchyCallsResponse,
+    project: Model<Project>,
+    mut cx: AsyncAppContext,
+) -> Result<Vec<CallHierarchyCall>> {
+    let mut calls = Vec::new();
+    for call in message.calls {
+        let item = hierarchy_item_from_proto(
+            call.item
+                .ok_or_else(|| anyhow!("missing call hierarchy item"))?,
+            &project,
+            &mut cx,
+        )
+        .await?;
+        let mut call_sites = Vec::new();
+        for call_site in call.call_sites {
+            call_sites.push(location_from_proto(call_site, &project, &mut cx).await?);
+        }
+        calls.push(CallHierarchyCall { item, call_sites });
+    }
+    Ok(calls)
+}
+
+fn call_hierarchy_calls_to_proto(
+    calls: Vec<CallHierarchyCall>,
+    project: &mut Project,
+    peer_id: PeerId,
+    cx: &mut AppContext,
+) -> proto::CallHierarchyCallsResponse {
+    proto::CallHierarchyCallsResponse {
+        calls: calls
+            .into_iter()
+            .map(|call| proto::CallHierarchyCall {
+                item: Some(hierarchy_item_to_proto(call.item, project, peer_id, cx)),
+                call_sites: call
+                    .call_sites
+                    .into_iter()
+                    .map(|call_site| location_to_proto(call_site, project, peer_id, cx))
+                    .collect(),
+            })
+            .collect(),
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetIncomingCalls {
+    type Response = Vec<CallHierarchyCall>;
+    type LspRequest = lsp::request::CallHierarchyIncomingCalls;
+    type ProtoRequest = proto::GetIncomingCalls;
+
+    fn check_capabilities(&self, capabilities: &ServerCapabilities) -> bool {
+        call_hierarchy_supported(capabilities)
+    }
+
+    fn to_lsp(
+        &self,
+        _: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &AppContext,
+    ) -> lsp::CallHierarchyIncomingCallsParams {
+        lsp::CallHierarchyIncomingCallsParams {
+            item: self.item.clone(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        }
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::CallHierarchyIncomingCall>>,
+        project: Model<Project>,
+        buffer: Model<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<CallHierarchyCall>> {
+        let mut calls = Vec::new();
+        for incoming_call in message.unwrap_or_default() {
+            let Some(item) = hierarchy_items_from_lsp(
+                vec![incoming_call.from],
+                &project,
+                &buffer,
+                server_id,
+                &mut cx,
+            )
+            .await?
+            .pop() else {
+                continue;
+            };
+            // The call sites are in the caller.
+            let caller_buffer = item.location.buffer.clone();
+            let call_sites = caller_buffer.update(&mut cx, |caller, _| {
+                incoming_call
+                    .from_ranges
+                    .into_iter()
+                    .map(|range| Location {
+                        buffer: caller_buffer.clone(),
+                        range: anchor_range_from_lsp(caller, range),
+                    })
+                    .collect::<Vec<_>>()
+            })?;
+            calls.push(CallHierarchyCall { item, call_sites });
+        }
+        Ok(calls)
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetIncomingCalls {
+        proto::GetIncomingCalls {
+            project_id,
+            buffer_id: buffer.remote_id(),
+            lsp_item: serde_json::to_string(&self.item).unwrap_or_default(),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetIncomingCalls,
+        _: Model<Project>,
+        _: Model<Buffer>,
+        _: AsyncAppContext,
+    ) -> Result<Self> {
+        Ok(Self {
+            item: serde_json::from_str(&message.lsp_item)?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<CallHierarchyCall>,
+        project: &mut Project,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut AppContext,
+    ) -> proto::CallHierarchyCallsResponse {
+        call_hierarchy_calls_to_proto(response, project, peer_id, cx)
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::CallHierarchyCallsResponse,
+        project: Model<Project>,
+        _: Model<Buffer>,
+        cx: AsyncAppContext,
+    ) -> Result<Vec<CallHierarchyCall>> {
+        call_hierarchy_calls_from_proto(message, project, cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetIncomingCalls) -> u64 {
+        message.buffer_id
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetOutgoingCalls {
+    type Response = Vec<CallHierarchyCall>;
+    type LspRequest = lsp::request::CallHierarchyOutgoingCalls;
+    type ProtoRequest = proto::GetOutgoingCalls;
+
+    fn check_capabilities(&self, capabilities: &ServerCapabilities) -> bool {
+        call_hierarchy_supported(capabilities)
+    }
+
+    fn to_lsp(
+        &self,
+        _: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &AppContext,
+    ) -> lsp::CallHierarchyOutgoingCallsParams {
+        lsp::CallHierarchyOutgoingCallsParams {
+            item: self.item.clone(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        }
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::CallHierarchyOutgoingCall>>,
+        project: Model<Project>,
+        buffer: Model<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<CallHierarchyCall>> {
+        let mut calls = Vec::new();
+        for outgoing_call in message.unwrap_or_default() {
+            let Some(item) = hierarchy_items_from_lsp(
+                vec![outgoing_call.to],
+                &project,
+                &buffer,
+                server_id,
+                &mut cx,
+            )
+            .await?
+            .pop() else {
+                continue;
+            };
+            // The call sites are in the item whose callees were requested.
+            let call_sites = buffer.update(&mut cx, |caller, _| {
+                outgoing_call
+                    .from_ranges
+                    .into_iter()
+                    .map(|range| Location {
+                        buffer: buffer.clone(),
+                        range: anchor_range_from_lsp(caller, range),
+                    })
+                    .collect::<Vec<_>>()
+            })?;
+            calls.push(CallHierarchyCall { item, call_sites });
+        }
+        Ok(calls)
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetOutgoingCalls {
+        proto::GetOutgoingCalls {
+            project_id,
+            buffer_id: buffer.remote_id(),
+            lsp_item: serde_json::to_string(&self.item).unwrap_or_default(),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetOutgoingCalls,
+        _: Model<Project>,
+        _: Model<Buffer>,
+        _: AsyncAppContext,
+    ) -> Result<Self> {
+        Ok(Self {
+            item: serde_json::from_str(&message.lsp_item)?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<CallHierarchyCall>,
+        project: &mut Project,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut AppContext,
+    ) -> proto::CallHierarchyCallsResponse {
+        call_hierarchy_calls_to_proto(response, project, peer_id, cx)
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::CallHierarchyCallsResponse,
+        project: Model<Project>,
+        _: Model<Buffer>,
+        cx: AsyncAppContext,
+    ) -> Result<Vec<CallHierarchyCall>> {
+        call_hierarchy_calls_from_proto(message, project, cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetOutgoingCalls) -> u64 {
+        message.buffer_id
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for PrepareTypeHierarchy {
+    type Response = Vec<TypeHierarchyItem>;
+    type LspRequest = lsp::request::TypeHierarchyPrepare;
+    type ProtoRequest = proto::PrepareTypeHierarchy;
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &AppContext,
+    ) -> lsp::TypeHierarchyPrepareParams {
+        lsp::TypeHierarchyPrepareParams {
+            text_document_position_params: lsp::TextDocumentPositionParams {
+                text_document: lsp::TextDocumentIdentifier {
+                    uri: lsp::Url::from_file_path(path).unwrap(),
+                },
+                position: point_to_lsp(self.position),
+            },
+            work_done_progress_params: Default::default(),
+        }
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::TypeHierarchyItem>>,
+        project: Model<Project>,
+        buffer: Model<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<TypeHierarchyItem>> {
+        hierarchy_items_from_lsp(
+            message.unwrap_or_default(),
+            &project,
+            &buffer,
+            server_id,
+            &mut cx,
+        )
+        .await
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::PrepareTypeHierarchy {
+        proto::PrepareTypeHierarchy {
+            project_id,
+            buffer_id: buffer.remote_id(),
+            position: Some(language::proto::serialize_anchor(
+                &buffer.anchor_before(self.position),
+            )),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::PrepareTypeHierarchy,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Self> {
+        let position = message
+            .position
+            .and_then(deserialize_anchor)
+            .ok_or_else(|| anyhow!("invalid position"))?;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self {
+            position: buffer.update(&mut cx, |buffer, _| position.to_point_utf16(buffer))?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<TypeHierarchyItem>,
+        project: &mut Project,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut AppContext,
+    ) -> proto::HierarchyItemsResponse {
+        proto::HierarchyItemsResponse {
+            items: response
+                .into_iter()
+                .map(|item| hierarchy_item_to_proto(item, project, peer_id, cx))
+                .collect(),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::HierarchyItemsResponse,
+        project: Model<Project>,
+        _: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<TypeHierarchyItem>> {
+        let mut items = Vec::new();
+        for item in message.items {
+            items.push(hierarchy_item_from_proto(item, &project, &mut cx).await?);
+        }
+        Ok(items)
+    }
+
+    fn buffer_id_from_proto(message: &proto::PrepareTypeHierarchy) -> u64 {
+        message.buffer_id
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetSupertypes {
+    type Response = Vec<TypeHierarchyItem>;
+    type LspRequest = lsp::request::TypeHierarchySupertypes;
+    type ProtoRequest = proto::GetSupertypes;
+
+    fn to_lsp(
+        &self,
+        _: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &AppContext,
+    ) -> lsp::TypeHierarchySupertypesParams {
+        lsp::TypeHierarchySupertypesParams {
+            item: self.item.clone(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        }
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::TypeHierarchyItem>>,
+        project: Model<Project>,
+        buffer: Model<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<TypeHierarchyItem>> {
+        hierarchy_items_from_lsp(
+            message.unwrap_or_default(),
+            &project,
+            &buffer,
+            server_id,
+            &mut cx,
+        )
+        .await
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetSupertypes {
+        proto::GetSupertypes {
+            project_id,
+            buffer_id: buffer.remote_id(),
+            lsp_item: serde_json::to_string(&self.item).unwrap_or_default(),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetSupertypes,
+        _: Model<Project>,
+        _: Model<Buffer>,
+        _: AsyncAppContext,
+    ) -> Result<Self> {
+        Ok(Self {
+            item: serde_json::from_str(&message.lsp_item)?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<TypeHierarchyItem>,
+        project: &mut Project,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut AppContext,
+    ) -> proto::HierarchyItemsResponse {
+        proto::HierarchyItemsResponse {
+            items: response
+                .into_iter()
+                .map(|item| hierarchy_item_to_proto(item, project, peer_id, cx))
+                .collect(),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::HierarchyItemsResponse,
+        project: Model<Project>,
+        _: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<TypeHierarchyItem>> {
+        let mut items = Vec::new();
+        for item in message.items {
+            items.push(hierarchy_item_from_proto(item, &project, &mut cx).await?);
+        }
+        Ok(items)
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetSupertypes) -> u64 {
+        message.buffer_id
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetSubtypes {
+    type Response = Vec<TypeHierarchyItem>;
+    type LspRequest = lsp::request::TypeHierarchySubtypes;
+    type ProtoRequest = proto::GetSubtypes;
+
+    fn to_lsp(
+        &self,
+        _: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &AppContext,
+    ) -> lsp::TypeHierarchySubtypesParams {
+        lsp::TypeHierarchySubtypesParams {
+            item: self.item.clone(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        }
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::TypeHierarchyItem>>,
+        project: Model<Project>,
+        buffer: Model<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<TypeHierarchyItem>> {
+        hierarchy_items_from_lsp(
+            message.unwrap_or_default(),
+            &project,
+            &buffer,
+            server_id,
+            &mut cx,
+        )
+        .await
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetSubtypes {
+        proto::GetSubtypes {
+            project_id,
+            buffer_id: buffer.remote_id(),
+            lsp_item: serde_json::to_string(&self.item).unwrap_or_default(),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetSubtypes,
+        _: Model<Project>,
+        _: Model<Buffer>,
+        _: AsyncAppContext,
+    ) -> Result<Self> {
+        Ok(Self {
+            item: serde_json::from_str(&message.lsp_item)?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<TypeHierarchyItem>,
+        project: &mut Project,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut AppContext,
+    ) -> proto::HierarchyItemsResponse {
+        proto::HierarchyItemsResponse {
+            items: response
+                .into_iter()
+                .map(|item| hierarchy_item_to_proto(item, project, peer_id, cx))
+                .collect(),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::HierarchyItemsResponse,
+        project: Model<Project>,
+        _: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<TypeHierarchyItem>> {
+        let mut items = Vec::new();
+        for item in message.items {
+            items.push(hierarchy_item_from_proto(item, &project, &mut cx).await?);
+        }
+        Ok(items)
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetSubtypes) -> u64 {
+        message.buffer_id
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetSemanticTokens {
+    type Response = SemanticTokens;
+    type LspRequest = lsp::request::SemanticTokensFullRequest;
+    type ProtoRequest = proto::GetSemanticTokens;
+
+    fn check_capabilities(&self, capabilities: &ServerCapabilities) -> bool {
+        Self::options(capabilities).map_or(false, |options| match &options.full {
+            Some(lsp::SemanticTokensFullOptions::Bool(full)) => *full,
+            Some(lsp::SemanticTokensFullOptions::Delta { .. }) => true,
+            None => false,
+        })
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &AppContext,
+    ) -> lsp::SemanticTokensParams {
+        lsp::SemanticTokensParams {
+            text_document: lsp::TextDocumentIdentifier {
+                uri: lsp::Url::from_file_path(path).unwrap(),
+            },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        }
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<lsp::SemanticTokensResult>,
+        project: Model<Project>,
+        buffer: Model<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncAppContext,
+    ) -> Result<SemanticTokens> {
+        let (_, language_server) =
+            language_server_for_buffer(&project, &buffer, server_id, &mut cx)?;
+        let legend = Self::options(language_server.capabilities())
+            .map(|options| options.legend.clone())
+            .context("language server has no semantic tokens legend")?;
+        let (result_id, data) = match message {
+            Some(lsp::SemanticTokensResult::Tokens(tokens)) => (tokens.result_id, tokens.data),
+            Some(lsp::SemanticTokensResult::Partial(partial)) => (None, partial.data),
+            None => (None, Vec::new()),
+        };
+        buffer.update(&mut cx, |buffer, _| {
+            Self::tokens_from_lsp(result_id, data, &legend, server_id, buffer)
+        })
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetSemanticTokens {
+        proto::GetSemanticTokens {
+            project_id,
+            buffer_id: buffer.remote_id(),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetSemanticTokens,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Self> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self)
+    }
+
+    fn response_to_proto(
+        response: SemanticTokens,
+        _: &mut Project,
+        _: PeerId,
+        buffer_version: &clock::Global,
+        _: &mut AppContext,
+    ) -> proto::GetSemanticTokensResponse {
+        proto::GetSemanticTokensResponse {
+            tokens: response
+                .tokens
+                .into_iter()
+                .map(|token| proto::SemanticToken {
+                    start: Some(serialize_anchor(&token.range.start)),
+                    end: Some(serialize_anchor(&token.range.end)),
+                    token_type: token.token_type.to_string(),
+                    modifiers: token
+                        .modifiers
+                        .iter()
+                        .map(|modifier| modifier.to_string())
+                        .collect(),
+                })
+                .collect(),
+            version: serialize_version(buffer_version),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetSemanticTokensResponse,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<SemanticTokens> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+
+        let mut tokens = Vec::new();
+        for token in message.tokens {
+            let start = token
+                .start
+                .and_then(deserialize_anchor)
+                .context("invalid start")?;
+            let end = token
+                .end
+                .and_then(deserialize_anchor)
+                .context("invalid end")?;
+            tokens.push(SemanticToken {
+                range: start..end,
+                token_type: token.token_type.into(),
+                modifiers: token.modifiers.into_iter().map(Into::into).collect(),
+            });
+        }
+        Ok(SemanticTokens {
+            tokens,
+            ..Default::default()
+        })
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetSemanticTokens) -> u64 {
+        message.buffer_id
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetCodeLens {
+    type Response = Vec<CodeLens>;
+    type LspRequest = lsp::request::CodeLensRequest;
+    type ProtoRequest = proto::GetCodeLens;
+
+    fn check_capabilities(&self, capabilities: &ServerCapabilities) -> bool {
+        capabilities.code_lens_provider.is_some()
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &AppContext,
+    ) -> lsp::CodeLensParams {
+        lsp::CodeLensParams {
+            text_document: lsp::TextDocumentIdentifier {
+                uri: lsp::Url::from_file_path(path).unwrap(),
+            },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        }
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::CodeLens>>,
+        project: Model<Project>,
+        buffer: Model<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<CodeLens>> {
+        let (_, language_server) =
+            language_server_for_buffer(&project, &buffer, server_id, &mut cx)?;
+        let can_resolve = Self::can_resolve(language_server.capabilities());
+        let mut lsp_lenses = message.unwrap_or_default();
+        lsp_lenses.retain(|lsp_lens| lsp_lens.command.is_some() || can_resolve);
+        lsp_lenses.sort_by_key(|lsp_lens| lsp_lens.range.start);
+
+        buffer.update(&mut cx, |buffer, _| {
+            lsp_lenses
+                .into_iter()
+                .map(|lsp_lens| {
+                    let start =
+                        buffer.clip_point_utf16(point_from_lsp(lsp_lens.range.start), Bias::Left);
+                    let end =
+                        buffer.clip_point_utf16(point_from_lsp(lsp_lens.range.end), Bias::Left);
+                    CodeLens {
+                        server_id,
+                        range: buffer.anchor_after(start)..buffer.anchor_before(end),
+                        lsp_lens,
+                    }
+                })
+                .collect()
+        })
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetCodeLens {
+        proto::GetCodeLens {
+            project_id,
+            buffer_id: buffer.remote_id(),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetCodeLens,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Self> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self)
+    }
+
+    fn response_to_proto(
+        response: Vec<CodeLens>,
+        _: &mut Project,
+        _: PeerId,
+        buffer_version: &clock::Global,
+        _: &mut AppContext,
+    ) -> proto::GetCodeLensResponse {
+        proto::GetCodeLensResponse {
+            lenses: response.into_iter().map(Self::code_lens_to_proto).collect(),
+            version: serialize_version(buffer_version),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetCodeLensResponse,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<CodeLens>> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        message
+            .lenses
+            .into_iter()
+            .map(Self::code_lens_from_proto)
+            .collect()
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetCodeLens) -> u64 {
+        message.buffer_id
+    }
+}
+
+impl GetCodeLens {
+    pub fn can_resolve(capabilities: &ServerCapabilities) -> bool {
+        capabilities
+            .code_lens_provider
+            .as_ref()
+            .and_then(|options| options.resolve_provider)
+            .unwrap_or(false)
+    }
+
+    pub fn code_lens_to_proto(lens: CodeLens) -> proto::CodeLens {
+        proto::CodeLens {
+            server_id: lens.server_id.0 as u64,
+            start: Some(serialize_anchor(&lens.range.start)),
+            end: Some(serialize_anchor(&lens.range.end)),
+            lsp_lens: serde_json::to_vec(&lens.lsp_lens).unwrap(),
+        }
+    }
+
+    pub fn code_lens_from_proto(lens: proto::CodeLens) -> Result<CodeLens> {
+        let start = lens
+            .start
+            .and_then(deserialize_anchor)
+            .context("invalid start")?;
+        let end = lens
+            .end
+            .and_then(deserialize_anchor)
+            .context("invalid end")?;
+        Ok(CodeLens {
+            server_id: LanguageServerId(lens.server_id as usize),
+            range: start..end,
+            lsp_lens: serde_json::from_slice(&lens.lsp_lens)?,
+        })
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetDocumentLinks {
+    type Response = Vec<DocumentLink>;
+    type LspRequest = lsp::request::DocumentLinkRequest;
+    type ProtoRequest = proto::GetDocumentLinks;
+
+    fn check_capabilities(&self, capabilities: &ServerCapabilities) -> bool {
+        capabilities.document_link_provider.is_some()
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &AppContext,
+    ) -> lsp::DocumentLinkParams {
+        lsp::DocumentLinkParams {
+            text_document: lsp::TextDocumentIdentifier {
+                uri: lsp::Url::from_file_path(path).unwrap(),
+            },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        }
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::DocumentLink>>,
+        project: Model<Project>,
+        buffer: Model<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<DocumentLink>> {
+        let (_, language_server) =
+            language_server_for_buffer(&project, &buffer, server_id, &mut cx)?;
+        let can_resolve = language_server
+            .capabilities()
+            .document_link_provider
+            .as_ref()
+            .and_then(|options| options.resolve_provider)
+            .unwrap_or(false);
+        let links = message.unwrap_or_default().into_iter().map(|lsp_link| {
+            let language_server = language_server.clone();
+            async move {
+                if lsp_link.target.is_some() || !can_resolve {
+                    return lsp_link;
+                }
+                language_server
+                    .request::<lsp::request::DocumentLinkResolve>(lsp_link.clone())
+                    .await
+                    .log_err()
+                    .unwrap_or(lsp_link)
+            }
+        });
+        let mut lsp_links = future::join_all(links).await;
+        lsp_links.sort_by_key(|lsp_link| lsp_link.range.start);
+
+        buffer.update(&mut cx, |buffer, _| {
+            lsp_links
+                .into_iter()
+                .filter_map(|lsp_link| {
+                    let start =
+                        buffer.clip_point_utf16(point_from_lsp(lsp_link.range.start), Bias::Left);
+                    let end =
+                        buffer.clip_point_utf16(point_from_lsp(lsp_link.range.end), Bias::Left);
+                    Some(DocumentLink {
+                        range: buffer.anchor_after(start)..buffer.anchor_before(end),
+                        target: lsp_link.target?,
+                        tooltip: lsp_link.tooltip,
+                    })
+                })
+                .collect()
+        })
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetDocumentLinks {
+        proto::GetDocumentLinks {
+            project_id,
+            buffer_id: buffer.remote_id(),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetDocumentLinks,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Self> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self)
+    }
+
+    fn response_to_proto(
+        response: Vec<DocumentLink>,
+        _: &mut Project,
+        _: PeerId,
+        buffer_version: &clock::Global,
+        _: &mut AppContext,
+    ) -> proto::GetDocumentLinksResponse {
+        proto::GetDocumentLinksResponse {
+            links: response
+                .into_iter()
+                .map(|link| proto::DocumentLink {
+                    start: Some(serialize_anchor(&link.range.start)),
+                    end: Some(serialize_anchor(&link.range.end)),
+                    target: link.target.to_string(),
+                    tooltip: link.tooltip,
+                })
+                .collect(),
+            version: serialize_version(buffer_version),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetDocumentLinksResponse,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<DocumentLink>> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+
+        let mut links = Vec::new();
+        for link in message.links {
+            let start = link
+                .start
+                .and_then(deserialize_anchor)
+                .context("invalid start")?;
+            let end = link
+                .end
+                .and_then(deserialize_anchor)
+                .context("invalid end")?;
+            links.push(DocumentLink {
+                range: start..end,
+                target: lsp::Url::parse(&link.target)?,
+                tooltip: link.tooltip,
+            });
+        }
+        Ok(links)
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetDocumentLinks) -> u64 {
+        message.buffer_id
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetSelectionRanges {
+    type Response = Vec<Vec<Range<Anchor>>>;
+    type LspRequest = lsp::request::SelectionRangeRequest;
+    type ProtoRequest = proto::GetSelectionRanges;
+
+    fn check_capabilities(&self, capabilities: &ServerCapabilities) -> bool {
+        match &capabilities.selection_range_provider {
+            Some(lsp::SelectionRangeProviderCapability::Simple(enabled)) => *enabled,
+            Some(_) => true,
+            None => false,
+        }
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &AppContext,
+    ) -> lsp::SelectionRangeParams {
+        lsp::SelectionRangeParams {
+            text_document: lsp::TextDocumentIdentifier {
+                uri: lsp::Url::from_file_path(path).unwrap(),
+            },
+            positions: self
+                .positions
+                .iter()
+                .map(|position| point_to_lsp(*position))
+                .collect(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        }
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::SelectionRange>>,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        _: LanguageServerId,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<Vec<Range<Anchor>>>> {
+        buffer.update(&mut cx, |buffer, _| {
+            message
+                .unwrap_or_default()
+                .into_iter()
+                .map(|mut selection_range| {
+                    let mut ranges = Vec::new();
+                    loop {
+                        ranges.push(anchor_range_from_lsp(buffer, selection_range.range));
+                        match selection_range.parent {
+                            Some(parent) => selection_range = *parent,
+                            None => break,
+                        }
+                    }
+                    ranges
+                })
+                .collect()
+        })
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetSelectionRanges {
+        proto::GetSelectionRanges {
+            project_id,
+            buffer_id: buffer.remote_id(),
+            positions: self
+                .positions
+                .iter()
+                .map(|position| serialize_anchor(&buffer.anchor_before(*position)))
+                .collect(),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetSelectionRanges,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Self> {
+        let positions = message
+            .positions
+            .into_iter()
+            .map(|position| deserialize_anchor(position).context("invalid position"))
+            .collect::<Result<Vec<_>>>()?;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self {
+            positions: buffer.update(&mut cx, |buffer, _| {
+                positions
+                    .iter()
+                    .map(|position| position.to_point_utf16(buffer))
+                    .collect()
+            })?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<Vec<Range<Anchor>>>,
+        _: &mut Project,
+        _: PeerId,
+        buffer_version: &clock::Global,
+        _: &mut AppContext,
+    ) -> proto::GetSelectionRangesResponse {
+        proto::GetSelectionRangesResponse {
+            selection_ranges: response
+                .into_iter()
+                .map(|ranges| {
+                    let (starts, ends) = ranges
+                        .iter()
+                        .map(|range| (serialize_anchor(&range.start), serialize_anchor(&range.end)))
+                        .unzip();
+                    proto::SelectionRanges { starts, ends }
+                })
+                .collect(),
+            version: serialize_version(buffer_version),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetSelectionRangesResponse,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<Vec<Range<Anchor>>>> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        message
+            .selection_ranges
+            .into_iter()
+            .map(|ranges| deserialize_anchor_ranges(ranges.starts, ranges.ends))
+            .collect()
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetSelectionRanges) -> u64 {
+        message.buffer_id
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetLinkedEditingRanges {
+    type Response = Vec<Range<Anchor>>;
+    type LspRequest = lsp::request::LinkedEditingRange;
+    type ProtoRequest = proto::GetLinkedEditingRanges;
+
+    fn check_capabilities(&self, capabilities: &ServerCapabilities) -> bool {
+        match &capabilities.linked_editing_range_provider {
+            Some(lsp::LinkedEditingRangeServerCapabilities::Simple(enabled)) => *enabled,
+            Some(_) => true,
+            None => false,
+        }
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &AppContext,
+    ) -> lsp::LinkedEditingRangeParams {
+        lsp::LinkedEditingRangeParams {
+            text_document_position_params: lsp::TextDocumentPositionParams {
+                text_document: lsp::TextDocumentIdentifier {
+                    uri: lsp::Url::from_file_path(path).unwrap(),
+                },
+                position: point_to_lsp(self.position),
+            },
+            work_done_progress_params: Default::default(),
+        }
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<lsp::LinkedEditingRanges>,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        _: LanguageServerId,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<Range<Anchor>>> {
+        buffer.update(&mut cx, |buffer, _| {
+            message
+                .map(|linked_ranges| linked_ranges.ranges)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|range| {
+                    let start = buffer.clip_point_utf16(point_from_lsp(range.start), Bias::Left);
+                    let end = buffer.clip_point_utf16(point_from_lsp(range.end), Bias::Left);
+                    // Bias the anchors outwards so that text typed at either end of a range
+                    // becomes part of it.
+                    buffer.anchor_before(start)..buffer.anchor_after(end)
+                })
+                .collect()
+        })
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetLinkedEditingRanges {
+        proto::GetLinkedEditingRanges {
+            project_id,
+            buffer_id: buffer.remote_id(),
+            position: Some(serialize_anchor(&buffer.anchor_before(self.position))),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetLinkedEditingRanges,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Self> {
+        let position = message
+            .position
+            .and_then(deserialize_anchor)
+            .context("invalid position")?;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self {
+            position: buffer.update(&mut cx, |buffer, _| position.to_point_utf16(buffer))?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<Range<Anchor>>,
+        _: &mut Project,
+        _: PeerId,
+        buffer_version: &clock::Global,
+        _: &mut AppContext,
+    ) -> proto::GetLinkedEditingRangesResponse {
+        let (starts, ends) = response
+            .iter()
+            .map(|range| (serialize_anchor(&range.start), serialize_anchor(&range.end)))
+            .unzip();
+        proto::GetLinkedEditingRangesResponse {
+            starts,
+            ends,
+            version: serialize_version(buffer_version),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetLinkedEditingRangesResponse,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<Range<Anchor>>> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        deserialize_anchor_ranges(message.starts, message.ends)
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetLinkedEditingRanges) -> u64 {
+        message.buffer_id
+    }
+}
+
+impl GetSemanticTokens {
+    pub fn options(capabilities: &ServerCapabilities) -> Option<&lsp::SemanticTokensOptions> {
+        match capabilities.semantic_tokens_provider.as_ref()? {
+            lsp::SemanticTokensServerCapabilities::SemanticTokensOptions(options) => Some(options),
+            lsp::SemanticTokensServerCapabilities::SemanticTokensRegistrationOptions(options) => {
+                Some(&options.semantic_tokens_options)
+            }
+        }
+    }
+
+    pub fn supports_delta(capabilities: &ServerCapabilities) -> bool {
+        Self::options(capabilities).map_or(false, |options| {
+            matches!(
+                options.full,
+                Some(lsp::SemanticTokensFullOptions::Delta { delta: Some(true) })
+            )
+        })
+    }
+
+    /// Applies the edits of a delta response to the previous tokens. The edits' offsets count
+    /// the integers of the encoded tokens, five per token, and are relative to the previous
+    /// tokens, so they are applied from last to first. Returns `None` if an edit doesn't start
+    /// and end on a token boundary, in which case the whole tokens have to be requested again.
+    pub fn apply_edits(
+        mut data: Vec<lsp::SemanticToken>,
+        mut edits: Vec<lsp::SemanticTokensEdit>,
+    ) -> Option<Vec<lsp::SemanticToken>> {
+        if edits
+            .iter()
+            .any(|edit| edit.start % 5 != 0 || edit.delete_count % 5 != 0)
+        {
+            return None;
+        }
+        edits.sort_by_key(|edit| Reverse(edit.start));
+        for edit in edits {
+            let start = (edit.start as usize / 5).min(data.len());
+            let end = ((edit.start + edit.delete_count) as usize / 5).clamp(start, data.len());
+            data.splice(start..end, edit.data.unwrap_or_default());
+        }
+        Some(data)
+    }
+
+    /// Decodes the tokens' positions, which are relative to the previous token, and resolves
+    /// their types and modifiers using the server's legend. `buffer` must be the version of
+    /// the buffer that the server computed the tokens for.
+    pub fn tokens_from_lsp(
+        result_id: Option<String>,
+        data: Vec<lsp::SemanticToken>,
+        legend: &lsp::SemanticTokensLegend,
+        server_id: LanguageServerId,
+        buffer: &text::BufferSnapshot,
+    ) -> SemanticTokens {
+        let token_types = legend
+            .token_types
+            .iter()
+            .map(|token_type| Arc::<str>::from(token_type.as_str()))
+            .collect::<Vec<_>>();
+        let token_modifiers = legend
+            .token_modifiers
+            .iter()
+            .map(|modifier| Arc::<str>::from(modifier.as_str()))
+            .collect::<Vec<_>>();
+
+        let mut tokens = Vec::with_capacity(data.len());
+        let mut line = 0;
+        let mut start_character = 0;
+        for token in &data {
+            if token.delta_line > 0 {
+                line += token.delta_line;
+                start_character = 0;
+            }
+            start_character += token.delta_start;
+            let Some(token_type) = token_types.get(token.token_type as usize) else {
+                continue;
+            };
+            let modifiers = token_modifiers
+                .iter()
+                .take(32)
+                .enumerate()
+                .filter(|(ix, _)| token.token_modifiers_bitset & (1 << ix) != 0)
+                .map(|(_, modifier)| modifier.clone())
+                .collect();
+            let start = buffer.clip_point_utf16(
+                Unclipped(PointUtf16::new(line, start_character)),
+                Bias::Left,
+            );
+            let end = buffer.clip_point_utf16(
+                Unclipped(PointUtf16::new(line, start_character + token.length)),
+                Bias::Left,
+            );
+            tokens.push(SemanticToken {
+                range: buffer.anchor_after(start)..buffer.anchor_before(end),
+                token_type: token_type.clone(),
+                modifiers,
+            });
+        }
+
+        SemanticTokens {
+            tokens,
+            result_id,
+            data,
+            server_id: Some(server_id),
+        }
+    }
+}
+
 #[async_trait(?Send)]
 impl LspCommand for GetCompletions {
     type Response = Vec<Completion>;
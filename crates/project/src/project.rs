@@ -1,9 +1,14 @@
+pub mod debugger;
+mod file_operations;
+pub mod git_repository;
 mod ignore;
 pub mod lsp_command;
 pub mod lsp_ext_command;
 mod prettier_support;
 pub mod project_settings;
+mod pull_diagnostics;
 pub mod search;
+pub mod tasks;
 pub mod terminals;
 pub mod worktree;
 
@@ -17,6 +22,7 @@ use client::{proto, Client, Collaborator, TypedEnvelope, UserStore};
 use clock::ReplicaId;
 use collections::{hash_map, BTreeMap, HashMap, HashSet, VecDeque};
 use copilot::Copilot;
+use debugger::Debugger;
 use futures::{
     channel::{
         mpsc::{self, UnboundedReceiver},
@@ -29,7 +35,7 @@ use futures::{
 use globset::{Glob, GlobSet, GlobSetBuilder};
 use gpui::{
     AnyModel, AppContext, AsyncAppContext, BackgroundExecutor, Context, Entity, EventEmitter,
-    Model, ModelContext, Task, WeakModel,
+    Model, ModelContext, PromptLevel, Task, WeakModel,
 };
 use itertools::Itertools;
 use language::{
@@ -56,6 +62,7 @@ use parking_lot::{Mutex, RwLock};
 use postage::watch;
 use prettier_support::{DefaultPrettier, PrettierInstance};
 use project_settings::{LspSettings, ProjectSettings};
+use pull_diagnostics::PulledDiagnostics;
 use rand::prelude::*;
 use search::SearchQuery;
 use serde::Serialize;
@@ -80,11 +87,15 @@ use std::{
     },
     time::{Duration, Instant},
 };
+use tasks::Tasks;
 use terminals::Terminals;
 use text::Anchor;
 use util::{
-    debug_panic, defer, http::HttpClient, merge_json_value_into,
-    paths::LOCAL_SETTINGS_RELATIVE_PATH, post_inc, ResultExt, TryFutureExt as _,
+    debug_panic, defer,
+    http::HttpClient,
+    merge_json_value_into,
+    paths::{HOME, LOCAL_SETTINGS_RELATIVE_PATH},
+    post_inc, ResultExt, TryFutureExt as _,
 };
 
 pub use fs::*;
@@ -138,6 +149,7 @@ pub struct Project {
     /// Used for re-issuing buffer requests when peers temporarily disconnect
     incomplete_remote_buffers: HashMap<u64, Option<Model<Buffer>>>,
     buffer_snapshots: HashMap<u64, HashMap<LanguageServerId, Vec<LspBufferSnapshot>>>, // buffer_id -> server_id -> vec of snapshots
+    pulled_diagnostics: HashMap<LanguageServerId, PulledDiagnostics>,
     buffers_being_formatted: HashSet<u64>,
     buffers_needing_diff: HashSet<WeakModel<Buffer>>,
     git_diff_debouncer: DelayedDebounced,
@@ -145,6 +157,8 @@ pub struct Project {
     _maintain_buffer_languages: Task<()>,
     _maintain_workspace_config: Task<Result<()>>,
     terminals: Terminals,
+    tasks: Tasks,
+    debugger: Debugger,
     copilot_lsp_subscription: Option<gpui::Subscription>,
     copilot_log_subscription: Option<lsp::Subscription>,
     current_lsp_settings: HashMap<Arc<str>, LspSettings>,
@@ -261,6 +275,8 @@ pub enum Event {
     LanguageServerAdded(LanguageServerId),
     LanguageServerRemoved(LanguageServerId),
     LanguageServerLog(LanguageServerId, String),
+    LanguageServerPrompt(LanguageServerPromptRequest),
+    LanguageServerShowDocument(LanguageServerShowDocument),
     Notification(String),
     ActiveEntryChanged(Option<ProjectEntryId>),
     ActivateProjectPanel,
@@ -288,7 +304,64 @@ pub enum Event {
     CollaboratorJoined(proto::PeerId),
     CollaboratorLeft(proto::PeerId),
     RefreshInlayHints,
+    RefreshSemanticTokens,
+    RefreshCodeLens,
     RevealInProjectPanel(ProjectEntryId),
+    BreakpointsChanged(u64),
+    DebugPositionChanged,
+    GitRepositoriesUpdated(WorktreeId),
+}
+
+/// A message that a language server wants to show, with the actions the user can pick to answer
+/// it.
+#[derive(Clone, Debug)]
+pub struct LanguageServerPromptRequest {
+    pub level: PromptLevel,
+    pub message: String,
+    pub actions: Vec<lsp::MessageActionItem>,
+    pub lsp_name: String,
+    response_channel: Sender<lsp::MessageActionItem>,
+}
+
+impl LanguageServerPromptRequest {
+    /// Sends the action at `index` back to the server. Returns `None` if there's no such action
+    /// or if the server doesn't wait for an answer.
+    pub async fn respond(self, index: usize) -> Option<()> {
+        let action = self.actions.into_iter().nth(index)?;
+        self.response_channel.send(action).await.ok()
+    }
+}
+
+impl PartialEq for LanguageServerPromptRequest {
+    fn eq(&self, other: &Self) -> bool {
+        self.message == other.message
+            && self.actions == other.actions
+            && self.lsp_name == other.lsp_name
+    }
+}
+
+/// A file that a language server asked to show, optionally with a range to select.
+#[derive(Clone, Debug)]
+pub struct LanguageServerShowDocument {
+    pub abs_path: PathBuf,
+    pub selection: Option<Range<PointUtf16>>,
+    pub take_focus: bool,
+    response_channel: Sender<bool>,
+}
+
+impl LanguageServerShowDocument {
+    /// Tells the server whether the document could be shown.
+    pub async fn respond(self, success: bool) {
+        self.response_channel.send(success).await.ok();
+    }
+}
+
+impl PartialEq for LanguageServerShowDocument {
+    fn eq(&self, other: &Self) -> bool {
+        self.abs_path == other.abs_path
+            && self.selection == other.selection
+            && self.take_focus == other.take_focus
+    }
 }
 
 pub enum LanguageServerState {
@@ -445,6 +518,117 @@ impl Hover {
     }
 }
 
+/// A symbol in a call or type hierarchy.
+#[derive(Clone, Debug)]
+pub struct HierarchyItem<T> {
+    pub name: String,
+    pub kind: lsp::SymbolKind,
+    pub detail: Option<String>,
+    /// The symbol's whole definition, including its body.
+    pub location: Location,
+    /// The symbol's name within its definition.
+    pub selection_range: Range<language::Anchor>,
+    /// The item as sent by the language server, which needs it back to find the item's
+    /// neighbors in the hierarchy.
+    pub(crate) lsp_item: T,
+}
+
+pub type CallHierarchyItem = HierarchyItem<lsp::CallHierarchyItem>;
+pub type TypeHierarchyItem = HierarchyItem<lsp::TypeHierarchyItem>;
+
+/// A caller or a callee of a [`CallHierarchyItem`].
+#[derive(Clone, Debug)]
+pub struct CallHierarchyCall {
+    pub item: CallHierarchyItem,
+    /// Where the call is made: in the caller for incoming calls, and in the item whose
+    /// callees were requested for outgoing calls.
+    pub call_sites: Vec<Location>,
+}
+
+/// The signatures of the function being called at a position, as returned by
+/// `textDocument/signatureHelp`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignatureHelp {
+    pub signatures: Vec<SignatureInformation>,
+    pub active_signature: usize,
+    pub active_parameter: Option<usize>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignatureInformation {
+    pub label: String,
+    pub documentation: Option<MarkupContent>,
+    pub parameters: Vec<ParameterInformation>,
+    /// Overrides the active parameter of the [`SignatureHelp`] for this signature.
+    pub active_parameter: Option<usize>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParameterInformation {
+    /// The byte range of the parameter within its signature's label, if it could be found.
+    pub label_range: Option<Range<usize>>,
+    pub documentation: Option<MarkupContent>,
+}
+
+impl SignatureHelp {
+    pub fn active_parameter(&self, signature_ix: usize) -> Option<usize> {
+        self.signatures
+            .get(signature_ix)
+            .and_then(|signature| signature.active_parameter)
+            .or(self.active_parameter)
+    }
+}
+
+/// The semantic tokens of a buffer, as returned by `textDocument/semanticTokens/full` or
+/// computed from a previous result and `textDocument/semanticTokens/full/delta`.
+#[derive(Clone, Debug, Default)]
+pub struct SemanticTokens {
+    /// The tokens in buffer order, without overlaps.
+    pub tokens: Vec<SemanticToken>,
+    /// The server's id of this result, used to request a delta against it.
+    pub(crate) result_id: Option<String>,
+    /// The tokens as sent by the language server, which deltas are applied to.
+    pub(crate) data: Vec<lsp::SemanticToken>,
+    pub(crate) server_id: Option<LanguageServerId>,
+}
+
+/// A link in a buffer to a URL or a file, as returned by `textDocument/documentLink`.
+#[derive(Clone, Debug)]
+pub struct DocumentLink {
+    pub range: Range<language::Anchor>,
+    pub target: lsp::Url,
+    pub tooltip: Option<String>,
+}
+
+/// A command shown above a line of a buffer, as returned by `textDocument/codeLens`.
+#[derive(Clone, Debug)]
+pub struct CodeLens {
+    pub server_id: LanguageServerId,
+    pub range: Range<language::Anchor>,
+    pub lsp_lens: lsp::CodeLens,
+}
+
+impl CodeLens {
+    /// Whether the lens has a command, which servers may leave out until the lens is resolved.
+    pub fn is_resolved(&self) -> bool {
+        self.lsp_lens.command.is_some()
+    }
+
+    pub fn title(&self) -> Option<&str> {
+        self.lsp_lens
+            .command
+            .as_ref()
+            .map(|command| command.title.as_str())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SemanticToken {
+    pub range: Range<language::Anchor>,
+    pub token_type: Arc<str>,
+    pub modifiers: Vec<Arc<str>>,
+}
+
 #[derive(Default)]
 pub struct ProjectTransaction(pub HashMap<Model<Buffer>, language::Transaction>);
 
@@ -591,6 +775,8 @@ impl Project {
         client.add_model_request_handler(Self::handle_expand_project_entry);
         client.add_model_request_handler(Self::handle_apply_additional_edits_for_completion);
         client.add_model_request_handler(Self::handle_apply_code_action);
+        client.add_model_request_handler(Self::handle_apply_code_lens);
+        client.add_model_request_handler(Self::handle_resolve_code_lens);
         client.add_model_request_handler(Self::handle_on_type_formatting);
         client.add_model_request_handler(Self::handle_inlay_hints);
         client.add_model_request_handler(Self::handle_resolve_inlay_hint);
@@ -601,8 +787,22 @@ impl Project {
         client.add_model_request_handler(Self::handle_lsp_command::<GetCodeActions>);
         client.add_model_request_handler(Self::handle_lsp_command::<GetCompletions>);
         client.add_model_request_handler(Self::handle_lsp_command::<GetHover>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetSignatureHelp>);
+        client.add_model_request_handler(Self::handle_lsp_command::<PrepareCallHierarchy>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetIncomingCalls>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetOutgoingCalls>);
+        client.add_model_request_handler(Self::handle_lsp_command::<PrepareTypeHierarchy>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetSupertypes>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetSubtypes>);
         client.add_model_request_handler(Self::handle_lsp_command::<GetDefinition>);
         client.add_model_request_handler(Self::handle_lsp_command::<GetTypeDefinition>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetImplementation>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetDeclaration>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetSemanticTokens>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetCodeLens>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetDocumentLinks>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetSelectionRanges>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetLinkedEditingRanges>);
         client.add_model_request_handler(Self::handle_lsp_command::<GetDocumentHighlights>);
         client.add_model_request_handler(Self::handle_lsp_command::<GetReferences>);
         client.add_model_request_handler(Self::handle_lsp_command::<PrepareRename>);
@@ -644,6 +844,7 @@ impl Project {
                 local_buffer_ids_by_path: Default::default(),
                 local_buffer_ids_by_entry_id: Default::default(),
                 buffer_snapshots: Default::default(),
+                pulled_diagnostics: Default::default(),
                 join_project_response_message_id: 0,
                 client_state: ProjectClientState::Local,
                 opened_buffer: watch::channel(),
@@ -674,6 +875,8 @@ impl Project {
                 terminals: Terminals {
                     local_handles: Vec::new(),
                 },
+                debugger: Default::default(),
+                tasks: Default::default(),
                 copilot_lsp_subscription,
                 copilot_log_subscription: None,
                 current_lsp_settings: ProjectSettings::get_global(cx).lsp.clone(),
@@ -775,10 +978,13 @@ impl Project {
                 buffers_needing_diff: Default::default(),
                 git_diff_debouncer: DelayedDebounced::new(),
                 buffer_snapshots: Default::default(),
+                pulled_diagnostics: Default::default(),
                 nonce: StdRng::from_entropy().gen(),
                 terminals: Terminals {
                     local_handles: Vec::new(),
                 },
+                debugger: Default::default(),
+                tasks: Default::default(),
                 copilot_lsp_subscription,
                 copilot_log_subscription: None,
                 current_lsp_settings: ProjectSettings::get_global(cx).lsp.clone(),
@@ -1153,12 +1359,7 @@ impl Project {
             return Task::ready(Ok(None));
         };
         if self.is_local() {
-            worktree.update(cx, |worktree, cx| {
-                worktree
-                    .as_local_mut()
-                    .unwrap()
-                    .create_entry(project_path.path, is_directory, cx)
-            })
+            self.create_local_entry(worktree, project_path.path, is_directory, cx)
         } else {
             let client = self.client.clone();
             let project_id = self.remote_id().unwrap();
@@ -1245,12 +1446,7 @@ impl Project {
         };
         let new_path = new_path.into();
         if self.is_local() {
-            worktree.update(cx, |worktree, cx| {
-                worktree
-                    .as_local_mut()
-                    .unwrap()
-                    .rename_entry(entry_id, new_path, cx)
-            })
+            self.rename_local_entry(worktree, entry_id, new_path, cx)
         } else {
             let client = self.client.clone();
             let project_id = self.remote_id().unwrap();
@@ -1290,9 +1486,7 @@ impl Project {
         cx.emit(Event::DeletedEntry(entry_id));
 
         if self.is_local() {
-            worktree.update(cx, |worktree, cx| {
-                worktree.as_local_mut().unwrap().delete_entry(entry_id, cx)
-            })
+            self.delete_local_entry(worktree, entry_id, cx)
         } else {
             let client = self.client.clone();
             let project_id = self.remote_id().unwrap();
@@ -2058,13 +2252,16 @@ impl Project {
             }
         }
 
+        self.anchor_breakpoints(buffer.read(cx), cx);
         self.detect_language_for_buffer(buffer, cx);
         self.register_buffer_with_language_servers(buffer, cx);
         self.register_buffer_with_copilot(buffer, cx);
         cx.observe_release(buffer, |this, buffer, cx| {
+            this.unanchor_breakpoints(buffer, cx);
             if let Some(file) = File::from_dyn(buffer.file()) {
                 if file.is_local() {
                     let uri = lsp::Url::from_file_path(file.abs_path(cx)).unwrap();
+                    this.forget_pulled_diagnostics(buffer.remote_id(), &uri);
                     for server in this.language_servers_for_buffer(buffer, cx) {
                         server
                             .1
@@ -2164,6 +2361,7 @@ impl Project {
                         .or_default()
                         .insert(server.server_id(), vec![snapshot]);
                 }
+                self.pull_buffer_diagnostics(buffer_handle, cx);
             }
         }
     }
@@ -2345,6 +2543,7 @@ impl Project {
             }
 
             BufferEvent::Edited { .. } => {
+                self.pull_buffer_diagnostics(&buffer, cx);
                 let buffer = buffer.read(cx);
                 let file = File::from_dyn(buffer.file())?;
                 let abs_path = file.as_local()?.abs_path(cx);
@@ -2441,6 +2640,7 @@ impl Project {
             }
 
             BufferEvent::Saved => {
+                self.pull_buffer_diagnostics(&buffer, cx);
                 let file = File::from_dyn(buffer.read(cx).file())?;
                 let worktree_id = file.worktree_id(cx);
                 let abs_path = file.as_local()?.abs_path(cx);
@@ -3158,6 +3358,128 @@ impl Project {
             })
             .detach();
 
+        language_server
+            .on_request::<lsp::request::SemanticTokensRefresh, _, _>({
+                let this = this.clone();
+                move |(), mut cx| {
+                    let this = this.clone();
+                    async move {
+                        this.update(&mut cx, |_, cx| cx.emit(Event::RefreshSemanticTokens))?;
+                        Ok(())
+                    }
+                }
+            })
+            .detach();
+
+        language_server
+            .on_request::<lsp::request::CodeLensRefresh, _, _>({
+                let this = this.clone();
+                move |(), mut cx| {
+                    let this = this.clone();
+                    async move {
+                        this.update(&mut cx, |_, cx| cx.emit(Event::RefreshCodeLens))?;
+                        Ok(())
+                    }
+                }
+            })
+            .detach();
+
+        language_server
+            .on_request::<lsp::request::WorkspaceDiagnosticRefresh, _, _>({
+                let this = this.clone();
+                move |(), mut cx| {
+                    let this = this.clone();
+                    async move {
+                        this.update(&mut cx, |this, cx| this.pull_diagnostics(server_id, cx))?;
+                        Ok(())
+                    }
+                }
+            })
+            .detach();
+
+        language_server
+            .on_request::<lsp::request::ShowMessageRequest, _, _>({
+                let this = this.clone();
+                let lsp_name = adapter.name.0.to_string();
+                move |params, mut cx| {
+                    let this = this.clone();
+                    let lsp_name = lsp_name.clone();
+                    async move {
+                        let (response_channel, response) = smol::channel::bounded(1);
+                        let request = LanguageServerPromptRequest {
+                            level: prompt_level(params.typ),
+                            message: params.message,
+                            actions: params.actions.unwrap_or_default(),
+                            lsp_name,
+                            response_channel,
+                        };
+                        this.update(&mut cx, |_, cx| {
+                            cx.emit(Event::LanguageServerPrompt(request))
+                        })?;
+                        // The channel closes without an action when the prompt is dismissed.
+                        Ok(response.recv().await.ok())
+                    }
+                }
+            })
+            .detach();
+
+        language_server
+            .on_notification::<lsp::notification::ShowMessage, _>({
+                let this = this.clone();
+                let lsp_name = adapter.name.0.to_string();
+                move |params, mut cx| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+                    // Only requests get a prompt, as the server waits for an answer to those.
+                    // Errors are also shown as a notification, which doesn't interrupt typing.
+                    this.update(&mut cx, |_, cx| {
+                        if params.typ == lsp::MessageType::ERROR {
+                            cx.emit(Event::Notification(format!(
+                                "{lsp_name}: {}",
+                                params.message
+                            )));
+                        }
+                        cx.emit(Event::LanguageServerLog(server_id, params.message));
+                    })
+                    .ok();
+                }
+            })
+            .detach();
+
+        language_server
+            .on_request::<lsp::request::ShowDocument, _, _>({
+                let this = this.clone();
+                move |params, mut cx| {
+                    let this = this.clone();
+                    async move {
+                        let abs_path = match params.uri.to_file_path() {
+                            Ok(abs_path) if params.external != Some(true) => abs_path,
+                            _ => {
+                                cx.update(|cx| cx.open_url(params.uri.as_str()))?;
+                                return Ok(lsp::ShowDocumentResult { success: true });
+                            }
+                        };
+                        let (response_channel, response) = smol::channel::bounded(1);
+                        let request = LanguageServerShowDocument {
+                            abs_path,
+                            selection: params.selection.map(|range| {
+                                let range = range_from_lsp(range);
+                                range.start.0..range.end.0
+                            }),
+                            take_focus: params.take_focus.unwrap_or(false),
+                            response_channel,
+                        };
+                        this.update(&mut cx, |_, cx| {
+                            cx.emit(Event::LanguageServerShowDocument(request))
+                        })?;
+                        let success = response.recv().await.unwrap_or(false);
+                        Ok(lsp::ShowDocumentResult { success })
+                    }
+                }
+            })
+            .detach();
+
         let disk_based_diagnostics_progress_token =
             adapter.disk_based_diagnostics_progress_token.clone();
 
@@ -3321,6 +3643,7 @@ impl Project {
             }
         }
 
+        self.pull_diagnostics(server_id, cx);
         cx.notify();
         Ok(())
     }
@@ -3365,6 +3688,7 @@ impl Project {
             }
 
             self.language_server_statuses.remove(&server_id);
+            self.pulled_diagnostics.remove(&server_id);
             cx.notify();
 
             let server_state = self.language_servers.remove(&server_id);
@@ -3727,6 +4051,8 @@ impl Project {
     ) {
         if let Some(status) = self.language_server_statuses.get_mut(&language_server_id) {
             cx.emit(Event::RefreshInlayHints);
+            cx.emit(Event::RefreshSemanticTokens);
+            cx.emit(Event::RefreshCodeLens);
             status.pending_work.remove(&token);
             cx.notify();
         }
@@ -4506,6 +4832,36 @@ impl Project {
         )
     }
 
+    pub fn implementation<T: ToPointUtf16>(
+        &self,
+        buffer: &Model<Buffer>,
+        position: T,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<LocationLink>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Primary,
+            GetImplementation { position },
+            cx,
+        )
+    }
+
+    pub fn declaration<T: ToPointUtf16>(
+        &self,
+        buffer: &Model<Buffer>,
+        position: T,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<LocationLink>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Primary,
+            GetDeclaration { position },
+            cx,
+        )
+    }
+
     pub fn references<T: ToPointUtf16>(
         &self,
         buffer: &Model<Buffer>,
@@ -4770,6 +5126,119 @@ impl Project {
         )
     }
 
+    /// Requests the signatures of the call around `position`. A trigger character is the
+    /// text that was just typed, and a retrigger updates signature help that is already shown.
+    pub fn signature_help<T: ToPointUtf16>(
+        &self,
+        buffer: &Model<Buffer>,
+        position: T,
+        trigger_character: Option<String>,
+        is_retrigger: bool,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Option<SignatureHelp>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Primary,
+            GetSignatureHelp {
+                position,
+                trigger_character,
+                is_retrigger,
+            },
+            cx,
+        )
+    }
+
+    pub fn prepare_call_hierarchy<T: ToPointUtf16>(
+        &self,
+        buffer: &Model<Buffer>,
+        position: T,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<CallHierarchyItem>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Primary,
+            PrepareCallHierarchy { position },
+            cx,
+        )
+    }
+
+    pub fn incoming_calls(
+        &self,
+        item: &CallHierarchyItem,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<CallHierarchyCall>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Primary,
+            GetIncomingCalls {
+                item: item.lsp_item.clone(),
+            },
+            cx,
+        )
+    }
+
+    pub fn outgoing_calls(
+        &self,
+        item: &CallHierarchyItem,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<CallHierarchyCall>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Primary,
+            GetOutgoingCalls {
+                item: item.lsp_item.clone(),
+            },
+            cx,
+        )
+    }
+
+    pub fn prepare_type_hierarchy<T: ToPointUtf16>(
+        &self,
+        buffer: &Model<Buffer>,
+        position: T,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<TypeHierarchyItem>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Primary,
+            PrepareTypeHierarchy { position },
+            cx,
+        )
+    }
+
+    pub fn supertypes(
+        &self,
+        item: &TypeHierarchyItem,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<TypeHierarchyItem>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Primary,
+            GetSupertypes {
+                item: item.lsp_item.clone(),
+            },
+            cx,
+        )
+    }
+
+    pub fn subtypes(
+        &self,
+        item: &TypeHierarchyItem,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<TypeHierarchyItem>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Primary,
+            GetSubtypes {
+                item: item.lsp_item.clone(),
+            },
+            cx,
+        )
+    }
+
     pub fn completions<T: ToOffset + ToPointUtf16>(
         &self,
         buffer: &Model<Buffer>,
@@ -5173,29 +5642,7 @@ impl Project {
                 }
 
                 if let Some(command) = action.lsp_action.command {
-                    this.update(&mut cx, |this, _| {
-                        this.last_workspace_edits_by_language_server
-                            .remove(&lang_server.server_id());
-                    })?;
-
-                    let result = lang_server
-                        .request::<lsp::request::ExecuteCommand>(lsp::ExecuteCommandParams {
-                            command: command.command,
-                            arguments: command.arguments.unwrap_or_default(),
-                            ..Default::default()
-                        })
-                        .await;
-
-                    if let Err(err) = result {
-                        // TODO: LSP ERROR
-                        return Err(err);
-                    }
-
-                    return Ok(this.update(&mut cx, |this, _| {
-                        this.last_workspace_edits_by_language_server
-                            .remove(&lang_server.server_id())
-                            .unwrap_or_default()
-                    })?);
+                    return Self::execute_lsp_command(this, lang_server, command, &mut cx).await;
                 }
 
                 Ok(ProjectTransaction::default())
@@ -5223,6 +5670,83 @@ impl Project {
         }
     }
 
+    /// Runs the command of a code lens, which is resolved when it is fetched.
+    pub fn apply_code_lens(
+        &self,
+        buffer_handle: Model<Buffer>,
+        lens: CodeLens,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<ProjectTransaction>> {
+        if self.is_local() {
+            let buffer = buffer_handle.read(cx);
+            let Some((_, lang_server)) =
+                self.language_server_for_buffer(buffer, lens.server_id, cx)
+            else {
+                return Task::ready(Ok(Default::default()));
+            };
+            let lang_server = lang_server.clone();
+            let Some(command) = lens.lsp_lens.command else {
+                return Task::ready(Ok(Default::default()));
+            };
+            cx.spawn(move |this, mut cx| async move {
+                Self::execute_lsp_command(this, lang_server, command, &mut cx).await
+            })
+        } else if let Some(project_id) = self.remote_id() {
+            let client = self.client.clone();
+            let request = proto::ApplyCodeLens {
+                project_id,
+                buffer_id: buffer_handle.read(cx).remote_id(),
+                lens: Some(GetCodeLens::code_lens_to_proto(lens)),
+            };
+            cx.spawn(move |this, mut cx| async move {
+                let response = client
+                    .request(request)
+                    .await?
+                    .transaction
+                    .ok_or_else(|| anyhow!("missing transaction"))?;
+                this.update(&mut cx, |this, cx| {
+                    this.deserialize_project_transaction(response, true, cx)
+                })?
+                .await
+            })
+        } else {
+            Task::ready(Err(anyhow!("project does not have a remote id")))
+        }
+    }
+
+    /// Runs a command of a code action or a code lens with `workspace/executeCommand`, and
+    /// returns the edits that the server applied while running it.
+    async fn execute_lsp_command(
+        this: WeakModel<Self>,
+        lang_server: Arc<LanguageServer>,
+        command: lsp::Command,
+        cx: &mut AsyncAppContext,
+    ) -> Result<ProjectTransaction> {
+        this.update(cx, |this, _| {
+            this.last_workspace_edits_by_language_server
+                .remove(&lang_server.server_id());
+        })?;
+
+        let result = lang_server
+            .request::<lsp::request::ExecuteCommand>(lsp::ExecuteCommandParams {
+                command: command.command,
+                arguments: command.arguments.unwrap_or_default(),
+                ..Default::default()
+            })
+            .await;
+
+        if let Err(err) = result {
+            // TODO: LSP ERROR
+            return Err(err);
+        }
+
+        Ok(this.update(cx, |this, _| {
+            this.last_workspace_edits_by_language_server
+                .remove(&lang_server.server_id())
+                .unwrap_or_default()
+        })?)
+    }
+
     fn apply_on_type_formatting(
         &self,
         buffer: Model<Buffer>,
@@ -5669,6 +6193,222 @@ impl Project {
         }
     }
 
+    /// Requests the links of a buffer, resolving the targets that the server did not send
+    /// with the links.
+    pub fn document_links(
+        &self,
+        buffer: &Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<DocumentLink>>> {
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Primary,
+            GetDocumentLinks,
+            cx,
+        )
+    }
+
+    /// Returns, for each of the given positions, the ranges a selection can be expanded to,
+    /// from the innermost to the outermost one.
+    pub fn selection_ranges<T: ToPointUtf16>(
+        &self,
+        buffer: &Model<Buffer>,
+        positions: Vec<T>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<Vec<Range<Anchor>>>>> {
+        let positions = positions
+            .into_iter()
+            .map(|position| position.to_point_utf16(buffer.read(cx)))
+            .collect();
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Primary,
+            GetSelectionRanges { positions },
+            cx,
+        )
+    }
+
+    /// Returns the ranges that have the same content as the one at the given position and
+    /// should be edited along with it, such as the names of an opening and a closing tag.
+    pub fn linked_editing_ranges<T: ToPointUtf16>(
+        &self,
+        buffer: &Model<Buffer>,
+        position: T,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<Range<Anchor>>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Primary,
+            GetLinkedEditingRanges { position },
+            cx,
+        )
+    }
+
+    /// Requests the code lenses of a buffer. Lenses that the server sent without a command
+    /// have to be resolved with [`Self::resolve_code_lens`] before they can be shown.
+    pub fn code_lens(
+        &self,
+        buffer: &Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<CodeLens>>> {
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Primary,
+            GetCodeLens,
+            cx,
+        )
+    }
+
+    /// Requests the command of a code lens that the server sent without one.
+    pub fn resolve_code_lens(
+        &self,
+        buffer_handle: Model<Buffer>,
+        lens: CodeLens,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<CodeLens>> {
+        if lens.is_resolved() {
+            return Task::ready(Ok(lens));
+        }
+
+        if self.is_local() {
+            let buffer = buffer_handle.read(cx);
+            let Some((_, lang_server)) =
+                self.language_server_for_buffer(buffer, lens.server_id, cx)
+            else {
+                return Task::ready(Ok(lens));
+            };
+            if !GetCodeLens::can_resolve(lang_server.capabilities()) {
+                return Task::ready(Ok(lens));
+            }
+            let lang_server = lang_server.clone();
+            cx.spawn(move |_, _| async move {
+                let lsp_lens = lang_server
+                    .request::<lsp::request::CodeLensResolve>(lens.lsp_lens.clone())
+                    .await
+                    .context("code lens resolve LSP request")?;
+                Ok(CodeLens { lsp_lens, ..lens })
+            })
+        } else if let Some(project_id) = self.remote_id() {
+            let client = self.client.clone();
+            let request = proto::ResolveCodeLens {
+                project_id,
+                buffer_id: buffer_handle.read(cx).remote_id(),
+                lens: Some(GetCodeLens::code_lens_to_proto(lens.clone())),
+            };
+            cx.spawn(move |_, _| async move {
+                let response = client
+                    .request(request)
+                    .await
+                    .context("code lens resolve proto request")?;
+                match response.lens {
+                    Some(resolved_lens) => GetCodeLens::code_lens_from_proto(resolved_lens),
+                    None => Ok(lens),
+                }
+            })
+        } else {
+            Task::ready(Err(anyhow!("project does not have a remote id")))
+        }
+    }
+
+    /// Requests the semantic tokens of a whole buffer. When `previous` came from the same
+    /// local language server and that server supports it, only the changes since `previous`
+    /// are requested. Resolves to `None` when the buffer was edited while the request was in
+    /// flight, as the tokens describe a version of the buffer that no longer exists.
+    pub fn semantic_tokens(
+        &self,
+        buffer_handle: &Model<Buffer>,
+        previous: Option<&SemanticTokens>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Option<SemanticTokens>>> {
+        let buffer = buffer_handle.read(cx);
+        let snapshot = buffer.text_snapshot();
+        let version = snapshot.version().clone();
+        let delta_request = previous.filter(|_| self.is_local()).and_then(|previous| {
+            let (_, server) = self.primary_language_server_for_buffer(buffer, cx)?;
+            let file = File::from_dyn(buffer.file()).and_then(File::as_local)?;
+            let can_request_delta = previous.result_id.is_some()
+                && previous.server_id == Some(server.server_id())
+                && GetSemanticTokens::supports_delta(server.capabilities());
+            can_request_delta.then(|| (previous.clone(), server.clone(), file.abs_path(cx)))
+        });
+
+        let buffer_handle = buffer_handle.clone();
+        let Some((previous, server, abs_path)) = delta_request else {
+            let request = self.request_lsp(
+                buffer_handle.clone(),
+                LanguageServerToQuery::Primary,
+                GetSemanticTokens,
+                cx,
+            );
+            return cx.spawn(move |_, mut cx| async move {
+                let tokens = request.await?;
+                let is_stale = buffer_handle.update(&mut cx, |buffer, _| {
+                    buffer.version().changed_since(&version)
+                })?;
+                Ok((!is_stale).then_some(tokens))
+            });
+        };
+
+        cx.spawn(move |this, mut cx| async move {
+            let params = lsp::SemanticTokensDeltaParams {
+                text_document: lsp::TextDocumentIdentifier {
+                    uri: lsp::Url::from_file_path(abs_path).unwrap(),
+                },
+                previous_result_id: previous.result_id.unwrap_or_default(),
+                work_done_progress_params: Default::default(),
+                partial_result_params: Default::default(),
+            };
+            let response = server
+                .request::<lsp::request::SemanticTokensFullDeltaRequest>(params)
+                .await
+                .context("semantic tokens delta LSP request")?;
+            let is_stale = buffer_handle.update(&mut cx, |buffer, _| {
+                buffer.version().changed_since(&version)
+            })?;
+            if is_stale {
+                return Ok(None);
+            }
+
+            let tokens = match response {
+                Some(lsp::SemanticTokensFullDeltaResult::Tokens(tokens)) => {
+                    Some((tokens.result_id, tokens.data))
+                }
+                Some(lsp::SemanticTokensFullDeltaResult::TokensDelta(delta)) => {
+                    GetSemanticTokens::apply_edits(previous.data, delta.edits)
+                        .map(|data| (delta.result_id, data))
+                }
+                Some(lsp::SemanticTokensFullDeltaResult::PartialTokensDelta { edits }) => {
+                    GetSemanticTokens::apply_edits(previous.data, edits).map(|data| (None, data))
+                }
+                None => Some((None, Vec::new())),
+            };
+            let Some((result_id, data)) = tokens else {
+                return this
+                    .update(&mut cx, |this, cx| {
+                        this.semantic_tokens(&buffer_handle, None, cx)
+                    })?
+                    .await;
+            };
+            let legend = GetSemanticTokens::options(server.capabilities())
+                .map(|options| options.legend.clone())
+                .context("language server has no semantic tokens legend")?;
+            let tokens = cx
+                .background_executor()
+                .spawn(async move {
+                    GetSemanticTokens::tokens_from_lsp(
+                        result_id,
+                        data,
+                        &legend,
+                        server.server_id(),
+                        &snapshot,
+                    )
+                })
+                .await;
+            Ok(Some(tokens))
+        })
+    }
+
     #[allow(clippy::type_complexity)]
     pub fn search(
         &self,
@@ -6367,6 +7107,8 @@ impl Project {
     }
 
     pub fn remove_worktree(&mut self, id_to_remove: WorktreeId, cx: &mut ModelContext<Self>) {
+        self.remove_worktree_tasks(id_to_remove);
+
         let mut servers_to_remove = HashMap::default();
         let mut servers_to_preserve = HashSet::default();
         for ((worktree_id, server_name), &server_id) in &self.language_server_ids {
@@ -6440,6 +7182,7 @@ impl Project {
                     this.update_local_worktree_buffers(&worktree, changes, cx);
                     this.update_local_worktree_language_servers(&worktree, changes, cx);
                     this.update_local_worktree_settings(&worktree, changes, cx);
+                    this.update_local_worktree_tasks(&worktree, changes, cx);
                     this.update_prettier_settings(&worktree, changes, cx);
                     cx.emit(Event::WorktreeUpdatedEntries(
                         worktree.read(cx).id(),
@@ -6447,7 +7190,12 @@ impl Project {
                     ));
                 }
                 worktree::Event::UpdatedGitRepositories(updated_repos) => {
-                    this.update_local_worktree_buffers_git_repos(worktree, updated_repos, cx)
+                    this.update_local_worktree_buffers_git_repos(
+                        worktree.clone(),
+                        updated_repos,
+                        cx,
+                    );
+                    cx.emit(Event::GitRepositoriesUpdated(worktree.read(cx).id()));
                 }
             })
             .detach();
@@ -6934,6 +7682,46 @@ impl Project {
         Some(ProjectPath { worktree_id, path })
     }
 
+    /// Finds the existing entry for a path written in a buffer. Relative paths are looked up
+    /// next to `relative_to`, and then from the root of each visible worktree.
+    pub fn project_path_for_link(
+        &self,
+        path: &Path,
+        relative_to: Option<&ProjectPath>,
+        cx: &AppContext,
+    ) -> Option<ProjectPath> {
+        let path = match path.strip_prefix("~") {
+            Ok(path_in_home) => HOME.join(path_in_home),
+            Err(_) => path.to_path_buf(),
+        };
+        if path.is_absolute() {
+            let (worktree, relative_path) = self.find_local_worktree(&path, cx)?;
+            let project_path = ProjectPath {
+                worktree_id: worktree.read(cx).id(),
+                path: relative_path.into(),
+            };
+            return self
+                .entry_for_path(&project_path, cx)
+                .is_some()
+                .then_some(project_path);
+        }
+
+        let next_to_buffer = relative_to.and_then(|relative_to| {
+            Some(ProjectPath {
+                worktree_id: relative_to.worktree_id,
+                path: resolve_path(relative_to.path.parent()?, &path).into(),
+            })
+        });
+        let from_roots = self.visible_worktrees(cx).map(|worktree| ProjectPath {
+            worktree_id: worktree.read(cx).id(),
+            path: resolve_path(Path::new(""), &path).into(),
+        });
+        next_to_buffer
+            .into_iter()
+            .chain(from_roots)
+            .find(|project_path| self.entry_for_path(project_path, cx).is_some())
+    }
+
     pub fn absolute_path(&self, project_path: &ProjectPath, cx: &AppContext) -> Option<PathBuf> {
         let workspace_root = self
             .worktree_for_id(project_path.worktree_id, cx)?
@@ -7138,11 +7926,10 @@ impl Project {
                 .ok_or_else(|| anyhow!("worktree not found"))
         })??;
         let worktree_scan_id = worktree.update(&mut cx, |worktree, _| worktree.scan_id())?;
-        let entry = worktree
-            .update(&mut cx, |worktree, cx| {
-                let worktree = worktree.as_local_mut().unwrap();
+        let entry = this
+            .update(&mut cx, |this, cx| {
                 let path = PathBuf::from(envelope.payload.path);
-                worktree.create_entry(path, envelope.payload.is_directory, cx)
+                this.create_local_entry(worktree, path.into(), envelope.payload.is_directory, cx)
             })?
             .await?;
         Ok(proto::ProjectEntryResponse {
@@ -7163,13 +7950,10 @@ impl Project {
                 .ok_or_else(|| anyhow!("worktree not found"))
         })??;
         let worktree_scan_id = worktree.update(&mut cx, |worktree, _| worktree.scan_id())?;
-        let entry = worktree
-            .update(&mut cx, |worktree, cx| {
+        let entry = this
+            .update(&mut cx, |this, cx| {
                 let new_path = PathBuf::from(envelope.payload.new_path);
-                worktree
-                    .as_local_mut()
-                    .unwrap()
-                    .rename_entry(entry_id, new_path, cx)
+                this.rename_local_entry(worktree, entry_id, new_path.into(), cx)
             })?
             .await?;
         Ok(proto::ProjectEntryResponse {
@@ -7220,15 +8004,11 @@ impl Project {
                 .ok_or_else(|| anyhow!("worktree not found"))
         })??;
         let worktree_scan_id = worktree.update(&mut cx, |worktree, _| worktree.scan_id())?;
-        worktree
-            .update(&mut cx, |worktree, cx| {
-                worktree
-                    .as_local_mut()
-                    .unwrap()
-                    .delete_entry(entry_id, cx)
-                    .ok_or_else(|| anyhow!("invalid entry"))
-            })??
-            .await?;
+        this.update(&mut cx, |this, cx| {
+            this.delete_local_entry(worktree, entry_id, cx)
+                .ok_or_else(|| anyhow!("invalid entry"))
+        })??
+        .await?;
         Ok(proto::ProjectEntryResponse {
             entry: None,
             worktree_scan_id: worktree_scan_id as u64,
@@ -7782,6 +8562,64 @@ impl Project {
         })
     }
 
+    async fn handle_apply_code_lens(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::ApplyCodeLens>,
+        _: Arc<Client>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::ApplyCodeLensResponse> {
+        let sender_id = envelope.original_sender_id()?;
+        let lens = GetCodeLens::code_lens_from_proto(
+            envelope
+                .payload
+                .lens
+                .ok_or_else(|| anyhow!("invalid code lens"))?,
+        )?;
+        let apply_code_lens = this.update(&mut cx, |this, cx| {
+            let buffer = this
+                .opened_buffers
+                .get(&envelope.payload.buffer_id)
+                .and_then(|buffer| buffer.upgrade())
+                .ok_or_else(|| anyhow!("unknown buffer id {}", envelope.payload.buffer_id))?;
+            Ok::<_, anyhow::Error>(this.apply_code_lens(buffer, lens, cx))
+        })??;
+
+        let project_transaction = apply_code_lens.await?;
+        let project_transaction = this.update(&mut cx, |this, cx| {
+            this.serialize_project_transaction_for_peer(project_transaction, sender_id, cx)
+        })?;
+        Ok(proto::ApplyCodeLensResponse {
+            transaction: Some(project_transaction),
+        })
+    }
+
+    async fn handle_resolve_code_lens(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::ResolveCodeLens>,
+        _: Arc<Client>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::ResolveCodeLensResponse> {
+        let lens = GetCodeLens::code_lens_from_proto(
+            envelope
+                .payload
+                .lens
+                .ok_or_else(|| anyhow!("invalid code lens"))?,
+        )?;
+        let resolve_code_lens = this.update(&mut cx, |this, cx| {
+            let buffer = this
+                .opened_buffers
+                .get(&envelope.payload.buffer_id)
+                .and_then(|buffer| buffer.upgrade())
+                .ok_or_else(|| anyhow!("unknown buffer id {}", envelope.payload.buffer_id))?;
+            Ok::<_, anyhow::Error>(this.resolve_code_lens(buffer, lens, cx))
+        })??;
+
+        let lens = resolve_code_lens.await?;
+        Ok(proto::ResolveCodeLensResponse {
+            lens: Some(GetCodeLens::code_lens_to_proto(lens)),
+        })
+    }
+
     async fn handle_on_type_formatting(
         this: Model<Self>,
         envelope: TypedEnvelope<proto::OnTypeFormatting>,
@@ -8987,3 +9825,11 @@ fn include_text(server: &lsp::LanguageServer) -> bool {
         })
         .unwrap_or(false)
 }
+
+fn prompt_level(message_type: lsp::MessageType) -> PromptLevel {
+    match message_type {
+        lsp::MessageType::ERROR => PromptLevel::Critical,
+        lsp::MessageType::WARNING => PromptLevel::Warning,
+        _ => PromptLevel::Info,
+    }
+}
@@ -1,4 +1,6 @@
+use crate::tasks::TaskDefinition;
 use collections::HashMap;
+use dap::DebugAdapterBinary;
 use gpui::AppContext;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -34,6 +36,17 @@ pub struct ProjectSettings {
     /// ]
     #[serde(default)]
     pub file_scan_exclusions: Option<Vec<String>>,
+
+    /// Debug adapters that can be started from the debugger panel, keyed by name.
+    /// Default: {}
+    #[serde(default)]
+    pub debuggers: HashMap<Arc<str>, DebugAdapterSettings>,
+
+    /// Tasks that can be spawned in a terminal from any project. Worktrees can
+    /// define additional tasks in `.zed/tasks.json`.
+    /// Default: []
+    #[serde(default)]
+    pub tasks: Vec<TaskDefinition>,
 }
 
 #[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
@@ -61,6 +74,33 @@ pub struct LspSettings {
     pub initialization_options: Option<serde_json::Value>,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct DebugAdapterSettings {
+    /// The adapter executable, its arguments and, for adapters listening on
+    /// a socket, the TCP connection to use.
+    #[serde(flatten)]
+    pub binary: DebugAdapterBinary,
+    /// Whether to launch a new debuggee or attach to a running one.
+    ///
+    /// Default: launch
+    #[serde(default)]
+    pub request: DebugRequestKind,
+    /// Adapter-specific arguments for the launch or attach request,
+    /// such as `program` and `args`.
+    #[serde(default)]
+    pub configuration: serde_json::Value,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DebugRequestKind {
+    /// Start the debuggee through the adapter.
+    #[default]
+    Launch,
+    /// Attach to an already running debuggee.
+    Attach,
+}
+
 impl Settings for ProjectSettings {
     const KEY: Option<&'static str> = None;
 
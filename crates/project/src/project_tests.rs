@@ -1,10 +1,10 @@
-use crate::{Event, *};
+use crate::{tasks::TaskContext, Event, *};
 use fs::FakeFs;
 use futures::{future, StreamExt};
 use gpui::AppContext;
 use language::{
     language_settings::{AllLanguageSettings, LanguageSettingsContent},
-    tree_sitter_rust, tree_sitter_typescript, Diagnostic, FakeLspAdapter, LanguageConfig,
+    tree_sitter_rust, tree_sitter_typescript, Buffer, Diagnostic, FakeLspAdapter, LanguageConfig,
     LineEnding, OffsetRangeExt, Point, ToPoint,
 };
 use lsp::Url;
@@ -142,6 +142,91 @@ async fn test_managing_project_specific_settings(cx: &mut gpui::TestAppContext)
     });
 }
 
+#[gpui::test]
+async fn test_managing_project_specific_tasks(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/the-root",
+        json!({
+            ".zed": {
+                "tasks.json": r#"[
+                    // Tasks may contain comments.
+                    {
+                        "label": "test file",
+                        "command": "cargo",
+                        "args": ["test", "--", "$FILE:$ROW"],
+                        "cwd": "crates"
+                    }
+                ]"#
+            },
+            "a.rs": "fn a() {}",
+            "nested": {
+                ".zed": {
+                    "tasks.json": r#"[
+                        {
+                            "label": "echo selection",
+                            "command": "echo",
+                            "args": ["$SELECTION"],
+                            "cwd": "."
+                        }
+                    ]"#
+                }
+            }
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/the-root".as_ref()], cx).await;
+    cx.executor().run_until_parked();
+
+    let tasks = project.read_with(cx, |project, cx| project.task_definitions(cx));
+    assert_eq!(
+        tasks
+            .iter()
+            .map(|task| task.label.as_str())
+            .collect::<Vec<_>>(),
+        ["test file", "echo selection"]
+    );
+
+    let spawn = tasks[0].resolve(&TaskContext {
+        file: Some("/the-root/a.rs".into()),
+        row: Some(3),
+        selection: None,
+        symbol: None,
+        worktree_root: Some("/the-root".into()),
+    });
+    assert_eq!(spawn.command, "cargo");
+    assert_eq!(spawn.args, ["test", "--", "/the-root/a.rs:3"]);
+    assert_eq!(spawn.cwd, Some("/the-root/crates".into()));
+
+    // Substituted values are not expanded again, and a relative `cwd` is resolved
+    // against the directory the task was defined in.
+    let spawn = tasks[1].resolve(&TaskContext {
+        file: Some("/the-root/a.rs".into()),
+        row: Some(3),
+        selection: Some("$FILE:$ROW".into()),
+        symbol: None,
+        worktree_root: Some("/the-root".into()),
+    });
+    assert_eq!(spawn.args, ["$FILE:$ROW"]);
+    assert_eq!(spawn.cwd, Some("/the-root/nested".into()));
+
+    fs.remove_file("/the-root/.zed/tasks.json".as_ref(), Default::default())
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    assert_eq!(
+        project
+            .read_with(cx, |project, cx| project.task_definitions(cx))
+            .iter()
+            .map(|task| task.label.as_str())
+            .collect::<Vec<_>>(),
+        ["echo selection"]
+    );
+}
+
 #[gpui::test]
 async fn test_managing_language_servers(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -2344,24 +2429,21 @@ async fn test_definition(cx: &mut gpui::TestAppContext) {
 }
 
 #[gpui::test]
-async fn test_completions_without_edit_ranges(cx: &mut gpui::TestAppContext) {
+async fn test_implementation(cx: &mut gpui::TestAppContext) {
     init_test(cx);
 
     let mut language = Language::new(
         LanguageConfig {
-            name: "TypeScript".into(),
-            path_suffixes: vec!["ts".to_string()],
+            name: "Rust".into(),
+            path_suffixes: vec!["rs".to_string()],
             ..Default::default()
         },
-        Some(tree_sitter_typescript::language_typescript()),
+        Some(tree_sitter_rust::language()),
     );
-    let mut fake_language_servers = language
+    let mut fake_servers = language
         .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
             capabilities: lsp::ServerCapabilities {
-                completion_provider: Some(lsp::CompletionOptions {
-                    trigger_characters: Some(vec![":".to_string()]),
-                    ..Default::default()
-                }),
+                implementation_provider: Some(lsp::ImplementationProviderCapability::Simple(true)),
                 ..Default::default()
             },
             ..Default::default()
@@ -2372,93 +2454,104 @@ async fn test_completions_without_edit_ranges(cx: &mut gpui::TestAppContext) {
     fs.insert_tree(
         "/dir",
         json!({
-            "a.ts": "",
+            "a.rs": "trait T { fn f(); }",
+            "b.rs": "impl T for A { fn f() {} }\nimpl T for B { fn f() {} }",
         }),
     )
     .await;
 
     let project = Project::test(fs, ["/dir".as_ref()], cx).await;
     project.update(cx, |project, _| project.languages.add(Arc::new(language)));
+
     let buffer = project
-        .update(cx, |p, cx| p.open_local_buffer("/dir/a.ts", cx))
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
         .await
         .unwrap();
 
-    let fake_server = fake_language_servers.next().await.unwrap();
+    let fake_server = fake_servers.next().await.unwrap();
+    fake_server.handle_request::<lsp::request::GotoImplementation, _, _>(|params, _| async move {
+        let params = params.text_document_position_params;
+        assert_eq!(
+            params.text_document.uri.to_file_path().unwrap(),
+            Path::new("/dir/a.rs"),
+        );
+        assert_eq!(params.position, lsp::Position::new(0, 13));
 
-    let text = "let a = b.fqn";
-    buffer.update(cx, |buffer, cx| buffer.set_text(text, cx));
-    let completions = project.update(cx, |project, cx| {
-        project.completions(&buffer, text.len(), cx)
+        let uri = lsp::Url::from_file_path("/dir/b.rs").unwrap();
+        Ok(Some(lsp::GotoDefinitionResponse::Array(vec![
+            lsp::Location::new(
+                uri.clone(),
+                lsp::Range::new(lsp::Position::new(0, 18), lsp::Position::new(0, 19)),
+            ),
+            lsp::Location::new(
+                uri,
+                lsp::Range::new(lsp::Position::new(1, 18), lsp::Position::new(1, 19)),
+            ),
+        ])))
     });
 
-    fake_server
-        .handle_request::<lsp::request::Completion, _, _>(|_, _| async move {
-            Ok(Some(lsp::CompletionResponse::Array(vec![
-                lsp::CompletionItem {
-                    label: "fullyQualifiedName?".into(),
-                    insert_text: Some("fullyQualifiedName".into()),
-                    ..Default::default()
-                },
-            ])))
-        })
-        .next()
-        .await;
-    let completions = completions.await.unwrap();
-    let snapshot = buffer.update(cx, |buffer, _| buffer.snapshot());
-    assert_eq!(completions.len(), 1);
-    assert_eq!(completions[0].new_text, "fullyQualifiedName");
-    assert_eq!(
-        completions[0].old_range.to_offset(&snapshot),
-        text.len() - 3..text.len()
-    );
+    let implementations = project
+        .update(cx, |project, cx| project.implementation(&buffer, 13, cx))
+        .await
+        .unwrap();
 
-    let text = "let a = \"atoms/cmp\"";
-    buffer.update(cx, |buffer, cx| buffer.set_text(text, cx));
-    let completions = project.update(cx, |project, cx| {
-        project.completions(&buffer, text.len() - 1, cx)
+    cx.update(|cx| {
+        let ranges = implementations
+            .iter()
+            .map(|implementation| {
+                let target_buffer = implementation.target.buffer.read(cx);
+                assert_eq!(
+                    target_buffer
+                        .file()
+                        .unwrap()
+                        .as_local()
+                        .unwrap()
+                        .abs_path(cx),
+                    Path::new("/dir/b.rs"),
+                );
+                implementation.target.range.to_point(target_buffer)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ranges,
+            [
+                Point::new(0, 18)..Point::new(0, 19),
+                Point::new(1, 18)..Point::new(1, 19),
+            ]
+        );
     });
-
-    fake_server
-        .handle_request::<lsp::request::Completion, _, _>(|_, _| async move {
-            Ok(Some(lsp::CompletionResponse::Array(vec![
-                lsp::CompletionItem {
-                    label: "component".into(),
-                    ..Default::default()
-                },
-            ])))
-        })
-        .next()
-        .await;
-    let completions = completions.await.unwrap();
-    let snapshot = buffer.update(cx, |buffer, _| buffer.snapshot());
-    assert_eq!(completions.len(), 1);
-    assert_eq!(completions[0].new_text, "component");
-    assert_eq!(
-        completions[0].old_range.to_offset(&snapshot),
-        text.len() - 4..text.len() - 1
-    );
 }
 
 #[gpui::test]
-async fn test_completions_with_carriage_returns(cx: &mut gpui::TestAppContext) {
+async fn test_semantic_tokens(cx: &mut gpui::TestAppContext) {
     init_test(cx);
 
     let mut language = Language::new(
         LanguageConfig {
-            name: "TypeScript".into(),
-            path_suffixes: vec!["ts".to_string()],
+            name: "Rust".into(),
+            path_suffixes: vec!["rs".to_string()],
             ..Default::default()
         },
-        Some(tree_sitter_typescript::language_typescript()),
+        Some(tree_sitter_rust::language()),
     );
-    let mut fake_language_servers = language
+    let mut fake_servers = language
         .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
             capabilities: lsp::ServerCapabilities {
-                completion_provider: Some(lsp::CompletionOptions {
-                    trigger_characters: Some(vec![":".to_string()]),
-                    ..Default::default()
-                }),
+                semantic_tokens_provider: Some(
+                    lsp::SemanticTokensServerCapabilities::SemanticTokensOptions(
+                        lsp::SemanticTokensOptions {
+                            legend: lsp::SemanticTokensLegend {
+                                token_types: vec![
+                                    lsp::SemanticTokenType::VARIABLE,
+                                    lsp::SemanticTokenType::FUNCTION,
+                                ],
+                                token_modifiers: vec![lsp::SemanticTokenModifier::DECLARATION],
+                            },
+                            full: Some(lsp::SemanticTokensFullOptions::Delta { delta: Some(true) }),
+                            ..Default::default()
+                        },
+                    ),
+                ),
                 ..Default::default()
             },
             ..Default::default()
@@ -2466,81 +2559,836 @@ async fn test_completions_with_carriage_returns(cx: &mut gpui::TestAppContext) {
         .await;
 
     let fs = FakeFs::new(cx.executor());
-    fs.insert_tree(
-        "/dir",
-        json!({
-            "a.ts": "",
-        }),
-    )
-    .await;
+    fs.insert_tree("/dir", json!({ "a.rs": "fn main() { let x = 1; }" }))
+        .await;
 
     let project = Project::test(fs, ["/dir".as_ref()], cx).await;
     project.update(cx, |project, _| project.languages.add(Arc::new(language)));
+
     let buffer = project
-        .update(cx, |p, cx| p.open_local_buffer("/dir/a.ts", cx))
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
         .await
         .unwrap();
 
-    let fake_server = fake_language_servers.next().await.unwrap();
+    let token = |delta_start, length, token_type, token_modifiers_bitset| lsp::SemanticToken {
+        delta_line: 0,
+        delta_start,
+        length,
+        token_type,
+        token_modifiers_bitset,
+    };
+    let fake_server = fake_servers.next().await.unwrap();
+    fake_server.handle_request::<lsp::request::SemanticTokensFullRequest, _, _>(
+        move |_, _| async move {
+            Ok(Some(lsp::SemanticTokensResult::Tokens(
+                lsp::SemanticTokens {
+                    result_id: Some("1".into()),
+                    data: vec![token(3, 4, 1, 0), token(13, 1, 0, 1)],
+                },
+            )))
+        },
+    );
+    fake_server.handle_request::<lsp::request::SemanticTokensFullDeltaRequest, _, _>(
+        move |params, _| async move {
+            // The second delta doesn't line up with whole tokens.
+            let edit = match params.previous_result_id.as_str() {
+                "1" => lsp::SemanticTokensEdit {
+                    start: 5,
+                    delete_count: 5,
+                    data: Some(vec![token(13, 1, 0, 0)]),
+                },
+                "2" => lsp::SemanticTokensEdit {
+                    start: 9,
+                    delete_count: 1,
+                    data: None,
+                },
+                previous_result_id => panic!("unexpected result id {previous_result_id:?}"),
+            };
+            Ok(Some(lsp::SemanticTokensFullDeltaResult::TokensDelta(
+                lsp::SemanticTokensDelta {
+                    result_id: Some("2".into()),
+                    edits: vec![edit],
+                },
+            )))
+        },
+    );
 
-    let text = "let a = b.fqn";
-    buffer.update(cx, |buffer, cx| buffer.set_text(text, cx));
-    let completions = project.update(cx, |project, cx| {
-        project.completions(&buffer, text.len(), cx)
+    let tokens = project
+        .update(cx, |project, cx| project.semantic_tokens(&buffer, None, cx))
+        .await
+        .unwrap()
+        .unwrap();
+    buffer.update(cx, |buffer, _| {
+        assert_eq!(
+            tokens
+                .tokens
+                .iter()
+                .map(|token| (
+                    token.range.to_point(buffer),
+                    token.token_type.as_ref(),
+                    token
+                        .modifiers
+                        .iter()
+                        .map(|modifier| modifier.as_ref())
+                        .collect::<Vec<_>>(),
+                ))
+                .collect::<Vec<_>>(),
+            [
+                (Point::new(0, 3)..Point::new(0, 7), "function", vec![]),
+                (
+                    Point::new(0, 16)..Point::new(0, 17),
+                    "variable",
+                    vec!["declaration"]
+                ),
+            ]
+        );
     });
 
-    fake_server
-        .handle_request::<lsp::request::Completion, _, _>(|_, _| async move {
-            Ok(Some(lsp::CompletionResponse::Array(vec![
-                lsp::CompletionItem {
-                    label: "fullyQualifiedName?".into(),
-                    insert_text: Some("fully\rQualified\r\nName".into()),
-                    ..Default::default()
-                },
-            ])))
+    let tokens = project
+        .update(cx, |project, cx| {
+            project.semantic_tokens(&buffer, Some(&tokens), cx)
         })
-        .next()
-        .await;
-    let completions = completions.await.unwrap();
-    assert_eq!(completions.len(), 1);
-    assert_eq!(completions[0].new_text, "fully\nQualified\nName");
+        .await
+        .unwrap()
+        .unwrap();
+    buffer.update(cx, |buffer, _| {
+        assert_eq!(
+            tokens
+                .tokens
+                .iter()
+                .map(|token| (token.range.to_point(buffer), token.modifiers.len()))
+                .collect::<Vec<_>>(),
+            [
+                (Point::new(0, 3)..Point::new(0, 7), 0),
+                (Point::new(0, 16)..Point::new(0, 17), 0),
+            ]
+        );
+    });
+
+    // A delta that can't be applied falls back to requesting the whole tokens.
+    let tokens = project
+        .update(cx, |project, cx| {
+            project.semantic_tokens(&buffer, Some(&tokens), cx)
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(tokens.result_id.as_deref(), Some("1"));
+    buffer.update(cx, |buffer, _| {
+        assert_eq!(
+            tokens
+                .tokens
+                .iter()
+                .map(|token| (token.range.to_point(buffer), token.modifiers.len()))
+                .collect::<Vec<_>>(),
+            [
+                (Point::new(0, 3)..Point::new(0, 7), 0),
+                (Point::new(0, 16)..Point::new(0, 17), 1),
+            ]
+        );
+    });
 }
 
-#[gpui::test(iterations = 10)]
-async fn test_apply_code_actions_with_commands(cx: &mut gpui::TestAppContext) {
+#[gpui::test]
+async fn test_code_lens(cx: &mut gpui::TestAppContext) {
     init_test(cx);
 
     let mut language = Language::new(
         LanguageConfig {
-            name: "TypeScript".into(),
-            path_suffixes: vec!["ts".to_string()],
+            name: "Rust".into(),
+            path_suffixes: vec!["rs".to_string()],
             ..Default::default()
         },
-        None,
+        Some(tree_sitter_rust::language()),
     );
-    let mut fake_language_servers = language.set_fake_lsp_adapter(Default::default()).await;
+    let mut fake_servers = language
+        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                code_lens_provider: Some(lsp::CodeLensOptions {
+                    resolve_provider: Some(true),
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        }))
+        .await;
 
     let fs = FakeFs::new(cx.executor());
     fs.insert_tree(
         "/dir",
-        json!({
-            "a.ts": "a",
-        }),
+        json!({ "a.rs": "fn main() {}\n\n#[test]\nfn test_main() {}" }),
     )
     .await;
 
     let project = Project::test(fs, ["/dir".as_ref()], cx).await;
     project.update(cx, |project, _| project.languages.add(Arc::new(language)));
+
     let buffer = project
-        .update(cx, |p, cx| p.open_local_buffer("/dir/a.ts", cx))
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
         .await
         .unwrap();
 
-    let fake_server = fake_language_servers.next().await.unwrap();
+    let fake_server = fake_servers.next().await.unwrap();
+    fake_server.handle_request::<lsp::request::CodeLensRequest, _, _>(|_, _| async move {
+        Ok(Some(vec![
+            lsp::CodeLens {
+                range: lsp::Range::new(lsp::Position::new(3, 3), lsp::Position::new(3, 12)),
+                command: Some(lsp::Command {
+                    title: "Run Test".into(),
+                    command: "run".into(),
+                    arguments: None,
+                }),
+                data: None,
+            },
+            lsp::CodeLens {
+                range: lsp::Range::new(lsp::Position::new(0, 3), lsp::Position::new(0, 7)),
+                command: None,
+                data: Some(json!("references")),
+            },
+        ]))
+    });
+    fake_server.handle_request::<lsp::request::CodeLensResolve, _, _>(|lens, _| async move {
+        assert_eq!(lens.data, Some(json!("references")));
+        Ok(lsp::CodeLens {
+            command: Some(lsp::Command {
+                title: "2 references".into(),
+                command: "references".into(),
+                arguments: None,
+            }),
+            ..lens
+        })
+    });
 
-    // Language server returns code actions that contain commands, and not edits.
-    let actions = project.update(cx, |project, cx| project.code_actions(&buffer, 0..0, cx));
-    fake_server
+    let lenses = project
+        .update(cx, |project, cx| project.code_lens(&buffer, cx))
+        .await
+        .unwrap();
+    buffer.update(cx, |buffer, _| {
+        assert_eq!(
+            lenses
+                .iter()
+                .map(|lens| (lens.range.to_point(buffer), lens.title()))
+                .collect::<Vec<_>>(),
+            [
+                (Point::new(0, 3)..Point::new(0, 7), None),
+                (Point::new(3, 3)..Point::new(3, 12), Some("Run Test")),
+            ]
+        );
+    });
+
+    let resolved_lens = project
+        .update(cx, |project, cx| {
+            project.resolve_code_lens(buffer.clone(), lenses[0].clone(), cx)
+        })
+        .await
+        .unwrap();
+    buffer.update(cx, |buffer, _| {
+        assert_eq!(
+            resolved_lens.range.to_point(buffer),
+            Point::new(0, 3)..Point::new(0, 7)
+        );
+    });
+    assert_eq!(resolved_lens.title(), Some("2 references"));
+}
+
+#[gpui::test]
+async fn test_selection_ranges(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let mut language = Language::new(
+        LanguageConfig {
+            name: "Rust".into(),
+            path_suffixes: vec!["rs".to_string()],
+            ..Default::default()
+        },
+        Some(tree_sitter_rust::language()),
+    );
+    let mut fake_servers = language
+        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                selection_range_provider: Some(lsp::SelectionRangeProviderCapability::Simple(true)),
+                ..Default::default()
+            },
+            ..Default::default()
+        }))
+        .await;
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({ "a.rs": "fn main() { let a = 1; }" }))
+        .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    project.update(cx, |project, _| project.languages.add(Arc::new(language)));
+
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    fake_server.handle_request::<lsp::request::SelectionRangeRequest, _, _>(
+        |params, _| async move {
+            assert_eq!(params.positions, [lsp::Position::new(0, 16)]);
+            Ok(Some(vec![lsp::SelectionRange {
+                range: lsp::Range::new(lsp::Position::new(0, 16), lsp::Position::new(0, 17)),
+                parent: Some(Box::new(lsp::SelectionRange {
+                    range: lsp::Range::new(lsp::Position::new(0, 12), lsp::Position::new(0, 22)),
+                    parent: Some(Box::new(lsp::SelectionRange {
+                        range: lsp::Range::new(lsp::Position::new(0, 0), lsp::Position::new(0, 24)),
+                        parent: None,
+                    })),
+                })),
+            }]))
+        },
+    );
+
+    let selection_ranges = project
+        .update(cx, |project, cx| {
+            project.selection_ranges(&buffer, vec![Point::new(0, 16)], cx)
+        })
+        .await
+        .unwrap();
+    buffer.update(cx, |buffer, _| {
+        assert_eq!(
+            selection_ranges
+                .iter()
+                .map(|ranges| ranges
+                    .iter()
+                    .map(|range| range.to_point(buffer))
+                    .collect::<Vec<_>>())
+                .collect::<Vec<_>>(),
+            [vec![
+                Point::new(0, 16)..Point::new(0, 17),
+                Point::new(0, 12)..Point::new(0, 22),
+                Point::new(0, 0)..Point::new(0, 24),
+            ]]
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_pull_diagnostics(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let mut language = Language::new(
+        LanguageConfig {
+            name: "Rust".into(),
+            path_suffixes: vec!["rs".to_string()],
+            ..Default::default()
+        },
+        Some(tree_sitter_rust::language()),
+    );
+    let mut fake_servers = language
+        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                diagnostic_provider: Some(lsp::DiagnosticServerCapabilities::Options(
+                    lsp::DiagnosticOptions::default(),
+                )),
+                ..Default::default()
+            },
+            ..Default::default()
+        }))
+        .await;
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({ "a.rs": "fn main() { A }" }))
+        .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    project.update(cx, |project, _| project.languages.add(Arc::new(language)));
+
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+
+    // The server reports the diagnostics on the first pull, and that they didn't change on
+    // the following ones.
+    let pull_count = Arc::new(AtomicUsize::new(0));
+    let fake_server = fake_servers.next().await.unwrap();
+    fake_server.handle_request::<lsp::request::DocumentDiagnosticRequest, _, _>({
+        let pull_count = pull_count.clone();
+        move |params, _| {
+            let pull_count = pull_count.fetch_add(1, SeqCst);
+            async move {
+                assert_eq!(
+                    params.text_document.uri,
+                    Url::from_file_path("/dir/a.rs").unwrap()
+                );
+                if pull_count == 0 {
+                    Ok(lsp::DocumentDiagnosticReportResult::Report(
+                        lsp::DocumentDiagnosticReport::Full(
+                            lsp::RelatedFullDocumentDiagnosticReport {
+                                related_documents: None,
+                                full_document_diagnostic_report:
+                                    lsp::FullDocumentDiagnosticReport {
+                                        result_id: Some("1".into()),
+                                        items: vec![lsp::Diagnostic {
+                                            range: lsp::Range::new(
+                                                lsp::Position::new(0, 12),
+                                                lsp::Position::new(0, 13),
+                                            ),
+                                            severity: Some(lsp::DiagnosticSeverity::ERROR),
+                                            message: "undefined variable 'A'".to_string(),
+                                            ..Default::default()
+                                        }],
+                                    },
+                            },
+                        ),
+                    ))
+                } else {
+                    assert_eq!(params.previous_result_id.as_deref(), Some("1"));
+                    Ok(lsp::DocumentDiagnosticReportResult::Report(
+                        lsp::DocumentDiagnosticReport::Unchanged(
+                            lsp::RelatedUnchangedDocumentDiagnosticReport {
+                                related_documents: None,
+                                unchanged_document_diagnostic_report:
+                                    lsp::UnchangedDocumentDiagnosticReport {
+                                        result_id: "1".into(),
+                                    },
+                            },
+                        ),
+                    ))
+                }
+            }
+        }
+    });
+
+    let assert_diagnostics = |cx: &mut gpui::TestAppContext| {
+        buffer.update(cx, |buffer, _| {
+            let diagnostics = buffer
+                .snapshot()
+                .diagnostics_in_range::<_, Point>(0..buffer.len(), false)
+                .collect::<Vec<_>>();
+            assert_eq!(
+                diagnostics,
+                &[DiagnosticEntry {
+                    range: Point::new(0, 12)..Point::new(0, 13),
+                    diagnostic: Diagnostic {
+                        severity: lsp::DiagnosticSeverity::ERROR,
+                        message: "undefined variable 'A'".to_string(),
+                        group_id: 0,
+                        is_primary: true,
+                        ..Default::default()
+                    }
+                }]
+            );
+        });
+    };
+
+    // Edits are debounced into a single pull.
+    buffer.update(cx, |buffer, cx| {
+        buffer.edit([(15..15, "\n")], None, cx);
+        buffer.edit([(16..16, "\n")], None, cx);
+    });
+    cx.executor().advance_clock(Duration::from_millis(200));
+    cx.executor().run_until_parked();
+    let first_pull_count = pull_count.load(SeqCst);
+    assert!(first_pull_count > 0);
+    assert_diagnostics(cx);
+
+    // An unchanged report keeps the previous diagnostics.
+    buffer.update(cx, |buffer, cx| buffer.edit([(17..17, "\n")], None, cx));
+    cx.executor().advance_clock(Duration::from_millis(200));
+    cx.executor().run_until_parked();
+    assert_eq!(pull_count.load(SeqCst), first_pull_count + 1);
+    assert_diagnostics(cx);
+}
+
+#[gpui::test]
+async fn test_language_server_prompt(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let mut language = Language::new(
+        LanguageConfig {
+            name: "Rust".into(),
+            path_suffixes: vec!["rs".to_string()],
+            ..Default::default()
+        },
+        Some(tree_sitter_rust::language()),
+    );
+    let mut fake_servers = language
+        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+            name: "the-language-server",
+            ..Default::default()
+        }))
+        .await;
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({ "a.rs": "" })).await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    project.update(cx, |project, _| project.languages.add(Arc::new(language)));
+
+    let _buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+
+    let mut events = cx.events(&project);
+    let fake_server = fake_servers.next().await.unwrap();
+    let actions = vec![
+        lsp::MessageActionItem {
+            title: "Not now".into(),
+            properties: Default::default(),
+        },
+        lsp::MessageActionItem {
+            title: "Reload".into(),
+            properties: Default::default(),
+        },
+    ];
+    let response = cx.executor().spawn({
+        let actions = actions.clone();
+        async move {
+            fake_server
+                .request::<lsp::request::ShowMessageRequest>(lsp::ShowMessageRequestParams {
+                    typ: lsp::MessageType::INFO,
+                    message: "Reload the workspace?".into(),
+                    actions: Some(actions),
+                })
+                .await
+        }
+    });
+
+    let request = loop {
+        if let Event::LanguageServerPrompt(request) = events.next().await.unwrap() {
+            break request;
+        }
+    };
+    assert_eq!(request.message, "Reload the workspace?");
+    assert_eq!(request.lsp_name, "the-language-server");
+    assert_eq!(request.actions, actions);
+
+    request.respond(1).await.unwrap();
+    assert_eq!(response.await.unwrap(), Some(actions[1].clone()));
+}
+
+#[gpui::test]
+async fn test_call_hierarchy(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let mut language = Language::new(
+        LanguageConfig {
+            name: "Rust".into(),
+            path_suffixes: vec!["rs".to_string()],
+            ..Default::default()
+        },
+        Some(tree_sitter_rust::language()),
+    );
+    let mut fake_servers = language
+        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                call_hierarchy_provider: Some(lsp::CallHierarchyServerCapability::Simple(true)),
+                ..Default::default()
+            },
+            ..Default::default()
+        }))
+        .await;
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.rs": "fn a() { b() }",
+            "b.rs": "fn b() {}",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    project.update(cx, |project, _| project.languages.add(Arc::new(language)));
+
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/b.rs", cx))
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    let lsp_item = |name: &str, path: &str, range: lsp::Range| lsp::CallHierarchyItem {
+        name: name.to_string(),
+        kind: lsp::SymbolKind::FUNCTION,
+        tags: None,
+        detail: None,
+        uri: lsp::Url::from_file_path(path).unwrap(),
+        range,
+        selection_range: range,
+        data: None,
+    };
+    let b_item = lsp_item(
+        "b",
+        "/dir/b.rs",
+        lsp::Range::new(lsp::Position::new(0, 3), lsp::Position::new(0, 4)),
+    );
+    let a_item = lsp_item(
+        "a",
+        "/dir/a.rs",
+        lsp::Range::new(lsp::Position::new(0, 3), lsp::Position::new(0, 4)),
+    );
+
+    fake_server.handle_request::<lsp::request::CallHierarchyPrepare, _, _>({
+        let b_item = b_item.clone();
+        move |params, _| {
+            let b_item = b_item.clone();
+            async move {
+                assert_eq!(
+                    params.text_document_position_params.position,
+                    lsp::Position::new(0, 3)
+                );
+                Ok(Some(vec![b_item]))
+            }
+        }
+    });
+    fake_server.handle_request::<lsp::request::CallHierarchyIncomingCalls, _, _>(
+        move |params, _| {
+            let a_item = a_item.clone();
+            async move {
+                assert_eq!(params.item.name, "b");
+                Ok(Some(vec![lsp::CallHierarchyIncomingCall {
+                    from: a_item,
+                    from_ranges: vec![lsp::Range::new(
+                        lsp::Position::new(0, 9),
+                        lsp::Position::new(0, 10),
+                    )],
+                }]))
+            }
+        },
+    );
+
+    let items = project
+        .update(cx, |project, cx| {
+            project.prepare_call_hierarchy(&buffer, Point::new(0, 3), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].name, "b");
+
+    let calls = project
+        .update(cx, |project, cx| project.incoming_calls(&items[0], cx))
+        .await
+        .unwrap();
+    assert_eq!(calls.len(), 1);
+    cx.update(|cx| {
+        let call = &calls[0];
+        assert_eq!(call.item.name, "a");
+        let caller_buffer = call.item.location.buffer.read(cx);
+        assert_eq!(
+            caller_buffer.file().unwrap().path().as_ref(),
+            Path::new("a.rs")
+        );
+        let call_sites = call
+            .call_sites
+            .iter()
+            .map(|call_site| {
+                call_site
+                    .buffer
+                    .read(cx)
+                    .text_for_range(call_site.range.clone())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(call_sites, ["b"]);
+    });
+}
+
+#[gpui::test]
+async fn test_completions_without_edit_ranges(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let mut language = Language::new(
+        LanguageConfig {
+            name: "TypeScript".into(),
+            path_suffixes: vec!["ts".to_string()],
+            ..Default::default()
+        },
+        Some(tree_sitter_typescript::language_typescript()),
+    );
+    let mut fake_language_servers = language
+        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                completion_provider: Some(lsp::CompletionOptions {
+                    trigger_characters: Some(vec![":".to_string()]),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        }))
+        .await;
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.ts": "",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    project.update(cx, |project, _| project.languages.add(Arc::new(language)));
+    let buffer = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/a.ts", cx))
+        .await
+        .unwrap();
+
+    let fake_server = fake_language_servers.next().await.unwrap();
+
+    let text = "let a = b.fqn";
+    buffer.update(cx, |buffer, cx| buffer.set_text(text, cx));
+    let completions = project.update(cx, |project, cx| {
+        project.completions(&buffer, text.len(), cx)
+    });
+
+    fake_server
+        .handle_request::<lsp::request::Completion, _, _>(|_, _| async move {
+            Ok(Some(lsp::CompletionResponse::Array(vec![
+                lsp::CompletionItem {
+                    label: "fullyQualifiedName?".into(),
+                    insert_text: Some("fullyQualifiedName".into()),
+                    ..Default::default()
+                },
+            ])))
+        })
+        .next()
+        .await;
+    let completions = completions.await.unwrap();
+    let snapshot = buffer.update(cx, |buffer, _| buffer.snapshot());
+    assert_eq!(completions.len(), 1);
+    assert_eq!(completions[0].new_text, "fullyQualifiedName");
+    assert_eq!(
+        completions[0].old_range.to_offset(&snapshot),
+        text.len() - 3..text.len()
+    );
+
+    let text = "let a = \"atoms/cmp\"";
+    buffer.update(cx, |buffer, cx| buffer.set_text(text, cx));
+    let completions = project.update(cx, |project, cx| {
+        project.completions(&buffer, text.len() - 1, cx)
+    });
+
+    fake_server
+        .handle_request::<lsp::request::Completion, _, _>(|_, _| async move {
+            Ok(Some(lsp::CompletionResponse::Array(vec![
+                lsp::CompletionItem {
+                    label: "component".into(),
+                    ..Default::default()
+                },
+            ])))
+        })
+        .next()
+        .await;
+    let completions = completions.await.unwrap();
+    let snapshot = buffer.update(cx, |buffer, _| buffer.snapshot());
+    assert_eq!(completions.len(), 1);
+    assert_eq!(completions[0].new_text, "component");
+    assert_eq!(
+        completions[0].old_range.to_offset(&snapshot),
+        text.len() - 4..text.len() - 1
+    );
+}
+
+#[gpui::test]
+async fn test_completions_with_carriage_returns(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let mut language = Language::new(
+        LanguageConfig {
+            name: "TypeScript".into(),
+            path_suffixes: vec!["ts".to_string()],
+            ..Default::default()
+        },
+        Some(tree_sitter_typescript::language_typescript()),
+    );
+    let mut fake_language_servers = language
+        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                completion_provider: Some(lsp::CompletionOptions {
+                    trigger_characters: Some(vec![":".to_string()]),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        }))
+        .await;
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.ts": "",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    project.update(cx, |project, _| project.languages.add(Arc::new(language)));
+    let buffer = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/a.ts", cx))
+        .await
+        .unwrap();
+
+    let fake_server = fake_language_servers.next().await.unwrap();
+
+    let text = "let a = b.fqn";
+    buffer.update(cx, |buffer, cx| buffer.set_text(text, cx));
+    let completions = project.update(cx, |project, cx| {
+        project.completions(&buffer, text.len(), cx)
+    });
+
+    fake_server
+        .handle_request::<lsp::request::Completion, _, _>(|_, _| async move {
+            Ok(Some(lsp::CompletionResponse::Array(vec![
+                lsp::CompletionItem {
+                    label: "fullyQualifiedName?".into(),
+                    insert_text: Some("fully\rQualified\r\nName".into()),
+                    ..Default::default()
+                },
+            ])))
+        })
+        .next()
+        .await;
+    let completions = completions.await.unwrap();
+    assert_eq!(completions.len(), 1);
+    assert_eq!(completions[0].new_text, "fully\nQualified\nName");
+}
+
+#[gpui::test(iterations = 10)]
+async fn test_apply_code_actions_with_commands(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let mut language = Language::new(
+        LanguageConfig {
+            name: "TypeScript".into(),
+            path_suffixes: vec!["ts".to_string()],
+            ..Default::default()
+        },
+        None,
+    );
+    let mut fake_language_servers = language.set_fake_lsp_adapter(Default::default()).await;
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.ts": "a",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    project.update(cx, |project, _| project.languages.add(Arc::new(language)));
+    let buffer = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/a.ts", cx))
+        .await
+        .unwrap();
+
+    let fake_server = fake_language_servers.next().await.unwrap();
+
+    // Language server returns code actions that contain commands, and not edits.
+    let actions = project.update(cx, |project, cx| project.code_actions(&buffer, 0..0, cx));
+    fake_server
         .handle_request::<lsp::request::CodeActionRequest, _, _>(|_, _| async move {
             Ok(Some(vec![
                 lsp::CodeActionOrCommand::CodeAction(lsp::CodeAction {
@@ -3065,6 +3913,115 @@ async fn test_buffer_identity_across_renames(cx: &mut gpui::TestAppContext) {
     buffer.update(cx, |buffer, _| assert!(!buffer.is_dirty()));
 }
 
+#[gpui::test]
+async fn test_rename_entry_with_file_operations(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let mut language = Language::new(
+        LanguageConfig {
+            name: "Rust".into(),
+            path_suffixes: vec!["rs".to_string()],
+            ..Default::default()
+        },
+        Some(tree_sitter_rust::language()),
+    );
+    let rust_files = lsp::FileOperationRegistrationOptions {
+        filters: vec![lsp::FileOperationFilter {
+            scheme: Some("file".into()),
+            pattern: lsp::FileOperationPattern {
+                glob: "**/*.rs".into(),
+                matches: Some(lsp::FileOperationPatternKind::File),
+                options: None,
+            },
+        }],
+    };
+    let mut fake_servers = language
+        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                workspace: Some(lsp::WorkspaceServerCapabilities {
+                    workspace_folders: None,
+                    file_operations: Some(lsp::WorkspaceFileOperationsServerCapabilities {
+                        will_rename: Some(rust_files.clone()),
+                        did_rename: Some(rust_files),
+                        ..Default::default()
+                    }),
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        }))
+        .await;
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "main.rs": "mod one;",
+            "one.rs": "",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    project.update(cx, |project, _| project.languages.add(Arc::new(language)));
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/main.rs", cx)
+        })
+        .await
+        .unwrap();
+
+    let mut fake_server = fake_servers.next().await.unwrap();
+    fake_server.handle_request::<lsp::request::WillRenameFiles, _, _>(|params, _| async move {
+        assert_eq!(
+            params.files,
+            [lsp::FileRename {
+                old_uri: "file:///dir/one.rs".into(),
+                new_uri: "file:///dir/two.rs".into(),
+            }]
+        );
+        Ok(Some(lsp::WorkspaceEdit {
+            changes: Some(
+                [(
+                    lsp::Url::from_file_path("/dir/main.rs").unwrap(),
+                    vec![lsp::TextEdit::new(
+                        lsp::Range::new(lsp::Position::new(0, 4), lsp::Position::new(0, 7)),
+                        "two".into(),
+                    )],
+                )]
+                .into_iter()
+                .collect(),
+            ),
+            ..Default::default()
+        }))
+    });
+
+    let entry_id = project.update(cx, |project, cx| {
+        let worktree = project.worktrees().next().unwrap();
+        worktree.read(cx).entry_for_path("one.rs").unwrap().id
+    });
+    project
+        .update(cx, |project, cx| {
+            project.rename_entry(entry_id, Path::new("two.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    // The server's edits are applied before the rename, and the server hears about it after.
+    buffer.update(cx, |buffer, _| assert_eq!(buffer.text(), "mod two;"));
+    assert_eq!(
+        fake_server
+            .receive_notification::<lsp::notification::DidRenameFiles>()
+            .await
+            .files,
+        [lsp::FileRename {
+            old_uri: "file:///dir/one.rs".into(),
+            new_uri: "file:///dir/two.rs".into(),
+        }]
+    );
+    assert!(fs.is_file(Path::new("/dir/two.rs")).await);
+}
+
 #[gpui::test]
 async fn test_buffer_deduping(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -4347,6 +5304,340 @@ async fn test_create_entry(cx: &mut gpui::TestAppContext) {
     assert!(result.is_err())
 }
 
+#[gpui::test]
+async fn test_toggling_breakpoints(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "main.rs": "fn main() {\n    let a = 1;\n    let b = 2;\n}\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/main.rs", cx)
+        })
+        .await
+        .unwrap();
+    let breakpoint_rows = |buffer: &Model<Buffer>, cx: &mut gpui::TestAppContext| {
+        project.read_with(cx, |project, cx| {
+            let snapshot = buffer.read(cx).snapshot();
+            project
+                .breakpoints_for_buffer(buffer.read(cx), cx)
+                .iter()
+                .map(|breakpoint| breakpoint.row(&snapshot))
+                .collect::<Vec<_>>()
+        })
+    };
+
+    project.update(cx, |project, cx| {
+        let snapshot = buffer.read(cx).snapshot();
+        project.toggle_breakpoint(&buffer, snapshot.anchor_before(Point::new(2, 8)), cx);
+        project.toggle_breakpoint(&buffer, snapshot.anchor_before(Point::new(1, 4)), cx);
+    });
+    assert_eq!(breakpoint_rows(&buffer, cx), vec![1, 2]);
+
+    // Breakpoints move along with edits above them.
+    buffer.update(cx, |buffer, cx| {
+        buffer.edit([(0..0, "// comment\n")], None, cx)
+    });
+    assert_eq!(breakpoint_rows(&buffer, cx), vec![2, 3]);
+
+    // Toggling anywhere on a row that already has a breakpoint removes it.
+    project.update(cx, |project, cx| {
+        let snapshot = buffer.read(cx).snapshot();
+        project.toggle_breakpoint(&buffer, snapshot.anchor_before(Point::new(2, 0)), cx);
+    });
+    assert_eq!(breakpoint_rows(&buffer, cx), vec![3]);
+
+    // Breakpoints are kept when the buffer is released, and restored when its file is reopened.
+    buffer.update(cx, |buffer, cx| buffer.edit([(0..0, "\n")], None, cx));
+    project
+        .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+        .await
+        .unwrap();
+    let released_buffer = buffer.downgrade();
+    drop(buffer);
+    cx.run_until_parked();
+    assert!(released_buffer.upgrade().is_none());
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/main.rs", cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(breakpoint_rows(&buffer, cx), vec![4]);
+}
+
+#[gpui::test]
+async fn test_breakpoints_in_debug_session(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "main.rs": "fn main() {\n    let a = 1;\n    let b = 2;\n}\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/main.rs", cx)
+        })
+        .await
+        .unwrap();
+    project.update(cx, |project, cx| {
+        let snapshot = buffer.read(cx).snapshot();
+        project.toggle_breakpoint(&buffer, snapshot.anchor_before(Point::new(2, 0)), cx);
+    });
+
+    let (mut fake_adapter, session) = project.update(cx, |project, cx| {
+        project.start_fake_debug_session("the-adapter".into(), Default::default(), cx)
+    });
+    let session = session.await.unwrap();
+    assert_eq!(
+        session.read_with(cx, |session, _| session.status()),
+        debugger::DebugSessionStatus::Starting
+    );
+
+    // The existing breakpoints are sent once the adapter is initialized.
+    fake_adapter.send_event::<dap::Initialized>(None);
+    let arguments = fake_adapter.receive_request::<dap::SetBreakpoints>().await;
+    assert_eq!(arguments.source.path.as_deref(), Some("/dir/main.rs"));
+    assert_eq!(
+        arguments
+            .breakpoints
+            .iter()
+            .map(|breakpoint| breakpoint.line)
+            .collect::<Vec<_>>(),
+        vec![3]
+    );
+    cx.run_until_parked();
+    assert_eq!(
+        session.read_with(cx, |session, _| session.status()),
+        debugger::DebugSessionStatus::Running
+    );
+
+    // Toggling a breakpoint resends the file's breakpoints, at their rows after edits.
+    buffer.update(cx, |buffer, cx| {
+        buffer.edit([(0..0, "// comment\n")], None, cx)
+    });
+    project.update(cx, |project, cx| {
+        let snapshot = buffer.read(cx).snapshot();
+        project.toggle_breakpoint(&buffer, snapshot.anchor_before(Point::new(0, 0)), cx);
+    });
+    let arguments = fake_adapter.receive_request::<dap::SetBreakpoints>().await;
+    assert_eq!(
+        arguments
+            .breakpoints
+            .iter()
+            .map(|breakpoint| breakpoint.line)
+            .collect::<Vec<_>>(),
+        vec![1, 4]
+    );
+}
+
+#[gpui::test]
+async fn test_git_staging_and_commit(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            ".git": {},
+            "a.txt": "one\nTWO\nthree\nFOUR\n",
+            "b.txt": "new\n",
+        }),
+    )
+    .await;
+    let dot_git = Path::new("/dir/.git");
+    let committed_text = "one\ntwo\nthree\nfour\n".to_string();
+    fs.set_head_for_repo(dot_git, &[(Path::new("a.txt"), committed_text.clone())]);
+    fs.set_index_for_repo(dot_git, &[(Path::new("a.txt"), committed_text.clone())]);
+    fs.set_status_for_repo_via_git_operation(
+        dot_git,
+        &[
+            (Path::new("a.txt"), repository::GitFileStatus::Modified),
+            (Path::new("b.txt"), repository::GitFileStatus::Added),
+        ],
+    );
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let worktree_id = project.read_with(cx, |project, cx| {
+        project.worktrees().next().unwrap().read(cx).id()
+    });
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/a.txt", cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    let index_text = || {
+        let mut text = None;
+        fs.with_git_state(dot_git, false, |state| {
+            text = state.index_contents.get(Path::new("a.txt")).cloned();
+        });
+        text.unwrap()
+    };
+
+    // Only the hunk containing the given row is staged.
+    project
+        .update(cx, |project, cx| project.stage_hunk(&buffer, 1, cx))
+        .await
+        .unwrap();
+    assert_eq!(index_text(), "one\nTWO\nthree\nfour\n");
+
+    // Unstaging it restores the committed text.
+    project
+        .update(cx, |project, cx| project.unstage_hunk(&buffer, 1, cx))
+        .await
+        .unwrap();
+    assert_eq!(index_text(), committed_text);
+
+    project
+        .update(cx, |project, cx| {
+            let path = ProjectPath {
+                worktree_id,
+                path: Path::new("b.txt").into(),
+            };
+            project.stage_paths(vec![path], cx)
+        })
+        .await
+        .unwrap();
+    let statuses = project
+        .update(cx, |project, cx| project.git_statuses(cx))
+        .await
+        .unwrap();
+    assert_eq!(
+        statuses
+            .iter()
+            .map(|status| (
+                status.project_path.path.as_ref(),
+                status.entry.staged,
+                status.entry.unstaged
+            ))
+            .collect::<Vec<_>>(),
+        [
+            (
+                Path::new("a.txt"),
+                None,
+                Some(repository::GitFileStatus::Modified)
+            ),
+            (
+                Path::new("b.txt"),
+                Some(repository::GitFileStatus::Added),
+                None
+            ),
+        ]
+    );
+
+    project
+        .update(cx, |project, cx| project.commit("Add b.txt".into(), cx))
+        .await
+        .unwrap();
+    fs.with_git_state(dot_git, false, |state| {
+        assert_eq!(state.commit_messages, ["Add b.txt"]);
+        assert!(state.staged_paths.is_empty());
+        assert!(!state
+            .worktree_statuses
+            .contains_key(&repository::RepoPath::from(Path::new("b.txt"))));
+    });
+
+    // There is nothing left to commit.
+    assert!(project
+        .update(cx, |project, cx| project.commit("Empty".into(), cx))
+        .await
+        .is_err());
+}
+
+#[gpui::test]
+async fn test_git_log(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            ".git": {},
+            "a.txt": "one\ntwo\n",
+            "b.txt": "three\n",
+        }),
+    )
+    .await;
+    let commit = |sha: &str, message: &str, paths: &[&str]| repository::LogEntry {
+        sha: sha.into(),
+        author: "Author".into(),
+        author_time: 0,
+        message: message.into(),
+        changed_paths: paths
+            .iter()
+            .map(|path| repository::RepoPath::from(Path::new(path)))
+            .collect(),
+    };
+    fs.set_log_for_repo(
+        Path::new("/dir/.git"),
+        vec![
+            (
+                commit("3", "Edit a.txt", &["a.txt"]),
+                vec![repository::CommitFileDiff {
+                    path: Path::new("a.txt").into(),
+                    old_text: Some("one\n".into()),
+                    new_text: Some("one\ntwo\n".into()),
+                }],
+            ),
+            (commit("2", "Add b.txt", &["b.txt"]), Vec::new()),
+            (commit("1", "Add a.txt", &["a.txt"]), Vec::new()),
+        ],
+    );
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    cx.executor().run_until_parked();
+    let worktree_id = project.read_with(cx, |project, cx| {
+        project.worktrees().next().unwrap().read(cx).id()
+    });
+    let project_path = |path: &str| ProjectPath {
+        worktree_id,
+        path: Path::new(path).into(),
+    };
+    let log = |path: &str, skip: usize, limit: usize| {
+        project.update(cx, |project, cx| {
+            project.git_log(project_path(path), skip, limit, cx)
+        })
+    };
+    let shas = |entries: Vec<repository::LogEntry>| {
+        entries
+            .into_iter()
+            .map(|entry| entry.sha)
+            .collect::<Vec<_>>()
+    };
+
+    // The work directory lists the whole history, a file only the commits that changed it.
+    assert_eq!(shas(log("", 0, 10).await.unwrap()), ["3", "2", "1"]);
+    assert_eq!(shas(log("", 1, 1).await.unwrap()), ["2"]);
+    assert_eq!(shas(log("a.txt", 0, 10).await.unwrap()), ["3", "1"]);
+    assert_eq!(shas(log("a.txt", 1, 10).await.unwrap()), ["1"]);
+
+    let diff = project
+        .update(cx, |project, cx| {
+            project.git_commit_diff(project_path(""), "3".into(), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(diff.len(), 1);
+    assert_eq!(diff[0].new_text.as_deref(), Some("one\ntwo\n"));
+}
+
 async fn search(
     project: &Model<Project>,
     query: SearchQuery,
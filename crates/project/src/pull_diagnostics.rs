@@ -0,0 +1,373 @@
+use std::{sync::Arc, time::Duration};
+
+use collections::HashMap;
+use gpui::{Model, ModelContext, Task};
+use language::Buffer;
+use lsp::{LanguageServer, LanguageServerId};
+use util::{ResultExt, TryFutureExt};
+
+use crate::{File, LanguageServerState, Project};
+
+const DOCUMENT_DIAGNOSTICS_DEBOUNCE: Duration = Duration::from_millis(150);
+const WORKSPACE_DIAGNOSTICS_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The state of a language server that provides diagnostics with the pull model, through
+/// `textDocument/diagnostic` and `workspace/diagnostic` requests.
+#[derive(Default)]
+pub(crate) struct PulledDiagnostics {
+    /// The result id of the latest report for each document, sent back with the next pull so
+    /// that the server can answer that the diagnostics didn't change.
+    result_ids: HashMap<lsp::Url, String>,
+    /// The pending pulls of each buffer's diagnostics, by buffer id.
+    document_pulls: HashMap<u64, Task<Option<()>>>,
+    workspace_pulls: Option<Task<()>>,
+}
+
+/// The diagnostics of a document pulled from a language server. The diagnostics are `None`
+/// when the server reported that they didn't change since the previous pull.
+struct PulledReport {
+    uri: lsp::Url,
+    version: Option<i32>,
+    result_id: Option<String>,
+    diagnostics: Option<Vec<lsp::Diagnostic>>,
+}
+
+impl Project {
+    /// Pulls the diagnostics of the open buffers from a language server that supports the pull
+    /// model, and (re)starts pulling the diagnostics of the whole workspace periodically if the
+    /// server supports that too.
+    pub(crate) fn pull_diagnostics(
+        &mut self,
+        server_id: LanguageServerId,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(server) = self.language_server_for_id(server_id) else {
+            return;
+        };
+        let Some(options) = diagnostic_options(server.capabilities()) else {
+            return;
+        };
+
+        let workspace_pulls = options.workspace_diagnostics.then(|| {
+            cx.spawn(move |this, mut cx| async move {
+                loop {
+                    let Ok(Some((server, params))) = this.update(&mut cx, |this, _| {
+                        this.workspace_diagnostic_params(server_id)
+                    }) else {
+                        break;
+                    };
+                    let previous_result_ids = params
+                        .previous_result_ids
+                        .iter()
+                        .map(|previous| (previous.uri.clone(), previous.value.clone()))
+                        .collect::<HashMap<_, _>>();
+                    let result = server
+                        .request::<lsp::request::WorkspaceDiagnosticRequest>(params)
+                        .await;
+                    if let Some(result) = result.log_err() {
+                        let reports = workspace_reports(result);
+                        let updated = this.update(&mut cx, |this, cx| {
+                            // Skip the documents whose diagnostics were pulled again while
+                            // the workspace pull was pending, as those results are newer.
+                            let reports = this
+                                .pulled_diagnostics
+                                .get(&server_id)
+                                .map(|state| {
+                                    reports
+                                        .into_iter()
+                                        .filter(|report| {
+                                            state.result_ids.get(&report.uri)
+                                                == previous_result_ids.get(&report.uri)
+                                        })
+                                        .collect()
+                                })
+                                .unwrap_or_default();
+                            this.update_pulled_diagnostics(server_id, reports, cx)
+                        });
+                        if updated.is_err() {
+                            break;
+                        }
+                    }
+                    cx.background_executor()
+                        .timer(WORKSPACE_DIAGNOSTICS_INTERVAL)
+                        .await;
+                }
+            })
+        });
+        self.pulled_diagnostics
+            .entry(server_id)
+            .or_default()
+            .workspace_pulls = workspace_pulls;
+
+        let buffers = self
+            .opened_buffers
+            .values()
+            .filter_map(|buffer| buffer.upgrade())
+            .filter(|buffer| {
+                self.language_server_ids_for_buffer(buffer.read(cx), cx)
+                    .contains(&server_id)
+            })
+            .collect::<Vec<_>>();
+        for buffer in buffers {
+            self.pull_document_diagnostics(buffer, server_id, Duration::ZERO, cx);
+        }
+    }
+
+    /// Schedules pulling the diagnostics of a buffer from each of its language servers that
+    /// use the pull model.
+    pub(crate) fn pull_buffer_diagnostics(
+        &mut self,
+        buffer: &Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let server_ids = self
+            .language_server_ids_for_buffer(buffer.read(cx), cx)
+            .into_iter()
+            .filter(|server_id| self.pulled_diagnostics.contains_key(server_id))
+            .collect::<Vec<_>>();
+        for server_id in server_ids {
+            self.pull_document_diagnostics(
+                buffer.clone(),
+                server_id,
+                DOCUMENT_DIAGNOSTICS_DEBOUNCE,
+                cx,
+            );
+        }
+    }
+
+    fn pull_document_diagnostics(
+        &mut self,
+        buffer: Model<Buffer>,
+        server_id: LanguageServerId,
+        delay: Duration,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(server) = self.language_server_for_id(server_id) else {
+            return;
+        };
+        let Some(identifier) =
+            diagnostic_options(server.capabilities()).map(|options| options.identifier.clone())
+        else {
+            return;
+        };
+        let buffer = buffer.read(cx);
+        let Some(file) = File::from_dyn(buffer.file()).and_then(|file| file.as_local()) else {
+            return;
+        };
+        let uri = lsp::Url::from_file_path(file.abs_path(cx)).unwrap();
+        let buffer_id = buffer.remote_id();
+
+        // Replacing a pending pull drops it, so that a burst of edits results in a single pull.
+        let pull = cx.spawn(move |this, mut cx| {
+            async move {
+                cx.background_executor().timer(delay).await;
+                let (version, previous_result_id) = this.update(&mut cx, |this, _| {
+                    let version = this
+                        .buffer_snapshots
+                        .get(&buffer_id)
+                        .and_then(|snapshots| snapshots.get(&server_id)?.last())
+                        .map(|snapshot| snapshot.version);
+                    let previous_result_id = this
+                        .pulled_diagnostics
+                        .get(&server_id)
+                        .and_then(|state| state.result_ids.get(&uri).cloned());
+                    (version, previous_result_id)
+                })?;
+                let result = server
+                    .request::<lsp::request::DocumentDiagnosticRequest>(
+                        lsp::DocumentDiagnosticParams {
+                            text_document: lsp::TextDocumentIdentifier { uri: uri.clone() },
+                            identifier,
+                            previous_result_id,
+                            work_done_progress_params: Default::default(),
+                            partial_result_params: Default::default(),
+                        },
+                    )
+                    .await?;
+                let reports = document_reports(uri, version, result);
+                this.update(&mut cx, |this, cx| {
+                    this.update_pulled_diagnostics(server_id, reports, cx)
+                })
+            }
+            .log_err()
+        });
+        if let Some(state) = self.pulled_diagnostics.get_mut(&server_id) {
+            state.document_pulls.insert(buffer_id, pull);
+        }
+    }
+
+    /// Forgets the pulls and result ids of a buffer that was released.
+    pub(crate) fn forget_pulled_diagnostics(&mut self, buffer_id: u64, uri: &lsp::Url) {
+        for state in self.pulled_diagnostics.values_mut() {
+            state.document_pulls.remove(&buffer_id);
+            state.result_ids.remove(uri);
+        }
+    }
+
+    fn workspace_diagnostic_params(
+        &self,
+        server_id: LanguageServerId,
+    ) -> Option<(Arc<LanguageServer>, lsp::WorkspaceDiagnosticParams)> {
+        let server = self.language_server_for_id(server_id)?;
+        let identifier = diagnostic_options(server.capabilities())?
+            .identifier
+            .clone();
+        let previous_result_ids = self
+            .pulled_diagnostics
+            .get(&server_id)?
+            .result_ids
+            .iter()
+            .map(|(uri, result_id)| lsp::PreviousResultId {
+                uri: uri.clone(),
+                value: result_id.clone(),
+            })
+            .collect();
+        Some((
+            server,
+            lsp::WorkspaceDiagnosticParams {
+                identifier,
+                previous_result_ids,
+                work_done_progress_params: Default::default(),
+                partial_result_params: Default::default(),
+            },
+        ))
+    }
+
+    /// Records the result ids of pulled reports and passes their diagnostics to the same path
+    /// as the published ones.
+    fn update_pulled_diagnostics(
+        &mut self,
+        server_id: LanguageServerId,
+        reports: Vec<PulledReport>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(LanguageServerState::Running { adapter, .. }) =
+            self.language_servers.get(&server_id)
+        else {
+            return;
+        };
+        let adapter = adapter.clone();
+
+        for report in reports {
+            if let Some(state) = self.pulled_diagnostics.get_mut(&server_id) {
+                match report.result_id {
+                    Some(result_id) => state.result_ids.insert(report.uri.clone(), result_id),
+                    None => state.result_ids.remove(&report.uri),
+                };
+            }
+            if let Some(diagnostics) = report.diagnostics {
+                let mut params = lsp::PublishDiagnosticsParams {
+                    uri: report.uri,
+                    diagnostics,
+                    version: report.version,
+                };
+                adapter.process_diagnostics(&mut params);
+                self.update_diagnostics(
+                    server_id,
+                    params,
+                    &adapter.disk_based_diagnostic_sources,
+                    cx,
+                )
+                .log_err();
+            }
+        }
+    }
+}
+
+fn diagnostic_options(capabilities: &lsp::ServerCapabilities) -> Option<&lsp::DiagnosticOptions> {
+    match capabilities.diagnostic_provider.as_ref()? {
+        lsp::DiagnosticServerCapabilities::Options(options) => Some(options),
+        lsp::DiagnosticServerCapabilities::RegistrationOptions(options) => {
+            Some(&options.diagnostic_options)
+        }
+    }
+}
+
+/// The reports of a document pull, where `version` is the version of the document that was
+/// pulled.
+fn document_reports(
+    uri: lsp::Url,
+    version: Option<i32>,
+    result: lsp::DocumentDiagnosticReportResult,
+) -> Vec<PulledReport> {
+    let (report, related_documents) = match result {
+        lsp::DocumentDiagnosticReportResult::Report(lsp::DocumentDiagnosticReport::Full(
+            report,
+        )) => (
+            Some(PulledReport::full(
+                uri,
+                version,
+                report.full_document_diagnostic_report,
+            )),
+            report.related_documents,
+        ),
+        lsp::DocumentDiagnosticReportResult::Report(lsp::DocumentDiagnosticReport::Unchanged(
+            report,
+        )) => (
+            Some(PulledReport::unchanged(
+                uri,
+                report.unchanged_document_diagnostic_report,
+            )),
+            report.related_documents,
+        ),
+        lsp::DocumentDiagnosticReportResult::Partial(partial) => (None, partial.related_documents),
+    };
+
+    report
+        .into_iter()
+        .chain(related_documents.unwrap_or_default().into_iter().map(
+            |(uri, report)| match report {
+                lsp::DocumentDiagnosticReportKind::Full(report) => {
+                    PulledReport::full(uri, None, report)
+                }
+                lsp::DocumentDiagnosticReportKind::Unchanged(report) => {
+                    PulledReport::unchanged(uri, report)
+                }
+            },
+        ))
+        .collect()
+}
+
+fn workspace_reports(result: lsp::WorkspaceDiagnosticReportResult) -> Vec<PulledReport> {
+    let items = match result {
+        lsp::WorkspaceDiagnosticReportResult::Report(report) => report.items,
+        lsp::WorkspaceDiagnosticReportResult::Partial(partial) => partial.items,
+    };
+    items
+        .into_iter()
+        .map(|item| match item {
+            lsp::WorkspaceDocumentDiagnosticReport::Full(report) => PulledReport::full(
+                report.uri,
+                report.version.map(|version| version as i32),
+                report.full_document_diagnostic_report,
+            ),
+            lsp::WorkspaceDocumentDiagnosticReport::Unchanged(report) => {
+                PulledReport::unchanged(report.uri, report.unchanged_document_diagnostic_report)
+            }
+        })
+        .collect()
+}
+
+impl PulledReport {
+    fn full(
+        uri: lsp::Url,
+        version: Option<i32>,
+        report: lsp::FullDocumentDiagnosticReport,
+    ) -> Self {
+        Self {
+            uri,
+            version,
+            result_id: report.result_id,
+            diagnostics: Some(report.items),
+        }
+    }
+
+    fn unchanged(uri: lsp::Url, report: lsp::UnchangedDocumentDiagnosticReport) -> Self {
+        Self {
+            uri,
+            version: None,
+            result_id: Some(report.result_id),
+            diagnostics: None,
+        }
+    }
+}
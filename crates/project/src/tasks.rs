@@ -0,0 +1,240 @@
+use crate::{
+    project_settings::ProjectSettings,
+    worktree::{PathChange, UpdatedEntriesSet, Worktree, WorktreeId},
+    Project,
+};
+use anyhow::Result;
+use collections::{BTreeMap, HashMap};
+use fs::Fs;
+use gpui::{Model, ModelContext};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::Settings;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use util::{paths::LOCAL_TASKS_RELATIVE_PATH, ResultExt};
+
+/// A named command, defined in the `tasks` setting or in a worktree's `.zed/tasks.json`.
+///
+/// The command, its arguments, environment and working directory may reference the
+/// variables `$FILE`, `$ROW`, `$SELECTION`, `$SYMBOL` and `$WORKTREE_ROOT`, which are
+/// substituted from the active editor when the task is spawned.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct TaskDefinition {
+    /// The name shown in the task picker.
+    pub label: String,
+    /// The program to run.
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    /// The directory to run the command in, defaulting to the worktree root.
+    #[serde(default)]
+    pub cwd: Option<String>,
+    /// The directory a relative `cwd` is resolved against. For tasks loaded from a
+    /// `.zed/tasks.json` file, this is the directory containing that `.zed` folder.
+    #[serde(skip)]
+    pub source_dir: Option<PathBuf>,
+}
+
+/// The editor state that task variables are substituted from.
+#[derive(Clone, Debug, Default)]
+pub struct TaskContext {
+    pub file: Option<PathBuf>,
+    /// The 1-based row of the cursor.
+    pub row: Option<u32>,
+    pub selection: Option<String>,
+    /// The name of the test or function a runnable was spawned for.
+    pub symbol: Option<String>,
+    pub worktree_root: Option<PathBuf>,
+}
+
+/// A task with its variables substituted, ready to run in a terminal.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct SpawnInTerminal {
+    pub label: String,
+    pub command: String,
+    pub args: Vec<String>,
+    pub env: HashMap<String, String>,
+    pub cwd: Option<PathBuf>,
+}
+
+#[derive(Default)]
+pub struct Tasks {
+    local: BTreeMap<(WorktreeId, Arc<Path>), Vec<TaskDefinition>>,
+    last_spawned: Option<SpawnInTerminal>,
+}
+
+impl TaskContext {
+    fn variables(&self) -> [(&'static str, Option<String>); 5] {
+        [
+            // Longer names first, so that no variable is a prefix of one matched after it.
+            (
+                "$WORKTREE_ROOT",
+                self.worktree_root
+                    .as_ref()
+                    .map(|path| path.to_string_lossy().into_owned()),
+            ),
+            ("$SELECTION", self.selection.clone()),
+            ("$SYMBOL", self.symbol.clone()),
+            (
+                "$FILE",
+                self.file
+                    .as_ref()
+                    .map(|path| path.to_string_lossy().into_owned()),
+            ),
+            ("$ROW", self.row.map(|row| row.to_string())),
+        ]
+    }
+
+    /// Replaces every known variable in `text`. Variables without a value in this context
+    /// are replaced with an empty string. Substituted values are not themselves expanded.
+    pub fn substitute(&self, text: &str) -> String {
+        let variables = self.variables();
+        let mut result = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(ix) = rest.find('$') {
+            result.push_str(&rest[..ix]);
+            rest = &rest[ix..];
+            match variables.iter().find(|(name, _)| rest.starts_with(name)) {
+                Some((name, value)) => {
+                    result.push_str(value.as_deref().unwrap_or(""));
+                    rest = &rest[name.len()..];
+                }
+                None => {
+                    result.push('$');
+                    rest = &rest[1..];
+                }
+            }
+        }
+        result.push_str(rest);
+        result
+    }
+}
+
+impl TaskDefinition {
+    pub fn resolve(&self, context: &TaskContext) -> SpawnInTerminal {
+        let cwd = match &self.cwd {
+            Some(cwd) => {
+                let cwd = PathBuf::from(context.substitute(cwd));
+                match self.source_dir.as_ref().or(context.worktree_root.as_ref()) {
+                    Some(dir) if cwd.is_relative() => Some(dir.join(cwd)),
+                    _ => Some(cwd),
+                }
+            }
+            None => context.worktree_root.clone(),
+        };
+        SpawnInTerminal {
+            label: self.label.clone(),
+            command: context.substitute(&self.command),
+            args: self
+                .args
+                .iter()
+                .map(|arg| context.substitute(arg))
+                .collect(),
+            env: self
+                .env
+                .iter()
+                .map(|(key, value)| (key.clone(), context.substitute(value)))
+                .collect(),
+            cwd,
+        }
+    }
+}
+
+impl Project {
+    /// All tasks available in this project, with those from `.zed/tasks.json` files
+    /// listed before the global ones.
+    pub fn task_definitions(&self, cx: &gpui::AppContext) -> Vec<TaskDefinition> {
+        self.tasks
+            .local
+            .values()
+            .flatten()
+            .chain(&ProjectSettings::get_global(cx).tasks)
+            .cloned()
+            .collect()
+    }
+
+    pub fn last_spawned_task(&self) -> Option<&SpawnInTerminal> {
+        self.tasks.last_spawned.as_ref()
+    }
+
+    pub(crate) fn record_spawned_task(&mut self, task: SpawnInTerminal) {
+        self.tasks.last_spawned = Some(task);
+    }
+
+    pub(crate) fn remove_worktree_tasks(&mut self, worktree_id: WorktreeId) {
+        self.tasks
+            .local
+            .retain(|(task_worktree_id, _), _| *task_worktree_id != worktree_id);
+    }
+
+    pub(crate) fn update_local_worktree_tasks(
+        &mut self,
+        worktree: &Model<Worktree>,
+        changes: &UpdatedEntriesSet,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let worktree = worktree.read(cx).as_local().unwrap();
+        let worktree_id = worktree.id();
+
+        let mut task_contents = Vec::new();
+        for (path, _, change) in changes.iter() {
+            if path.ends_with(&*LOCAL_TASKS_RELATIVE_PATH) {
+                let tasks_dir: Arc<Path> = Arc::from(
+                    path.ancestors()
+                        .nth(LOCAL_TASKS_RELATIVE_PATH.components().count())
+                        .unwrap(),
+                );
+                let fs = self.fs.clone();
+                let removed = *change == PathChange::Removed;
+                let abs_path = worktree.absolutize(path);
+                let source_dir = worktree.abs_path().join(&tasks_dir);
+                task_contents.push(async move {
+                    let tasks = if removed {
+                        None
+                    } else {
+                        load_tasks(fs, abs_path).await.log_err().map(|mut tasks| {
+                            for task in &mut tasks {
+                                task.source_dir = Some(source_dir.clone());
+                            }
+                            tasks
+                        })
+                    };
+                    (tasks_dir, tasks)
+                });
+            }
+        }
+
+        if task_contents.is_empty() {
+            return;
+        }
+
+        cx.spawn(move |this, mut cx| async move {
+            let task_contents = futures::future::join_all(task_contents).await;
+            this.update(&mut cx, |this, cx| {
+                for (directory, tasks) in task_contents {
+                    match tasks {
+                        Some(tasks) => {
+                            this.tasks.local.insert((worktree_id, directory), tasks);
+                        }
+                        None => {
+                            this.tasks.local.remove(&(worktree_id, directory));
+                        }
+                    }
+                }
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+}
+
+async fn load_tasks(fs: Arc<dyn Fs>, abs_path: Result<PathBuf>) -> Result<Vec<TaskDefinition>> {
+    let content = fs.load(&abs_path?).await?;
+    settings::parse_json_with_comments(&content)
+}
@@ -1,9 +1,9 @@
-use crate::Project;
+use crate::{tasks::SpawnInTerminal, Project};
 use gpui::{AnyWindowHandle, Context, Entity, Model, ModelContext, WeakModel};
 use settings::Settings;
 use std::path::{Path, PathBuf};
 use terminal::{
-    terminal_settings::{self, TerminalSettings, VenvSettingsContent},
+    terminal_settings::{self, Shell, TerminalSettings, VenvSettings, VenvSettingsContent},
     Terminal, TerminalBuilder,
 };
 
@@ -15,9 +15,11 @@ pub struct Terminals {
 }
 
 impl Project {
+    /// Creates a terminal running the user's shell, or running `spawn_task` when one is given.
     pub fn create_terminal(
         &mut self,
         working_directory: Option<PathBuf>,
+        spawn_task: Option<SpawnInTerminal>,
         window: AnyWindowHandle,
         cx: &mut ModelContext<Self>,
     ) -> anyhow::Result<Model<Terminal>> {
@@ -27,15 +29,29 @@ impl Project {
             ));
         } else {
             let settings = TerminalSettings::get_global(cx);
-            let python_settings = settings.detect_venv.clone();
-            let shell = settings.shell.clone();
+            let mut python_settings = settings.detect_venv.clone();
+            let mut shell = settings.shell.clone();
+            let mut env = settings.env.clone();
+            let mut working_directory = working_directory;
+            let is_task = spawn_task.is_some();
+            if let Some(spawn_task) = spawn_task {
+                shell = Shell::WithArguments {
+                    program: spawn_task.command.clone(),
+                    args: spawn_task.args.clone(),
+                };
+                env.extend(spawn_task.env.clone());
+                working_directory = spawn_task.cwd.clone().or(working_directory);
+                python_settings = VenvSettings::Off;
+                self.record_spawned_task(spawn_task);
+            }
 
             let terminal = TerminalBuilder::new(
                 working_directory.clone(),
-                shell.clone(),
-                settings.env.clone(),
+                shell,
+                env,
                 Some(settings.blinking.clone()),
                 settings.alternate_scroll,
+                is_task,
                 window,
             )
             .map(|builder| {
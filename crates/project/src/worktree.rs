@@ -683,13 +683,15 @@ impl LocalWorktree {
                 .background_executor()
                 .spawn(async move { text::Buffer::new(0, id, contents) })
                 .await;
-            cx.new_model(|_| {
-                Buffer::build(
+            cx.new_model(|cx| {
+                let mut buffer = Buffer::build(
                     text_buffer,
                     diff_base,
                     Some(Arc::new(file)),
                     Capability::ReadWrite,
-                )
+                );
+                buffer.refresh_merge_conflicts(cx);
+                buffer
             })
         })
     }
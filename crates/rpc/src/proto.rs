@@ -140,6 +140,8 @@ messages!(
     (AddProjectCollaborator, Foreground),
     (ApplyCodeAction, Background),
     (ApplyCodeActionResponse, Background),
+    (ApplyCodeLens, Background),
+    (ApplyCodeLensResponse, Background),
     (ApplyCompletionAdditionalEdits, Background),
     (ApplyCompletionAdditionalEditsResponse, Background),
     (BufferReloaded, Foreground),
@@ -176,12 +178,36 @@ messages!(
     (GetCodeActionsResponse, Background),
     (GetCompletions, Background),
     (GetCompletionsResponse, Background),
+    (GetDeclaration, Background),
+    (GetDeclarationResponse, Background),
     (GetDefinition, Background),
     (GetDefinitionResponse, Background),
     (GetDocumentHighlights, Background),
     (GetDocumentHighlightsResponse, Background),
     (GetHover, Background),
     (GetHoverResponse, Background),
+    (GetImplementation, Background),
+    (GetImplementationResponse, Background),
+    (GetSignatureHelp, Background),
+    (GetSignatureHelpResponse, Background),
+    (PrepareCallHierarchy, Background),
+    (HierarchyItemsResponse, Background),
+    (GetIncomingCalls, Background),
+    (GetOutgoingCalls, Background),
+    (CallHierarchyCallsResponse, Background),
+    (PrepareTypeHierarchy, Background),
+    (GetSupertypes, Background),
+    (GetSubtypes, Background),
+    (GetSemanticTokens, Background),
+    (GetSemanticTokensResponse, Background),
+    (GetCodeLens, Background),
+    (GetCodeLensResponse, Background),
+    (GetDocumentLinks, Background),
+    (GetDocumentLinksResponse, Background),
+    (GetSelectionRanges, Background),
+    (GetSelectionRangesResponse, Background),
+    (GetLinkedEditingRanges, Background),
+    (GetLinkedEditingRangesResponse, Background),
     (GetNotifications, Foreground),
     (GetNotificationsResponse, Foreground),
     (GetPrivateUserInfo, Foreground),
@@ -241,6 +267,8 @@ messages!(
     (RenameChannelResponse, Foreground),
     (RenameProjectEntry, Foreground),
     (RequestContact, Foreground),
+    (ResolveCodeLens, Background),
+    (ResolveCodeLensResponse, Background),
     (ResolveCompletionDocumentation, Background),
     (ResolveCompletionDocumentationResponse, Background),
     (ResolveInlayHint, Background),
@@ -288,6 +316,7 @@ messages!(
 
 request_messages!(
     (ApplyCodeAction, ApplyCodeActionResponse),
+    (ApplyCodeLens, ApplyCodeLensResponse),
     (
         ApplyCompletionAdditionalEdits,
         ApplyCompletionAdditionalEditsResponse
@@ -310,9 +339,23 @@ request_messages!(
     (GetChannelMessagesById, GetChannelMessagesResponse),
     (GetCodeActions, GetCodeActionsResponse),
     (GetCompletions, GetCompletionsResponse),
+    (GetDeclaration, GetDeclarationResponse),
     (GetDefinition, GetDefinitionResponse),
     (GetDocumentHighlights, GetDocumentHighlightsResponse),
     (GetHover, GetHoverResponse),
+    (GetImplementation, GetImplementationResponse),
+    (GetSignatureHelp, GetSignatureHelpResponse),
+    (PrepareCallHierarchy, HierarchyItemsResponse),
+    (GetIncomingCalls, CallHierarchyCallsResponse),
+    (GetOutgoingCalls, CallHierarchyCallsResponse),
+    (PrepareTypeHierarchy, HierarchyItemsResponse),
+    (GetSupertypes, HierarchyItemsResponse),
+    (GetSubtypes, HierarchyItemsResponse),
+    (GetSemanticTokens, GetSemanticTokensResponse),
+    (GetCodeLens, GetCodeLensResponse),
+    (GetDocumentLinks, GetDocumentLinksResponse),
+    (GetSelectionRanges, GetSelectionRangesResponse),
+    (GetLinkedEditingRanges, GetLinkedEditingRangesResponse),
     (GetNotifications, GetNotificationsResponse),
     (GetPrivateUserInfo, GetPrivateUserInfoResponse),
     (GetProjectSymbols, GetProjectSymbolsResponse),
@@ -348,6 +391,7 @@ request_messages!(
     (RenameChannel, RenameChannelResponse),
     (RenameProjectEntry, ProjectEntryResponse),
     (RequestContact, Ack),
+    (ResolveCodeLens, ResolveCodeLensResponse),
     (
         ResolveCompletionDocumentation,
         ResolveCompletionDocumentationResponse
@@ -375,6 +419,7 @@ entity_messages!(
     {project_id, ShareProject},
     AddProjectCollaborator,
     ApplyCodeAction,
+    ApplyCodeLens,
     ApplyCompletionAdditionalEdits,
     BufferReloaded,
     BufferSaved,
@@ -385,12 +430,24 @@ entity_messages!(
     ExpandProjectEntry,
     FormatBuffers,
     GetCodeActions,
+    GetCodeLens,
     GetCompletions,
+    GetDeclaration,
     GetDefinition,
     GetDocumentHighlights,
+    GetDocumentLinks,
     GetHover,
+    GetImplementation,
+    GetIncomingCalls,
+    GetLinkedEditingRanges,
+    GetOutgoingCalls,
     GetProjectSymbols,
     GetReferences,
+    GetSelectionRanges,
+    GetSemanticTokens,
+    GetSignatureHelp,
+    GetSubtypes,
+    GetSupertypes,
     GetTypeDefinition,
     InlayHints,
     JoinProject,
@@ -400,11 +457,14 @@ entity_messages!(
     OpenBufferByPath,
     OpenBufferForSymbol,
     PerformRename,
+    PrepareCallHierarchy,
     PrepareRename,
+    PrepareTypeHierarchy,
     RefreshInlayHints,
     ReloadBuffers,
     RemoveProjectCollaborator,
     RenameProjectEntry,
+    ResolveCodeLens,
     ResolveCompletionDocumentation,
     ResolveInlayHint,
     SaveBuffer,
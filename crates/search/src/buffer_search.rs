@@ -891,6 +891,11 @@ impl BufferSearchBar {
         }
     }
 
+    /// Whether the search found a match to select.
+    pub fn has_active_match(&self) -> bool {
+        self.active_match_index.is_some()
+    }
+
     pub fn select_match(&mut self, direction: Direction, count: usize, cx: &mut ViewContext<Self>) {
         if let Some(index) = self.active_match_index {
             if let Some(searchable_item) = self.active_searchable_item.as_ref() {
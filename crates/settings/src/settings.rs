@@ -8,7 +8,9 @@ use util::asset_str;
 
 pub use keymap_file::KeymapFile;
 pub use settings_file::*;
-pub use settings_store::{Settings, SettingsJsonSchemaParams, SettingsStore};
+pub use settings_store::{
+    parse_json_with_comments, Settings, SettingsJsonSchemaParams, SettingsStore,
+};
 
 #[derive(RustEmbed)]
 #[folder = "../../assets"]
@@ -0,0 +1,64 @@
+mod modal;
+
+use editor::Editor;
+use gpui::{actions, AppContext, ViewContext, WindowContext};
+use language::Point;
+use modal::TasksModal;
+use project::tasks::TaskContext;
+use workspace::{SpawnTaskInTerminal, Workspace};
+
+actions!(tasks, [Spawn, Rerun]);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(
+        |workspace: &mut Workspace, _: &mut ViewContext<Workspace>| {
+            workspace
+                .register_action(|workspace, _: &Spawn, cx| {
+                    let task_context = task_context(workspace, cx);
+                    let project = workspace.project().clone();
+                    workspace.toggle_modal(cx, |cx| TasksModal::new(project, task_context, cx));
+                })
+                .register_action(|workspace, _: &Rerun, cx| {
+                    if let Some(task) = workspace.project().read(cx).last_spawned_task().cloned() {
+                        cx.dispatch_action(Box::new(SpawnTaskInTerminal { task }));
+                    }
+                });
+        },
+    )
+    .detach();
+}
+
+/// Collects the values of task variables from the active editor.
+fn task_context(workspace: &Workspace, cx: &WindowContext) -> TaskContext {
+    let project = workspace.project().read(cx);
+    let mut context = TaskContext::default();
+
+    let active_item = workspace.active_item(cx);
+    if let Some(project_path) = active_item.as_ref().and_then(|item| item.project_path(cx)) {
+        context.file = project.absolute_path(&project_path, cx);
+        context.worktree_root = project
+            .worktree_for_id(project_path.worktree_id, cx)
+            .map(|worktree| worktree.read(cx).abs_path().to_path_buf());
+    }
+    if context.worktree_root.is_none() {
+        context.worktree_root = project
+            .visible_worktrees(cx)
+            .next()
+            .map(|worktree| worktree.read(cx).abs_path().to_path_buf());
+    }
+
+    if let Some(editor) = active_item.and_then(|item| item.act_as::<Editor>(cx)) {
+        let editor = editor.read(cx);
+        let selection = editor.selections.newest::<Point>(cx);
+        context.row = Some(selection.head().row + 1);
+        if !selection.is_empty() {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            context.selection = Some(
+                snapshot
+                    .text_for_range(selection.start..selection.end)
+                    .collect(),
+            );
+        }
+    }
+    context
+}
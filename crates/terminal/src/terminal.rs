@@ -292,6 +292,7 @@ impl TerminalBuilder {
         mut env: HashMap<String, String>,
         blink_settings: Option<TerminalBlink>,
         alternate_scroll: AlternateScroll,
+        hold: bool,
         window: AnyWindowHandle,
     ) -> Result<TerminalBuilder> {
         let pty_config = {
@@ -304,7 +305,8 @@ impl TerminalBuilder {
             PtyConfig {
                 shell: alac_shell,
                 working_directory: working_directory.clone(),
-                hold: false,
+                // Keeps the output of a one-off command visible after it exits.
+                hold,
             }
         };
 
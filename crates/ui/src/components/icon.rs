@@ -56,6 +56,7 @@ pub enum IconName {
     CopilotInit,
     Copy,
     Dash,
+    Debug,
     Delete,
     Disconnected,
     Ellipsis,
@@ -92,6 +93,7 @@ pub enum IconName {
     Option,
     PageDown,
     PageUp,
+    Play,
     Plus,
     Public,
     Quote,
@@ -147,6 +149,7 @@ impl IconName {
             IconName::CopilotInit => "icons/copilot_init.svg",
             IconName::Copy => "icons/copy.svg",
             IconName::Dash => "icons/dash.svg",
+            IconName::Debug => "icons/debug.svg",
             IconName::Delete => "icons/delete.svg",
             IconName::Disconnected => "icons/disconnected.svg",
             IconName::Ellipsis => "icons/ellipsis.svg",
@@ -183,6 +186,7 @@ impl IconName {
             IconName::Option => "icons/option.svg",
             IconName::PageDown => "icons/page_down.svg",
             IconName::PageUp => "icons/page_up.svg",
+            IconName::Play => "icons/play.svg",
             IconName::Plus => "icons/plus.svg",
             IconName::Public => "icons/public.svg",
             IconName::Quote => "icons/quote.svg",
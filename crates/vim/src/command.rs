@@ -1,26 +1,131 @@
-use command_palette::CommandInterceptResult;
-use editor::actions::{SortLinesCaseInsensitive, SortLinesCaseSensitive};
-use gpui::{impl_actions, Action, AppContext, ViewContext};
+use std::{
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::atomic::AtomicBool,
+};
+
+use command_palette::{CommandInterceptResult, CommandPalette};
+use editor::{scroll::Autoscroll, Anchor, Editor, MultiBufferSnapshot, ToPoint};
+use gpui::{
+    actions, impl_actions, Action, AppContext, Keystroke, Model, Task, ViewContext, WindowContext,
+};
+use language::Point;
+use project::{File, PathMatchCandidateSet, Worktree};
+use regex::Regex;
 use serde_derive::Deserialize;
 use workspace::{SaveIntent, Workspace};
 
 use crate::{
-    motion::{EndOfDocument, Motion},
+    motion::Motion,
     normal::{
         move_cursor,
+        repeat::{parse_keystrokes, wait_for_replays},
         search::{FindCommand, ReplaceCommand},
-        JoinLines,
     },
+    registers::ShowRegisters,
     state::Mode,
+    utils::{copy_selections_content, yank_selections_content},
     Vim,
 };
 
+/// The number of files that `:edit` offers to complete a path with.
+const MAX_PATH_COMPLETIONS: usize = 20;
+
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct GoToLine {
     pub line: u32,
 }
 
-impl_actions!(vim, [GoToLine]);
+/// An ex command that applies to a range of lines, such as `:.,+2d` or `:g/pattern/d`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ExCommand {
+    pub range: Option<CommandRange>,
+    pub command: LineCommand,
+}
+
+/// The lines that an ex command applies to, such as `.,$` or `'<,'>`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CommandRange {
+    pub start: Position,
+    pub end: Option<Position>,
+    /// Whether the end follows a `;`, which makes it relative to the start.
+    pub end_from_start: bool,
+}
+
+/// An address in a range, with the offset that follows it, as in `'a+2`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Position {
+    pub address: Address,
+    pub offset: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub enum Address {
+    /// A line number, where 0 is above the first line.
+    Line(u32),
+    CurrentLine,
+    LastLine,
+    Mark(char),
+    /// The next line that matches a pattern, or the previous one when searching backwards.
+    Pattern {
+        pattern: String,
+        backwards: bool,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub enum LineCommand {
+    /// Moves to the last line of the range, for a command that's only a range.
+    GoTo,
+    Delete {
+        register: Option<char>,
+        count: Option<u32>,
+    },
+    Yank {
+        register: Option<char>,
+        count: Option<u32>,
+    },
+    Move {
+        destination: Position,
+    },
+    Copy {
+        destination: Position,
+    },
+    Join {
+        count: Option<u32>,
+    },
+    Shift {
+        outdent: bool,
+        times: usize,
+    },
+    Sort {
+        reverse: bool,
+        ignore_case: bool,
+        unique: bool,
+    },
+    Normal {
+        keys: String,
+    },
+    /// Runs a command on each line that matches a pattern, or that doesn't when inverted.
+    Global {
+        invert: bool,
+        pattern: String,
+        command: Box<ExCommand>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct EditFile {
+    pub path: PathBuf,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct WriteFile {
+    pub path: PathBuf,
+}
+
+actions!(vim, [VisualCommand]);
+impl_actions!(vim, [GoToLine, ExCommand, EditFile, WriteFile]);
 
 pub fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
     workspace.register_action(|_: &mut Workspace, action: &GoToLine, cx| {
@@ -29,22 +134,48 @@ pub fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
             move_cursor(vim, Motion::StartOfDocument, Some(action.line as usize), cx);
         });
     });
+    workspace.register_action(|workspace: &mut Workspace, _: &VisualCommand, cx| {
+        Vim::update(cx, |vim, cx| {
+            vim.update_active_editor(cx, |vim, editor, cx| vim.record_visual_marks(editor, cx));
+        });
+        CommandPalette::toggle(workspace, "'<,'>", cx);
+    });
+    workspace.register_action(ex_command);
+    workspace.register_action(edit_file);
+    workspace.register_action(write_file);
 }
 
-pub fn command_interceptor(mut query: &str, _: &AppContext) -> Option<CommandInterceptResult> {
-    // Note: this is a very poor simulation of vim's command palette.
-    // In the future we should adjust it to handle parsing range syntax,
-    // and then calling the appropriate commands with/without ranges.
-    //
-    // We also need to support passing arguments to commands like :w
-    // (ideally with filename autocompletion).
-    //
-    // For now, you can only do a replace on the % range, and you can
-    // only use a specific line number range to "go to line"
+pub fn command_interceptor(mut query: &str, cx: &AppContext) -> Task<Vec<CommandInterceptResult>> {
     while query.starts_with(":") {
         query = &query[1..];
     }
 
+    let (name, action) = if let Some((name, action)) = simple_command(query) {
+        (name, action)
+    } else if let Some(action) = parse_command(query) {
+        (query.trim(), action)
+    } else {
+        return Task::ready(Vec::new());
+    };
+    let string = ":".to_owned() + name;
+    let positions = generate_positions(&string, query);
+
+    let mut results = vec![CommandInterceptResult {
+        action,
+        string,
+        positions,
+    }];
+    match path_completions(query, cx) {
+        Some(completions) => cx.foreground_executor().spawn(async move {
+            results.extend(completions.await);
+            results
+        }),
+        None => Task::ready(results),
+    }
+}
+
+/// The commands that take neither a range nor arguments, with their full names.
+fn simple_command(query: &str) -> Option<(&'static str, Box<dyn Action>)> {
     let (name, action) = match query {
         // save and quit
         "w" | "wr" | "wri" | "writ" | "write" => (
@@ -224,52 +355,866 @@ pub fn command_interceptor(mut query: &str, _: &AppContext) -> Option<CommandInt
             ("lNext", editor::actions::GoToPrevDiagnostic.boxed_clone())
         }
 
-        // modify the buffer (should accept [range])
-        "j" | "jo" | "joi" | "join" => ("join", JoinLines.boxed_clone()),
-        "d" | "de" | "del" | "dele" | "delet" | "delete" | "dl" | "dell" | "delel" | "deletl"
-        | "deletel" | "dp" | "dep" | "delp" | "delep" | "deletp" | "deletep" => {
-            ("delete", editor::actions::DeleteLine.boxed_clone())
+        // registers
+        "reg" | "regi" | "regis" | "regist" | "registe" | "register" | "registers" | "di"
+        | "dis" | "disp" | "displ" | "displa" | "display" => {
+            ("registers", ShowRegisters.boxed_clone())
+        }
+
+        _ => return None,
+    };
+    Some((name, action))
+}
+
+/// Parses a command that can take a range or arguments, such as `:.,+2d`, `:g/pattern/d`
+/// or `:e path`.
+fn parse_command(query: &str) -> Option<Box<dyn Action>> {
+    let (range, rest) = parse_range(query)?;
+    let (name, bang, args) = split_command(rest);
+
+    if name.is_empty() && !bang {
+        if !args.is_empty() {
+            return None;
+        }
+        let range = range?;
+        // `:/pattern` on its own searches, like `/` in normal mode does.
+        if let (
+            Position {
+                address: Address::Pattern { pattern, backwards },
+                offset: 0,
+            },
+            None,
+        ) = (&range.start, &range.end)
+        {
+            return Some(
+                FindCommand {
+                    query: pattern.clone(),
+                    backwards: *backwards,
+                }
+                .boxed_clone(),
+            );
+        }
+        return Some(
+            ExCommand {
+                range: Some(range),
+                command: LineCommand::GoTo,
+            }
+            .boxed_clone(),
+        );
+    }
+
+    // Substitution is done by the buffer search, which only replaces in the whole buffer.
+    if name == "s" && query.trim_start().starts_with("%s") {
+        return Some(
+            ReplaceCommand {
+                query: query.trim_start().to_string(),
+            }
+            .boxed_clone(),
+        );
+    }
+    if range.is_none() && !args.is_empty() {
+        if abbreviates(name, "edit", 1) {
+            return Some(EditFile { path: args.into() }.boxed_clone());
+        }
+        if abbreviates(name, "write", 1) && !bang {
+            return Some(WriteFile { path: args.into() }.boxed_clone());
+        }
+    }
+
+    let command = parse_line_command(name, bang, args)?;
+    Some(ExCommand { range, command }.boxed_clone())
+}
+
+/// Parses the part of a command that follows its range, for the commands that apply to lines.
+fn parse_line_command(name: &str, bang: bool, args: &str) -> Option<LineCommand> {
+    let command = if abbreviates(name, "sort", 3) {
+        let mut command = LineCommand::Sort {
+            reverse: bang,
+            ignore_case: false,
+            unique: false,
+        };
+        for flag in args.chars().filter(|c| !c.is_whitespace()) {
+            match (&mut command, flag) {
+                (LineCommand::Sort { ignore_case, .. }, 'i') => *ignore_case = true,
+                (LineCommand::Sort { unique, .. }, 'u') => *unique = true,
+                _ => return None,
+            }
+        }
+        command
+    } else if abbreviates(name, "normal", 4) {
+        LineCommand::Normal {
+            keys: args.to_string(),
+        }
+    } else if abbreviates(name, "global", 1) || abbreviates(name, "vglobal", 1) {
+        let mut chars = args.chars();
+        let delimiter = chars
+            .next()
+            .filter(|c| !c.is_alphanumeric() && !"\"|".contains(*c))?;
+        let (pattern, rest) = parse_pattern(chars.as_str(), delimiter);
+        let (range, rest) = parse_range(rest)?;
+        let (command_name, command_bang, command_args) = split_command(rest);
+        let command = match parse_line_command(command_name, command_bang, command_args)? {
+            // Like vim, `:global` can't be nested.
+            LineCommand::Global { .. } => return None,
+            command => command,
+        };
+        LineCommand::Global {
+            invert: bang || name.starts_with('v'),
+            pattern,
+            command: Box::new(ExCommand { range, command }),
+        }
+    } else if bang {
+        return None;
+    } else if abbreviates(name, "delete", 1) {
+        let (register, count) = parse_register_and_count(args)?;
+        LineCommand::Delete { register, count }
+    } else if abbreviates(name, "yank", 1) {
+        let (register, count) = parse_register_and_count(args)?;
+        LineCommand::Yank { register, count }
+    } else if abbreviates(name, "move", 1) {
+        LineCommand::Move {
+            destination: parse_destination(args)?,
+        }
+    } else if name == "t" || abbreviates(name, "copy", 2) {
+        LineCommand::Copy {
+            destination: parse_destination(args)?,
+        }
+    } else if abbreviates(name, "join", 1) {
+        let count = match args {
+            "" => None,
+            count => Some(count.parse().ok()?),
+        };
+        LineCommand::Join { count }
+    } else if name.starts_with(['<', '>']) && name.chars().all(|c| c == '<' || c == '>') {
+        if name.contains('<') && name.contains('>') {
+            return None;
+        }
+        LineCommand::Shift {
+            outdent: name.starts_with('<'),
+            times: name.len(),
+        }
+    } else {
+        return None;
+    };
+    Some(command)
+}
+
+/// Splits a command into its name, whether it's followed by `!`, and its arguments. Names are
+/// either letters, or a run of `<` or `>`.
+fn split_command(command: &str) -> (&str, bool, &str) {
+    let command = command.trim_start();
+    let name_len = if command.starts_with(['<', '>']) {
+        command
+            .find(|c| c != '<' && c != '>')
+            .unwrap_or(command.len())
+    } else {
+        command
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(command.len())
+    };
+    let (name, rest) = command.split_at(name_len);
+    let (bang, args) = match rest.strip_prefix('!') {
+        Some(args) => (true, args),
+        None => (false, rest),
+    };
+    (name, bang, args.trim())
+}
+
+/// Whether `name` is `command`, or an abbreviation of it that's at least `min_len` long.
+fn abbreviates(name: &str, command: &str, min_len: usize) -> bool {
+    name.len() >= min_len && command.starts_with(name)
+}
+
+/// Parses the optional register and count of `:delete` and `:yank`, as in `:d a 3`.
+fn parse_register_and_count(args: &str) -> Option<(Option<char>, Option<u32>)> {
+    let mut args = args;
+    let mut register = None;
+    if let Some(c) = args.chars().next().filter(|c| !c.is_ascii_digit()) {
+        if !c.is_ascii_alphabetic() && !"\"-_+*".contains(c) {
+            return None;
+        }
+        register = Some(c).filter(|c| *c != '"');
+        args = args[c.len_utf8()..].trim_start();
+    }
+    let count = match args {
+        "" => None,
+        count => Some(count.parse().ok()?),
+    };
+    Some((register, count))
+}
+
+/// Parses the address that `:move` and `:copy` put lines below.
+fn parse_destination(args: &str) -> Option<Position> {
+    let mut rest = args;
+    let destination = parse_position(&mut rest)?;
+    rest.trim().is_empty().then_some(destination).flatten()
+}
+
+/// Splits a command into its range and the rest of the command.
+fn parse_range(command: &str) -> Option<(Option<CommandRange>, &str)> {
+    let mut rest = command.trim_start();
+    if let Some(rest) = rest.strip_prefix('%') {
+        let range = CommandRange {
+            start: Position {
+                address: Address::Line(1),
+                offset: 0,
+            },
+            end: Some(Position {
+                address: Address::LastLine,
+                offset: 0,
+            }),
+            end_from_start: false,
+        };
+        return Some((Some(range), rest));
+    }
+
+    let start = parse_position(&mut rest)?;
+    let separator = rest.chars().next().filter(|c| *c == ',' || *c == ';');
+    let Some(separator) = separator else {
+        let range = start.map(|start| CommandRange {
+            start,
+            end: None,
+            end_from_start: false,
+        });
+        return Some((range, rest));
+    };
+    rest = &rest[1..];
+    let end = parse_position(&mut rest)?;
+    // Like vim, a missing address on either side of the separator is the current line.
+    let current_line = || Position {
+        address: Address::CurrentLine,
+        offset: 0,
+    };
+    let range = CommandRange {
+        start: start.unwrap_or_else(current_line),
+        end: Some(end.unwrap_or_else(current_line)),
+        end_from_start: separator == ';',
+    };
+    Some((Some(range), rest))
+}
+
+/// Parses an address and its offsets from the start of `rest`, advancing past them. Returns
+/// `Some(None)` when there's no address.
+fn parse_position(rest: &mut &str) -> Option<Option<Position>> {
+    let mut chars = rest.chars();
+    let address = match chars.next() {
+        Some(c) if c.is_ascii_digit() => {
+            let len = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            let line = rest[..len].parse().ok()?;
+            *rest = &rest[len..];
+            Some(Address::Line(line))
+        }
+        Some('.') => {
+            *rest = chars.as_str();
+            Some(Address::CurrentLine)
+        }
+        Some('$') => {
+            *rest = chars.as_str();
+            Some(Address::LastLine)
+        }
+        Some('\'') => {
+            let mark = chars.next()?;
+            *rest = chars.as_str();
+            Some(Address::Mark(mark))
+        }
+        Some(delimiter @ ('/' | '?')) => {
+            let (pattern, after) = parse_pattern(chars.as_str(), delimiter);
+            *rest = after;
+            Some(Address::Pattern {
+                pattern,
+                backwards: delimiter == '?',
+            })
+        }
+        _ => None,
+    };
+
+    let mut offset = None;
+    loop {
+        let sign = match rest.chars().next() {
+            Some('+') => 1,
+            Some('-') => -1,
+            _ => break,
+        };
+        *rest = &rest[1..];
+        let len = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let amount = match len {
+            0 => 1,
+            _ => rest[..len].parse::<i32>().ok()?,
+        };
+        *rest = &rest[len..];
+        offset = Some(offset.unwrap_or(0) + sign * amount);
+    }
+
+    Some(match (address, offset) {
+        (Some(address), offset) => Some(Position {
+            address,
+            offset: offset.unwrap_or(0),
+        }),
+        (None, Some(offset)) => Some(Position {
+            address: Address::CurrentLine,
+            offset,
+        }),
+        (None, None) => None,
+    })
+}
+
+/// Reads a pattern up to an unescaped `delimiter`, returning it and the text that follows the
+/// delimiter. Like in vim, the pattern can also end at the end of the command.
+fn parse_pattern(text: &str, delimiter: char) -> (String, &str) {
+    let mut pattern = String::new();
+    let mut chars = text.char_indices();
+    while let Some((ix, c)) = chars.next() {
+        if c == delimiter {
+            return (pattern, &text[ix + c.len_utf8()..]);
+        }
+        if c == '\\' {
+            if let Some((_, next)) = chars.next() {
+                if next != delimiter {
+                    pattern.push('\\');
+                }
+                pattern.push(next);
+                continue;
+            }
         }
-        "sor" | "sor " | "sort" | "sort " => ("sort", SortLinesCaseSensitive.boxed_clone()),
-        "sor i" | "sort i" => ("sort i", SortLinesCaseInsensitive.boxed_clone()),
-
-        // goto (other ranges handled under _ => )
-        "$" => ("$", EndOfDocument.boxed_clone()),
-
-        _ => {
-            if query.starts_with("/") || query.starts_with("?") {
-                (
-                    query,
-                    FindCommand {
-                        query: query[1..].to_string(),
-                        backwards: query.starts_with("?"),
+        pattern.push(c);
+    }
+    (pattern, "")
+}
+
+/// Completes the path of `:edit` with the files in the worktree that relative paths are
+/// resolved against, fuzzy-matching them on the background executor.
+fn path_completions(query: &str, cx: &AppContext) -> Option<Task<Vec<CommandInterceptResult>>> {
+    let (name, bang, path) = split_command(query);
+    if !abbreviates(name, "edit", 1) || bang || path.is_empty() {
+        return None;
+    }
+    let workspace = cx
+        .active_window()
+        .and_then(|window| window.downcast::<Workspace>())
+        .and_then(|window| window.read(cx).ok())?;
+    let candidate_set = PathMatchCandidateSet {
+        snapshot: base_worktree(workspace, cx)?.read(cx).snapshot(),
+        include_ignored: false,
+        include_root_name: false,
+    };
+
+    let name = name.to_string();
+    let path = path.to_string();
+    let query = query.to_string();
+    let executor = cx.background_executor().clone();
+    let matches = cx.background_executor().spawn(async move {
+        let matches = fuzzy::match_path_sets(
+            &[candidate_set],
+            &path,
+            None,
+            false,
+            MAX_PATH_COMPLETIONS,
+            &AtomicBool::new(false),
+            executor,
+        )
+        .await;
+        matches
+            .into_iter()
+            .map(|path_match| path_match.path.to_string_lossy().to_string())
+            .filter(|candidate| *candidate != path)
+            .collect::<Vec<_>>()
+    });
+    Some(cx.foreground_executor().spawn(async move {
+        matches
+            .await
+            .into_iter()
+            .map(|candidate| {
+                let string = format!(":{name} {candidate}");
+                let positions = generate_positions(&string, &query);
+                CommandInterceptResult {
+                    action: EditFile {
+                        path: candidate.into(),
                     }
                     .boxed_clone(),
-                )
-            } else if query.starts_with("%") {
-                (
-                    query,
-                    ReplaceCommand {
-                        query: query.to_string(),
+                    string,
+                    positions,
+                }
+            })
+            .collect()
+    }))
+}
+
+/// The worktree that relative paths are resolved against, which is the one containing the
+/// active file, or else the first one.
+fn base_worktree(workspace: &Workspace, cx: &AppContext) -> Option<Model<Worktree>> {
+    workspace
+        .active_item_as::<Editor>(cx)
+        .and_then(|editor| editor.read(cx).buffer().read(cx).as_singleton())
+        .and_then(|buffer| Some(File::from_dyn(buffer.read(cx).file())?.worktree.clone()))
+        .filter(|worktree| worktree.read(cx).is_visible())
+        .or_else(|| workspace.visible_worktrees(cx).next())
+}
+
+fn absolute_path(path: &Path, workspace: &Workspace, cx: &AppContext) -> Option<PathBuf> {
+    if path.is_absolute() {
+        return Some(path.to_path_buf());
+    }
+    let worktree = base_worktree(workspace, cx)?;
+    let abs_path = worktree.read(cx).abs_path().join(path);
+    Some(abs_path)
+}
+
+fn edit_file(workspace: &mut Workspace, action: &EditFile, cx: &mut ViewContext<Workspace>) {
+    let Some(abs_path) = absolute_path(&action.path, workspace, cx) else {
+        return;
+    };
+    workspace
+        .open_abs_path(abs_path, true, cx)
+        .detach_and_log_err(cx);
+}
+
+fn write_file(workspace: &mut Workspace, action: &WriteFile, cx: &mut ViewContext<Workspace>) {
+    let Some(abs_path) = absolute_path(&action.path, workspace, cx) else {
+        return;
+    };
+    let Some(buffer) = workspace
+        .active_item_as::<Editor>(cx)
+        .and_then(|editor| editor.read(cx).buffer().read(cx).as_singleton())
+    else {
+        return;
+    };
+    let project = workspace.project().clone();
+
+    let file_abs_path = buffer
+        .read(cx)
+        .file()
+        .and_then(|file| Some(file.as_local()?.abs_path(cx)));
+    match file_abs_path {
+        Some(file_abs_path) if file_abs_path == abs_path => workspace
+            .save_active_item(SaveIntent::Save, cx)
+            .detach_and_log_err(cx),
+        // Like vim, writing a file's buffer to another path writes a copy, and the buffer
+        // stays on its file.
+        Some(_) => {
+            let buffer = buffer.read(cx);
+            let text = buffer.as_rope().clone();
+            let line_ending = buffer.line_ending();
+            let fs = project.read(cx).fs().clone();
+            cx.background_executor()
+                .spawn(async move { fs.save(&abs_path, &text, line_ending).await })
+                .detach_and_log_err(cx);
+        }
+        None => project
+            .update(cx, |project, cx| {
+                project.save_buffer_as(buffer, abs_path, cx)
+            })
+            .detach_and_log_err(cx),
+    }
+}
+
+fn ex_command(_: &mut Workspace, action: &ExCommand, cx: &mut ViewContext<Workspace>) {
+    let pending_normal = Vim::update(cx, |vim, cx| {
+        vim.switch_mode(Mode::Normal, false, cx);
+        vim.update_active_editor(cx, |vim, editor, cx| {
+            let current_row = editor.selections.newest::<Point>(cx).head().row;
+            let mut pending_normal = None;
+            editor.transact(cx, |editor, cx| {
+                pending_normal = action.run(vim, editor, current_row, cx);
+            });
+            pending_normal
+        })
+    })
+    .flatten();
+
+    if let Some((lines, keys)) = pending_normal {
+        run_normal(lines, &keys, cx);
+    }
+}
+
+impl ExCommand {
+    /// Runs the command against the editor's buffer, where `current_row` is the line that `.`
+    /// refers to. Returns the lines that `:normal` still has to run its keys on, since keys are
+    /// dispatched after the command returns.
+    fn run(
+        &self,
+        vim: &mut Vim,
+        editor: &mut Editor,
+        current_row: u32,
+        cx: &mut ViewContext<Editor>,
+    ) -> Option<(Vec<Anchor>, String)> {
+        let snapshot = editor.buffer().read(cx).snapshot(cx);
+        let rows = match &self.range {
+            Some(range) => range.rows(vim, editor, current_row, &snapshot, cx)?,
+            None => match self.command {
+                LineCommand::Sort { .. } | LineCommand::Global { .. } => {
+                    0..snapshot.max_buffer_row() + 1
+                }
+                _ => current_row..current_row + 1,
+            },
+        };
+
+        match &self.command {
+            LineCommand::GoTo => {
+                let origin = editor.selections.newest_anchor().head();
+                move_to_line(editor, rows.end - 1, cx);
+                editor.record_jump(origin, cx);
+            }
+            LineCommand::Delete { register, count } => {
+                let rows = with_count(rows, *count, &snapshot);
+                select_lines(editor, rows.clone(), cx);
+                vim.workspace_state.selected_register = *register;
+                copy_selections_content(vim, editor, true, cx);
+                editor.insert("", cx);
+                move_to_line(editor, rows.start, cx);
+            }
+            LineCommand::Yank { register, count } => {
+                let rows = with_count(rows, *count, &snapshot);
+                let selections = editor.selections.disjoint_anchors();
+                select_lines(editor, rows, cx);
+                vim.workspace_state.selected_register = *register;
+                yank_selections_content(vim, editor, true, cx);
+                editor.change_selections(None, cx, |s| s.select_anchors(selections.to_vec()));
+            }
+            LineCommand::Move { destination } => {
+                let destination = destination.line(vim, editor, current_row, &snapshot, cx)?;
+                let line_count = rows.end - rows.start;
+                // Moving lines to where they already are leaves them alone.
+                if (rows.start..=rows.end).contains(&destination) {
+                    move_to_line(editor, rows.end - 1, cx);
+                    return None;
+                }
+                let text = lines_text(rows.clone(), &snapshot);
+                let deleted_range = line_range(rows.clone(), &snapshot);
+                if destination > rows.end {
+                    insert_lines(editor, destination, &text, cx);
+                    editor.edit([(deleted_range, "")], cx);
+                    move_to_line(editor, destination - 1, cx);
+                } else {
+                    editor.edit([(deleted_range, "")], cx);
+                    insert_lines(editor, destination, &text, cx);
+                    move_to_line(editor, destination + line_count - 1, cx);
+                }
+            }
+            LineCommand::Copy { destination } => {
+                let destination = destination.line(vim, editor, current_row, &snapshot, cx)?;
+                let text = lines_text(rows.clone(), &snapshot);
+                insert_lines(editor, destination, &text, cx);
+                move_to_line(editor, destination + rows.end - rows.start - 1, cx);
+            }
+            LineCommand::Join { count } => {
+                let mut rows = with_count(rows, *count, &snapshot);
+                // Joining a single line joins it with the next one.
+                if rows.end - rows.start == 1 {
+                    rows.end += 1;
+                }
+                rows.end = rows.end.min(snapshot.max_buffer_row() + 1);
+                if rows.end - rows.start < 2 {
+                    return None;
+                }
+                let range = Point::new(rows.start, 0)..Point::new(rows.end - 1, 0);
+                editor.change_selections(None, cx, |s| s.select_ranges([range]));
+                editor.join_lines(&Default::default(), cx);
+                move_to_line(editor, rows.start, cx);
+            }
+            LineCommand::Shift { outdent, times } => {
+                let last_row = rows.end - 1;
+                let range =
+                    Point::new(rows.start, 0)..Point::new(last_row, snapshot.line_len(last_row));
+                editor.change_selections(None, cx, |s| s.select_ranges([range]));
+                for _ in 0..*times {
+                    if *outdent {
+                        editor.outdent(&Default::default(), cx);
+                    } else {
+                        editor.indent(&Default::default(), cx);
                     }
-                    .boxed_clone(),
-                )
-            } else if let Ok(line) = query.parse::<u32>() {
-                (query, GoToLine { line }.boxed_clone())
-            } else {
-                return None;
+                }
+                move_to_line(editor, last_row, cx);
+            }
+            LineCommand::Sort {
+                reverse,
+                ignore_case,
+                unique,
+            } => {
+                let text = lines_text(rows.clone(), &snapshot);
+                let mut lines = text.split('\n').collect::<Vec<_>>();
+                let key = |line: &str| {
+                    if *ignore_case {
+                        line.to_lowercase()
+                    } else {
+                        line.to_string()
+                    }
+                };
+                lines.sort_by_cached_key(|line| key(*line));
+                if *unique {
+                    lines.dedup_by(|a, b| key(*a) == key(*b));
+                }
+                if *reverse {
+                    lines.reverse();
+                }
+                let last_row = rows.end - 1;
+                let range =
+                    Point::new(rows.start, 0)..Point::new(last_row, snapshot.line_len(last_row));
+                editor.edit([(range, lines.join("\n"))], cx);
+                move_to_line(editor, rows.start, cx);
+            }
+            LineCommand::Normal { keys } => {
+                let lines = rows
+                    .map(|row| snapshot.anchor_after(Point::new(row, 0)))
+                    .collect();
+                return Some((lines, keys.clone()));
             }
+            LineCommand::Global {
+                invert,
+                pattern,
+                command,
+            } => {
+                let regex = search_regex(pattern, vim, cx)?;
+                let lines = rows
+                    .filter(|row| regex.is_match(&lines_text(*row..*row + 1, &snapshot)) != *invert)
+                    .map(|row| snapshot.anchor_after(Point::new(row, 0)))
+                    .collect::<Vec<_>>();
+                if let LineCommand::Normal { keys } = &command.command {
+                    return Some((lines, keys.clone()));
+                }
+                for line in lines {
+                    // Lines that an earlier run of the command deleted are skipped.
+                    let snapshot = editor.buffer().read(cx).snapshot(cx);
+                    if line.is_valid(&snapshot) {
+                        let row = line.to_point(&snapshot).row;
+                        command.run(vim, editor, row, cx);
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+impl CommandRange {
+    /// The rows of the lines in the range.
+    fn rows(
+        &self,
+        vim: &Vim,
+        editor: &Editor,
+        current_row: u32,
+        snapshot: &MultiBufferSnapshot,
+        cx: &AppContext,
+    ) -> Option<Range<u32>> {
+        let start = self.start.line(vim, editor, current_row, snapshot, cx)?;
+        let end = match &self.end {
+            Some(end) => {
+                // After `;`, the end is relative to the start instead of the cursor.
+                let current_row = if self.end_from_start {
+                    start.saturating_sub(1)
+                } else {
+                    current_row
+                };
+                end.line(vim, editor, current_row, snapshot, cx)?
+            }
+            None => start,
+        };
+        let (start, end) = if start > end {
+            (end, start)
+        } else {
+            (start, end)
+        };
+        Some(start.max(1) - 1..end.max(1))
+    }
+}
+
+impl Position {
+    /// The number of the line that the position refers to, clamped to the buffer, where 0 is
+    /// above the first line.
+    fn line(
+        &self,
+        vim: &Vim,
+        editor: &Editor,
+        current_row: u32,
+        snapshot: &MultiBufferSnapshot,
+        cx: &AppContext,
+    ) -> Option<u32> {
+        let line_count = snapshot.max_buffer_row() + 1;
+        let line = match &self.address {
+            Address::Line(line) => *line,
+            Address::CurrentLine => current_row + 1,
+            Address::LastLine => line_count,
+            Address::Mark(mark) => {
+                vim.mark_in_editor(*mark, editor, cx)?
+                    .to_point(snapshot)
+                    .row
+                    + 1
+            }
+            Address::Pattern { pattern, backwards } => {
+                // Like vim, the search starts next to the current line and wraps around.
+                let regex = search_regex(pattern, vim, cx)?;
+                (1..=line_count).find_map(|distance| {
+                    let row = if *backwards {
+                        (current_row + line_count - distance) % line_count
+                    } else {
+                        (current_row + distance) % line_count
+                    };
+                    regex
+                        .is_match(&lines_text(row..row + 1, snapshot))
+                        .then_some(row + 1)
+                })?
+            }
+        };
+        let line = (line as i64 + self.offset as i64).clamp(0, line_count as i64);
+        Some(line as u32)
+    }
+}
+
+/// The regex of a pattern in a command, where an empty pattern is the last search.
+fn search_regex(pattern: &str, vim: &Vim, cx: &AppContext) -> Option<Regex> {
+    if pattern.is_empty() {
+        Regex::new(&vim.read_register(Some('/'), cx)?.text).ok()
+    } else {
+        Regex::new(pattern).ok()
+    }
+}
+
+/// Applies the count of `:delete`, `:yank` and `:join`, which counts lines from the last
+/// line of the range.
+fn with_count(rows: Range<u32>, count: Option<u32>, snapshot: &MultiBufferSnapshot) -> Range<u32> {
+    match count {
+        Some(count) if count > 0 => {
+            let start = rows.end - 1;
+            start..(start + count).min(snapshot.max_buffer_row() + 1)
         }
+        _ => rows,
+    }
+}
+
+/// The text of the lines, without the newline after the last one.
+fn lines_text(rows: Range<u32>, snapshot: &MultiBufferSnapshot) -> String {
+    let last_row = rows.end - 1;
+    snapshot
+        .text_for_range(
+            Point::new(rows.start, 0)..Point::new(last_row, snapshot.line_len(last_row)),
+        )
+        .collect()
+}
+
+/// The range that removes whole lines, including a newline that separates them from the rest
+/// of the buffer.
+fn line_range(rows: Range<u32>, snapshot: &MultiBufferSnapshot) -> Range<Point> {
+    if rows.end <= snapshot.max_buffer_row() {
+        Point::new(rows.start, 0)..Point::new(rows.end, 0)
+    } else if rows.start > 0 {
+        let previous_row = rows.start - 1;
+        Point::new(previous_row, snapshot.line_len(previous_row))..snapshot.max_point()
+    } else {
+        Point::zero()..snapshot.max_point()
+    }
+}
+
+/// Selects whole lines, in the way that linewise operators select them.
+fn select_lines(editor: &mut Editor, rows: Range<u32>, cx: &mut ViewContext<Editor>) {
+    let snapshot = editor.buffer().read(cx).snapshot(cx);
+    let range = line_range(rows, &snapshot);
+    editor.change_selections(None, cx, |s| s.select_ranges([range]));
+}
+
+/// Inserts lines below a line, where line 0 is above the first line.
+fn insert_lines(editor: &mut Editor, line: u32, text: &str, cx: &mut ViewContext<Editor>) {
+    let snapshot = editor.buffer().read(cx).snapshot(cx);
+    if line == 0 {
+        editor.edit([(Point::zero()..Point::zero(), format!("{text}\n"))], cx);
+    } else {
+        let row = (line - 1).min(snapshot.max_buffer_row());
+        let end_of_line = Point::new(row, snapshot.line_len(row));
+        editor.edit([(end_of_line..end_of_line, format!("\n{text}"))], cx);
+    }
+}
+
+/// Moves the cursor to the first non-blank character of a line.
+fn move_to_line(editor: &mut Editor, row: u32, cx: &mut ViewContext<Editor>) {
+    let snapshot = editor.buffer().read(cx).snapshot(cx);
+    let row = row.min(snapshot.max_buffer_row());
+    let line_len = snapshot.line_len(row);
+    let column = snapshot.indent_size_for_line(row).len;
+    // A line of only whitespace puts the cursor on its last character.
+    let column = if column == line_len {
+        line_len.saturating_sub(1)
+    } else {
+        column
     };
+    let point = Point::new(row, column);
+    editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
+        s.select_ranges([point..point])
+    });
+}
 
-    let string = ":".to_owned() + name;
-    let positions = generate_positions(&string, query);
+/// Runs keys in normal mode from the start of each line, for `:normal`, as a single undo step.
+/// Lines that were deleted by the keys run on earlier lines are skipped, as are the rest of a
+/// line's keys once a motion fails.
+fn run_normal(lines: Vec<Anchor>, keys: &str, cx: &mut WindowContext) {
+    let mut keystrokes = parse_keystrokes(keys);
+    // Like vim, a command that the keys leave incomplete, such as insert mode, is ended.
+    keystrokes.extend(Keystroke::parse("escape").ok());
+    let Some(editor) = Vim::read(cx).active_editor.clone() else {
+        return;
+    };
+    let Some(multi_buffer) = editor
+        .update(cx, |editor, cx| {
+            editor
+                .buffer()
+                .update(cx, |buffer, cx| buffer.start_transaction(cx));
+            editor.buffer().clone()
+        })
+        .ok()
+    else {
+        return;
+    };
 
-    Some(CommandInterceptResult {
-        action,
-        string,
-        positions,
+    let window = cx.window_handle();
+    cx.spawn(move |mut cx| async move {
+        let result = async {
+            for line in lines {
+                let started = window.update(&mut cx, |_, cx| {
+                    Vim::update(cx, |vim, cx| {
+                        vim.switch_mode(Mode::Normal, false, cx);
+                        vim.update_active_editor(cx, |_, editor, cx| {
+                            let snapshot = editor.buffer().read(cx).snapshot(cx);
+                            if !line.is_valid(&snapshot) {
+                                return false;
+                            }
+                            let point = Point::new(line.to_point(&snapshot).row, 0);
+                            editor.change_selections(None, cx, |s| s.select_ranges([point..point]));
+                            true
+                        })
+                    })
+                })?;
+                match started {
+                    Some(true) => {}
+                    Some(false) => continue,
+                    None => break,
+                }
+
+                // The keys are replayed like a macro, so that a motion that fails skips the rest
+                // of them. Keystrokes that were already queued, such as those of a macro that
+                // ran `:normal`, aren't waited for.
+                let remaining = window.update(&mut cx, |_, cx| {
+                    Vim::update(cx, |vim, cx| {
+                        let remaining = vim.workspace_state.replayed_keystrokes.len();
+                        vim.replay_keystrokes(keystrokes.clone(), cx);
+                        remaining
+                    })
+                })?;
+                wait_for_replays(remaining, window, &mut cx).await?;
+            }
+            anyhow::Ok(())
+        }
+        .await;
+
+        // Like vim, keys that were skipped don't leave the editor in insert mode.
+        window.update(&mut cx, |_, cx| {
+            Vim::update(cx, |vim, cx| vim.switch_mode(Mode::Normal, false, cx))
+        })?;
+
+        multi_buffer.update(&mut cx, |buffer, cx| buffer.end_transaction(cx))?;
+        result
     })
+    .detach_and_log_err(cx);
 }
 
 fn generate_positions(string: &str, query: &str) -> Vec<usize> {
@@ -298,7 +1243,10 @@ fn generate_positions(string: &str, query: &str) -> Vec<usize> {
 mod test {
     use std::path::Path;
 
-    use crate::test::{NeovimBackedTestContext, VimTestContext};
+    use crate::{
+        state::Mode,
+        test::{NeovimBackedTestContext, VimTestContext},
+    };
     use gpui::TestAppContext;
     use indoc::indoc;
 
@@ -395,6 +1343,219 @@ mod test {
             .await;
     }
 
+    #[gpui::test]
+    async fn test_command_ranges(cx: &mut TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+
+        cx.set_shared_state(indoc! {"
+            ˇa
+            b
+            c
+            d
+            e"})
+            .await;
+        cx.simulate_shared_keystrokes([":", "2", ",", "3", "d", "enter"])
+            .await;
+        cx.assert_shared_state(indoc! {"
+            a
+            ˇd
+            e"})
+            .await;
+        cx.simulate_shared_keystrokes([":", "1", "m", "$", "enter"])
+            .await;
+        cx.assert_shared_state(indoc! {"
+            d
+            e
+            ˇa"})
+            .await;
+        cx.simulate_shared_keystrokes([":", "1", "t", "0", "enter"])
+            .await;
+        cx.assert_shared_state(indoc! {"
+            ˇd
+            d
+            e
+            a"})
+            .await;
+        cx.simulate_shared_keystrokes([":", "/", "e", "/", "d", "enter"])
+            .await;
+        cx.assert_shared_state(indoc! {"
+            d
+            d
+            ˇa"})
+            .await;
+        cx.simulate_shared_keystrokes([":", "1", "y", "enter"])
+            .await;
+        cx.simulate_shared_keystrokes(["p"]).await;
+        cx.assert_shared_state(indoc! {"
+            d
+            d
+            a
+            ˇd"})
+            .await;
+    }
+
+    #[gpui::test]
+    async fn test_command_sort_and_join(cx: &mut TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+
+        cx.set_shared_state(indoc! {"
+            ˇc
+            b
+            a
+            d"})
+            .await;
+        cx.simulate_shared_keystrokes([":", "s", "o", "r", "t", "enter"])
+            .await;
+        cx.assert_shared_state(indoc! {"
+            ˇa
+            b
+            c
+            d"})
+            .await;
+        cx.simulate_shared_keystrokes([":", "2", ",", "3", "j", "enter"])
+            .await;
+        cx.assert_shared_state(indoc! {"
+            a
+            ˇb c
+            d"})
+            .await;
+        cx.simulate_shared_keystrokes([":", "%", "s", "o", "r", "t", "!", "enter"])
+            .await;
+        cx.assert_shared_state(indoc! {"
+            ˇd
+            b c
+            a"})
+            .await;
+    }
+
+    #[gpui::test]
+    async fn test_command_global(cx: &mut TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+
+        cx.set_shared_state(indoc! {"
+            ˇa1
+            b
+            a2
+            c"})
+            .await;
+        cx.simulate_shared_keystrokes([":", "g", "/", "a", "/", "d", "enter"])
+            .await;
+        cx.assert_shared_state(indoc! {"
+            b
+            ˇc"})
+            .await;
+
+        cx.set_shared_state(indoc! {"
+            ˇa
+            b
+            c"})
+            .await;
+        cx.simulate_shared_keystrokes([
+            ":", "v", "/", "b", "/", "n", "o", "r", "m", "!", "shift-a", "x", "enter",
+        ])
+        .await;
+        cx.assert_shared_state(indoc! {"
+            ax
+            b
+            cˇx"})
+            .await;
+    }
+
+    #[gpui::test]
+    async fn test_command_normal_undo(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state(
+            indoc! {"
+            ˇa
+            b
+            c"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes([":", "%", "n", "o", "r", "m", "!", "shift-a", "x", "enter"]);
+        cx.assert_state(
+            indoc! {"
+            ax
+            bx
+            cˇx"},
+            Mode::Normal,
+        );
+
+        // the keys run on every line are undone together
+        cx.simulate_keystrokes(["u"]);
+        cx.update_editor(|editor, cx| assert_eq!(editor.text(cx), "a\nb\nc"));
+    }
+
+    #[gpui::test]
+    async fn test_command_normal_failed_motion(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        // the rest of a line's keys are skipped once a motion fails on it
+        cx.set_state(
+            indoc! {"
+            ˇa,b
+            cd
+            e,f"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes([":", "%", "n", "o", "r", "m", "!", "f", ",", "x", "enter"]);
+        cx.assert_state(
+            indoc! {"
+            ab
+            cd
+            eˇf"},
+            Mode::Normal,
+        );
+
+        // including when it fails on the first line
+        cx.set_state(
+            indoc! {"
+            ˇab
+            c,d"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes([
+            ":", "g", "/", ".", "/", "n", "o", "r", "m", "!", "f", ",", "x", "enter",
+        ]);
+        cx.assert_state(
+            indoc! {"
+            ab
+            cˇd"},
+            Mode::Normal,
+        );
+    }
+
+    #[gpui::test]
+    async fn test_command_visual_range(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state(
+            indoc! {"
+                ˇa
+                b
+                c"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(["shift-v", "j", ":", "d", "enter"]);
+        cx.assert_state("ˇc", Mode::Normal);
+
+        cx.set_state(
+            indoc! {"
+                ˇa
+                b
+                c"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(["v", "j", ":", ">", "enter"]);
+        cx.assert_state(
+            indoc! {"
+                    a
+                    ˇb
+                c"},
+            Mode::Normal,
+        );
+    }
+
     #[gpui::test]
     async fn test_command_write(cx: &mut TestAppContext) {
         let mut cx = VimTestContext::new(cx, true).await;
@@ -424,6 +1585,32 @@ mod test {
         assert_eq!(fs.load(&path).await.unwrap(), "@@\n");
     }
 
+    #[gpui::test]
+    async fn test_command_write_and_edit_path(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        let fs = cx.workspace(|workspace, cx| workspace.project().read(cx).fs().clone());
+
+        cx.simulate_keystrokes(["i", "@", "escape"]);
+        cx.simulate_keystrokes([
+            ":", "w", "/", "r", "o", "o", "t", "/", "d", "i", "r", "/", "c", "o", "p", "y", ".",
+            "r", "s", "enter",
+        ]);
+        cx.run_until_parked();
+        assert_eq!(
+            fs.load(Path::new("/root/dir/copy.rs")).await.unwrap(),
+            "@\n"
+        );
+
+        cx.simulate_keystrokes([
+            ":", "e", "/", "r", "o", "o", "t", "/", "d", "i", "r", "/", "c", "o", "p", "y", ".",
+            "r", "s", "enter",
+        ]);
+        cx.workspace(|workspace, cx| {
+            let path = workspace.active_item(cx).unwrap().project_path(cx).unwrap();
+            assert_eq!(path.path.as_ref(), Path::new("copy.rs"));
+        });
+    }
+
     #[gpui::test]
     async fn test_command_quit(cx: &mut TestAppContext) {
         let mut cx = VimTestContext::new(cx, true).await;
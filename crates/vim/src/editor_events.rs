@@ -21,7 +21,7 @@ pub fn init(cx: &mut AppContext) {
 fn focused(editor: View<Editor>, cx: &mut WindowContext) {
     if Vim::read(cx).active_editor.clone().is_some() {
         Vim::update(cx, |vim, cx| {
-            vim.update_active_editor(cx, |previously_active_editor, cx| {
+            vim.update_active_editor(cx, |vim, previously_active_editor, cx| {
                 vim.unhook_vim_settings(previously_active_editor, cx)
             });
         });
@@ -1,10 +1,10 @@
 use editor::{
     display_map::{DisplaySnapshot, FoldPoint, ToDisplayPoint},
     movement::{self, find_boundary, find_preceding_boundary, FindRange, TextLayoutDetails},
-    Bias, DisplayPoint, ToOffset,
+    Anchor, Bias, DisplayPoint, ToOffset,
 };
 use gpui::{actions, impl_actions, px, ViewContext, WindowContext};
-use language::{char_kind, CharKind, Point, Selection, SelectionGoal};
+use language::{char_kind, CharKind, Point, Selection, SelectionGoal, TextObject};
 use serde::Deserialize;
 use workspace::Workspace;
 
@@ -41,6 +41,15 @@ pub enum Motion {
     StartOfLineDownward,
     EndOfLineDownward,
     GoToColumn,
+    Jump { anchor: Anchor, line: bool },
+    NextMethodStart,
+    NextMethodEnd,
+    PreviousMethodStart,
+    PreviousMethodEnd,
+    NextSectionStart,
+    NextSectionEnd,
+    PreviousSectionStart,
+    PreviousSectionEnd,
 }
 
 #[derive(Clone, Deserialize, PartialEq)]
@@ -136,6 +145,14 @@ actions!(
         StartOfLineDownward,
         EndOfLineDownward,
         GoToColumn,
+        NextMethodStart,
+        NextMethodEnd,
+        PreviousMethodStart,
+        PreviousMethodEnd,
+        NextSectionStart,
+        NextSectionEnd,
+        PreviousSectionStart,
+        PreviousSectionEnd,
     ]
 );
 
@@ -228,20 +245,51 @@ pub fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
     });
     workspace
         .register_action(|_: &mut Workspace, &GoToColumn, cx: _| motion(Motion::GoToColumn, cx));
+    workspace.register_action(|_: &mut Workspace, &NextMethodStart, cx: _| {
+        motion(Motion::NextMethodStart, cx)
+    });
+    workspace.register_action(|_: &mut Workspace, &NextMethodEnd, cx: _| {
+        motion(Motion::NextMethodEnd, cx)
+    });
+    workspace.register_action(|_: &mut Workspace, &PreviousMethodStart, cx: _| {
+        motion(Motion::PreviousMethodStart, cx)
+    });
+    workspace.register_action(|_: &mut Workspace, &PreviousMethodEnd, cx: _| {
+        motion(Motion::PreviousMethodEnd, cx)
+    });
+    workspace.register_action(|_: &mut Workspace, &NextSectionStart, cx: _| {
+        motion(Motion::NextSectionStart, cx)
+    });
+    workspace.register_action(|_: &mut Workspace, &NextSectionEnd, cx: _| {
+        motion(Motion::NextSectionEnd, cx)
+    });
+    workspace.register_action(|_: &mut Workspace, &PreviousSectionStart, cx: _| {
+        motion(Motion::PreviousSectionStart, cx)
+    });
+    workspace.register_action(|_: &mut Workspace, &PreviousSectionEnd, cx: _| {
+        motion(Motion::PreviousSectionEnd, cx)
+    });
     workspace.register_action(|_: &mut Workspace, action: &RepeatFind, cx: _| {
         repeat_motion(action.backwards, cx)
     });
 }
 
 pub(crate) fn motion(motion: Motion, cx: &mut WindowContext) {
-    if let Some(Operator::FindForward { .. }) | Some(Operator::FindBackward { .. }) =
-        Vim::read(cx).active_operator()
+    if let Some(
+        Operator::FindForward { .. } | Operator::FindBackward { .. } | Operator::Jump { .. },
+    ) = Vim::read(cx).active_operator()
     {
         Vim::update(cx, |vim, cx| vim.pop_operator(cx));
     }
 
     let count = Vim::update(cx, |vim, cx| vim.take_count(cx));
     let operator = Vim::read(cx).active_operator();
+    let jump_origin = Vim::update(cx, |vim, cx| {
+        if operator.is_some() || !motion.push_to_jump_list() {
+            return None;
+        }
+        vim.update_active_editor(cx, |_, editor, _| editor.selections.newest_anchor().head())
+    });
     match Vim::read(cx).state().mode {
         Mode::Normal => normal_motion(motion, operator, count, cx),
         Mode::Visual | Mode::VisualLine | Mode::VisualBlock => visual_motion(motion, count, cx),
@@ -249,7 +297,12 @@ pub(crate) fn motion(motion: Motion, cx: &mut WindowContext) {
             // Shouldn't execute a motion in insert mode. Ignoring
         }
     }
-    Vim::update(cx, |vim, cx| vim.clear_operator(cx));
+    Vim::update(cx, |vim, cx| {
+        if let Some(origin) = jump_origin {
+            vim.update_active_editor(cx, |_, editor, cx| editor.record_jump(origin, cx));
+        }
+        vim.clear_operator(cx)
+    });
 }
 
 fn repeat_motion(backwards: bool, cx: &mut WindowContext) {
@@ -296,6 +349,7 @@ impl Motion {
             | StartOfLineDownward
             | StartOfParagraph
             | EndOfParagraph => true,
+            Jump { line, .. } => *line,
             EndOfLine { .. }
             | NextWordEnd { .. }
             | Matching
@@ -309,14 +363,22 @@ impl Motion {
             | NextWordStart { .. }
             | PreviousWordStart { .. }
             | FirstNonWhitespace { .. }
-            | FindBackward { .. } => false,
+            | FindBackward { .. }
+            | NextMethodStart
+            | NextMethodEnd
+            | PreviousMethodStart
+            | PreviousMethodEnd
+            | NextSectionStart
+            | NextSectionEnd
+            | PreviousSectionStart
+            | PreviousSectionEnd => false,
         }
     }
 
     pub fn infallible(&self) -> bool {
         use Motion::*;
         match self {
-            StartOfDocument | EndOfDocument | CurrentLine => true,
+            StartOfDocument | EndOfDocument | CurrentLine | Jump { .. } => true,
             Down { .. }
             | Up { .. }
             | EndOfLine { .. }
@@ -336,7 +398,15 @@ impl Motion {
             | PreviousWordStart { .. }
             | FirstNonWhitespace { .. }
             | FindBackward { .. }
-            | NextLineStart => false,
+            | NextLineStart
+            | NextMethodStart
+            | NextMethodEnd
+            | PreviousMethodStart
+            | PreviousMethodEnd
+            | NextSectionStart
+            | NextSectionEnd
+            | PreviousSectionStart
+            | PreviousSectionEnd => false,
         }
     }
 
@@ -355,6 +425,7 @@ impl Motion {
             | FindForward { .. }
             | NextLineStart => true,
             Left
+            | Jump { .. }
             | Backspace
             | Right
             | StartOfLine { .. }
@@ -365,7 +436,54 @@ impl Motion {
             | NextWordStart { .. }
             | PreviousWordStart { .. }
             | FirstNonWhitespace { .. }
-            | FindBackward { .. } => false,
+            | FindBackward { .. }
+            | NextMethodStart
+            | NextMethodEnd
+            | PreviousMethodStart
+            | PreviousMethodEnd
+            | NextSectionStart
+            | NextSectionEnd
+            | PreviousSectionStart
+            | PreviousSectionEnd => false,
+        }
+    }
+
+    /// Whether moving the cursor with this motion is a jump, which `ctrl-o` returns from.
+    pub fn push_to_jump_list(&self) -> bool {
+        use Motion::*;
+        match self {
+            StartOfDocument
+            | EndOfDocument
+            | StartOfParagraph
+            | EndOfParagraph
+            | Matching
+            | Jump { .. }
+            | NextMethodStart
+            | NextMethodEnd
+            | PreviousMethodStart
+            | PreviousMethodEnd
+            | NextSectionStart
+            | NextSectionEnd
+            | PreviousSectionStart
+            | PreviousSectionEnd => true,
+            Left
+            | Backspace
+            | Down { .. }
+            | Up { .. }
+            | Right
+            | NextWordStart { .. }
+            | NextWordEnd { .. }
+            | PreviousWordStart { .. }
+            | FirstNonWhitespace { .. }
+            | CurrentLine
+            | StartOfLine { .. }
+            | EndOfLine { .. }
+            | FindForward { .. }
+            | FindBackward { .. }
+            | NextLineStart
+            | StartOfLineDownward
+            | EndOfLineDownward
+            | GoToColumn => false,
         }
     }
 
@@ -446,6 +564,46 @@ impl Motion {
             StartOfLineDownward => (next_line_start(map, point, times - 1), SelectionGoal::None),
             EndOfLineDownward => (next_line_end(map, point, times), SelectionGoal::None),
             GoToColumn => (go_to_column(map, point, times), SelectionGoal::None),
+            Jump { anchor, line } => {
+                let point = anchor.to_display_point(map);
+                if *line {
+                    (first_non_whitespace(map, false, point), SelectionGoal::None)
+                } else {
+                    (point, SelectionGoal::None)
+                }
+            }
+            NextMethodStart => (
+                text_object_boundary(map, point, TextObject::AroundFunction, true, false, times),
+                SelectionGoal::None,
+            ),
+            NextMethodEnd => (
+                text_object_boundary(map, point, TextObject::AroundFunction, true, true, times),
+                SelectionGoal::None,
+            ),
+            PreviousMethodStart => (
+                text_object_boundary(map, point, TextObject::AroundFunction, false, false, times),
+                SelectionGoal::None,
+            ),
+            PreviousMethodEnd => (
+                text_object_boundary(map, point, TextObject::AroundFunction, false, true, times),
+                SelectionGoal::None,
+            ),
+            NextSectionStart => (
+                text_object_boundary(map, point, TextObject::AroundClass, true, false, times),
+                SelectionGoal::None,
+            ),
+            NextSectionEnd => (
+                text_object_boundary(map, point, TextObject::AroundClass, true, true, times),
+                SelectionGoal::None,
+            ),
+            PreviousSectionStart => (
+                text_object_boundary(map, point, TextObject::AroundClass, false, false, times),
+                SelectionGoal::None,
+            ),
+            PreviousSectionEnd => (
+                text_object_boundary(map, point, TextObject::AroundClass, false, true, times),
+                SelectionGoal::None,
+            ),
         };
 
         (new_point != point || infallible).then_some((new_point, goal))
@@ -941,6 +1099,71 @@ fn go_to_column(map: &DisplaySnapshot, point: DisplayPoint, times: usize) -> Dis
     right(map, correct_line, times.saturating_sub(1))
 }
 
+/// The number of bytes around the cursor that text objects are looked for in first. The
+/// window doubles until it holds enough text objects or covers the buffer.
+const TEXT_OBJECT_SEARCH_WINDOW: usize = 4096;
+
+/// Moves to the start, or with `end` the last character, of the next or previous text object
+/// from the language's `textobjects` query. With a count larger than the number of text
+/// objects, it moves to the last one.
+fn text_object_boundary(
+    map: &DisplaySnapshot,
+    point: DisplayPoint,
+    text_object: TextObject,
+    forwards: bool,
+    end: bool,
+    times: usize,
+) -> DisplayPoint {
+    let buffer = &map.buffer_snapshot;
+    let offset = point.to_offset(map, Bias::Left);
+    let mut window = TEXT_OBJECT_SEARCH_WINDOW;
+    loop {
+        let range = if forwards {
+            offset..buffer.clip_offset(offset.saturating_add(window).min(buffer.len()), Bias::Right)
+        } else {
+            buffer.clip_offset(offset.saturating_sub(window), Bias::Left)..offset
+        };
+        let searched_buffer = if forwards {
+            range.end == buffer.len()
+        } else {
+            range.start == 0
+        };
+
+        // Text objects that overlap the window can have boundaries outside of it, and those
+        // may come after ones in the text that the window doesn't reach yet.
+        let mut boundaries = buffer
+            .text_object_ranges(range.clone(), &[text_object])
+            .into_iter()
+            .map(|(range, _)| {
+                if end {
+                    buffer.clip_offset(range.end.saturating_sub(1), Bias::Left)
+                } else {
+                    range.start
+                }
+            })
+            .filter(|boundary| {
+                if forwards {
+                    *boundary > offset && *boundary <= range.end
+                } else {
+                    *boundary < offset && *boundary >= range.start
+                }
+            })
+            .collect::<Vec<_>>();
+        boundaries.sort_unstable();
+        boundaries.dedup();
+
+        if boundaries.len() >= times || searched_buffer {
+            let boundary = if forwards {
+                boundaries.into_iter().take(times).last()
+            } else {
+                boundaries.into_iter().rev().take(times).last()
+            };
+            return boundary.map_or(point, |boundary| boundary.to_display_point(map));
+        }
+        window = window.saturating_mul(2);
+    }
+}
+
 pub(crate) fn next_line_end(
     map: &DisplaySnapshot,
     mut point: DisplayPoint,
@@ -955,7 +1178,10 @@ pub(crate) fn next_line_end(
 #[cfg(test)]
 mod test {
 
-    use crate::test::NeovimBackedTestContext;
+    use crate::{
+        state::Mode,
+        test::{NeovimBackedTestContext, VimTestContext},
+    };
     use indoc::indoc;
 
     #[gpui::test]
@@ -1104,4 +1330,103 @@ mod test {
         cx.simulate_shared_keystrokes(["enter"]).await;
         cx.assert_shared_state("one\n  ˇtwo\nthree").await;
     }
+
+    #[gpui::test]
+    async fn test_method_and_section_motions(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.set_state(
+            indoc! {"
+            ˇimpl A {
+                fn a() {}
+            }
+
+            impl B {
+                fn b() {}
+            }"
+            },
+            Mode::Normal,
+        );
+
+        cx.simulate_keystrokes(["]", "m"]);
+        cx.assert_state(
+            indoc! {"
+            impl A {
+                ˇfn a() {}
+            }
+
+            impl B {
+                fn b() {}
+            }"
+            },
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(["]", "m"]);
+        cx.assert_state(
+            indoc! {"
+            impl A {
+                fn a() {}
+            }
+
+            impl B {
+                ˇfn b() {}
+            }"
+            },
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(["[", "m"]);
+        cx.simulate_keystrokes(["]", "shift-m"]);
+        cx.assert_state(
+            indoc! {"
+            impl A {
+                fn a() {ˇ}
+            }
+
+            impl B {
+                fn b() {}
+            }"
+            },
+            Mode::Normal,
+        );
+
+        cx.simulate_keystrokes(["]", "]"]);
+        cx.assert_state(
+            indoc! {"
+            impl A {
+                fn a() {}
+            }
+
+            ˇimpl B {
+                fn b() {}
+            }"
+            },
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(["[", "]"]);
+        cx.assert_state(
+            indoc! {"
+            impl A {
+                fn a() {}
+            ˇ}
+
+            impl B {
+                fn b() {}
+            }"
+            },
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(["[", "["]);
+        cx.simulate_keystrokes(["2", "]", "m"]);
+        cx.assert_state(
+            indoc! {"
+            impl A {
+                fn a() {}
+            }
+
+            impl B {
+                ˇfn b() {}
+            }"
+            },
+            Mode::Normal,
+        );
+    }
 }
@@ -0,0 +1,595 @@
+use std::sync::Arc;
+
+use editor::{scroll::Autoscroll, Anchor, Bias, Editor, ToOffset, ToPoint};
+use gpui::{actions, AppContext, ViewContext, WindowContext};
+use language::{Buffer, Point, ToPoint as _};
+use workspace::Workspace;
+
+use crate::{
+    motion::{self, Motion},
+    persistence::DB,
+    state::GlobalMark,
+    Vim,
+};
+
+/// The number of changes that `g;` and `g,` remember in each buffer.
+const MAX_CHANGE_LIST_LEN: usize = 100;
+
+actions!(vim, [PreviousChange, NextChange]);
+
+pub(crate) fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+    workspace.register_action(|_: &mut Workspace, _: &PreviousChange, cx| {
+        move_in_change_list(false, cx)
+    });
+    workspace
+        .register_action(|_: &mut Workspace, _: &NextChange, cx| move_in_change_list(true, cx));
+}
+
+impl Vim {
+    /// Sets a mark at the cursor, as typed after `m`.
+    pub(crate) fn create_mark(&mut self, mark: char, cx: &mut WindowContext) {
+        self.pop_operator(cx);
+        if mark.is_ascii_uppercase() {
+            self.create_global_mark(mark, cx);
+        } else if mark.is_ascii_lowercase() || "[]<>".contains(mark) {
+            self.update_active_editor(cx, |vim, editor, cx| {
+                vim.set_mark(mark, editor.selections.newest_anchor().head(), editor, cx)
+            });
+        } else {
+            self.clear_operator(cx);
+        }
+    }
+
+    /// Sets a buffer-local mark of the buffer at the anchor.
+    pub(crate) fn set_mark(
+        &mut self,
+        mark: char,
+        anchor: Anchor,
+        editor: &Editor,
+        cx: &mut AppContext,
+    ) {
+        if let Some((buffer_id, anchor)) = self.buffer_anchor(anchor, editor, cx) {
+            self.workspace_state
+                .marks
+                .entry(buffer_id)
+                .or_default()
+                .insert(mark, anchor);
+        }
+    }
+
+    /// Sets an uppercase mark, and saves it with the workspace when its buffer has a path.
+    fn create_global_mark(&mut self, mark: char, cx: &mut WindowContext) {
+        let Some(global_mark) = self.update_active_editor(cx, |vim, editor, cx| {
+            let anchor = editor.selections.newest_anchor().head();
+            let buffer = editor.buffer().read(cx);
+            let abs_path = buffer.as_singleton().and_then(|buffer| {
+                let file = buffer.read(cx).file()?.as_local()?;
+                Some(file.abs_path(cx))
+            });
+            let point = anchor.to_point(&buffer.snapshot(cx));
+            GlobalMark {
+                anchor: vim.buffer_anchor(anchor, editor, cx),
+                abs_path,
+                point,
+                workspace_id: editor
+                    .workspace()
+                    .map(|workspace| workspace.read(cx).database_id()),
+            }
+        }) else {
+            return;
+        };
+
+        save_global_mark(mark, &global_mark, cx);
+        self.workspace_state.global_marks.insert(mark, global_mark);
+    }
+
+    /// Saves the uppercase marks of the workspace again at the positions they moved to in
+    /// their open buffers.
+    pub(crate) fn save_global_marks(&mut self, workspace: &Workspace, cx: &AppContext) {
+        let workspace_id = workspace.database_id();
+        let project = workspace.project().read(cx);
+        for (mark, global_mark) in &mut self.workspace_state.global_marks {
+            if global_mark.workspace_id != Some(workspace_id) {
+                continue;
+            }
+            let Some(buffer) = global_mark
+                .anchor
+                .and_then(|(buffer_id, _)| project.buffer_for_id(buffer_id))
+            else {
+                continue;
+            };
+            if update_global_mark_point(global_mark, buffer.read(cx)) {
+                save_global_mark(*mark, global_mark, cx);
+            }
+        }
+    }
+
+    /// An uppercase mark, which is read from the workspace's saved marks if it wasn't set
+    /// since the workspace was opened.
+    fn global_mark(&mut self, mark: char, cx: &mut WindowContext) -> Option<GlobalMark> {
+        if let Some(global_mark) = self.workspace_state.global_marks.get(&mark) {
+            return Some(global_mark.clone());
+        }
+        let workspace = self.update_active_editor(cx, |_, editor, _| editor.workspace())??;
+        let workspace_id = workspace.read(cx).database_id();
+        let (abs_path, row, column) = DB.get_global_mark(workspace_id, mark.to_string()).ok()??;
+        let global_mark = GlobalMark {
+            anchor: None,
+            abs_path: Some(abs_path),
+            point: Point::new(row, column),
+            workspace_id: Some(workspace_id),
+        };
+        self.workspace_state
+            .global_marks
+            .insert(mark, global_mark.clone());
+        Some(global_mark)
+    }
+
+    /// Sets the `<` and `>` marks to the first and last characters of the newest selection,
+    /// when leaving visual mode.
+    pub(crate) fn record_visual_marks(&mut self, editor: &Editor, cx: &mut AppContext) {
+        let snapshot = editor.buffer().read(cx).snapshot(cx);
+        let selection = editor.selections.newest::<usize>(cx);
+        let end = snapshot
+            .reversed_chars_at(selection.end)
+            .next()
+            .filter(|_| !selection.is_empty())
+            .map_or(selection.end, |c| selection.end - c.len_utf8());
+        self.set_mark('<', snapshot.anchor_before(selection.start), editor, cx);
+        self.set_mark('>', snapshot.anchor_before(end), editor, cx);
+    }
+
+    /// Records the position of an edit as the `.` mark, and in the buffer's change list.
+    pub(crate) fn record_change(&mut self, editor: &Editor, cx: &mut AppContext) {
+        let anchor = editor.selections.newest_anchor().head();
+        self.set_mark('.', anchor, editor, cx);
+        let Some((buffer_id, anchor)) = self.buffer_anchor(anchor, editor, cx) else {
+            return;
+        };
+        let Some(buffer) = editor.buffer().read(cx).buffer(buffer_id) else {
+            return;
+        };
+        let buffer = buffer.read(cx);
+
+        let change_list = self
+            .workspace_state
+            .change_lists
+            .entry(buffer_id)
+            .or_default();
+        change_list.position = None;
+        // Like vim, consecutive changes within a line are remembered as one.
+        if let Some(last_change) = change_list.changes.last_mut() {
+            if last_change.to_point(buffer).row == anchor.to_point(buffer).row {
+                *last_change = anchor;
+                return;
+            }
+        }
+        change_list.changes.push(anchor);
+        if change_list.changes.len() > MAX_CHANGE_LIST_LEN {
+            change_list.changes.remove(0);
+        }
+    }
+
+    /// The buffer of a position in the editor and the position within that buffer. The buffer
+    /// is watched so that its marks and changes are forgotten once it's released.
+    fn buffer_anchor(
+        &self,
+        anchor: Anchor,
+        editor: &Editor,
+        cx: &mut AppContext,
+    ) -> Option<(u64, language::Anchor)> {
+        let multi_buffer = editor.buffer().read(cx);
+        let (buffer, anchor) = match anchor.buffer_id.and_then(|id| multi_buffer.buffer(id)) {
+            Some(buffer) => (buffer, anchor.text_anchor),
+            None => {
+                let offset = anchor.to_offset(&multi_buffer.snapshot(cx));
+                multi_buffer.text_anchor_for_position(offset, cx)?
+            }
+        };
+        let buffer_id = buffer.read(cx).remote_id();
+
+        let state = &self.workspace_state;
+        if !state.marks.contains_key(&buffer_id) && !state.change_lists.contains_key(&buffer_id) {
+            cx.observe_release(&buffer, |buffer, cx| {
+                cx.update_global(|vim: &mut Vim, cx| vim.forget_buffer(buffer, cx))
+            })
+            .detach();
+        }
+        Some((buffer_id, anchor))
+    }
+
+    /// Forgets the marks and changes of a released buffer. Its uppercase marks keep the
+    /// position they were at, to reopen the file with, and are saved there.
+    fn forget_buffer(&mut self, buffer: &Buffer, cx: &AppContext) {
+        let buffer_id = buffer.remote_id();
+        self.workspace_state.marks.remove(&buffer_id);
+        self.workspace_state.change_lists.remove(&buffer_id);
+        for (mark, global_mark) in &mut self.workspace_state.global_marks {
+            if global_mark
+                .anchor
+                .map_or(false, |(mark_buffer_id, _)| mark_buffer_id == buffer_id)
+            {
+                if update_global_mark_point(global_mark, buffer) {
+                    save_global_mark(*mark, global_mark, cx);
+                }
+                global_mark.anchor = None;
+            }
+        }
+    }
+
+    /// The anchor of a buffer-local mark in the active editor.
+    fn mark(&mut self, mark: char, cx: &mut WindowContext) -> Option<Anchor> {
+        self.update_active_editor(cx, |vim, editor, cx| vim.mark_in_editor(mark, editor, cx))?
+    }
+
+    /// The anchor of a mark in the buffer of the editor's newest cursor, which is either a
+    /// buffer-local mark or an uppercase mark that was set in the same buffer.
+    pub(crate) fn mark_in_editor(
+        &self,
+        mark: char,
+        editor: &Editor,
+        cx: &AppContext,
+    ) -> Option<Anchor> {
+        let (buffer_id, anchor) = if mark.is_ascii_uppercase() {
+            self.workspace_state.global_marks.get(&mark)?.anchor?
+        } else {
+            let buffer_id = cursor_buffer_id(editor, cx)?;
+            let anchor = *self.workspace_state.marks.get(&buffer_id)?.get(&mark)?;
+            (buffer_id, anchor)
+        };
+        editor_anchor(editor, buffer_id, anchor, cx)
+    }
+}
+
+/// Moves the saved position of an uppercase mark to where its anchor is in the buffer,
+/// returning whether it moved.
+fn update_global_mark_point(global_mark: &mut GlobalMark, buffer: &Buffer) -> bool {
+    let Some((_, anchor)) = global_mark.anchor else {
+        return false;
+    };
+    let point = anchor.to_point(buffer);
+    let moved = point != global_mark.point;
+    global_mark.point = point;
+    moved
+}
+
+/// Saves an uppercase mark with its workspace, if it has one and its buffer has a path.
+fn save_global_mark(mark: char, global_mark: &GlobalMark, cx: &AppContext) {
+    let (Some(workspace_id), Some(abs_path)) =
+        (global_mark.workspace_id, global_mark.abs_path.clone())
+    else {
+        return;
+    };
+    let point = global_mark.point;
+    cx.background_executor()
+        .spawn(async move {
+            DB.save_global_mark(
+                workspace_id,
+                mark.to_string(),
+                abs_path,
+                point.row,
+                point.column,
+            )
+            .await
+        })
+        .detach_and_log_err(cx);
+}
+
+/// The remote id of the buffer that the editor's newest cursor is in.
+fn cursor_buffer_id(editor: &Editor, cx: &AppContext) -> Option<u64> {
+    let head = editor.selections.newest_anchor().head();
+    if head.buffer_id.is_some() {
+        return head.buffer_id;
+    }
+    let multi_buffer = editor.buffer().read(cx);
+    let offset = head.to_offset(&multi_buffer.snapshot(cx));
+    let (buffer, _) = multi_buffer.text_anchor_for_position(offset, cx)?;
+    Some(buffer.read(cx).remote_id())
+}
+
+/// The position in the editor of an anchor in one of its buffers, if one of the editor's
+/// excerpts shows it.
+fn editor_anchor(
+    editor: &Editor,
+    buffer_id: u64,
+    anchor: language::Anchor,
+    cx: &AppContext,
+) -> Option<Anchor> {
+    let multi_buffer = editor.buffer().read(cx);
+    let buffer = multi_buffer.buffer(buffer_id)?;
+    let buffer_snapshot = buffer.read(cx);
+    let (excerpt_id, _) = multi_buffer
+        .excerpts_for_buffer(&buffer, cx)
+        .into_iter()
+        .find(|(_, range)| {
+            range.context.start.cmp(&anchor, buffer_snapshot).is_le()
+                && range.context.end.cmp(&anchor, buffer_snapshot).is_ge()
+        })?;
+    Some(
+        multi_buffer
+            .snapshot(cx)
+            .anchor_in_excerpt(excerpt_id, anchor),
+    )
+}
+
+/// Jumps to a mark, as typed after `` ` `` or `'`. With `'`, the jump goes to the first
+/// non-blank character of the mark's line and is linewise.
+pub(crate) fn jump(text: Arc<str>, line: bool, cx: &mut WindowContext) {
+    let Some(mark) = text.chars().next() else {
+        return;
+    };
+    if mark.is_ascii_uppercase() {
+        return jump_to_global_mark(mark, line, cx);
+    }
+
+    match Vim::update(cx, |vim, cx| vim.mark(mark, cx)) {
+        Some(anchor) => motion::motion(Motion::Jump { anchor, line }, cx),
+        None => Vim::update(cx, |vim, cx| vim.clear_operator(cx)),
+    }
+}
+
+fn jump_to_global_mark(mark: char, line: bool, cx: &mut WindowContext) {
+    let Some((global_mark, anchor, workspace)) = Vim::update(cx, |vim, cx| {
+        let global_mark = vim.global_mark(mark, cx)?;
+        let (anchor, workspace) = vim.update_active_editor(cx, |_, editor, cx| {
+            let anchor = global_mark
+                .anchor
+                .and_then(|(buffer_id, anchor)| editor_anchor(editor, buffer_id, anchor, cx));
+            (anchor, editor.workspace())
+        })?;
+        Some((global_mark, anchor, workspace))
+    }) else {
+        Vim::update(cx, |vim, cx| vim.clear_operator(cx));
+        return;
+    };
+
+    if let Some(anchor) = anchor {
+        return motion::motion(Motion::Jump { anchor, line }, cx);
+    }
+
+    // The mark is in another file, so operators don't apply to the jump.
+    Vim::update(cx, |vim, cx| vim.clear_operator(cx));
+    let (Some(workspace), Some(abs_path)) = (workspace, global_mark.abs_path) else {
+        return;
+    };
+    let open = workspace.update(cx, |workspace, cx| {
+        workspace.open_abs_path(abs_path, true, cx)
+    });
+    cx.spawn(|mut cx| async move {
+        let item = open.await?;
+        let Some(editor) = item.downcast::<Editor>() else {
+            return Ok(());
+        };
+        editor.update(&mut cx, |editor, cx| {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            // The anchor belongs to a buffer that has since been closed, so the saved position
+            // is used instead.
+            let mut point = match global_mark
+                .anchor
+                .and_then(|(buffer_id, anchor)| editor_anchor(editor, buffer_id, anchor, cx))
+            {
+                Some(anchor) => anchor.to_point(&snapshot),
+                None => snapshot.clip_point(global_mark.point, Bias::Left),
+            };
+            if line {
+                point = Point::new(point.row, snapshot.indent_size_for_line(point.row).len);
+            }
+            editor.change_selections(Some(Autoscroll::center()), cx, |s| {
+                s.select_ranges([point..point])
+            });
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+/// Moves to an older change with `g;`, or a newer one with `g,`, in the buffer of the cursor.
+fn move_in_change_list(newer: bool, cx: &mut WindowContext) {
+    let anchor = Vim::update(cx, |vim, cx| {
+        let count = vim.take_count(cx).unwrap_or(1);
+        let editor = vim.active_editor.clone()?.upgrade()?;
+        let editor = editor.read(cx);
+        let buffer_id = cursor_buffer_id(editor, cx)?;
+        let change_list = vim.workspace_state.change_lists.get_mut(&buffer_id)?;
+        let len = change_list.changes.len();
+        let current = change_list.position.unwrap_or(len);
+        // Like vim, a count past either end of the list stops at that end.
+        let position = if newer {
+            if current + 1 >= len {
+                return None;
+            }
+            (current + count).min(len - 1)
+        } else {
+            if current == 0 {
+                return None;
+            }
+            current.saturating_sub(count)
+        };
+        change_list.position = Some(position);
+        editor_anchor(editor, buffer_id, change_list.changes[position], cx)
+    });
+
+    match anchor {
+        Some(anchor) => motion::motion(
+            Motion::Jump {
+                anchor,
+                line: false,
+            },
+            cx,
+        ),
+        None => Vim::update(cx, |vim, cx| vim.clear_operator(cx)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use editor::Editor;
+    use gpui::Context;
+    use indoc::indoc;
+    use language::Buffer;
+
+    use crate::{
+        state::Mode,
+        test::{NeovimBackedTestContext, VimTestContext},
+        Vim,
+    };
+
+    #[gpui::test]
+    async fn test_marks(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+
+        cx.set_shared_state(indoc! {"
+            liˇne one
+                line two
+            line three"})
+            .await;
+        cx.simulate_shared_keystrokes(["m", "a", "j", "j", "`", "a"])
+            .await;
+        cx.assert_shared_state(indoc! {"
+            liˇne one
+                line two
+            line three"})
+            .await;
+        cx.simulate_shared_keystrokes(["j", "m", "b", "k", "'", "b"])
+            .await;
+        cx.assert_shared_state(indoc! {"
+            line one
+                ˇline two
+            line three"})
+            .await;
+        cx.simulate_shared_keystrokes(["d", "'", "a"]).await;
+        cx.assert_shared_state("ˇline three").await;
+    }
+
+    #[gpui::test]
+    async fn test_special_marks(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+
+        cx.set_shared_state(indoc! {"
+            ˇone
+            two
+            three"})
+            .await;
+        cx.simulate_shared_keystrokes(["j", "x", "shift-g", "'", "."])
+            .await;
+        cx.assert_shared_state(indoc! {"
+            one
+            ˇwo
+            three"})
+            .await;
+
+        cx.set_shared_state("ˇone two three").await;
+        cx.simulate_shared_keystrokes(["v", "e", "escape", "$", "`", "<"])
+            .await;
+        cx.assert_shared_state("ˇone two three").await;
+        cx.simulate_shared_keystrokes(["`", ">"]).await;
+        cx.assert_shared_state("onˇe two three").await;
+    }
+
+    #[gpui::test]
+    async fn test_change_list(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+
+        cx.set_shared_state(indoc! {"
+            ˇone
+            two
+            three
+            four"})
+            .await;
+        cx.simulate_shared_keystrokes(["x", "j", "j", "x", "shift-g", "g", ";"])
+            .await;
+        cx.assert_shared_state(indoc! {"
+            ne
+            two
+            ˇhree
+            four"})
+            .await;
+        cx.simulate_shared_keystrokes(["g", ";"]).await;
+        cx.assert_shared_state(indoc! {"
+            ˇne
+            two
+            hree
+            four"})
+            .await;
+        cx.simulate_shared_keystrokes(["g", ","]).await;
+        cx.assert_shared_state(indoc! {"
+            ne
+            two
+            ˇhree
+            four"})
+            .await;
+    }
+
+    #[gpui::test]
+    async fn test_jump_list(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+
+        cx.set_shared_state(indoc! {"
+            oˇne
+            two
+            three"})
+            .await;
+        cx.simulate_shared_keystrokes(["shift-g", "ctrl-o"]).await;
+        cx.assert_shared_state(indoc! {"
+            oˇne
+            two
+            three"})
+            .await;
+        cx.simulate_shared_keystrokes(["ctrl-i"]).await;
+        cx.assert_shared_state(indoc! {"
+            one
+            two
+            ˇthree"})
+            .await;
+    }
+
+    #[gpui::test]
+    async fn test_global_marks(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state(
+            indoc! {"
+            one
+            tˇwo
+            three"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(["m", "shift-a", "g", "g", "`", "shift-a"]);
+        cx.assert_state(
+            indoc! {"
+            one
+            tˇwo
+            three"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(["shift-g", "'", "shift-a"]);
+        cx.assert_state(
+            indoc! {"
+            one
+            ˇtwo
+            three"},
+            Mode::Normal,
+        );
+    }
+
+    #[gpui::test]
+    async fn test_marks_of_released_buffers(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        let buffer = cx.new_model(|_| Buffer::new(0, 100, "one\ntwo\n"));
+        let window = cx.add_window(|cx| Editor::for_buffer(buffer, None, cx));
+        cx.update(|cx| {
+            window.update(cx, |editor, cx| {
+                let anchor = editor.selections.newest_anchor().head();
+                cx.update_global(|vim: &mut Vim, cx| vim.set_mark('a', anchor, editor, cx));
+            })
+        })
+        .unwrap();
+        cx.update(|cx| assert!(Vim::read(cx).workspace_state.marks.contains_key(&100)));
+
+        // the marks are forgotten once the buffer's editor is closed
+        cx.update(|cx| window.update(cx, |_, cx| cx.remove_window()))
+            .unwrap();
+        cx.run_until_parked();
+        cx.update(|cx| assert!(!Vim::read(cx).workspace_state.marks.contains_key(&100)));
+    }
+}
@@ -1,8 +1,6 @@
 use std::{borrow::Cow, cmp};
 
-use editor::{
-    display_map::ToDisplayPoint, movement, scroll::Autoscroll, ClipboardSelection, DisplayPoint,
-};
+use editor::{display_map::ToDisplayPoint, movement, scroll::Autoscroll, DisplayPoint};
 use gpui::{impl_actions, ViewContext};
 use language::{Bias, SelectionGoal};
 use serde::Deserialize;
@@ -10,9 +8,9 @@ use workspace::Workspace;
 
 use crate::{state::Mode, utils::copy_selections_content, Vim};
 
-#[derive(Clone, Deserialize, PartialEq)]
+#[derive(Clone, Default, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
-struct Paste {
+pub(crate) struct Paste {
     #[serde(default)]
     before: bool,
     #[serde(default)]
@@ -28,27 +26,29 @@ pub(crate) fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>
 fn paste(_: &mut Workspace, action: &Paste, cx: &mut ViewContext<Workspace>) {
     Vim::update(cx, |vim, cx| {
         vim.record_current_action(cx);
-        vim.update_active_editor(cx, |editor, cx| {
+        vim.update_active_editor(cx, |vim, editor, cx| {
             let text_layout_details = editor.text_layout_details(cx);
             editor.transact(cx, |editor, cx| {
                 editor.set_clip_at_line_ends(false, cx);
 
-                let Some(item) = cx.read_from_clipboard() else {
+                let selected_register = vim.workspace_state.selected_register.take();
+                let Some(register) = vim.read_register(selected_register, cx) else {
                     return;
                 };
-                let clipboard_text = Cow::Borrowed(item.text());
+                let clipboard_text = Cow::Borrowed(register.text.as_str());
                 if clipboard_text.is_empty() {
                     return;
                 }
 
                 if !action.preserve_clipboard && vim.state().mode.is_visual() {
-                    copy_selections_content(editor, vim.state().mode == Mode::VisualLine, cx);
+                    copy_selections_content(vim, editor, vim.state().mode == Mode::VisualLine, cx);
                 }
 
                 // if we are copying from multi-cursor (of visual block mode), we want
                 // to
                 let clipboard_selections =
-                    item.metadata::<Vec<ClipboardSelection>>()
+                    register
+                        .clipboard_selections
                         .filter(|clipboard_selections| {
                             clipboard_selections.len() > 1 && vim.state().mode != Mode::VisualLine
                         });
@@ -472,4 +472,97 @@ mod test {
             Mode::Normal,
         );
     }
+
+    #[gpui::test]
+    async fn test_named_registers_update_unnamed(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+
+        cx.set_shared_state(indoc! {"
+            The quick brown
+            fox ˇjumps over
+            the lazy dog"})
+            .await;
+        cx.simulate_shared_keystrokes(["\"", "a", "y", "w"]).await;
+        cx.assert_shared_clipboard("jumps ").await;
+
+        // appending puts the whole register in the unnamed one
+        cx.simulate_shared_keystrokes(["\"", "A", "y", "w"]).await;
+        cx.assert_shared_clipboard("jumps jumps ").await;
+
+        cx.simulate_shared_keystrokes(["\"", "b", "d", "d"]).await;
+        cx.assert_shared_clipboard("fox jumps over\n").await;
+        cx.simulate_shared_keystroke("p").await;
+        cx.assert_shared_state(indoc! {"
+            The quick brown
+            the lazy dog
+            ˇfox jumps over"})
+            .await;
+    }
+
+    #[gpui::test]
+    async fn test_paste_registers(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        // named registers keep their text through later deletes
+        cx.set_state(
+            indoc! {"
+            The quick brown
+            fox ˇjumps over
+            the lazy dog"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(["\"", "a", "y", "w", "d", "d", "\"", "a", "p"]);
+        cx.assert_state(
+            indoc! {"
+            The quick brown
+            tjumpsˇ he lazy dog"},
+            Mode::Normal,
+        );
+
+        // uppercase registers append
+        cx.set_state("ˇone two", Mode::Normal);
+        cx.simulate_keystrokes(["\"", "a", "y", "w", "w", "\"", "A", "y", "w"]);
+        cx.simulate_keystrokes(["\"", "a", "p"]);
+        cx.assert_state("one tone twˇowo", Mode::Normal);
+
+        // yanks are kept in "0 and linewise deletes in "1
+        cx.set_state(
+            indoc! {"
+            ˇone
+            two
+            three"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(["y", "y", "j", "d", "d", "\"", "0", "p"]);
+        cx.assert_state(
+            indoc! {"
+            one
+            three
+            ˇone"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(["\"", "1", "shift-p"]);
+        cx.assert_state(
+            indoc! {"
+            one
+            three
+            ˇtwo
+            one"},
+            Mode::Normal,
+        );
+
+        // the black hole register leaves the clipboard alone
+        cx.simulate_keystrokes(["\"", "_", "d", "d"]);
+        assert_eq!(
+            cx.read_from_clipboard()
+                .map(|item| item.text().clone())
+                .unwrap(),
+            "two\n"
+        );
+
+        // the last inserted text is in ".
+        cx.set_state("ˇab", Mode::Normal);
+        cx.simulate_keystrokes(["i", "x", "y", "escape", "\"", ".", "p"]);
+        cx.assert_state("xyxˇyab", Mode::Normal);
+    }
 }
@@ -1,14 +1,19 @@
 use crate::{
     insert::NormalBefore,
     motion::Motion,
-    state::{Mode, RecordedSelection, ReplayableAction},
+    state::{Mode, Operator, RecordedSelection, Register, ReplayableAction},
     visual::visual_motion,
     Vim,
 };
-use gpui::{actions, Action, ViewContext, WindowContext};
+use anyhow::Result;
+use gpui::{
+    actions, Action, AnyWindowHandle, AsyncWindowContext, Keystroke, KeystrokeEvent, Modifiers,
+    ViewContext, WindowContext,
+};
+use smol::future::yield_now;
 use workspace::Workspace;
 
-actions!(vim, [Repeat, EndRepeat]);
+actions!(vim, [Repeat, EndRepeat, ToggleRecord]);
 
 fn should_replay(action: &Box<dyn Action>) -> bool {
     // skip so that we don't leave the character palette open
@@ -48,6 +53,328 @@ pub(crate) fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>
     });
 
     workspace.register_action(|_: &mut Workspace, _: &Repeat, cx| repeat(cx, false));
+
+    workspace.register_action(|_: &mut Workspace, _: &ToggleRecord, cx| {
+        Vim::update(cx, |vim, cx| {
+            if vim.workspace_state.recording_register.is_some() {
+                vim.stop_recording_macro();
+            } else {
+                vim.push_operator(Operator::RecordRegister, cx);
+            }
+        })
+    });
+}
+
+impl Vim {
+    /// Starts recording the keystrokes that follow into a register, as typed after `q`.
+    pub(crate) fn start_recording_macro(&mut self, register: char, cx: &mut WindowContext) {
+        self.pop_operator(cx);
+        if register.is_ascii_alphanumeric() {
+            self.workspace_state.recording_register = Some(register);
+            self.workspace_state.recorded_keystrokes.clear();
+        } else {
+            self.clear_operator(cx);
+        }
+    }
+
+    /// Stores the recorded keystrokes as the text of the register, so that the macro can be
+    /// pasted, edited and yanked back. An uppercase register appends to the macro.
+    fn stop_recording_macro(&mut self) {
+        let Some(register) = self.workspace_state.recording_register.take() else {
+            return;
+        };
+        let keystrokes = std::mem::take(&mut self.workspace_state.recorded_keystrokes);
+        let registers = &mut self.workspace_state.registers;
+        if register.is_ascii_uppercase() {
+            let register = registers.entry(register.to_ascii_lowercase()).or_default();
+            register.text.push_str(&keystrokes);
+            register.clipboard_selections = None;
+        } else {
+            registers.insert(
+                register,
+                Register {
+                    text: keystrokes,
+                    clipboard_selections: None,
+                },
+            );
+        }
+    }
+
+    /// Appends an observed keystroke to the macro being recorded.
+    pub(crate) fn record_macro_keystroke(
+        &mut self,
+        keystroke_event: &KeystrokeEvent,
+        cx: &WindowContext,
+    ) {
+        if self.workspace_state.recording_register.is_none() || self.workspace_state.replaying_macro
+        {
+            return;
+        }
+        let typed = &keystroke_event.keystroke;
+        // The earlier keystrokes of a multi-key binding are pending rather than observed, so
+        // they're recovered from the binding that dispatched the action.
+        let keystrokes = keystroke_event
+            .action
+            .as_ref()
+            .and_then(|action| {
+                cx.bindings_for_action(&**action)
+                    .into_iter()
+                    .map(|binding| binding.keystrokes().to_vec())
+                    .filter(|keystrokes| {
+                        keystrokes.last().map_or(false, |keystroke| {
+                            (keystroke.key == typed.key && keystroke.modifiers == typed.modifiers)
+                                || Some(&keystroke.key) == typed.ime_key.as_ref()
+                        })
+                    })
+                    .min_by_key(|keystrokes| keystrokes.len())
+            })
+            .unwrap_or_else(|| vec![typed.clone()]);
+        for keystroke in &keystrokes {
+            self.workspace_state
+                .recorded_keystrokes
+                .push_str(&keystroke_text(keystroke));
+        }
+    }
+
+    /// Replays the text of a register as keystrokes, as typed after `@`. `@@` replays the last
+    /// register again.
+    pub(crate) fn replay_register(&mut self, register: char, cx: &mut WindowContext) {
+        self.pop_operator(cx);
+        let count = self.take_count(cx).unwrap_or(1);
+        let register = if register == '@' {
+            self.workspace_state.last_replayed_register
+        } else {
+            Some(register)
+        };
+        let Some(register) = register else {
+            return;
+        };
+        let Some(text) = self
+            .read_register(Some(register), cx)
+            .map(|register| register.text)
+        else {
+            return;
+        };
+        self.workspace_state.last_replayed_register = Some(register);
+
+        let keystrokes = parse_keystrokes(&text);
+        let keystrokes = (0..count).flat_map(|_| keystrokes.iter().cloned());
+        self.replay_keystrokes(keystrokes.collect(), cx);
+    }
+
+    /// Dispatches keystrokes one at a time, so that escape can interrupt them and they wait
+    /// for `.` to finish replaying its own actions. A macro that replays another one queues the
+    /// other's keystrokes ahead of its own.
+    pub(crate) fn replay_keystrokes(&mut self, keystrokes: Vec<Keystroke>, cx: &mut WindowContext) {
+        for keystroke in keystrokes.into_iter().rev() {
+            self.workspace_state
+                .replayed_keystrokes
+                .push_front(keystroke);
+        }
+        if self.workspace_state.replaying_macro {
+            return;
+        }
+
+        let window = cx.window_handle();
+        cx.spawn(move |mut cx| async move {
+            loop {
+                yield_now().await;
+                let Ok(true) = window.update(&mut cx, |_, cx| replay_next_keystroke(cx)) else {
+                    break;
+                };
+            }
+        })
+        .detach();
+    }
+
+    /// Abandons the rest of the keystrokes being replayed, such as when a motion or search
+    /// fails.
+    pub(crate) fn stop_replaying_macro(&mut self) {
+        self.workspace_state.replayed_keystrokes.clear();
+    }
+
+    /// Stops replaying macros when escape or ctrl-c is typed, which ends recursive macros.
+    pub(crate) fn interrupt_macro(&mut self, keystroke: &Keystroke) {
+        if self.workspace_state.replaying_macro
+            && !self.workspace_state.dispatching_replayed_keystroke
+            && (keystroke.key == "escape" || (keystroke.key == "c" && keystroke.modifiers.control))
+        {
+            self.stop_replaying_macro();
+        }
+    }
+}
+
+/// Dispatches the next keystroke being replayed, returning false once there are none left.
+/// Keystrokes wait while `.` is replaying actions, since those are dispatched asynchronously
+/// too.
+fn replay_next_keystroke(cx: &mut WindowContext) -> bool {
+    if Vim::read(cx).workspace_state.replaying {
+        return true;
+    }
+    let Some(keystroke) = Vim::update(cx, |vim, _| {
+        let keystroke = vim.workspace_state.replayed_keystrokes.pop_front();
+        vim.workspace_state.replaying_macro = keystroke.is_some();
+        vim.workspace_state.dispatching_replayed_keystroke = keystroke.is_some();
+        keystroke
+    }) else {
+        return false;
+    };
+    cx.dispatch_keystroke(keystroke);
+    Vim::update(cx, |vim, _| {
+        vim.workspace_state.dispatching_replayed_keystroke = false
+    });
+    true
+}
+
+/// Waits until the keystrokes being replayed have been dispatched, or only the given number
+/// of them are left, as well as for `.` to finish replaying.
+pub(crate) async fn wait_for_replays(
+    remaining: usize,
+    window: AnyWindowHandle,
+    cx: &mut AsyncWindowContext,
+) -> Result<()> {
+    while window.update(cx, |_, cx| {
+        let state = &Vim::read(cx).workspace_state;
+        state.replaying || state.replayed_keystrokes.len() > remaining
+    })? {
+        yield_now().await;
+    }
+    Ok(())
+}
+
+/// The vim notation of a keystroke, e.g. `a`, `A`, `<Esc>` or `<C-d>`.
+fn keystroke_text(keystroke: &Keystroke) -> String {
+    let modifiers = keystroke.modifiers;
+    if !modifiers.control && !modifiers.alt && !modifiers.command && !modifiers.function {
+        let text = match keystroke.ime_key.as_deref() {
+            Some(ime_key) if ime_key.chars().count() == 1 => Some(ime_key.to_string()),
+            _ if keystroke.key == "space" => Some(" ".to_string()),
+            _ if keystroke.key.chars().count() == 1 => Some(if modifiers.shift {
+                keystroke.key.to_uppercase()
+            } else {
+                keystroke.key.clone()
+            }),
+            _ => None,
+        };
+        if let Some(text) = text {
+            return if text == "<" {
+                "<lt>".to_string()
+            } else {
+                text
+            };
+        }
+    }
+
+    let mut text = "<".to_string();
+    if modifiers.control {
+        text.push_str("C-");
+    }
+    if modifiers.alt {
+        text.push_str("M-");
+    }
+    if modifiers.shift {
+        text.push_str("S-");
+    }
+    if modifiers.command {
+        text.push_str("D-");
+    }
+    match keystroke.key.as_str() {
+        "escape" => text.push_str("Esc"),
+        "enter" => text.push_str("CR"),
+        "backspace" => text.push_str("BS"),
+        "pageup" => text.push_str("PageUp"),
+        "pagedown" => text.push_str("PageDown"),
+        "<" => text.push_str("lt"),
+        key => {
+            let mut chars = key.chars();
+            if let Some(first) = chars.next() {
+                text.extend(first.to_uppercase());
+                text.push_str(chars.as_str());
+            }
+        }
+    }
+    text.push('>');
+    text
+}
+
+/// Parses keystrokes in vim notation, treating a `<` that doesn't start a known key as typed.
+pub(crate) fn parse_keystrokes(text: &str) -> Vec<Keystroke> {
+    let mut keystrokes = Vec::new();
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        if c == '<' {
+            if let Some((keystroke, len)) = rest
+                .find('>')
+                .and_then(|end| Some((parse_special_key(&rest[1..end])?, end + 1)))
+            {
+                keystrokes.push(keystroke);
+                rest = &rest[len..];
+                continue;
+            }
+        }
+        rest = &rest[c.len_utf8()..];
+        keystrokes.push(match c {
+            ' ' => Keystroke {
+                modifiers: Modifiers::default(),
+                key: "space".to_string(),
+                ime_key: Some(" ".to_string()),
+            },
+            '\n' => Keystroke {
+                modifiers: Modifiers::default(),
+                key: "enter".to_string(),
+                ime_key: None,
+            },
+            c => Keystroke {
+                modifiers: Modifiers {
+                    shift: c.is_uppercase(),
+                    ..Default::default()
+                },
+                key: c.to_lowercase().collect(),
+                ime_key: Some(c.to_string()),
+            },
+        });
+    }
+    keystrokes
+}
+
+/// Parses the inside of `<...>`, such as `Esc`, `lt` or `C-d`.
+fn parse_special_key(text: &str) -> Option<Keystroke> {
+    let mut modifiers = Modifiers::default();
+    let mut name = text;
+    while let Some((modifier, rest)) = name.split_once('-').filter(|(_, rest)| !rest.is_empty()) {
+        match modifier {
+            "C" | "c" => modifiers.control = true,
+            "M" | "m" | "A" | "a" => modifiers.alt = true,
+            "S" | "s" => modifiers.shift = true,
+            "D" | "d" => modifiers.command = true,
+            _ => return None,
+        }
+        name = rest;
+    }
+
+    let key = match name.to_ascii_lowercase().as_str() {
+        "lt" => "<".to_string(),
+        "esc" => "escape".to_string(),
+        "cr" | "enter" | "return" => "enter".to_string(),
+        "bs" => "backspace".to_string(),
+        "del" | "delete" => "delete".to_string(),
+        key @ ("tab" | "space" | "up" | "down" | "left" | "right" | "home" | "end" | "pageup"
+        | "pagedown") => key.to_string(),
+        key if name.chars().count() == 1 && modifiers != Modifiers::default() => key.to_string(),
+        _ => return None,
+    };
+    let ime_key = if modifiers.control || modifiers.alt || modifiers.command {
+        None
+    } else if key == "space" {
+        Some(" ".to_string())
+    } else {
+        (key.chars().count() == 1).then(|| key.clone())
+    };
+    Some(Keystroke {
+        modifiers,
+        key,
+        ime_key,
+    })
 }
 
 pub(crate) fn repeat(cx: &mut WindowContext, from_insert_mode: bool) {
@@ -134,6 +134,10 @@ fn search_submit(workspace: &mut Workspace, _: &SearchSubmit, cx: &mut ViewConte
                     }
                     state.count = 1;
                     search_bar.select_match(direction, count, cx);
+                    if !search_bar.has_active_match() {
+                        vim.stop_replaying_macro();
+                    }
+                    vim.record_last_search(search_bar.query(cx));
                     search_bar.focus_editor(&Default::default(), cx);
                 });
             }
@@ -173,9 +177,13 @@ pub fn move_to_internal(
                     let search_bar = search_bar.downgrade();
                     cx.spawn(|_, mut cx| async move {
                         search.await?;
-                        search_bar.update(&mut cx, |search_bar, cx| {
-                            search_bar.select_match(direction, count, cx)
+                        let found = search_bar.update(&mut cx, |search_bar, cx| {
+                            search_bar.select_match(direction, count, cx);
+                            search_bar.has_active_match()
                         })?;
+                        if !found {
+                            cx.update(|cx| Vim::update(cx, |vim, _| vim.stop_replaying_macro()))?;
+                        }
                         anyhow::Ok(())
                     })
                     .detach_and_log_err(cx);
@@ -205,9 +213,13 @@ fn find_command(workspace: &mut Workspace, action: &FindCommand, cx: &mut ViewCo
                 };
 
                 search_bar.activate_search_mode(SearchMode::Regex, cx);
-                Some(search_bar.search(&query, Some(SearchOptions::CASE_SENSITIVE), cx))
+                let search = search_bar.search(&query, Some(SearchOptions::CASE_SENSITIVE), cx);
+                Some((query, search))
             });
-            let Some(search) = search else { return };
+            let Some((query, search)) = search else {
+                return;
+            };
+            Vim::update(cx, |vim, _| vim.record_last_search(query));
             let search_bar = search_bar.downgrade();
             let direction = if action.backwards {
                 Direction::Prev
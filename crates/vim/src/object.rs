@@ -6,7 +6,7 @@ use editor::{
     Bias, DisplayPoint,
 };
 use gpui::{actions, impl_actions, ViewContext, WindowContext};
-use language::{char_kind, CharKind, Selection};
+use language::{char_kind, CharKind, Point, Selection, TextObject};
 use serde::Deserialize;
 use workspace::Workspace;
 
@@ -27,6 +27,10 @@ pub enum Object {
     SquareBrackets,
     CurlyBrackets,
     AngleBrackets,
+    Method,
+    Class,
+    Comment,
+    Argument,
 }
 
 #[derive(Clone, Deserialize, PartialEq)]
@@ -49,7 +53,11 @@ actions!(
         Parentheses,
         SquareBrackets,
         CurlyBrackets,
-        AngleBrackets
+        AngleBrackets,
+        Method,
+        Class,
+        Comment,
+        Argument
     ]
 );
 
@@ -82,6 +90,11 @@ pub fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
     workspace.register_action(|_: &mut Workspace, _: &VerticalBars, cx: _| {
         object(Object::VerticalBars, cx)
     });
+    workspace.register_action(|_: &mut Workspace, _: &Method, cx: _| object(Object::Method, cx));
+    workspace.register_action(|_: &mut Workspace, _: &Class, cx: _| object(Object::Class, cx));
+    workspace.register_action(|_: &mut Workspace, _: &Comment, cx: _| object(Object::Comment, cx));
+    workspace
+        .register_action(|_: &mut Workspace, _: &Argument, cx: _| object(Object::Argument, cx));
 }
 
 fn object(object: Object, cx: &mut WindowContext) {
@@ -106,7 +119,11 @@ impl Object {
             | Object::Parentheses
             | Object::AngleBrackets
             | Object::CurlyBrackets
-            | Object::SquareBrackets => true,
+            | Object::SquareBrackets
+            | Object::Method
+            | Object::Class
+            | Object::Comment
+            | Object::Argument => true,
         }
     }
 
@@ -120,7 +137,11 @@ impl Object {
             | Object::Parentheses
             | Object::SquareBrackets
             | Object::CurlyBrackets
-            | Object::AngleBrackets => true,
+            | Object::AngleBrackets
+            | Object::Method
+            | Object::Class
+            | Object::Comment
+            | Object::Argument => true,
         }
     }
 
@@ -136,7 +157,11 @@ impl Object {
             | Object::Parentheses
             | Object::SquareBrackets
             | Object::CurlyBrackets
-            | Object::AngleBrackets => Mode::Visual,
+            | Object::AngleBrackets
+            | Object::Method
+            | Object::Class
+            | Object::Comment
+            | Object::Argument => Mode::Visual,
         }
     }
 
@@ -179,6 +204,22 @@ impl Object {
             Object::AngleBrackets => {
                 surrounding_markers(map, relative_to, around, self.is_multiline(), '<', '>')
             }
+            Object::Method => syntax_block(
+                map,
+                relative_to,
+                around,
+                TextObject::AroundFunction,
+                TextObject::InsideFunction,
+            ),
+            Object::Class => syntax_block(
+                map,
+                relative_to,
+                around,
+                TextObject::AroundClass,
+                TextObject::InsideClass,
+            ),
+            Object::Comment => comment(map, relative_to, around),
+            Object::Argument => argument(map, relative_to, around),
         }
     }
 
@@ -366,6 +407,148 @@ fn sentence(
     Some(range)
 }
 
+/// The ranges of a text object from the language's `textobjects` query that contain the
+/// offset, from the innermost to the outermost one.
+fn containing_text_objects(
+    map: &DisplaySnapshot,
+    offset: usize,
+    text_object: TextObject,
+) -> Vec<Range<usize>> {
+    let end = (offset + 1).min(map.buffer_snapshot.len());
+    let mut ranges = map
+        .buffer_snapshot
+        .text_object_ranges(offset..end, &[text_object])
+        .into_iter()
+        .filter(|(range, _)| range.start <= offset && offset < range.end)
+        .map(|(range, _)| range)
+        .collect::<Vec<_>>();
+    ranges.sort_by_key(|range| range.len());
+    ranges
+}
+
+fn to_display_range(map: &DisplaySnapshot, range: Range<usize>) -> Range<DisplayPoint> {
+    range.start.to_display_point(map)..range.end.to_display_point(map)
+}
+
+/// Returns the function or class that `relative_to` is in, or with `around` false, its body.
+///
+/// A body in brackets is shrunk to what's between them, and like `i{`, leaves out the lines
+/// that the brackets are on when they're on lines of their own.
+fn syntax_block(
+    map: &DisplaySnapshot,
+    relative_to: DisplayPoint,
+    around: bool,
+    around_object: TextObject,
+    inside_object: TextObject,
+) -> Option<Range<DisplayPoint>> {
+    let offset = relative_to.to_offset(map, Bias::Left);
+    let block = containing_text_objects(map, offset, around_object)
+        .into_iter()
+        .next()?;
+    if around {
+        return Some(to_display_range(map, block));
+    }
+
+    // The body of the block is the largest one in it, rather than that of a nested block.
+    let buffer = &map.buffer_snapshot;
+    let body = buffer
+        .text_object_ranges(block.clone(), &[inside_object])
+        .into_iter()
+        .filter(|(range, _)| block.start <= range.start && range.end <= block.end)
+        .map(|(range, _)| range)
+        .max_by_key(|range| range.len())?;
+
+    let first = buffer.chars_at(body.start).next();
+    let last = buffer.reversed_chars_at(body.end).next();
+    let in_brackets = matches!(
+        (first, last),
+        (Some('{'), Some('}')) | (Some('('), Some(')')) | (Some('['), Some(']'))
+    );
+    if !in_brackets || body.len() < 2 {
+        return Some(to_display_range(map, body));
+    }
+
+    let mut start = buffer.offset_to_point(body.start + 1);
+    let mut end = buffer.offset_to_point(body.end - 1);
+    if start.row < end.row {
+        let line_end = Point::new(start.row, buffer.line_len(start.row));
+        if is_blank(buffer.text_for_range(start..line_end)) {
+            start = Point::new(start.row + 1, 0);
+        }
+        let line_start = Point::new(end.row, 0);
+        if is_blank(buffer.text_for_range(line_start..end)) {
+            end = line_start;
+        }
+    }
+    let start = buffer.point_to_offset(start);
+    let end = buffer.point_to_offset(end);
+    Some(to_display_range(map, start..end.max(start)))
+}
+
+fn is_blank<'a>(mut chunks: impl Iterator<Item = &'a str>) -> bool {
+    chunks.all(|chunk| chunk.chars().all(char::is_whitespace))
+}
+
+/// Returns the comment that `relative_to` is in, or with `around`, all of the comments next
+/// to it.
+fn comment(
+    map: &DisplaySnapshot,
+    relative_to: DisplayPoint,
+    around: bool,
+) -> Option<Range<DisplayPoint>> {
+    let offset = relative_to.to_offset(map, Bias::Left);
+    let range = if around {
+        // Queries can match part of a run of comments as well, so take the longest match.
+        containing_text_objects(map, offset, TextObject::AroundComment).pop()?
+    } else {
+        containing_text_objects(map, offset, TextObject::InsideComment)
+            .into_iter()
+            .next()?
+    };
+    Some(to_display_range(map, range))
+}
+
+/// Returns the argument or parameter that `relative_to` is in. With `around`, the comma after
+/// it and the whitespace after that are included, or for the last argument, the comma and
+/// whitespace before it.
+fn argument(
+    map: &DisplaySnapshot,
+    relative_to: DisplayPoint,
+    around: bool,
+) -> Option<Range<DisplayPoint>> {
+    let offset = relative_to.to_offset(map, Bias::Left);
+    let mut range = containing_text_objects(map, offset, TextObject::Argument)
+        .into_iter()
+        .next()?;
+    if !around {
+        return Some(to_display_range(map, range));
+    }
+
+    let buffer = &map.buffer_snapshot;
+    let mut end = range.end;
+    let mut chars = buffer.chars_at(range.end).peekable();
+    while let Some(char) = chars.next_if(|char| char.is_whitespace()) {
+        end += char.len_utf8();
+    }
+    if chars.next() == Some(',') {
+        end += ','.len_utf8();
+        for char in chars.take_while(|char| char.is_whitespace()) {
+            end += char.len_utf8();
+        }
+        range.end = end;
+    } else {
+        let mut start = range.start;
+        let mut chars = buffer.reversed_chars_at(range.start).peekable();
+        while let Some(char) = chars.next_if(|char| char.is_whitespace()) {
+            start -= char.len_utf8();
+        }
+        if chars.next() == Some(',') {
+            range.start = start - ','.len_utf8();
+        }
+    }
+    Some(to_display_range(map, range))
+}
+
 fn is_possible_sentence_start(character: char) -> bool {
     !character.is_whitespace() && character != '.'
 }
@@ -1007,6 +1190,116 @@ mod test {
         );
     }
 
+    #[gpui::test]
+    async fn test_method_and_class_objects(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        let text = indoc! {"
+            impl A {
+                fn a(b: u32) {
+                    let ˇc = b;
+                }
+            }"
+        };
+
+        cx.set_state(text, Mode::Normal);
+        cx.simulate_keystrokes(["v", "a", "f"]);
+        cx.assert_state(
+            indoc! {"
+            impl A {
+                «fn a(b: u32) {
+                    let c = b;
+                }ˇ»
+            }"
+            },
+            Mode::Visual,
+        );
+
+        cx.set_state(text, Mode::Normal);
+        cx.simulate_keystrokes(["d", "i", "f"]);
+        cx.assert_state(
+            indoc! {"
+            impl A {
+                fn a(b: u32) {
+            ˇ    }
+            }"
+            },
+            Mode::Normal,
+        );
+
+        cx.set_state(text, Mode::Normal);
+        cx.simulate_keystrokes(["v", "a", "c"]);
+        cx.assert_state(
+            indoc! {"
+            «impl A {
+                fn a(b: u32) {
+                    let c = b;
+                }
+            }ˇ»"
+            },
+            Mode::Visual,
+        );
+
+        cx.set_state(text, Mode::Normal);
+        cx.simulate_keystrokes(["d", "i", "c"]);
+        cx.assert_state(
+            indoc! {"
+            impl A {
+            ˇ}"
+            },
+            Mode::Normal,
+        );
+    }
+
+    #[gpui::test]
+    async fn test_argument_object(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("fn a(b: u32, ˇc: u32) {}", Mode::Normal);
+        cx.simulate_keystrokes(["d", "i", "a"]);
+        cx.assert_state("fn a(b: u32, ˇ) {}", Mode::Normal);
+
+        // The last argument takes the comma before it.
+        cx.set_state("fn a(b: u32, ˇc: u32) {}", Mode::Normal);
+        cx.simulate_keystrokes(["d", "a", "a"]);
+        cx.assert_state("fn a(b: u32ˇ) {}", Mode::Normal);
+
+        cx.set_state("fn a(ˇb: u32, c: u32) {}", Mode::Normal);
+        cx.simulate_keystrokes(["d", "a", "a"]);
+        cx.assert_state("fn a(ˇc: u32) {}", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_comment_object(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        let text = indoc! {"
+            // a
+            // ˇb
+            fn c() {}"
+        };
+
+        cx.set_state(text, Mode::Normal);
+        cx.simulate_keystrokes(["d", "i", "/"]);
+        cx.assert_state(
+            indoc! {"
+            // a
+            ˇ
+            fn c() {}"
+            },
+            Mode::Normal,
+        );
+
+        cx.set_state(text, Mode::Normal);
+        cx.simulate_keystrokes(["v", "a", "/"]);
+        cx.assert_state(
+            indoc! {"
+            «// a
+            // bˇ»
+            fn c() {}"
+            },
+            Mode::Visual,
+        );
+    }
+
     #[gpui::test]
     async fn test_delete_surrounding_character_objects(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
@@ -0,0 +1,54 @@
+use std::path::PathBuf;
+
+use db::{define_connection, query, sqlez_macros::sql};
+use workspace::{WorkspaceDb, WorkspaceId};
+
+define_connection! {
+    // Current schema shape using pseudo-rust syntax:
+    // vim_global_marks(
+    //   workspace_id: usize,
+    //   mark: String,
+    //   path: PathBuf,
+    //   point_row: u32,
+    //   point_column: u32,
+    // )
+    pub static ref DB: VimDb<WorkspaceDb> =
+        &[sql!(
+            CREATE TABLE vim_global_marks (
+                workspace_id INTEGER NOT NULL,
+                mark TEXT NOT NULL,
+                path BLOB NOT NULL,
+                point_row INTEGER NOT NULL,
+                point_column INTEGER NOT NULL,
+                PRIMARY KEY(workspace_id, mark),
+                FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+                ON DELETE CASCADE
+                ON UPDATE CASCADE
+            ) STRICT;
+        )];
+}
+
+impl VimDb {
+    query! {
+        pub fn get_global_mark(workspace_id: WorkspaceId, mark: String) -> Result<Option<(PathBuf, u32, u32)>> {
+            SELECT path, point_row, point_column
+            FROM vim_global_marks
+            WHERE workspace_id = ? AND mark = ?
+        }
+    }
+
+    query! {
+        pub async fn save_global_mark(
+            workspace_id: WorkspaceId,
+            mark: String,
+            path: PathBuf,
+            point_row: u32,
+            point_column: u32
+        ) -> Result<()> {
+            INSERT OR REPLACE INTO vim_global_marks
+                (workspace_id, mark, path, point_row, point_column)
+            VALUES
+                (?1, ?2, ?3, ?4, ?5)
+        }
+    }
+}
@@ -0,0 +1,301 @@
+use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+use gpui::{
+    actions, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Render, Task,
+    View, ViewContext, VisualContext, WeakView, WindowContext,
+};
+use picker::{Picker, PickerDelegate};
+use std::sync::Arc;
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing};
+use workspace::{ModalView, Workspace};
+
+use crate::{
+    normal::paste::Paste,
+    state::{Register, ReplayableAction},
+    Vim,
+};
+
+actions!(vim, [ShowRegisters]);
+
+pub(crate) fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+    workspace.register_action(|workspace: &mut Workspace, _: &ShowRegisters, cx| {
+        let registers = cx.global::<Vim>().registers(cx);
+        workspace.toggle_modal(cx, |cx| RegistersView::new(registers, cx));
+    });
+}
+
+impl Vim {
+    /// Chooses the register that the next yank, delete or paste uses, as typed after `"`.
+    pub(crate) fn select_register(&mut self, register: char, cx: &mut WindowContext) {
+        self.pop_operator(cx);
+        if register.is_ascii_alphanumeric() || "\"-_+*./".contains(register) {
+            self.workspace_state.selected_register = Some(register);
+        } else {
+            self.clear_operator(cx);
+        }
+    }
+
+    /// Stores yanked or deleted text in the selected register. Without a selected register,
+    /// the text goes to the system clipboard, and yanks are kept in `"0` while deletes are
+    /// kept in `"1` (shifting the older ones up to `"9`), or `"-` when within a line. Like in
+    /// Vim, the unnamed register (the system clipboard) also gets the contents of a named
+    /// register that was written to.
+    pub(crate) fn write_registers(
+        &mut self,
+        content: Register,
+        is_yank: bool,
+        linewise: bool,
+        cx: &mut AppContext,
+    ) {
+        let registers = &mut self.workspace_state.registers;
+        match self.workspace_state.selected_register.take() {
+            Some('_') => {}
+            Some('+' | '*') => cx.write_to_clipboard(content.into()),
+            Some(register) if register.is_ascii_uppercase() => {
+                let register = registers.entry(register.to_ascii_lowercase()).or_default();
+                if linewise && !register.text.is_empty() && !register.text.ends_with('\n') {
+                    register.text.push('\n');
+                }
+                register.text.push_str(&content.text);
+                register.clipboard_selections = None;
+                cx.write_to_clipboard(register.clone().into());
+            }
+            Some(register) if register.is_ascii_alphanumeric() || register == '-' => {
+                cx.write_to_clipboard(content.clone().into());
+                registers.insert(register, content);
+            }
+            _ => {
+                cx.write_to_clipboard(content.clone().into());
+                if is_yank {
+                    registers.insert('0', content);
+                } else if linewise || content.text.contains('\n') {
+                    let numbered = ('1'..='9').collect::<Vec<_>>();
+                    for ix in (1..numbered.len()).rev() {
+                        if let Some(register) = registers.remove(&numbered[ix - 1]) {
+                            registers.insert(numbered[ix], register);
+                        }
+                    }
+                    registers.insert('1', content);
+                } else {
+                    registers.insert('-', content);
+                }
+            }
+        }
+    }
+
+    /// The contents of a register, where the unnamed register is the system clipboard.
+    pub(crate) fn read_register(
+        &self,
+        register: Option<char>,
+        cx: &AppContext,
+    ) -> Option<Register> {
+        match register {
+            None | Some('"' | '+' | '*') => cx.read_from_clipboard().map(Register::from),
+            Some('_') => None,
+            Some(register) => self
+                .workspace_state
+                .registers
+                .get(&register.to_ascii_lowercase())
+                .cloned(),
+        }
+    }
+
+    /// Stores the text typed since entering insert mode in the `.` register.
+    pub(crate) fn record_last_insert(&mut self) {
+        if self.workspace_state.replaying {
+            return;
+        }
+        let mut text = String::new();
+        for action in &self.workspace_state.recorded_actions {
+            match action {
+                ReplayableAction::Insertion { text: inserted, .. } => text.push_str(inserted),
+                ReplayableAction::Action(action) if action.name() == "editor::Backspace" => {
+                    text.pop();
+                }
+                ReplayableAction::Action(_) => {}
+            }
+        }
+        self.workspace_state.registers.insert(
+            '.',
+            Register {
+                text,
+                clipboard_selections: None,
+            },
+        );
+    }
+
+    /// Stores the latest search query in the `/` register.
+    pub(crate) fn record_last_search(&mut self, query: String) {
+        self.workspace_state.registers.insert(
+            '/',
+            Register {
+                text: query,
+                clipboard_selections: None,
+            },
+        );
+    }
+
+    /// The non-empty registers in the order that `:registers` lists them.
+    fn registers(&self, cx: &AppContext) -> Vec<(char, Register)> {
+        std::iter::once('"')
+            .chain('0'..='9')
+            .chain('a'..='z')
+            .chain(['-', '.', '/'])
+            .filter_map(|name| {
+                let register = self.read_register(Some(name), cx)?;
+                (!register.text.is_empty()).then_some((name, register))
+            })
+            .collect()
+    }
+}
+
+/// Lists the contents of the registers and pastes the selected one.
+pub struct RegistersView {
+    picker: View<Picker<RegistersViewDelegate>>,
+}
+
+impl RegistersView {
+    fn new(registers: Vec<(char, Register)>, cx: &mut ViewContext<Self>) -> Self {
+        let delegate = RegistersViewDelegate::new(cx.view().downgrade(), registers);
+        let picker = cx.new_view(|cx| Picker::new(delegate, cx));
+        Self { picker }
+    }
+}
+
+impl Render for RegistersView {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl FocusableView for RegistersView {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for RegistersView {}
+impl ModalView for RegistersView {}
+
+pub struct RegistersViewDelegate {
+    registers_view: WeakView<RegistersView>,
+    registers: Vec<char>,
+    candidates: Vec<StringMatchCandidate>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl RegistersViewDelegate {
+    fn new(registers_view: WeakView<RegistersView>, registers: Vec<(char, Register)>) -> Self {
+        let candidates = registers
+            .iter()
+            .enumerate()
+            .map(|(candidate_id, (name, register))| {
+                // Like vim, show newlines as `^J` so that each register fits on one line.
+                let text = register.text.replace('\n', "^J");
+                StringMatchCandidate::new(candidate_id, format!("\"{name}  {text}"))
+            })
+            .collect();
+        Self {
+            registers_view,
+            registers: registers.into_iter().map(|(name, _)| name).collect(),
+            candidates,
+            matches: Vec::new(),
+            selected_index: 0,
+        }
+    }
+}
+
+impl PickerDelegate for RegistersViewDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self) -> Arc<str> {
+        "Select a register to paste...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        if let Some(mat) = self.matches.get(self.selected_index) {
+            let register = self.registers[mat.candidate_id];
+            Vim::update(cx, |vim, _| {
+                vim.workspace_state.selected_register = Some(register)
+            });
+            cx.dispatch_action(Box::new(Paste::default()));
+        }
+        self.dismissed(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.registers_view
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .ok();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self.candidates.clone();
+        cx.spawn(|this, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, candidate)| StringMatch {
+                        candidate_id: index,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            this.update(&mut cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .ok();
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = &self.matches[ix];
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(HighlightedLabel::new(
+                    mat.string.clone(),
+                    mat.positions.clone(),
+                )),
+        )
+    }
+}
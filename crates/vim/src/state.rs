@@ -1,9 +1,11 @@
-use std::{ops::Range, sync::Arc};
+use std::{collections::VecDeque, ops::Range, path::PathBuf, sync::Arc};
 
-use gpui::{Action, KeyContext};
-use language::CursorShape;
+use collections::HashMap;
+use editor::ClipboardSelection;
+use gpui::{Action, ClipboardItem, KeyContext, Keystroke};
+use language::{Anchor, CursorShape, Point};
 use serde::{Deserialize, Serialize};
-use workspace::searchable::Direction;
+use workspace::{searchable::Direction, WorkspaceId};
 
 use crate::motion::Motion;
 
@@ -40,6 +42,11 @@ pub enum Operator {
     Object { around: bool },
     FindForward { before: bool },
     FindBackward { after: bool },
+    Register,
+    RecordRegister,
+    ReplayRegister,
+    Mark,
+    Jump { line: bool },
 }
 
 #[derive(Default, Clone)]
@@ -86,6 +93,74 @@ pub struct WorkspaceState {
     pub recorded_count: Option<usize>,
     pub recorded_actions: Vec<ReplayableAction>,
     pub recorded_selection: RecordedSelection,
+
+    pub registers: HashMap<char, Register>,
+    /// The register chosen with `"` for the next yank, delete or paste.
+    pub selected_register: Option<char>,
+
+    /// The register that `q` is recording a macro into.
+    pub recording_register: Option<char>,
+    /// The keystrokes of the macro being recorded, in vim's key notation.
+    pub recorded_keystrokes: String,
+    pub last_replayed_register: Option<char>,
+    pub replaying_macro: bool,
+    /// The keystrokes of the macros and `:normal` commands left to replay.
+    pub replayed_keystrokes: VecDeque<Keystroke>,
+    /// Whether a replayed keystroke is being dispatched, rather than one that was typed.
+    pub dispatching_replayed_keystroke: bool,
+
+    /// The lowercase and special marks of each buffer, by the buffer's remote id. They're
+    /// forgotten when the buffer is released.
+    pub marks: HashMap<u64, HashMap<char, Anchor>>,
+    /// The uppercase marks, which also jump to other files.
+    pub global_marks: HashMap<char, GlobalMark>,
+    pub change_lists: HashMap<u64, ChangeList>,
+}
+
+/// An uppercase mark. Besides the anchor in the buffer it was set in, the mark keeps its
+/// path and position so that it can reopen the file after the buffer has been closed.
+#[derive(Clone, Debug)]
+pub struct GlobalMark {
+    pub anchor: Option<(u64, Anchor)>,
+    pub abs_path: Option<PathBuf>,
+    pub point: Point,
+    /// The workspace that the mark is saved with.
+    pub workspace_id: Option<WorkspaceId>,
+}
+
+/// The positions of a buffer's recent changes, which `g;` and `g,` move through.
+#[derive(Clone, Debug, Default)]
+pub struct ChangeList {
+    pub changes: Vec<Anchor>,
+    /// The index of the change that was jumped to last, or `None` after a new change.
+    pub position: Option<usize>,
+}
+
+/// The contents of a register, along with the selections they were copied from so that
+/// pasting them keeps their linewise or blockwise shape.
+#[derive(Clone, Debug, Default)]
+pub struct Register {
+    pub text: String,
+    pub clipboard_selections: Option<Vec<ClipboardSelection>>,
+}
+
+impl From<ClipboardItem> for Register {
+    fn from(item: ClipboardItem) -> Self {
+        Self {
+            clipboard_selections: item.metadata(),
+            text: item.text().clone(),
+        }
+    }
+}
+
+impl From<Register> for ClipboardItem {
+    fn from(register: Register) -> Self {
+        let item = ClipboardItem::new(register.text);
+        match register.clipboard_selections {
+            Some(clipboard_selections) => item.with_metadata(clipboard_selections),
+            None => item,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -219,15 +294,26 @@ impl Operator {
             Operator::FindForward { before: true } => "t",
             Operator::FindBackward { after: false } => "F",
             Operator::FindBackward { after: true } => "T",
+            Operator::Register => "\"",
+            Operator::RecordRegister => "q",
+            Operator::ReplayRegister => "@",
+            Operator::Mark => "m",
+            Operator::Jump { line: true } => "'",
+            Operator::Jump { line: false } => "`",
         }
     }
 
     pub fn context_flags(&self) -> &'static [&'static str] {
         match self {
             Operator::Object { .. } => &["VimObject"],
-            Operator::FindForward { .. } | Operator::FindBackward { .. } | Operator::Replace => {
-                &["VimWaiting"]
-            }
+            Operator::FindForward { .. }
+            | Operator::FindBackward { .. }
+            | Operator::Replace
+            | Operator::Register
+            | Operator::RecordRegister
+            | Operator::ReplayRegister
+            | Operator::Mark
+            | Operator::Jump { .. } => &["VimWaiting"],
             _ => &[],
         }
     }
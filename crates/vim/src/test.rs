@@ -847,3 +847,133 @@ async fn test_comma_w(cx: &mut gpui::TestAppContext) {
     cx.assert_shared_state("hellˇo hello\nhello hello").await;
     cx.assert_shared_mode(Mode::Insert).await;
 }
+
+#[gpui::test]
+async fn test_record_replay(cx: &mut gpui::TestAppContext) {
+    let mut cx = NeovimBackedTestContext::new(cx).await;
+
+    cx.set_shared_state(indoc! {"
+        ˇone
+        two
+        three
+        four"})
+        .await;
+    cx.simulate_shared_keystrokes(["q", "a", "shift-a", "!", "escape", "j", "q"])
+        .await;
+    cx.assert_shared_state(indoc! {"
+        one!
+        twˇo
+        three
+        four"})
+        .await;
+    cx.simulate_shared_keystrokes(["@", "a"]).await;
+    cx.assert_shared_state(indoc! {"
+        one!
+        two!
+        thrˇee
+        four"})
+        .await;
+    cx.simulate_shared_keystrokes(["@", "@"]).await;
+    cx.assert_shared_state(indoc! {"
+        one!
+        two!
+        three!
+        fouˇr"})
+        .await;
+
+    cx.set_shared_state(indoc! {"
+        ˇa
+        b
+        c
+        d"})
+        .await;
+    cx.simulate_shared_keystrokes(["q", "b", "shift-a", "!", "escape", "j", "q"])
+        .await;
+    cx.simulate_shared_keystrokes(["2", "@", "b"]).await;
+    cx.assert_shared_state(indoc! {"
+        a!
+        b!
+        c!
+        ˇd"})
+        .await;
+}
+
+#[gpui::test]
+async fn test_macro_registers(cx: &mut gpui::TestAppContext) {
+    let mut cx = VimTestContext::new(cx, true).await;
+
+    // the keystrokes of macros are stored as text
+    cx.set_state("ˇone", Mode::Normal);
+    cx.simulate_keystrokes(["q", "a", "shift-a", "!", "escape", "q"]);
+    cx.simulate_keystrokes(["\"", "a", "p"]);
+    cx.assert_state("one!A!<Escˇ>", Mode::Normal);
+
+    // so they can be edited and yanked back
+    cx.set_state(
+        indoc! {"
+        ˇ
+        two"},
+        Mode::Normal,
+    );
+    cx.simulate_keystrokes(["\"", "a", "p", "0", "l", "r", "x"]);
+    cx.simulate_keystrokes(["0", "\"", "a", "y", "$", "j", "@", "a"]);
+    cx.assert_state(
+        indoc! {"
+        Ax<Esc>
+        twoˇx"},
+        Mode::Normal,
+    );
+
+    // and yanked text is replayed as keys too
+    cx.set_state(
+        indoc! {"
+        ˇdw
+        hello world"},
+        Mode::Normal,
+    );
+    cx.simulate_keystrokes(["\"", "a", "y", "i", "w", "j", "@", "a"]);
+    cx.assert_state(
+        indoc! {"
+        dw
+        ˇworld"},
+        Mode::Normal,
+    );
+
+    // every keystroke of multi-key bindings is recorded
+    cx.set_state(
+        indoc! {"
+        one
+        ˇtwo"},
+        Mode::Normal,
+    );
+    cx.simulate_keystrokes(["q", "c", "g", "g", "x", "q", "j", "@", "c"]);
+    cx.assert_state(
+        indoc! {"
+        ˇe
+        two"},
+        Mode::Normal,
+    );
+}
+
+#[gpui::test]
+async fn test_recursive_macro(cx: &mut gpui::TestAppContext) {
+    let mut cx = VimTestContext::new(cx, true).await;
+
+    // the replay stops once one of the macro's motions fails
+    cx.set_state(
+        indoc! {"
+        ˇone
+        two
+        three"},
+        Mode::Normal,
+    );
+    cx.simulate_keystrokes(["q", "a", "shift-a", "!", "escape", "j", "@", "a", "q"]);
+    cx.simulate_keystrokes(["@", "a"]);
+    cx.assert_state(
+        indoc! {"
+        one!
+        two!
+        threeˇ!"},
+        Mode::Normal,
+    );
+}
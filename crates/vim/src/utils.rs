@@ -1,8 +1,36 @@
 use editor::{ClipboardSelection, Editor};
-use gpui::{AppContext, ClipboardItem};
+use gpui::AppContext;
 use language::{CharKind, Point};
 
-pub fn copy_selections_content(editor: &mut Editor, linewise: bool, cx: &mut AppContext) {
+use crate::{state::Register, Vim};
+
+/// Copies the selected text into the registers as yanked text.
+pub fn yank_selections_content(
+    vim: &mut Vim,
+    editor: &mut Editor,
+    linewise: bool,
+    cx: &mut AppContext,
+) {
+    copy_selections_content_internal(vim, editor, linewise, true, cx);
+}
+
+/// Copies the selected text into the registers as deleted text.
+pub fn copy_selections_content(
+    vim: &mut Vim,
+    editor: &mut Editor,
+    linewise: bool,
+    cx: &mut AppContext,
+) {
+    copy_selections_content_internal(vim, editor, linewise, false, cx);
+}
+
+fn copy_selections_content_internal(
+    vim: &mut Vim,
+    editor: &mut Editor,
+    linewise: bool,
+    is_yank: bool,
+    cx: &mut AppContext,
+) {
     let selections = editor.selections.all_adjusted(cx);
     let buffer = editor.buffer().read(cx).snapshot(cx);
     let mut text = String::new();
@@ -46,7 +74,27 @@ pub fn copy_selections_content(editor: &mut Editor, linewise: bool, cx: &mut App
         }
     }
 
-    cx.write_to_clipboard(ClipboardItem::new(text).with_metadata(clipboard_selections));
+    if let (Some(first), Some(last)) = (selections.first(), selections.last()) {
+        let end = buffer
+            .reversed_chars_at(last.end)
+            .next()
+            .filter(|_| last.start != last.end)
+            .map_or(last.end, |c| {
+                buffer.offset_to_point(buffer.point_to_offset(last.end) - c.len_utf8())
+            });
+        vim.set_mark('[', buffer.anchor_before(first.start), editor, cx);
+        vim.set_mark(']', buffer.anchor_before(end), editor, cx);
+    }
+
+    vim.write_registers(
+        Register {
+            text,
+            clipboard_selections: Some(clipboard_selections),
+        },
+        is_yank,
+        linewise,
+        cx,
+    );
 }
 
 pub fn coerce_punctuation(kind: CharKind, treat_punctuation_as_word: bool) -> CharKind {
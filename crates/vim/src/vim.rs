@@ -10,6 +10,8 @@ mod mode_indicator;
 mod motion;
 mod normal;
 mod object;
+mod persistence;
+mod registers;
 mod state;
 mod utils;
 mod visual;
@@ -25,7 +27,7 @@ use gpui::{
 use language::{CursorShape, Point, Selection, SelectionGoal};
 pub use mode_indicator::ModeIndicator;
 use motion::Motion;
-use normal::normal_replace;
+use normal::{mark::jump, normal_replace};
 use serde::Deserialize;
 use settings::{update_settings_file, Settings, SettingsStore};
 use state::{EditorState, Mode, Operator, RecordedSelection, WorkspaceState};
@@ -121,17 +123,32 @@ fn register(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
         })
     });
 
+    // Uppercase marks move with edits, so they're saved again along with the workspace.
+    let workspace_handle = cx.view().clone();
+    cx.subscribe(&workspace_handle, |workspace, _, event, cx| {
+        if let workspace::Event::Serialized = event {
+            Vim::update(cx, |vim, cx| vim.save_global_marks(workspace, cx));
+        }
+    })
+    .detach();
+
     normal::register(workspace, cx);
     insert::register(workspace, cx);
     motion::register(workspace, cx);
     command::register(workspace, cx);
     object::register(workspace, cx);
+    registers::register(workspace, cx);
     visual::register(workspace, cx);
 }
 
 /// Registers a keystroke observer to observe keystrokes for the Vim integration.
 pub fn observe_keystrokes(cx: &mut WindowContext) {
     cx.observe_keystrokes(|keystroke_event, cx| {
+        Vim::update(cx, |vim, cx| {
+            vim.interrupt_macro(&keystroke_event.keystroke);
+            vim.record_macro_keystroke(keystroke_event, cx)
+        });
+
         if let Some(action) = keystroke_event
             .action
             .as_ref()
@@ -160,7 +177,14 @@ pub fn observe_keystrokes(cx: &mut WindowContext) {
 
         Vim::update(cx, |vim, cx| match vim.active_operator() {
             Some(
-                Operator::FindForward { .. } | Operator::FindBackward { .. } | Operator::Replace,
+                Operator::FindForward { .. }
+                | Operator::FindBackward { .. }
+                | Operator::Replace
+                | Operator::Register
+                | Operator::RecordRegister
+                | Operator::ReplayRegister
+                | Operator::Mark
+                | Operator::Jump { .. },
             ) => {}
             Some(_) => {
                 vim.clear_operator(cx);
@@ -212,6 +236,9 @@ impl Vim {
                 text,
                 utf16_range_to_replace: range_to_replace,
             } => Vim::record_insertion(text, range_to_replace.clone(), cx),
+            EditorEvent::Edited => Vim::update(cx, |vim, cx| {
+                editor.update(cx, |editor, cx| vim.record_change(editor, cx))
+            }),
             _ => {}
         }));
 
@@ -255,12 +282,12 @@ impl Vim {
     }
 
     fn update_active_editor<S>(
-        &self,
+        &mut self,
         cx: &mut WindowContext,
-        update: impl FnOnce(&mut Editor, &mut ViewContext<Editor>) -> S,
+        update: impl FnOnce(&mut Vim, &mut Editor, &mut ViewContext<Editor>) -> S,
     ) -> Option<S> {
         let editor = self.active_editor.clone()?.upgrade()?;
-        Some(editor.update(cx, update))
+        Some(editor.update(cx, |editor, cx| update(self, editor, cx)))
     }
 
     /// When doing an action that modifies the buffer, we start recording so that `.`
@@ -359,7 +386,14 @@ impl Vim {
         }
 
         // Adjust selections
-        self.update_active_editor(cx, |editor, cx| {
+        self.update_active_editor(cx, |vim, editor, cx| {
+            if last_mode.is_visual() && !mode.is_visual() {
+                vim.record_visual_marks(editor, cx);
+            }
+            if last_mode == Mode::Insert && mode != Mode::Insert {
+                vim.set_mark('^', editor.selections.newest_anchor().head(), editor, cx);
+            }
+
             if last_mode != Mode::VisualBlock && last_mode.is_visual() && mode == Mode::VisualBlock
             {
                 visual_block_motion(true, editor, cx, |_, point, goal| Some((point, goal)))
@@ -456,6 +490,7 @@ impl Vim {
     }
     fn clear_operator(&mut self, cx: &mut WindowContext) {
         self.take_count(cx);
+        self.workspace_state.selected_register = None;
         self.update_state(|state| state.operator_stack.clear());
         self.sync_vim_settings(cx);
     }
@@ -495,6 +530,19 @@ impl Vim {
                 Mode::Visual | Mode::VisualLine | Mode::VisualBlock => visual_replace(text, cx),
                 _ => Vim::update(cx, |vim, cx| vim.clear_operator(cx)),
             },
+            Some(Operator::Register) => Vim::update(cx, |vim, cx| {
+                vim.select_register(text.chars().next().unwrap(), cx)
+            }),
+            Some(Operator::RecordRegister) => Vim::update(cx, |vim, cx| {
+                vim.start_recording_macro(text.chars().next().unwrap(), cx)
+            }),
+            Some(Operator::ReplayRegister) => Vim::update(cx, |vim, cx| {
+                vim.replay_register(text.chars().next().unwrap(), cx)
+            }),
+            Some(Operator::Mark) => Vim::update(cx, |vim, cx| {
+                vim.create_mark(text.chars().next().unwrap(), cx)
+            }),
+            Some(Operator::Jump { line }) => jump(text, line, cx),
             _ => {}
         }
     }
@@ -561,13 +609,11 @@ impl Vim {
         ret
     }
 
-    fn sync_vim_settings(&self, cx: &mut WindowContext) {
-        let state = self.state();
-        let cursor_shape = state.cursor_shape();
-
-        self.update_active_editor(cx, |editor, cx| {
-            if self.enabled && editor.mode() == EditorMode::Full {
-                editor.set_cursor_shape(cursor_shape, cx);
+    fn sync_vim_settings(&mut self, cx: &mut WindowContext) {
+        self.update_active_editor(cx, |vim, editor, cx| {
+            let state = vim.state();
+            if vim.enabled && editor.mode() == EditorMode::Full {
+                editor.set_cursor_shape(state.cursor_shape(), cx);
                 editor.set_clip_at_line_ends(state.clip_at_line_ends(), cx);
                 editor.set_collapse_matches(true);
                 editor.set_input_enabled(!state.vim_controlled());
@@ -579,7 +625,7 @@ impl Vim {
                 // Note: set_collapse_matches is not in unhook_vim_settings, as that method is called on blur,
                 // but we need collapse_matches to persist when the search bar is focused.
                 editor.set_collapse_matches(false);
-                self.unhook_vim_settings(editor, cx);
+                vim.unhook_vim_settings(editor, cx);
             }
         });
     }
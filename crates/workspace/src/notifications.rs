@@ -4,7 +4,9 @@ use gpui::{
     AnyView, AppContext, AsyncWindowContext, DismissEvent, Entity, EntityId, EventEmitter, Render,
     Task, View, ViewContext, VisualContext, WindowContext,
 };
+use project::LanguageServerPromptRequest;
 use std::{any::TypeId, ops::DerefMut};
+use ui::prelude::*;
 
 pub fn init(cx: &mut AppContext) {
     cx.set_global(NotificationTracker::new());
@@ -166,6 +168,75 @@ impl Workspace {
     }
 }
 
+/// A message from a language server, with a button for each action the server offers to
+/// answer it with.
+pub struct LanguageServerPrompt {
+    request: LanguageServerPromptRequest,
+}
+
+impl EventEmitter<DismissEvent> for LanguageServerPrompt {}
+
+impl LanguageServerPrompt {
+    pub fn new(request: LanguageServerPromptRequest) -> Self {
+        Self { request }
+    }
+
+    fn respond(&mut self, index: usize, cx: &mut ViewContext<Self>) {
+        let request = self.request.clone();
+        cx.background_executor()
+            .spawn(async move { request.respond(index).await })
+            .detach();
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for LanguageServerPrompt {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let icon = match self.request.level {
+            gpui::PromptLevel::Critical => Some(Icon::new(IconName::XCircle).color(Color::Error)),
+            gpui::PromptLevel::Warning => {
+                Some(Icon::new(IconName::ExclamationTriangle).color(Color::Warning))
+            }
+            gpui::PromptLevel::Info => None,
+        };
+
+        v_flex()
+            .elevation_3(cx)
+            .p_4()
+            .gap_2()
+            .child(
+                h_flex()
+                    .justify_between()
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .children(icon)
+                            .child(Label::new(self.request.lsp_name.clone()).color(Color::Muted)),
+                    )
+                    .child(
+                        div()
+                            .id("cancel")
+                            .child(Icon::new(IconName::Close))
+                            .cursor_pointer()
+                            .on_click(cx.listener(|_, _, cx| cx.emit(DismissEvent))),
+                    ),
+            )
+            .child(
+                div()
+                    .max_w_80()
+                    .child(Label::new(self.request.message.clone())),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .children(self.request.actions.iter().enumerate().map(|(ix, action)| {
+                        Button::new(ix, action.title.clone())
+                            .on_click(cx.listener(move |this, _, cx| this.respond(ix, cx)))
+                    })),
+            )
+    }
+}
+
 pub mod simple_message_notification {
     use gpui::{
         div, DismissEvent, EventEmitter, InteractiveElement, ParentElement, Render, SharedString,
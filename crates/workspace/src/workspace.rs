@@ -39,7 +39,9 @@ use language::{LanguageRegistry, Rope};
 use lazy_static::lazy_static;
 pub use modal_layer::*;
 use node_runtime::NodeRuntime;
-use notifications::{simple_message_notification::MessageNotification, NotificationHandle};
+use notifications::{
+    simple_message_notification::MessageNotification, LanguageServerPrompt, NotificationHandle,
+};
 pub use pane::*;
 pub use pane_group::*;
 use persistence::{model::SerializedWorkspace, SerializedWindowsBounds, DB};
@@ -57,7 +59,10 @@ pub use status_bar::StatusItemView;
 use std::{
     any::TypeId,
     borrow::Cow,
-    cmp, env,
+    cmp,
+    collections::hash_map::DefaultHasher,
+    env,
+    hash::{Hash, Hasher},
     path::{Path, PathBuf},
     sync::{atomic::AtomicUsize, Arc},
     time::Duration,
@@ -164,6 +169,7 @@ impl_actions!(
         OpenTerminal,
         Save,
         SaveAll,
+        SpawnTaskInTerminal,
         SwapPaneInDirection,
     ]
 );
@@ -218,6 +224,11 @@ pub struct OpenTerminal {
     pub working_directory: PathBuf,
 }
 
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct SpawnTaskInTerminal {
+    pub task: project::tasks::SpawnInTerminal,
+}
+
 pub type WorkspaceId = i64;
 
 pub fn init_settings(cx: &mut AppContext) {
@@ -428,6 +439,8 @@ pub enum Event {
     PaneAdded(View<Pane>),
     ContactRequestedJoin(u64),
     WorkspaceCreated(WeakView<Workspace>),
+    /// The workspace was saved to the database, under its [`Workspace::database_id`].
+    Serialized,
 }
 
 pub enum OpenVisible {
@@ -535,6 +548,16 @@ impl Workspace {
                     cx.new_view(|_| MessageNotification::new(message.clone()))
                 }),
 
+                project::Event::LanguageServerPrompt(request) => {
+                    let mut hasher = DefaultHasher::new();
+                    request.lsp_name.hash(&mut hasher);
+                    request.message.hash(&mut hasher);
+                    let id = hasher.finish() as usize;
+                    this.show_notification(id, cx, |cx| {
+                        cx.new_view(|_| LanguageServerPrompt::new(request.clone()))
+                    });
+                }
+
                 _ => {}
             }
             cx.notify()
@@ -3005,7 +3028,7 @@ impl Workspace {
         }));
     }
 
-    fn serialize_workspace(&self, cx: &mut WindowContext) {
+    fn serialize_workspace(&self, cx: &mut ViewContext<Self>) {
         fn serialize_pane_handle(pane_handle: &View<Pane>, cx: &WindowContext) -> SerializedPane {
             let (items, active) = {
                 let pane = pane_handle.read(cx);
@@ -3118,8 +3141,9 @@ impl Workspace {
                     docks,
                 };
 
-                cx.spawn(|_| persistence::DB.save_workspace(serialized_workspace))
+                cx.spawn(|_, _| persistence::DB.save_workspace(serialized_workspace))
                     .detach();
+                cx.emit(Event::Serialized);
             }
         }
     }
@@ -133,6 +133,8 @@ pub fn app_menus() -> Vec<Menu<'static>> {
                 MenuItem::action("Go to Symbol in Editor", outline::Toggle),
                 MenuItem::action("Go to Definition", editor::actions::GoToDefinition),
                 MenuItem::action("Go to Type Definition", editor::actions::GoToTypeDefinition),
+                MenuItem::action("Go to Implementation", editor::actions::GoToImplementation),
+                MenuItem::action("Go to Declaration", editor::actions::GoToDeclaration),
                 MenuItem::action("Find All References", editor::actions::FindAllReferences),
                 MenuItem::action("Go to Line/Column", go_to_line::Toggle),
                 MenuItem::separator(),
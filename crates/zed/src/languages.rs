@@ -277,6 +277,9 @@ fn load_queries(name: &str) -> LanguageQueries {
         indents: load_query(name, "/indents"),
         outline: load_query(name, "/outline"),
         embedding: load_query(name, "/embedding"),
+        runnables: load_query(name, "/runnables"),
+        folds: load_query(name, "/folds"),
+        text_objects: load_query(name, "/textobjects"),
         injections: load_query(name, "/injections"),
         overrides: load_query(name, "/overrides"),
     }
@@ -0,0 +1,21 @@
+(function_definition
+    body: (_) @function.inside) @function.around
+
+[
+    (struct_specifier
+        body: (_) @class.inside)
+    (union_specifier
+        body: (_) @class.inside)
+    (enum_specifier
+        body: (_) @class.inside)
+] @class.around
+
+(comment) @comment.inside
+
+(comment)+ @comment.around
+
+(parameter_list
+    (_) @argument)
+
+(argument_list
+    (_) @argument)
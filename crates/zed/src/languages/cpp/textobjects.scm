@@ -0,0 +1,33 @@
+[
+    (function_definition
+        body: (_) @function.inside)
+    (lambda_expression
+        body: (_) @function.inside)
+] @function.around
+
+[
+    (class_specifier
+        body: (_) @class.inside)
+    (struct_specifier
+        body: (_) @class.inside)
+    (union_specifier
+        body: (_) @class.inside)
+    (enum_specifier
+        body: (_) @class.inside)
+] @class.around
+
+(comment) @comment.inside
+
+(comment)+ @comment.around
+
+(parameter_list
+    (_) @argument)
+
+(argument_list
+    (_) @argument)
+
+(template_parameter_list
+    (_) @argument)
+
+(template_argument_list
+    (_) @argument)
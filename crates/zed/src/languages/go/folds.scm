@@ -0,0 +1,10 @@
+(_ "{" "}") @fold
+(_ "[" "]") @fold
+(_ "(" ")") @fold
+
+[
+    (comment)
+    (raw_string_literal)
+] @fold
+
+(comment) @fold.run
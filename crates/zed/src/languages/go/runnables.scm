@@ -0,0 +1,20 @@
+(
+    (function_declaration
+        name: (identifier) @name) @run
+    (#match? @name "^Test")
+    (#set! "tag" "go-test")
+)
+
+(
+    (function_declaration
+        name: (identifier) @name) @run
+    (#match? @name "^Benchmark")
+    (#set! "tag" "go-bench")
+)
+
+(
+    (function_declaration
+        name: (identifier) @name) @run
+    (#eq? @name "main")
+    (#set! "tag" "go-main")
+)
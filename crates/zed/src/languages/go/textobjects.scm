@@ -0,0 +1,27 @@
+[
+    (function_declaration
+        body: (_) @function.inside)
+    (method_declaration
+        body: (_) @function.inside)
+    (func_literal
+        body: (_) @function.inside)
+] @function.around
+
+(type_declaration
+    (type_spec
+        type: (struct_type
+            (field_declaration_list) @class.inside))) @class.around
+
+(type_declaration
+    (type_spec
+        type: (interface_type))) @class.around
+
+(comment) @comment.inside
+
+(comment)+ @comment.around
+
+(parameter_list
+    (_) @argument)
+
+(argument_list
+    (_) @argument)
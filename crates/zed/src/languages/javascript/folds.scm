@@ -0,0 +1,12 @@
+(_ "{" "}") @fold
+(_ "[" "]") @fold
+(_ "(" ")") @fold
+
+[
+    (comment)
+    (template_string)
+] @fold
+
+(jsx_element) @fold
+
+(comment) @fold.run
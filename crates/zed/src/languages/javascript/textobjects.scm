@@ -0,0 +1,29 @@
+[
+    (function_declaration
+        body: (_) @function.inside)
+    (generator_function_declaration
+        body: (_) @function.inside)
+    (function
+        body: (_) @function.inside)
+    (arrow_function
+        body: (_) @function.inside)
+    (method_definition
+        body: (_) @function.inside)
+] @function.around
+
+[
+    (class_declaration
+        body: (_) @class.inside)
+    (class
+        body: (_) @class.inside)
+] @class.around
+
+(comment) @comment.inside
+
+(comment)+ @comment.around
+
+(formal_parameters
+    (_) @argument)
+
+(arguments
+    (_) @argument)
@@ -0,0 +1,11 @@
+(_ "{" "}") @fold
+(_ "[" "]") @fold
+(_ "(" ")") @fold
+
+[
+    (function_definition)
+    (class_definition)
+    (string)
+] @fold
+
+(comment) @fold.run
@@ -0,0 +1,16 @@
+(
+    (function_definition
+        name: (identifier) @name) @run
+    (#match? @name "^test_")
+    (#set! "tag" "python-test")
+)
+
+(
+    (if_statement
+        condition: (comparison_operator
+            (identifier) @_name
+            (string) @_main)) @run
+    (#eq? @_name "__name__")
+    (#match? @_main "__main__")
+    (#set! "tag" "python-main")
+)
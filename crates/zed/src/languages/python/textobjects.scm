@@ -0,0 +1,21 @@
+(function_definition
+    body: (_) @function.inside) @function.around
+
+(lambda
+    body: (_) @function.inside) @function.around
+
+(class_definition
+    body: (_) @class.inside) @class.around
+
+(comment) @comment.inside
+
+(comment)+ @comment.around
+
+(parameters
+    (_) @argument)
+
+(lambda_parameters
+    (_) @argument)
+
+(argument_list
+    (_) @argument)
@@ -0,0 +1,11 @@
+(_ "{" "}") @fold
+(_ "[" "]") @fold
+(_ "(" ")") @fold
+
+[
+    (block_comment)
+    (string_literal)
+    (raw_string_literal)
+] @fold
+
+(line_comment) @fold.run
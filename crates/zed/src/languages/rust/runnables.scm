@@ -0,0 +1,27 @@
+(
+    (attribute_item (attribute (identifier) @_attribute))
+    .
+    (attribute_item)*
+    .
+    (function_item
+        name: (_) @name) @run
+    (#eq? @_attribute "test")
+    (#set! "tag" "rust-test")
+)
+
+(
+    (attribute_item (attribute (identifier) @_attribute))
+    .
+    (attribute_item)*
+    .
+    (function_item
+        name: (_) @name) @run
+    (#eq? @_attribute "bench")
+    (#set! "tag" "rust-bench")
+)
+
+(source_file
+    (function_item
+        name: (identifier) @name) @run
+    (#eq? @name "main")
+    (#set! "tag" "rust-main"))
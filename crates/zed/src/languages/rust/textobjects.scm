@@ -0,0 +1,44 @@
+(function_item
+    body: (_) @function.inside) @function.around
+
+(closure_expression
+    body: (_) @function.inside) @function.around
+
+[
+    (struct_item
+        body: (_) @class.inside)
+    (enum_item
+        body: (_) @class.inside)
+    (union_item
+        body: (_) @class.inside)
+    (trait_item
+        body: (_) @class.inside)
+    (impl_item
+        body: (_) @class.inside)
+    (mod_item
+        body: (_) @class.inside)
+] @class.around
+
+[
+    (line_comment)
+    (block_comment)
+] @comment.inside
+
+(line_comment)+ @comment.around
+
+(block_comment) @comment.around
+
+(parameters
+    (_) @argument)
+
+(closure_parameters
+    (_) @argument)
+
+(arguments
+    (_) @argument)
+
+(type_parameters
+    (_) @argument)
+
+(type_arguments
+    (_) @argument)
@@ -0,0 +1,10 @@
+(_ "{" "}") @fold
+(_ "[" "]") @fold
+(_ "(" ")") @fold
+
+[
+    (comment)
+    (template_string)
+] @fold
+
+(comment) @fold.run
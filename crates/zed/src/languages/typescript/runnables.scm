@@ -0,0 +1,9 @@
+(
+    (call_expression
+        function: (identifier) @_function
+        arguments: (arguments
+            .
+            (string (string_fragment) @name))) @run
+    (#match? @_function "^(it|test|describe)$")
+    (#set! "tag" "js-test")
+)
@@ -156,6 +156,7 @@ fn main() {
         language::init(cx);
         editor::init(cx);
         diagnostics::init(cx);
+        diff_view::init(cx);
         copilot::init(
             copilot_language_server_id,
             http.clone(),
@@ -215,6 +216,10 @@ fn main() {
         semantic_index::init(fs.clone(), http.clone(), languages.clone(), cx);
         vim::init(cx);
         terminal_view::init(cx);
+        debugger_ui::init(cx);
+        git_ui::init(cx);
+        hierarchy_panel::init(cx);
+        tasks_ui::init(cx);
 
         journal::init(app_state.clone(), cx);
         language_selector::init(cx);
@@ -7,11 +7,14 @@ pub use app_menus::*;
 use assistant::AssistantPanel;
 use breadcrumbs::Breadcrumbs;
 use collections::VecDeque;
+use debugger_ui::debugger_panel::DebuggerPanel;
 use editor::{Editor, MultiBuffer};
+use git_ui::git_panel::GitPanel;
 use gpui::{
     actions, point, px, AppContext, Context, FocusableView, PromptLevel, TitlebarOptions, View,
     ViewContext, VisualContext, WindowBounds, WindowKind, WindowOptions,
 };
+use hierarchy_panel::HierarchyPanel;
 pub use only_instance::*;
 pub use open_listener::*;
 
@@ -162,6 +165,9 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
                 workspace_handle.clone(),
                 cx.clone(),
             );
+            let debugger_panel = DebuggerPanel::load(workspace_handle.clone(), cx.clone());
+            let git_panel = GitPanel::load(workspace_handle.clone(), cx.clone());
+            let hierarchy_panel = HierarchyPanel::load(workspace_handle.clone(), cx.clone());
             let (
                 project_panel,
                 terminal_panel,
@@ -169,6 +175,9 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
                 channels_panel,
                 chat_panel,
                 notification_panel,
+                debugger_panel,
+                git_panel,
+                hierarchy_panel,
             ) = futures::try_join!(
                 project_panel,
                 terminal_panel,
@@ -176,6 +185,9 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
                 channels_panel,
                 chat_panel,
                 notification_panel,
+                debugger_panel,
+                git_panel,
+                hierarchy_panel,
             )?;
 
             workspace_handle.update(&mut cx, |workspace, cx| {
@@ -186,6 +198,9 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
                 workspace.add_panel(channels_panel, cx);
                 workspace.add_panel(chat_panel, cx);
                 workspace.add_panel(notification_panel, cx);
+                workspace.add_panel(debugger_panel, cx);
+                workspace.add_panel(git_panel, cx);
+                workspace.add_panel(hierarchy_panel, cx);
 
                 if !was_deserialized
                     && workspace